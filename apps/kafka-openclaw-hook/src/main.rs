@@ -1,11 +1,16 @@
 use anyhow::Result;
 use hook_runtime::smash;
+use relay_core::redact::Redactor;
 use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    tracing_subscriber::fmt().with_env_filter(filter).init();
+    let redactor = Redactor::shared();
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(move || redactor.writer(std::io::stdout()))
+        .init();
 
     smash::run_from_env().await
 }
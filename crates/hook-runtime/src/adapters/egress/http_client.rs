@@ -0,0 +1,306 @@
+use crate::smash::config::{HttpPoolConfig, OutboundHeaders};
+use crate::smash::metrics::write_header;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context as TaskContext, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+
+/// Connection churn counters for the outbound HTTP clients, shared by every
+/// adapter so `GET /metrics` shows the whole process.
+#[derive(Debug, Default)]
+pub struct HttpClientMetrics {
+    requests: AtomicU64,
+    connections_opened: AtomicU64,
+    dns_lookups: AtomicU64,
+    dns_failures: AtomicU64,
+    dns_micros: AtomicU64,
+}
+
+impl HttpClientMetrics {
+    /// Counts a request that reached the upstream and got a response.
+    pub fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_dns(&self, elapsed: Duration, ok: bool) {
+        self.dns_lookups.fetch_add(1, Ordering::Relaxed);
+        self.dns_micros.fetch_add(
+            u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+        if !ok {
+            self.dns_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn render(&self, output: &mut String) {
+        let requests = self.requests.load(Ordering::Relaxed);
+        let opened = self.connections_opened.load(Ordering::Relaxed);
+
+        write_header(
+            output,
+            "webhook_relay_http_client_requests_total",
+            "counter",
+            "Outbound HTTP requests that received a response.",
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_http_client_requests_total {requests}"
+        );
+
+        write_header(
+            output,
+            "webhook_relay_http_client_connections_opened_total",
+            "counter",
+            "New outbound connections established, TLS included.",
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_http_client_connections_opened_total {opened}"
+        );
+
+        write_header(
+            output,
+            "webhook_relay_http_client_connections_reused_total",
+            "counter",
+            "Requests served on a pooled connection (requests minus connections opened).",
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_http_client_connections_reused_total {}",
+            requests.saturating_sub(opened)
+        );
+
+        write_header(
+            output,
+            "webhook_relay_http_client_dns_lookup_seconds",
+            "summary",
+            "Time spent resolving upstream host names.",
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_http_client_dns_lookup_seconds_sum {}",
+            self.dns_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_http_client_dns_lookup_seconds_count {}",
+            self.dns_lookups.load(Ordering::Relaxed)
+        );
+
+        write_header(
+            output,
+            "webhook_relay_http_client_dns_failures_total",
+            "counter",
+            "Upstream host name lookups that failed.",
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_http_client_dns_failures_total {}",
+            self.dns_failures.load(Ordering::Relaxed)
+        );
+    }
+}
+
+/// Builds a client with the configured pool settings, static headers and
+/// churn metrics. Request-level headers such as `Authorization` win over the
+/// static ones.
+pub fn build_http_client(
+    pool: &HttpPoolConfig,
+    outbound_headers: &OutboundHeaders,
+    timeout: Duration,
+    metrics: Arc<HttpClientMetrics>,
+) -> Result<Client> {
+    let mut default_headers = HeaderMap::new();
+    for (name, value) in &outbound_headers.headers {
+        default_headers.insert(
+            HeaderName::from_bytes(name.as_bytes()).context("forward header name")?,
+            HeaderValue::from_str(value).context("forward header value")?,
+        );
+    }
+    let mut builder = Client::builder()
+        .timeout(timeout)
+        .user_agent(outbound_headers.user_agent.as_str())
+        .default_headers(default_headers)
+        .pool_idle_timeout(Duration::from_secs(pool.idle_timeout_seconds))
+        .dns_resolver(Arc::new(TimedResolver {
+            metrics: metrics.clone(),
+        }))
+        .connector_layer(CountConnectionsLayer { metrics });
+    if let Some(max_idle) = pool.max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    if let Some(seconds) = pool.http2_keep_alive_seconds {
+        builder = builder
+            .http2_keep_alive_interval(Duration::from_secs(seconds))
+            .http2_keep_alive_while_idle(true);
+    }
+    builder.build().context("build reqwest client")
+}
+
+/// System resolver, timed. reqwest resolves once per new connection.
+struct TimedResolver {
+    metrics: Arc<HttpClientMetrics>,
+}
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let metrics = self.metrics.clone();
+        Box::pin(async move {
+            let started = Instant::now();
+            let resolved = tokio::net::lookup_host((name.as_str(), 0)).await;
+            metrics.record_dns(started.elapsed(), resolved.is_ok());
+            let addrs: Addrs = Box::new(resolved?.collect::<Vec<_>>().into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[derive(Clone)]
+struct CountConnectionsLayer {
+    metrics: Arc<HttpClientMetrics>,
+}
+
+impl<S> Layer<S> for CountConnectionsLayer {
+    type Service = CountConnections<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CountConnections {
+            inner,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct CountConnections<S> {
+    inner: S,
+    metrics: Arc<HttpClientMetrics>,
+}
+
+impl<S, R> Service<R> for CountConnections<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, context: &mut TaskContext<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(context)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let connecting = self.inner.call(request);
+        let metrics = self.metrics.clone();
+        Box::pin(async move {
+            let connection = connecting.await?;
+            metrics.connections_opened.fetch_add(1, Ordering::Relaxed);
+            Ok(connection)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::HeaderMap as RequestHeaders;
+    use axum::routing::post;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn pooled_requests_reuse_one_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let port = listener.local_addr().expect("addr").port();
+        tokio::spawn(async move {
+            let app = Router::new().route("/hook", post(|| async { "ok" }));
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let metrics = Arc::new(HttpClientMetrics::default());
+        let pool = HttpPoolConfig {
+            max_idle_per_host: Some(4),
+            idle_timeout_seconds: 90,
+            http2_keep_alive_seconds: None,
+        };
+        let client = build_http_client(
+            &pool,
+            &OutboundHeaders::default(),
+            Duration::from_secs(5),
+            metrics.clone(),
+        )
+        .expect("client");
+        for _ in 0..3 {
+            client
+                .post(format!("http://localhost:{port}/hook"))
+                .send()
+                .await
+                .expect("send")
+                .text()
+                .await
+                .expect("body");
+            metrics.record_request();
+        }
+
+        let mut output = String::new();
+        metrics.render(&mut output);
+        assert!(output.contains("webhook_relay_http_client_requests_total 3\n"));
+        assert!(output.contains("webhook_relay_http_client_connections_opened_total 1\n"));
+        assert!(output.contains("webhook_relay_http_client_connections_reused_total 2\n"));
+        assert!(output.contains("webhook_relay_http_client_dns_lookup_seconds_count 1\n"));
+    }
+
+    #[tokio::test]
+    async fn static_headers_and_user_agent_reach_upstream() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let port = listener.local_addr().expect("addr").port();
+        tokio::spawn(async move {
+            let app = Router::new().route(
+                "/hook",
+                post(|headers: RequestHeaders| async move {
+                    format!(
+                        "{}|{}",
+                        headers["user-agent"].to_str().unwrap_or_default(),
+                        headers["x-environment"].to_str().unwrap_or_default()
+                    )
+                }),
+            );
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let outbound_headers = OutboundHeaders {
+            user_agent: "gateway-relay/1".to_string(),
+            headers: vec![("X-Environment".to_string(), "staging".to_string())],
+        };
+        let client = build_http_client(
+            &HttpPoolConfig {
+                max_idle_per_host: None,
+                idle_timeout_seconds: 90,
+                http2_keep_alive_seconds: None,
+            },
+            &outbound_headers,
+            Duration::from_secs(5),
+            Arc::new(HttpClientMetrics::default()),
+        )
+        .expect("client");
+        let echoed = client
+            .post(format!("http://127.0.0.1:{port}/hook"))
+            .send()
+            .await
+            .expect("send")
+            .text()
+            .await
+            .expect("body");
+        assert_eq!(echoed, "gateway-relay/1|staging");
+    }
+}
@@ -1,6 +1,7 @@
-use crate::smash::config::Config;
+use crate::smash::config::{Config, OutboundHeaders};
 use anyhow::{Context, Result, anyhow};
 use rdkafka::ClientConfig;
+use rdkafka::message::{Header, OwnedHeaders};
 use rdkafka::producer::{FutureProducer, FutureRecord};
 use rdkafka::util::Timeout;
 use relay_core::model::WebhookEnvelope;
@@ -13,6 +14,7 @@ pub struct KafkaOutputAdapter {
     topic: String,
     key_mode: String,
     producer: FutureProducer,
+    headers: OwnedHeaders,
 }
 
 impl KafkaOutputAdapter {
@@ -22,6 +24,7 @@ impl KafkaOutputAdapter {
             topic,
             key_mode,
             producer,
+            headers: record_headers(&config.outbound_headers),
         })
     }
 
@@ -35,7 +38,9 @@ impl KafkaOutputAdapter {
             other => return Err(anyhow!("unsupported kafka_output key_mode '{}'", other)),
         };
 
-        let mut record = FutureRecord::to(&self.topic).payload(&payload);
+        let mut record = FutureRecord::to(&self.topic)
+            .payload(&payload)
+            .headers(self.headers.clone());
         if let Some(key) = key.as_ref() {
             record = record.key(key);
         }
@@ -51,6 +56,24 @@ impl KafkaOutputAdapter {
     }
 }
 
+/// Kafka has no User-Agent, so it travels as a `user-agent` record header
+/// next to the static forward headers.
+fn record_headers(outbound_headers: &OutboundHeaders) -> OwnedHeaders {
+    let headers = OwnedHeaders::new().insert(Header {
+        key: "user-agent",
+        value: Some(outbound_headers.user_agent.as_str()),
+    });
+    outbound_headers
+        .headers
+        .iter()
+        .fold(headers, |headers, (name, value)| {
+            headers.insert(Header {
+                key: name.as_str(),
+                value: Some(value.as_str()),
+            })
+        })
+}
+
 fn build_future_producer(config: &Config) -> Result<FutureProducer> {
     let mut client_config = ClientConfig::new();
     client_config
@@ -1,8 +1,10 @@
+use super::{DeliveryFailed, MAX_DLQ_RESPONSE_BODY_CHARS, truncate_chars};
 use anyhow::{Context, Result, anyhow};
-use relay_core::model::WebhookEnvelope;
+use chrono::{SecondsFormat, Utc};
+use relay_core::model::{AttemptOutcome, DeliveryAttempt, DeliveryFailure, WebhookEnvelope};
 use serde_json::json;
 use std::collections::BTreeMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::io::AsyncWriteExt;
 use tokio::process::Command as TokioCommand;
 use tokio::time::timeout;
@@ -55,6 +57,8 @@ impl McpToolOutputAdapter {
                         .ok_or_else(|| anyhow!("mcp http_sse auth token missing"))?;
                     request = request.header("Authorization", format!("Bearer {}", token));
                 }
+                let attempted_at = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+                let started = Instant::now();
                 let response = request
                     .json(&json!({
                         "tool": self.tool_name,
@@ -65,11 +69,34 @@ impl McpToolOutputAdapter {
                     .send()
                     .await
                     .context("call mcp http_sse endpoint")?;
-                if !response.status().is_success() {
-                    return Err(anyhow!(
-                        "mcp http_sse returned status {}",
-                        response.status()
-                    ));
+                let status = response.status();
+                if !status.is_success() {
+                    let response_body = response
+                        .text()
+                        .await
+                        .unwrap_or_else(|error| format!("unable to read response body: {error}"));
+                    let message = format!("mcp http_sse returned status {status}");
+                    let duration_ms =
+                        u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+                    return Err(DeliveryFailed {
+                        failure: DeliveryFailure {
+                            status_code: Some(status.as_u16()),
+                            response_body: Some(truncate_chars(
+                                &response_body,
+                                MAX_DLQ_RESPONSE_BODY_CHARS,
+                            )),
+                            attempts: vec![DeliveryAttempt {
+                                at: attempted_at,
+                                duration_ms,
+                                reason: message.clone(),
+                                status_code: Some(status.as_u16()),
+                                outcome: Some(AttemptOutcome::RejectedStatus),
+                            }],
+                            retry_duration_ms: duration_ms,
+                        },
+                        message,
+                    }
+                    .into());
                 }
                 Ok(())
             }
@@ -127,3 +154,61 @@ impl McpToolOutputAdapter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use relay_core::model::ENVELOPE_SCHEMA_VERSION;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn http_sse_rejection_keeps_response_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let url = format!("http://{}/tools", listener.local_addr().expect("addr"));
+        tokio::spawn(async move {
+            let app = Router::new().route(
+                "/tools",
+                post(|| async {
+                    (
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "arguments.envelope.payload.title is required",
+                    )
+                }),
+            );
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let adapter = McpToolOutputAdapter::new(
+            "notify".to_string(),
+            McpRuntimeTransport::HttpSse {
+                url,
+                auth_mode: "none".to_string(),
+                auth_token: None,
+            },
+        );
+        let envelope = WebhookEnvelope {
+            schema_version: ENVELOPE_SCHEMA_VERSION,
+            id: "evt-1".to_string(),
+            source: "github".to_string(),
+            event_type: "issues.opened".to_string(),
+            received_at: "2026-03-04T00:00:00Z".to_string(),
+            payload: json!({"action": "opened"}),
+            meta: None,
+        };
+
+        let error = adapter.call(&envelope).await.expect_err("rejected");
+        let failure = &error
+            .downcast_ref::<DeliveryFailed>()
+            .expect("delivery failure")
+            .failure;
+        assert_eq!(failure.status_code, Some(422));
+        assert_eq!(
+            failure.response_body.as_deref(),
+            Some("arguments.envelope.payload.title is required")
+        );
+        assert_eq!(failure.attempts.len(), 1);
+    }
+}
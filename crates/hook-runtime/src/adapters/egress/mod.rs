@@ -1,3 +1,4 @@
+mod http_client;
 mod kafka;
 mod mcp;
 mod openclaw;
@@ -6,16 +7,56 @@ mod websocket_server;
 
 use crate::smash::config::{Config, SmashAdapterConfig, SmashTransportConfig};
 use anyhow::{Context, Result, anyhow};
-use relay_core::model::WebhookEnvelope;
+use relay_config::required_secret_env;
+use relay_core::model::{DeliveryFailure, WebhookEnvelope};
+use relay_core::signatures::decode_standard_webhook_secret;
 use std::collections::BTreeMap;
-use std::env;
+use std::sync::Arc;
 
+pub use http_client::HttpClientMetrics;
 use kafka::KafkaOutputAdapter;
 use mcp::{McpRuntimeTransport, McpToolOutputAdapter};
 use openclaw::{OpenclawOutputAdapter, OpenclawOutputTarget};
 use websocket_client::WebsocketClientOutputAdapter;
 use websocket_server::WebsocketServerOutputAdapter;
 
+/// Adapter error carrying per-attempt detail for the DLQ entry.
+#[derive(Debug)]
+pub struct DeliveryFailed {
+    pub message: String,
+    pub failure: DeliveryFailure,
+}
+
+impl std::fmt::Display for DeliveryFailed {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for DeliveryFailed {}
+
+/// Upstream response bodies kept in `DeliveryFailure`.
+const MAX_DLQ_RESPONSE_BODY_CHARS: usize = 1_024;
+
+fn truncate_chars(value: &str, max_chars: usize) -> String {
+    if value.chars().count() <= max_chars {
+        return value.to_string();
+    }
+
+    let preview_limit = max_chars.saturating_sub(3);
+    let mut output = String::new();
+    let mut char_count = 0usize;
+    for character in value.chars() {
+        if char_count >= preview_limit {
+            break;
+        }
+        output.push(character);
+        char_count = char_count.saturating_add(1);
+    }
+    output.push_str("...");
+    output
+}
+
 #[derive(Clone)]
 pub enum RuntimeAdapter {
     Openclaw(OpenclawOutputAdapter),
@@ -50,9 +91,21 @@ impl RuntimeAdapter {
                 .with_context(|| format!("mcp_tool_output adapter '{}'", adapter_id)),
         }
     }
+
+    /// Re-reads secrets the adapter can swap while running, so far only the
+    /// OpenClaw gateway token. True when one changed.
+    pub fn reload_secrets(&self) -> Result<bool> {
+        match self {
+            RuntimeAdapter::Openclaw(adapter) => adapter.reload_token(),
+            _ => Ok(false),
+        }
+    }
 }
 
-pub async fn build_runtime_adapters(config: &Config) -> Result<BTreeMap<String, RuntimeAdapter>> {
+pub async fn build_runtime_adapters(
+    config: &Config,
+    http_metrics: Arc<HttpClientMetrics>,
+) -> Result<BTreeMap<String, RuntimeAdapter>> {
     let mut by_id: BTreeMap<String, RuntimeAdapter> = BTreeMap::new();
     let transport_map = config
         .transports
@@ -66,23 +119,44 @@ pub async fn build_runtime_adapters(config: &Config) -> Result<BTreeMap<String,
                 id,
                 url,
                 token_env,
+                signing_secret_env,
                 timeout_seconds,
                 max_retries,
                 ..
             } => {
-                let token = required_env(token_env)?;
+                let token = required_secret_env(token_env)?;
+                let signing_secret = signing_secret_env
+                    .as_deref()
+                    .map(required_secret_env)
+                    .transpose()?;
+                if signing_secret
+                    .as_deref()
+                    .is_some_and(|secret| decode_standard_webhook_secret(secret).is_none())
+                {
+                    return Err(anyhow!(
+                        "smash adapter '{}' signing secret must be base64 (optionally whsec_-prefixed)",
+                        id
+                    ));
+                }
                 let target = OpenclawOutputTarget {
                     adapter_id: id.clone(),
                     webhook_url: url.clone(),
                     webhook_token: token,
+                    token_env: token_env.clone(),
+                    signing_secret,
                     message_max_bytes: config.openclaw_message_max_bytes,
                     http_timeout_seconds: *timeout_seconds,
                     max_retries: *max_retries,
                     backoff_base_seconds: config.backoff_base_seconds,
                     backoff_max_seconds: config.backoff_max_seconds,
                 };
-                let output = OpenclawOutputAdapter::new(target)
-                    .with_context(|| format!("initialize openclaw output adapter '{}'", id))?;
+                let output = OpenclawOutputAdapter::new(
+                    target,
+                    &config.http_pool,
+                    &config.outbound_headers,
+                    http_metrics.clone(),
+                )
+                .with_context(|| format!("initialize openclaw output adapter '{}'", id))?;
                 (id.clone(), RuntimeAdapter::Openclaw(output))
             }
             SmashAdapterConfig::KafkaOutput {
@@ -188,21 +262,13 @@ pub async fn build_runtime_adapters(config: &Config) -> Result<BTreeMap<String,
     Ok(by_id)
 }
 
-fn required_env(name: &str) -> Result<String> {
-    let value = env::var(name).with_context(|| format!("missing env var: {name}"))?;
-    if value.trim().is_empty() {
-        return Err(anyhow!("env var {name} cannot be empty"));
-    }
-    Ok(value)
-}
-
 fn resolve_optional_auth_token(auth_mode: &str, token_env: Option<&str>) -> Result<Option<String>> {
     match auth_mode.trim().to_ascii_lowercase().as_str() {
         "none" => Ok(None),
         "bearer" | "hmac" => {
             let token_env =
                 token_env.ok_or_else(|| anyhow!("auth_mode '{}' requires token_env", auth_mode))?;
-            let token = required_env(token_env)?;
+            let token = required_secret_env(token_env)?;
             Ok(Some(token))
         }
         other => Err(anyhow!("unsupported auth_mode '{}'", other)),
@@ -1,16 +1,30 @@
-use anyhow::{Context, Result, anyhow};
-use relay_core::model::WebhookEnvelope;
+use super::http_client::{HttpClientMetrics, build_http_client};
+use super::{DeliveryFailed, MAX_DLQ_RESPONSE_BODY_CHARS, truncate_chars};
+use crate::smash::config::{HttpPoolConfig, OutboundHeaders};
+use anyhow::{Result, anyhow};
+use chrono::{SecondsFormat, Utc};
+use relay_config::required_secret_env;
+use relay_core::model::{AttemptOutcome, DeliveryAttempt, DeliveryFailure, WebhookEnvelope};
+use relay_core::sanitize::risk_score;
+use relay_core::signatures::compute_standard_webhook_signature;
 use reqwest::Client;
 use serde::Serialize;
 use serde_json::Value;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use tokio::time::{Duration, sleep};
 use tracing::{debug, info, warn};
 
+const RISK_SCORE_HEADER: &str = "X-OpenClaw-Risk-Score";
+
 #[derive(Debug, Clone)]
 pub struct OpenclawOutputTarget {
     pub adapter_id: String,
     pub webhook_url: String,
     pub webhook_token: String,
+    /// Where `webhook_token` came from, for reloads.
+    pub token_env: String,
+    pub signing_secret: Option<String>,
     pub message_max_bytes: usize,
     pub http_timeout_seconds: u64,
     pub max_retries: u32,
@@ -21,7 +35,10 @@ pub struct OpenclawOutputTarget {
 #[derive(Clone)]
 pub struct OpenclawOutputAdapter {
     target: OpenclawOutputTarget,
+    /// Shared by clones, so a reload reaches every copy of the adapter.
+    webhook_token: Arc<RwLock<String>>,
     client: Client,
+    metrics: Arc<HttpClientMetrics>,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,23 +53,78 @@ struct MappedHookPayload {
 
 #[derive(Debug)]
 enum ForwardErrorKind {
-    Retryable(String),
-    Permanent(String),
+    Retryable(AttemptError),
+    Permanent(AttemptError),
+}
+
+#[derive(Debug)]
+struct AttemptError {
+    message: String,
+    status_code: Option<u16>,
+    response_body: Option<String>,
+    outcome: AttemptOutcome,
+}
+
+impl AttemptError {
+    fn new(message: String, outcome: AttemptOutcome) -> Self {
+        Self {
+            message,
+            status_code: None,
+            response_body: None,
+            outcome,
+        }
+    }
 }
 
 const MAX_OPENCLAW_RESPONSE_PREVIEW_CHARS: usize = 2_048;
 
 impl OpenclawOutputAdapter {
-    pub fn new(target: OpenclawOutputTarget) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(target.http_timeout_seconds))
-            .build()
-            .context("build reqwest client")?;
+    pub fn new(
+        target: OpenclawOutputTarget,
+        pool: &HttpPoolConfig,
+        outbound_headers: &OutboundHeaders,
+        metrics: Arc<HttpClientMetrics>,
+    ) -> Result<Self> {
+        let client = build_http_client(
+            pool,
+            outbound_headers,
+            Duration::from_secs(target.http_timeout_seconds),
+            metrics.clone(),
+        )?;
+
+        Ok(Self {
+            webhook_token: Arc::new(RwLock::new(target.webhook_token.clone())),
+            target,
+            client,
+            metrics,
+        })
+    }
 
-        Ok(Self { target, client })
+    /// Re-reads the gateway token from `token_env`. True when it changed;
+    /// on error the current token stays in use.
+    pub fn reload_token(&self) -> Result<bool> {
+        let token = required_secret_env(&self.target.token_env)?;
+        let mut current = self
+            .webhook_token
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if *current == token {
+            return Ok(false);
+        }
+        *current = token;
+        Ok(true)
+    }
+
+    fn webhook_token(&self) -> String {
+        self.webhook_token
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
     }
 
     pub async fn forward_with_retry(&self, envelope: &WebhookEnvelope) -> Result<()> {
+        let started = Instant::now();
+        let mut failure = DeliveryFailure::default();
         for attempt in 1..=self.target.max_retries {
             debug!(
                 adapter_id = self.target.adapter_id.as_str(),
@@ -63,56 +135,73 @@ impl OpenclawOutputAdapter {
                 max_attempts = self.target.max_retries,
                 "attempting to forward webhook envelope to openclaw"
             );
-            match self.forward_once(envelope).await {
+            let attempted_at = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+            let attempt_started = Instant::now();
+            let (error, retryable) = match self.forward_once(envelope).await {
                 Ok(()) => return Ok(()),
-                Err(ForwardErrorKind::Permanent(message)) => {
-                    warn!(
-                        adapter_id = self.target.adapter_id.as_str(),
-                        event_id = envelope.id.as_str(),
-                        source = envelope.source.as_str(),
-                        event_type = envelope.event_type.as_str(),
-                        attempt,
-                        error = message.as_str(),
-                        "openclaw forward failed permanently"
-                    );
-                    return Err(anyhow!("forward failed permanently: {message}"));
+                Err(ForwardErrorKind::Permanent(error)) => (error, false),
+                Err(ForwardErrorKind::Retryable(error)) => (error, true),
+            };
+            record_attempt(
+                &mut failure,
+                attempted_at,
+                elapsed_ms(attempt_started),
+                &error,
+            );
+            failure.retry_duration_ms = elapsed_ms(started);
+
+            if !retryable {
+                warn!(
+                    adapter_id = self.target.adapter_id.as_str(),
+                    event_id = envelope.id.as_str(),
+                    source = envelope.source.as_str(),
+                    event_type = envelope.event_type.as_str(),
+                    attempt,
+                    error = error.message.as_str(),
+                    "openclaw forward failed permanently"
+                );
+                return Err(DeliveryFailed {
+                    message: format!("forward failed permanently: {}", error.message),
+                    failure,
                 }
-                Err(ForwardErrorKind::Retryable(message)) => {
-                    if attempt >= self.target.max_retries {
-                        warn!(
-                            adapter_id = self.target.adapter_id.as_str(),
-                            event_id = envelope.id.as_str(),
-                            source = envelope.source.as_str(),
-                            event_type = envelope.event_type.as_str(),
-                            attempt,
-                            error = message.as_str(),
-                            "openclaw forward exhausted retries"
-                        );
-                        return Err(anyhow!(
-                            "forward failed after {} attempts: {}",
-                            attempt,
-                            message
-                        ));
-                    }
-
-                    let backoff_seconds = retry_backoff_seconds(
-                        self.target.backoff_base_seconds,
-                        self.target.backoff_max_seconds,
-                        attempt.saturating_sub(1),
-                    );
-                    warn!(
-                        adapter_id = self.target.adapter_id.as_str(),
-                        event_id = envelope.id.as_str(),
-                        source = envelope.source.as_str(),
-                        event_type = envelope.event_type.as_str(),
-                        attempt,
-                        backoff_seconds,
-                        error = message.as_str(),
-                        "openclaw forward failed; retrying after backoff"
-                    );
-                    sleep(Duration::from_secs(backoff_seconds)).await;
+                .into());
+            }
+            if attempt >= self.target.max_retries {
+                warn!(
+                    adapter_id = self.target.adapter_id.as_str(),
+                    event_id = envelope.id.as_str(),
+                    source = envelope.source.as_str(),
+                    event_type = envelope.event_type.as_str(),
+                    attempt,
+                    error = error.message.as_str(),
+                    "openclaw forward exhausted retries"
+                );
+                return Err(DeliveryFailed {
+                    message: format!(
+                        "forward failed after {} attempts: {}",
+                        attempt, error.message
+                    ),
+                    failure,
                 }
+                .into());
             }
+
+            let backoff_seconds = retry_backoff_seconds(
+                self.target.backoff_base_seconds,
+                self.target.backoff_max_seconds,
+                attempt.saturating_sub(1),
+            );
+            warn!(
+                adapter_id = self.target.adapter_id.as_str(),
+                event_id = envelope.id.as_str(),
+                source = envelope.source.as_str(),
+                event_type = envelope.event_type.as_str(),
+                attempt,
+                backoff_seconds,
+                error = error.message.as_str(),
+                "openclaw forward failed; retrying after backoff"
+            );
+            sleep(Duration::from_secs(backoff_seconds)).await;
         }
 
         Err(anyhow!("retry loop terminated unexpectedly"))
@@ -139,20 +228,40 @@ impl OpenclawOutputAdapter {
             "posting mapped webhook payload to openclaw"
         );
 
-        let response = match self
+        let body = serde_json::to_vec(&payload).map_err(|error| {
+            ForwardErrorKind::Permanent(AttemptError::new(
+                format!("serialize payload: {error}"),
+                AttemptOutcome::Error,
+            ))
+        })?;
+        let mut request = self
             .client
             .post(&self.target.webhook_url)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.target.webhook_token),
-            )
-            .header("Content-Type", "application/json")
-            .json(&payload)
-            .send()
-            .await
-        {
+            .header("Authorization", format!("Bearer {}", self.webhook_token()))
+            .header("Content-Type", "application/json");
+        if let Some(score) = risk_score(&envelope.payload) {
+            request = request.header(RISK_SCORE_HEADER, score);
+        }
+        if let Some(secret) = self.target.signing_secret.as_deref() {
+            // Re-signed per attempt so retries carry a fresh timestamp; the id
+            // stays the envelope id so receivers can dedup redeliveries.
+            for (name, value) in
+                standard_webhook_headers(secret, &envelope.id, Utc::now().timestamp(), &body)
+            {
+                request = request.header(name, value);
+            }
+        }
+
+        let response = match request.body(body).send().await {
             Ok(response) => response,
             Err(error) => {
+                let outcome = if error.is_timeout() {
+                    AttemptOutcome::Timeout
+                } else if error.is_connect() {
+                    AttemptOutcome::ConnectError
+                } else {
+                    AttemptOutcome::Error
+                };
                 if error.is_timeout() || error.is_connect() || error.is_request() {
                     warn!(
                         adapter_id = self.target.adapter_id.as_str(),
@@ -163,7 +272,10 @@ impl OpenclawOutputAdapter {
                         error = %error,
                         "retryable openclaw request error"
                     );
-                    return Err(ForwardErrorKind::Retryable(error.to_string()));
+                    return Err(ForwardErrorKind::Retryable(AttemptError::new(
+                        error.to_string(),
+                        outcome,
+                    )));
                 }
                 warn!(
                     adapter_id = self.target.adapter_id.as_str(),
@@ -174,10 +286,14 @@ impl OpenclawOutputAdapter {
                     error = %error,
                     "permanent openclaw request error"
                 );
-                return Err(ForwardErrorKind::Permanent(error.to_string()));
+                return Err(ForwardErrorKind::Permanent(AttemptError::new(
+                    error.to_string(),
+                    outcome,
+                )));
             }
         };
 
+        self.metrics.record_request();
         let status = response.status();
         let response_body = match response.text().await {
             Ok(body) => truncate_chars(&body, MAX_OPENCLAW_RESPONSE_PREVIEW_CHARS),
@@ -208,9 +324,12 @@ impl OpenclawOutputAdapter {
                 response_body = response_body.as_str(),
                 "openclaw returned retryable status"
             );
-            return Err(ForwardErrorKind::Retryable(format!(
-                "OpenClaw returned {status}"
-            )));
+            return Err(ForwardErrorKind::Retryable(AttemptError {
+                message: format!("OpenClaw returned {status}"),
+                status_code: Some(status.as_u16()),
+                response_body: Some(truncate_chars(&response_body, MAX_DLQ_RESPONSE_BODY_CHARS)),
+                outcome: AttemptOutcome::RetryableStatus,
+            }));
         }
 
         warn!(
@@ -223,12 +342,58 @@ impl OpenclawOutputAdapter {
             response_body = response_body.as_str(),
             "openclaw returned non-retryable status"
         );
-        Err(ForwardErrorKind::Permanent(format!(
-            "OpenClaw returned {status}"
-        )))
+        Err(ForwardErrorKind::Permanent(AttemptError {
+            message: format!("OpenClaw returned {status}"),
+            status_code: Some(status.as_u16()),
+            response_body: Some(truncate_chars(&response_body, MAX_DLQ_RESPONSE_BODY_CHARS)),
+            outcome: AttemptOutcome::RejectedStatus,
+        }))
+    }
+}
+
+/// The latest upstream status and body win; a later transport error keeps the
+/// last response that was actually received.
+fn record_attempt(
+    failure: &mut DeliveryFailure,
+    at: String,
+    duration_ms: u64,
+    error: &AttemptError,
+) {
+    failure.attempts.push(DeliveryAttempt {
+        at,
+        duration_ms,
+        reason: error.message.clone(),
+        status_code: error.status_code,
+        outcome: Some(error.outcome),
+    });
+    if error.status_code.is_some() {
+        failure.status_code = error.status_code;
+        failure.response_body = error.response_body.clone();
     }
 }
 
+fn elapsed_ms(since: Instant) -> u64 {
+    u64::try_from(since.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+fn standard_webhook_headers(
+    secret: &str,
+    message_id: &str,
+    timestamp: i64,
+    body: &[u8],
+) -> Vec<(&'static str, String)> {
+    let timestamp = timestamp.to_string();
+    let Some(signature) = compute_standard_webhook_signature(secret, message_id, &timestamp, body)
+    else {
+        return Vec::new();
+    };
+    vec![
+        ("webhook-id", message_id.to_string()),
+        ("webhook-timestamp", timestamp),
+        ("webhook-signature", format!("v1,{signature}")),
+    ]
+}
+
 fn summarize_payload(payload: &Value, limit_bytes: usize) -> String {
     let serialized = serde_json::to_string(payload).unwrap_or_else(|_| "{}".to_string());
     if serialized.len() <= limit_bytes {
@@ -252,25 +417,6 @@ pub fn retry_backoff_seconds(base_seconds: u64, max_seconds: u64, attempt_index:
     scaled.min(max_seconds)
 }
 
-fn truncate_chars(value: &str, max_chars: usize) -> String {
-    if value.chars().count() <= max_chars {
-        return value.to_string();
-    }
-
-    let preview_limit = max_chars.saturating_sub(3);
-    let mut output = String::new();
-    let mut char_count = 0usize;
-    for character in value.chars() {
-        if char_count >= preview_limit {
-            break;
-        }
-        output.push(character);
-        char_count = char_count.saturating_add(1);
-    }
-    output.push_str("...");
-    output
-}
-
 fn to_json_string<T: Serialize>(value: &T) -> String {
     serde_json::to_string(value)
         .unwrap_or_else(|error| format!("{{\"serialization_error\":\"{}\"}}", error))
@@ -279,7 +425,49 @@ fn to_json_string<T: Serialize>(value: &T) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::smash::config::{HttpPoolConfig, OutboundHeaders};
     use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn reload_swaps_the_gateway_token_for_every_clone() {
+        let token_env = "OPENCLAW_RELOAD_TEST_TOKEN";
+        let adapter = OpenclawOutputAdapter::new(
+            OpenclawOutputTarget {
+                adapter_id: "openclaw-output".to_string(),
+                webhook_url: "http://127.0.0.1:9/hooks/agent".to_string(),
+                webhook_token: "token-before".to_string(),
+                token_env: token_env.to_string(),
+                signing_secret: None,
+                message_max_bytes: 1_024,
+                http_timeout_seconds: 1,
+                max_retries: 1,
+                backoff_base_seconds: 1,
+                backoff_max_seconds: 1,
+            },
+            &HttpPoolConfig {
+                max_idle_per_host: None,
+                idle_timeout_seconds: 90,
+                http2_keep_alive_seconds: None,
+            },
+            &OutboundHeaders::default(),
+            Arc::new(HttpClientMetrics::default()),
+        )
+        .expect("build adapter");
+        let delivering = adapter.clone();
+
+        assert!(adapter.reload_token().is_err());
+        assert_eq!(delivering.webhook_token(), "token-before");
+
+        relay_config::set_secret_overrides(BTreeMap::from([(
+            token_env.to_string(),
+            "token-after".to_string(),
+        )]));
+        assert!(adapter.reload_token().expect("reload"));
+        assert!(!adapter.reload_token().expect("unchanged"));
+        relay_config::set_secret_overrides(BTreeMap::new());
+        assert_eq!(delivering.webhook_token(), "token-after");
+    }
 
     #[test]
     fn retry_backoff_scales_and_caps() {
@@ -291,6 +479,64 @@ mod tests {
         assert_eq!(retry_backoff_seconds(1, 30, 5), 30);
     }
 
+    #[test]
+    fn standard_webhook_headers_verify_with_receiver_check() {
+        let secret = "whsec_MfKQ9r8GKYqrTwjUPD8ILPZIo2LaLaSw";
+        let body = br#"{"source":"github"}"#;
+        let headers = standard_webhook_headers(secret, "evt-1", 1_700_000_000, body);
+        let value = |name: &str| {
+            headers
+                .iter()
+                .find(|(header, _)| *header == name)
+                .map(|(_, value)| value.clone())
+                .expect("header present")
+        };
+
+        assert_eq!(value("webhook-id"), "evt-1");
+        assert_eq!(value("webhook-timestamp"), "1700000000");
+        assert!(relay_core::signatures::verify_standard_webhook_signature(
+            secret,
+            "evt-1",
+            "1700000000",
+            body,
+            &value("webhook-signature"),
+        ));
+    }
+
+    #[test]
+    fn attempts_keep_last_upstream_response() {
+        let mut failure = DeliveryFailure::default();
+        record_attempt(
+            &mut failure,
+            "2026-01-01T00:00:00.000Z".to_string(),
+            40,
+            &AttemptError {
+                message: "OpenClaw returned 503 Service Unavailable".to_string(),
+                status_code: Some(503),
+                response_body: Some("busy".to_string()),
+                outcome: AttemptOutcome::RetryableStatus,
+            },
+        );
+        record_attempt(
+            &mut failure,
+            "2026-01-01T00:00:01.000Z".to_string(),
+            5,
+            &AttemptError::new(
+                "connection refused".to_string(),
+                AttemptOutcome::ConnectError,
+            ),
+        );
+
+        assert_eq!(failure.attempts.len(), 2);
+        assert_eq!(failure.attempts[1].status_code, None);
+        assert_eq!(
+            failure.attempts[1].outcome,
+            Some(AttemptOutcome::ConnectError)
+        );
+        assert_eq!(failure.status_code, Some(503));
+        assert_eq!(failure.response_body.as_deref(), Some("busy"));
+    }
+
     #[test]
     fn summarize_payload_within_limit() {
         let payload = json!({"number":42});
@@ -30,6 +30,7 @@ struct WebsocketServerState {
 }
 
 impl WebsocketServerOutputAdapter {
+    #[allow(clippy::too_many_arguments)]
     pub async fn start(
         adapter_id: &str,
         bind: &str,
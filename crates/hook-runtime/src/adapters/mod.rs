@@ -1,4 +1,4 @@
 pub mod egress;
 pub mod ingress;
 
-pub use egress::{RuntimeAdapter, build_runtime_adapters};
+pub use egress::{DeliveryFailed, HttpClientMetrics, RuntimeAdapter, build_runtime_adapters};
@@ -1,7 +1,14 @@
+use super::schedule::WeeklySchedule;
 use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use relay_config::{
+    KafkaClientConfig, OpenclawGatewayConfig, env_bool, env_f64, env_u64, optional_env, parse_csv,
+};
+use relay_core::tenants::load_tenants;
 use serde::Deserialize;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
+use std::path::Path;
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -21,6 +28,75 @@ pub struct Config {
     pub transports: Vec<SmashTransportConfig>,
     pub allow_no_output: bool,
     pub no_output_sink: Option<NoOutputSink>,
+    pub fault_injection: Option<FaultInjectionConfig>,
+    pub slo: Option<SloConfig>,
+    pub metrics_bind: Option<String>,
+    pub quiet_hours: Option<QuietHoursConfig>,
+    pub http_pool: HttpPoolConfig,
+    pub outbound_headers: OutboundHeaders,
+    /// Envelopes whose sanitizer risk score exceeds this go straight to the DLQ.
+    pub risk_block_threshold: Option<u64>,
+    /// Tenant id to the adapter for its own gateway, from `HOOK_TENANTS_FILE`.
+    pub tenant_gateways: BTreeMap<String, String>,
+    /// How long shutdown waits for the in-flight delivery before leaving its
+    /// offset uncommitted.
+    pub shutdown_drain_seconds: u64,
+    /// Interval for re-reading gateway tokens, shared with serve.
+    pub secrets_reload_seconds: Option<u64>,
+}
+
+/// Connection pool settings for outbound HTTP adapters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpPoolConfig {
+    /// `None` keeps every idle connection.
+    pub max_idle_per_host: Option<usize>,
+    pub idle_timeout_seconds: u64,
+    /// HTTP/2 ping interval; `None` disables keep-alive pings.
+    pub http2_keep_alive_seconds: Option<u64>,
+}
+
+/// Static headers added to every forward, HTTP and Kafka alike.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboundHeaders {
+    pub user_agent: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Default for OutboundHeaders {
+    fn default() -> Self {
+        Self {
+            user_agent: default_user_agent(),
+            headers: Vec::new(),
+        }
+    }
+}
+
+fn default_user_agent() -> String {
+    format!("webhook-relay-smash/{}", env!("CARGO_PKG_VERSION"))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SloConfig {
+    /// Share of deliveries that must land within `latency_seconds`.
+    pub target: f64,
+    pub latency_seconds: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuietHoursConfig {
+    pub schedule: WeeklySchedule,
+    /// Event type patterns (`*` globs) delivered even during quiet hours.
+    pub exempt_event_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaultInjectionConfig {
+    pub seed: u64,
+    pub upstream_error_rate: f64,
+    pub upstream_timeout_rate: f64,
+    pub upstream_timeout_ms: u64,
+    pub dlq_error_rate: f64,
+    pub wakeup_delay_max_ms: u64,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -32,6 +108,48 @@ pub struct SmashRouteConfig {
     pub event_filters: Vec<String>,
     #[serde(default)]
     pub destinations: Vec<RouteDestinationConfig>,
+    #[serde(default)]
+    pub schedule: Option<RouteSchedule>,
+}
+
+/// Limits a route to (or, with `outside`, excludes it from) weekly windows,
+/// e.g. business hours for a team channel and the rest for on-call.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RouteScheduleConfig")]
+pub struct RouteSchedule {
+    pub hours: WeeklySchedule,
+    pub outside: bool,
+}
+
+impl RouteSchedule {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.hours.contains(now) != self.outside
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RouteScheduleConfig {
+    hours: String,
+    #[serde(default = "default_schedule_utc_offset")]
+    utc_offset: String,
+    #[serde(default)]
+    outside: bool,
+}
+
+fn default_schedule_utc_offset() -> String {
+    "+00:00".to_string()
+}
+
+impl TryFrom<RouteScheduleConfig> for RouteSchedule {
+    type Error = anyhow::Error;
+
+    fn try_from(config: RouteScheduleConfig) -> Result<Self> {
+        Ok(Self {
+            hours: WeeklySchedule::parse(&config.hours, &config.utc_offset)?,
+            outside: config.outside,
+        })
+    }
 }
 
 fn default_required_destination() -> bool {
@@ -48,11 +166,16 @@ pub struct RouteDestinationConfig {
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "driver", rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
 pub enum SmashAdapterConfig {
     OpenclawHttpOutput {
         id: String,
         url: String,
         token_env: String,
+        /// Env var holding a `whsec_` secret; when set, deliveries carry
+        /// Standard Webhooks signature headers.
+        #[serde(default)]
+        signing_secret_env: Option<String>,
         timeout_seconds: u64,
         max_retries: u32,
         #[serde(default)]
@@ -139,7 +262,7 @@ impl Config {
     pub fn from_env() -> Result<Self> {
         let kafka_topics_from_env = env::var("KAFKA_TOPICS")
             .ok()
-            .map(|raw| parse_csv_topics(&raw))
+            .map(|raw| parse_csv(&raw))
             .filter(|topics| !topics.is_empty());
         let allow_no_output = env_bool("HOOK_ALLOW_NO_OUTPUT", false);
         let no_output_sink = parse_no_output_sink(env::var("HOOK_NO_OUTPUT_SINK").ok())?;
@@ -147,6 +270,7 @@ impl Config {
         let smash_routes_from_env = parse_routes_json_env()?;
         let adapters_from_env = parse_adapters_json_env()?;
         let transports_from_env = parse_transports_json_env()?;
+        let gateway = OpenclawGatewayConfig::from_env()?;
 
         let (smash_routes, adapters, transports, using_legacy_fallback) = if !smash_routes_from_env
             .is_empty()
@@ -172,10 +296,11 @@ impl Config {
             let default_adapter_id = "openclaw-output".to_string();
             let adapter = SmashAdapterConfig::OpenclawHttpOutput {
                 id: default_adapter_id.clone(),
-                url: required_env("OPENCLAW_WEBHOOK_URL")?,
-                token_env: "OPENCLAW_WEBHOOK_TOKEN".to_string(),
-                timeout_seconds: env_u64("OPENCLAW_HTTP_TIMEOUT_SECONDS", 20)?,
-                max_retries: env_u32("CONSUMER_MAX_RETRIES", 5)?,
+                url: gateway.required_url()?.to_string(),
+                token_env: OpenclawGatewayConfig::TOKEN_ENV.to_string(),
+                signing_secret_env: gateway.signing_secret_env.clone(),
+                timeout_seconds: gateway.timeout_seconds,
+                max_retries: gateway.max_retries,
                 plugins: Vec::new(),
             };
             let fallback_topics = kafka_topics_from_env.clone().unwrap_or_else(|| {
@@ -191,11 +316,16 @@ impl Config {
                         adapter_id: default_adapter_id.clone(),
                         required: true,
                     }],
+                    schedule: None,
                 })
                 .collect::<Vec<_>>();
             (routes, vec![adapter], Vec::new(), true)
         };
 
+        let (tenant_adapters, tenant_gateways) = parse_tenant_gateways_env(&gateway)?;
+        let mut adapters = adapters;
+        adapters.extend(tenant_adapters);
+
         let kafka_topics = match kafka_topics_from_env {
             Some(topics) => topics,
             None => derive_topics_from_routes(&smash_routes)?,
@@ -204,23 +334,17 @@ impl Config {
             return Err(anyhow!("KAFKA_TOPICS cannot be empty"));
         }
 
+        let kafka = KafkaClientConfig::from_env("plaintext")?;
         let config = Self {
-            kafka_brokers: required_env("KAFKA_BROKERS")?,
-            kafka_sasl_username: env::var("KAFKA_SASL_USERNAME")
-                .ok()
-                .filter(|value| !value.trim().is_empty()),
-            kafka_sasl_password: env::var("KAFKA_SASL_PASSWORD")
-                .ok()
-                .filter(|value| !value.trim().is_empty()),
-            kafka_security_protocol: env::var("KAFKA_SECURITY_PROTOCOL")
-                .unwrap_or_else(|_| "PLAINTEXT".to_string()),
-            kafka_sasl_mechanism: env::var("KAFKA_SASL_MECHANISM")
-                .ok()
-                .filter(|value| !value.trim().is_empty()),
+            kafka_brokers: kafka.brokers,
+            kafka_sasl_username: kafka.sasl_username,
+            kafka_sasl_password: kafka.sasl_password,
+            kafka_security_protocol: kafka.security_protocol,
+            kafka_sasl_mechanism: kafka.sasl_mechanism,
             kafka_group_id: env::var("KAFKA_GROUP_ID")
                 .unwrap_or_else(|_| "kafka-openclaw-hook".to_string()),
             kafka_topics,
-            openclaw_message_max_bytes: env_usize("OPENCLAW_MESSAGE_MAX_BYTES", 4_000)?,
+            openclaw_message_max_bytes: gateway.message_max_bytes,
             dlq_topic: env::var("KAFKA_DLQ_TOPIC").unwrap_or_else(|_| "webhooks.dlq".to_string()),
             backoff_base_seconds: env_u64("CONSUMER_BACKOFF_BASE_SECONDS", 1)?,
             backoff_max_seconds: env_u64("CONSUMER_BACKOFF_MAX_SECONDS", 30)?,
@@ -229,6 +353,24 @@ impl Config {
             transports,
             allow_no_output,
             no_output_sink,
+            fault_injection: parse_fault_injection_env()?,
+            slo: parse_slo_env()?,
+            metrics_bind: optional_env("HOOK_METRICS_BIND"),
+            quiet_hours: parse_quiet_hours_env()?,
+            http_pool: parse_http_pool_env()?,
+            outbound_headers: parse_outbound_headers_env()?,
+            risk_block_threshold: optional_env("HOOK_RISK_BLOCK_THRESHOLD")
+                .map(|raw| {
+                    raw.parse::<u64>()
+                        .context("invalid u64 for HOOK_RISK_BLOCK_THRESHOLD")
+                })
+                .transpose()?,
+            tenant_gateways,
+            shutdown_drain_seconds: env_u64("HOOK_SHUTDOWN_DRAIN_SECONDS", 30)?,
+            secrets_reload_seconds: optional_env("RELAY_SECRETS_RELOAD_SECONDS")
+                .map(|raw| raw.parse::<u64>())
+                .transpose()
+                .context("invalid u64 for RELAY_SECRETS_RELOAD_SECONDS")?,
         };
 
         config.validate(using_legacy_fallback)?;
@@ -244,6 +386,63 @@ impl Config {
             return Err(anyhow!("KAFKA_DLQ_TOPIC cannot be empty"));
         }
 
+        if self.secrets_reload_seconds == Some(0) {
+            return Err(anyhow!(
+                "RELAY_SECRETS_RELOAD_SECONDS must be a positive integer"
+            ));
+        }
+
+        if let Some(faults) = &self.fault_injection {
+            for (name, rate) in [
+                ("HOOK_FAULT_UPSTREAM_ERROR_RATE", faults.upstream_error_rate),
+                (
+                    "HOOK_FAULT_UPSTREAM_TIMEOUT_RATE",
+                    faults.upstream_timeout_rate,
+                ),
+                ("HOOK_FAULT_DLQ_ERROR_RATE", faults.dlq_error_rate),
+            ] {
+                if !(0.0..=1.0).contains(&rate) {
+                    return Err(anyhow!("{name} must be between 0 and 1"));
+                }
+            }
+        }
+
+        if let Some(slo) = &self.slo {
+            if !(slo.target > 0.0 && slo.target < 1.0) {
+                return Err(anyhow!(
+                    "HOOK_SLO_TARGET must be between 0 and 1 (exclusive)"
+                ));
+            }
+            if slo.latency_seconds <= 0.0 {
+                return Err(anyhow!("HOOK_SLO_LATENCY_SECONDS must be greater than 0"));
+            }
+            if self.metrics_bind.is_none() {
+                return Err(anyhow!(
+                    "HOOK_SLO_LATENCY_SECONDS requires HOOK_METRICS_BIND"
+                ));
+            }
+        }
+
+        if self.http_pool.idle_timeout_seconds == 0 {
+            return Err(anyhow!(
+                "HOOK_HTTP_POOL_IDLE_TIMEOUT_SECONDS must be greater than 0"
+            ));
+        }
+        if self.http_pool.http2_keep_alive_seconds == Some(0) {
+            return Err(anyhow!(
+                "HOOK_HTTP2_KEEP_ALIVE_SECONDS must be greater than 0"
+            ));
+        }
+
+        if self
+            .risk_block_threshold
+            .is_some_and(|threshold| threshold >= 100)
+        {
+            return Err(anyhow!(
+                "HOOK_RISK_BLOCK_THRESHOLD must be below 100, the highest risk score"
+            ));
+        }
+
         let mut adapter_ids = BTreeSet::new();
         for adapter in &self.adapters {
             let adapter_id = adapter_id(adapter);
@@ -257,6 +456,7 @@ impl Config {
                 SmashAdapterConfig::OpenclawHttpOutput {
                     url,
                     token_env,
+                    signing_secret_env,
                     timeout_seconds,
                     plugins,
                     ..
@@ -273,6 +473,15 @@ impl Config {
                             adapter_id
                         ));
                     }
+                    if signing_secret_env
+                        .as_deref()
+                        .is_some_and(|name| name.trim().is_empty())
+                    {
+                        return Err(anyhow!(
+                            "smash adapter '{}' signing_secret_env cannot be empty",
+                            adapter_id
+                        ));
+                    }
                     if *timeout_seconds == 0 {
                         return Err(anyhow!(
                             "smash adapter '{}' timeout_seconds must be greater than 0",
@@ -608,6 +817,133 @@ fn parse_transports_json_env() -> Result<Vec<SmashTransportConfig>> {
         .with_context(|| "parse HOOK_SMASH_TRANSPORTS_JSON".to_string())
 }
 
+fn parse_fault_injection_env() -> Result<Option<FaultInjectionConfig>> {
+    if !env_bool("HOOK_FAULT_INJECTION", false) {
+        return Ok(None);
+    }
+
+    Ok(Some(FaultInjectionConfig {
+        seed: env_u64("HOOK_FAULT_SEED", 0)?,
+        upstream_error_rate: env_f64("HOOK_FAULT_UPSTREAM_ERROR_RATE", 0.0)?,
+        upstream_timeout_rate: env_f64("HOOK_FAULT_UPSTREAM_TIMEOUT_RATE", 0.0)?,
+        upstream_timeout_ms: env_u64("HOOK_FAULT_UPSTREAM_TIMEOUT_MS", 1_000)?,
+        dlq_error_rate: env_f64("HOOK_FAULT_DLQ_ERROR_RATE", 0.0)?,
+        wakeup_delay_max_ms: env_u64("HOOK_FAULT_WAKEUP_DELAY_MAX_MS", 0)?,
+    }))
+}
+
+fn parse_slo_env() -> Result<Option<SloConfig>> {
+    let Some(latency_seconds) = optional_env("HOOK_SLO_LATENCY_SECONDS") else {
+        return Ok(None);
+    };
+    Ok(Some(SloConfig {
+        target: env_f64("HOOK_SLO_TARGET", 0.99)?,
+        latency_seconds: latency_seconds
+            .parse::<f64>()
+            .context("invalid f64 for HOOK_SLO_LATENCY_SECONDS")?,
+    }))
+}
+
+/// One `openclaw_http_output` adapter, `tenant-<id>`, per tenant with a
+/// gateway, using the same timeout and retry settings as the default one.
+fn parse_tenant_gateways_env(
+    gateway: &OpenclawGatewayConfig,
+) -> Result<(Vec<SmashAdapterConfig>, BTreeMap<String, String>)> {
+    let Some(path) = optional_env("HOOK_TENANTS_FILE") else {
+        return Ok((Vec::new(), BTreeMap::new()));
+    };
+    let mut adapters = Vec::new();
+    let mut gateways = BTreeMap::new();
+    for tenant in load_tenants(Path::new(&path))? {
+        let (Some(url), Some(token_env)) = (tenant.gateway_url, tenant.gateway_token_env) else {
+            continue;
+        };
+        let id = format!("tenant-{}", tenant.id);
+        adapters.push(SmashAdapterConfig::OpenclawHttpOutput {
+            id: id.clone(),
+            url,
+            token_env,
+            signing_secret_env: None,
+            timeout_seconds: gateway.timeout_seconds,
+            max_retries: gateway.max_retries,
+            plugins: Vec::new(),
+        });
+        gateways.insert(tenant.id, id);
+    }
+    Ok((adapters, gateways))
+}
+
+fn parse_http_pool_env() -> Result<HttpPoolConfig> {
+    let optional_u64 = |name: &str| {
+        optional_env(name)
+            .map(|raw| {
+                raw.parse::<u64>()
+                    .with_context(|| format!("invalid u64 for {name}"))
+            })
+            .transpose()
+    };
+    Ok(HttpPoolConfig {
+        max_idle_per_host: optional_u64("HOOK_HTTP_POOL_MAX_IDLE_PER_HOST")?
+            .map(|max| usize::try_from(max).unwrap_or(usize::MAX)),
+        idle_timeout_seconds: env_u64("HOOK_HTTP_POOL_IDLE_TIMEOUT_SECONDS", 90)?,
+        http2_keep_alive_seconds: optional_u64("HOOK_HTTP2_KEEP_ALIVE_SECONDS")?,
+    })
+}
+
+fn parse_outbound_headers_env() -> Result<OutboundHeaders> {
+    let user_agent = optional_env("HOOK_USER_AGENT").unwrap_or_else(default_user_agent);
+    reqwest::header::HeaderValue::from_str(&user_agent)
+        .context("HOOK_USER_AGENT is not a valid header value")?;
+    let headers = parse_csv(&optional_env("HOOK_FORWARD_HEADERS").unwrap_or_default())
+        .into_iter()
+        .map(|entry| parse_forward_header(&entry))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(OutboundHeaders {
+        user_agent,
+        headers,
+    })
+}
+
+fn parse_forward_header(entry: &str) -> Result<(String, String)> {
+    let (name, value) = entry
+        .split_once('=')
+        .ok_or_else(|| anyhow!("HOOK_FORWARD_HEADERS entry '{entry}' must be Name=value"))?;
+    let (name, value) = (name.trim(), value.trim());
+    let header_name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .with_context(|| format!("HOOK_FORWARD_HEADERS has invalid header name '{name}'"))?;
+    reqwest::header::HeaderValue::from_str(value)
+        .with_context(|| format!("HOOK_FORWARD_HEADERS has invalid value for '{name}'"))?;
+    if [
+        reqwest::header::AUTHORIZATION,
+        reqwest::header::CONTENT_TYPE,
+        reqwest::header::USER_AGENT,
+    ]
+    .contains(&header_name)
+        || header_name.as_str().starts_with("webhook-")
+    {
+        return Err(anyhow!(
+            "HOOK_FORWARD_HEADERS cannot set '{name}'; it is managed by the adapter"
+        ));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+fn parse_quiet_hours_env() -> Result<Option<QuietHoursConfig>> {
+    let Some(raw) = optional_env("HOOK_QUIET_HOURS") else {
+        return Ok(None);
+    };
+    let offset =
+        optional_env("HOOK_QUIET_HOURS_UTC_OFFSET").unwrap_or_else(default_schedule_utc_offset);
+    let schedule = WeeklySchedule::parse(&raw, &offset)
+        .context("invalid HOOK_QUIET_HOURS or HOOK_QUIET_HOURS_UTC_OFFSET")?;
+    Ok(Some(QuietHoursConfig {
+        schedule,
+        exempt_event_types: optional_env("HOOK_QUIET_HOURS_EXEMPT_EVENTS")
+            .map(|raw| parse_csv(&raw))
+            .unwrap_or_default(),
+    }))
+}
+
 fn derive_topics_from_routes(routes: &[SmashRouteConfig]) -> Result<Vec<String>> {
     let mut topics = BTreeSet::new();
     for route in routes {
@@ -641,68 +977,3 @@ fn parse_no_output_sink(raw: Option<String>) -> Result<Option<NoOutputSink>> {
         },
     }
 }
-
-fn parse_csv_topics(raw: &str) -> Vec<String> {
-    raw.split(',')
-        .map(str::trim)
-        .filter(|topic| !topic.is_empty())
-        .map(ToString::to_string)
-        .collect()
-}
-
-fn required_env(name: &str) -> Result<String> {
-    let value = env::var(name).with_context(|| format!("missing env var: {name}"))?;
-    if value.trim().is_empty() {
-        return Err(anyhow!("env var {name} cannot be empty"));
-    }
-    Ok(value)
-}
-
-fn env_u32(name: &str, default: u32) -> Result<u32> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<u32>()
-                .with_context(|| format!("invalid u32 for {name}"))
-        })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
-}
-
-fn env_u64(name: &str, default: u64) -> Result<u64> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<u64>()
-                .with_context(|| format!("invalid u64 for {name}"))
-        })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
-}
-
-fn env_usize(name: &str, default: usize) -> Result<usize> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<usize>()
-                .with_context(|| format!("invalid usize for {name}"))
-        })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
-}
-
-fn env_bool(name: &str, default: bool) -> bool {
-    match env::var(name) {
-        Ok(value) => matches!(
-            value.trim().to_ascii_lowercase().as_str(),
-            "1" | "true" | "yes" | "on"
-        ),
-        Err(_) => default,
-    }
-}
@@ -1,16 +1,27 @@
-use super::config::{Config, NoOutputSink, SmashPluginConfig, SmashRouteConfig};
+use super::config::{
+    Config, NoOutputSink, QuietHoursConfig, SmashAdapterConfig, SmashPluginConfig, SmashRouteConfig,
+};
 use super::dlq::DlqProducer;
-use crate::adapters::{RuntimeAdapter, build_runtime_adapters};
+use super::fault::FaultInjector;
+use super::metrics::EnvelopeVersionMetrics;
+use super::slo::SloTracker;
+use crate::adapters::{DeliveryFailed, HttpClientMetrics, RuntimeAdapter, build_runtime_adapters};
 use anyhow::{Context, Result, anyhow};
-use rdkafka::ClientConfig;
+use chrono::{DateTime, Utc};
 use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
 use rdkafka::message::{BorrowedMessage, Message};
-use relay_core::model::WebhookEnvelope;
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+use relay_core::model::{ENVELOPE_SCHEMA_VERSION, WebhookEnvelope, is_supported_envelope_version};
+use relay_core::redact::register_loaded_secrets;
+use relay_core::sanitize::risk_score;
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tracing::{Level, debug, error, info, warn};
 
 const MAX_KAFKA_PAYLOAD_PREVIEW_CHARS: usize = 4_096;
+const QUIET_HOURS_SEEK_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct KafkaConsumer {
     consumer: StreamConsumer,
@@ -20,6 +31,21 @@ pub struct KafkaConsumer {
     allow_no_output: bool,
     no_output_sink: Option<NoOutputSink>,
     dlq: DlqProducer,
+    faults: Option<Arc<FaultInjector>>,
+    slo: Option<Arc<SloTracker>>,
+    envelope_versions: Arc<EnvelopeVersionMetrics>,
+    quiet_hours: Option<QuietHoursConfig>,
+    risk_block_threshold: Option<u64>,
+    tenant_gateways: BTreeMap<String, String>,
+    openclaw_adapter_ids: BTreeSet<String>,
+    /// Set while partitions are paused for quiet hours; they resume together
+    /// once the latest window ends.
+    paused_until: Mutex<Option<DateTime<Utc>>>,
+    shutdown_drain: Duration,
+    secrets_reload: Option<Duration>,
+    /// Next offset per partition, committed again synchronously on shutdown
+    /// so no async commit is lost.
+    committed: Mutex<BTreeMap<(String, i32), i64>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,7 +55,14 @@ enum DeliveryOutcome {
 }
 
 impl KafkaConsumer {
-    pub async fn from_config(config: &Config, dlq: DlqProducer) -> Result<Self> {
+    pub async fn from_config(
+        config: &Config,
+        dlq: DlqProducer,
+        faults: Option<Arc<FaultInjector>>,
+        slo: Option<Arc<SloTracker>>,
+        http_metrics: Arc<HttpClientMetrics>,
+        envelope_versions: Arc<EnvelopeVersionMetrics>,
+    ) -> Result<Self> {
         let mut client_config = ClientConfig::new();
         client_config
             .set("bootstrap.servers", &config.kafka_brokers)
@@ -66,7 +99,7 @@ impl KafkaConsumer {
             "kafka consumer subscribed to topics"
         );
 
-        let adapters = build_runtime_adapters(config).await?;
+        let adapters = build_runtime_adapters(config, http_metrics).await?;
         let adapter_plugins = config
             .adapters
             .iter()
@@ -81,24 +114,122 @@ impl KafkaConsumer {
             allow_no_output: config.allow_no_output,
             no_output_sink: config.no_output_sink,
             dlq,
+            faults,
+            slo,
+            envelope_versions,
+            quiet_hours: config.quiet_hours.clone(),
+            risk_block_threshold: config.risk_block_threshold,
+            tenant_gateways: config.tenant_gateways.clone(),
+            openclaw_adapter_ids: config
+                .adapters
+                .iter()
+                .filter(|adapter| matches!(adapter, SmashAdapterConfig::OpenclawHttpOutput { .. }))
+                .map(|adapter| adapter.id().to_string())
+                .collect(),
+            paused_until: Mutex::new(None),
+            shutdown_drain: Duration::from_secs(config.shutdown_drain_seconds),
+            secrets_reload: config.secrets_reload_seconds.map(Duration::from_secs),
+            committed: Mutex::new(BTreeMap::new()),
         })
     }
 
+    /// Consumes until SIGTERM or Ctrl-C. The message being delivered then
+    /// gets up to `HOOK_SHUTDOWN_DRAIN_SECONDS` to finish; past that it is
+    /// abandoned uncommitted and redelivered after restart.
     pub async fn run(&self) -> Result<()> {
         info!("kafka-openclaw-hook started");
+        let secret_reloader = spawn_secret_reloader(self.adapters.clone(), self.secrets_reload)?;
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
 
         loop {
-            match self.consumer.recv().await {
-                Ok(message) => {
-                    if let Err(error) = self.process_message(message).await {
-                        error!(error = %error, "failed to process kafka message");
+            let paused_for = self
+                .paused_until()
+                .map(|until| (until - Utc::now()).to_std().unwrap_or_default());
+            let received = tokio::select! {
+                _ = &mut shutdown => break,
+                received = self.consumer.recv() => received,
+                _ = tokio::time::sleep(paused_for.unwrap_or_default()), if paused_for.is_some() => {
+                    if let Err(error) = self.resume_after_quiet_hours() {
+                        error!(error = %error, "failed to resume after quiet hours");
                     }
+                    continue;
                 }
+            };
+            let message = match received {
+                Ok(message) => message,
                 Err(error) => {
                     warn!(error = %error, "kafka poll error");
+                    continue;
+                }
+            };
+            if let Some(delay) = self.faults.as_ref().and_then(|f| f.wakeup_delay()) {
+                tokio::time::sleep(delay).await;
+            }
+            let processing = self.process_message(message);
+            tokio::pin!(processing);
+            let (result, stopping) = tokio::select! {
+                result = &mut processing => (Some(result), false),
+                _ = &mut shutdown => {
+                    info!(
+                        drain_seconds = self.shutdown_drain.as_secs(),
+                        "shutting down; finishing in-flight delivery"
+                    );
+                    (tokio::time::timeout(self.shutdown_drain, &mut processing).await.ok(), true)
                 }
+            };
+            match result {
+                Some(Err(error)) => error!(error = %error, "failed to process kafka message"),
+                None => warn!(
+                    "in-flight delivery did not finish within HOOK_SHUTDOWN_DRAIN_SECONDS; offset left uncommitted for redelivery"
+                ),
+                Some(Ok(())) => {}
             }
+            if stopping {
+                break;
+            }
+        }
+
+        secret_reloader.abort();
+        self.flush_commits()
+    }
+
+    fn commit(&self, message: &BorrowedMessage<'_>) -> Result<()> {
+        self.consumer
+            .commit_message(message, CommitMode::Async)
+            .context("commit kafka offset")?;
+        self.committed
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(
+                (message.topic().to_string(), message.partition()),
+                message.offset() + 1,
+            );
+        Ok(())
+    }
+
+    fn flush_commits(&self) -> Result<()> {
+        let committed = self
+            .committed
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if committed.is_empty() {
+            return Ok(());
+        }
+        let mut offsets = TopicPartitionList::new();
+        for ((topic, partition), offset) in committed.iter() {
+            offsets
+                .add_partition_offset(topic, *partition, Offset::Offset(*offset))
+                .context("build final commit")?;
         }
+        self.consumer
+            .commit(&offsets, CommitMode::Sync)
+            .context("commit kafka offsets on shutdown")?;
+        info!(
+            partitions = committed.len(),
+            "committed kafka offsets on shutdown"
+        );
+        Ok(())
     }
 
     async fn process_message(&self, message: BorrowedMessage<'_>) -> Result<()> {
@@ -138,10 +269,59 @@ impl KafkaConsumer {
             envelope_json = %to_json_string(&envelope),
             "deserialized webhook envelope from kafka"
         );
+        if !is_supported_envelope_version(envelope.schema_version) {
+            self.envelope_versions
+                .record_unsupported(envelope.schema_version);
+            warn!(
+                topic = topic.as_str(),
+                event_id = envelope.id.as_str(),
+                schema_version = envelope.schema_version,
+                current_schema_version = ENVELOPE_SCHEMA_VERSION,
+                "unsupported envelope schema version; delivering best effort"
+            );
+        }
+
+        if let Some(reason) = risk_block_reason(self.risk_block_threshold, &envelope) {
+            warn!(
+                topic = topic.as_str(),
+                event_id = envelope.id.as_str(),
+                source = envelope.source.as_str(),
+                event_type = envelope.event_type.as_str(),
+                reason = reason.as_str(),
+                "risk score above block threshold; moving envelope to dlq"
+            );
+            self.dlq
+                .publish_failed(&envelope, &reason, None)
+                .await
+                .context("publish risk-blocked envelope to dlq")?;
+            self.commit(&message)?;
+            return Ok(());
+        }
 
-        let delivery_outcome = self
-            .deliver_to_routes(topic.as_str(), &envelope)
-            .await
+        if let Some(until) = self.quiet_window_end(&envelope) {
+            self.hold_for_quiet_hours(topic.as_str(), partition, offset, until)?;
+            info!(
+                topic = topic.as_str(),
+                partition,
+                offset,
+                event_id = envelope.id.as_str(),
+                event_type = envelope.event_type.as_str(),
+                until = %until.to_rfc3339(),
+                "quiet hours; partition paused until window ends"
+            );
+            return Ok(());
+        }
+
+        let delivery_outcome = self.deliver_to_routes(topic.as_str(), &envelope).await;
+        if let Some(slo) = &self.slo {
+            match &delivery_outcome {
+                Ok(DeliveryOutcome::Commit) => {
+                    slo.record_delivered(&envelope.received_at, Utc::now())
+                }
+                _ => slo.record_failed(Utc::now()),
+            }
+        }
+        let delivery_outcome = delivery_outcome
             .with_context(|| format!("deliver routed envelope event_id={}", envelope.id))?;
 
         if matches!(delivery_outcome, DeliveryOutcome::DoNotCommit) {
@@ -155,9 +335,7 @@ impl KafkaConsumer {
             return Ok(());
         }
 
-        self.consumer
-            .commit_message(&message, CommitMode::Async)
-            .context("commit kafka offset")?;
+        self.commit(&message)?;
         debug!(
             topic = topic.as_str(),
             partition,
@@ -169,15 +347,81 @@ impl KafkaConsumer {
         Ok(())
     }
 
+    fn quiet_window_end(&self, envelope: &WebhookEnvelope) -> Option<DateTime<Utc>> {
+        let quiet = self.quiet_hours.as_ref()?;
+        if quiet
+            .exempt_event_types
+            .iter()
+            .any(|pattern| wildcard_matches(pattern, envelope.event_type.as_str()))
+        {
+            return None;
+        }
+        quiet.schedule.window_end(Utc::now())
+    }
+
+    /// Pauses the partition and rewinds it to the held message so it is
+    /// redelivered, uncommitted, after the window.
+    fn hold_for_quiet_hours(
+        &self,
+        topic: &str,
+        partition: i32,
+        offset: i64,
+        until: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut partitions = TopicPartitionList::new();
+        partitions.add_partition(topic, partition);
+        self.consumer
+            .pause(&partitions)
+            .context("pause partition for quiet hours")?;
+        self.consumer
+            .seek(
+                topic,
+                partition,
+                Offset::Offset(offset),
+                QUIET_HOURS_SEEK_TIMEOUT,
+            )
+            .context("rewind partition for quiet hours")?;
+        let mut paused_until = self
+            .paused_until
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *paused_until = Some(paused_until.map_or(until, |current| current.max(until)));
+        Ok(())
+    }
+
+    fn paused_until(&self) -> Option<DateTime<Utc>> {
+        *self
+            .paused_until
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn resume_after_quiet_hours(&self) -> Result<()> {
+        *self
+            .paused_until
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        let assignment = self
+            .consumer
+            .assignment()
+            .context("read partition assignment")?;
+        self.consumer
+            .resume(&assignment)
+            .context("resume partitions after quiet hours")?;
+        info!("quiet hours ended; partitions resumed");
+        Ok(())
+    }
+
     async fn deliver_to_routes(
         &self,
         topic: &str,
         envelope: &WebhookEnvelope,
     ) -> Result<DeliveryOutcome> {
+        let now = Utc::now();
         let matched_routes = self
             .smash_routes
             .iter()
-            .filter(|route| route_matches(route, topic, envelope.event_type.as_str()))
+            .filter(|route| route_matches(route, topic, envelope.event_type.as_str(), now))
             .collect::<Vec<_>>();
         if matched_routes.is_empty() {
             return self
@@ -222,8 +466,12 @@ impl KafkaConsumer {
                         error = %error,
                         "required destination failed"
                     );
+                    let failure = error
+                        .chain()
+                        .find_map(|cause| cause.downcast_ref::<DeliveryFailed>())
+                        .map(|failed| failed.failure.clone());
                     self.dlq
-                        .publish_failed(envelope, &reason)
+                        .publish_failed(envelope, &reason, failure)
                         .await
                         .context("publish required-delivery failure to dlq")?;
                     return Ok(DeliveryOutcome::DoNotCommit);
@@ -268,15 +516,24 @@ impl KafkaConsumer {
         adapter_id: &str,
         envelope: &WebhookEnvelope,
     ) -> Result<()> {
-        let Some(adapter) = self.adapters.get(adapter_id) else {
-            return Err(anyhow!("no adapter configured for '{}'", adapter_id));
-        };
         let plugins = self
             .adapter_plugins
             .get(adapter_id)
             .map(Vec::as_slice)
             .unwrap_or(&[]);
+        let adapter_id = tenant_destination(
+            adapter_id,
+            envelope,
+            &self.tenant_gateways,
+            &self.openclaw_adapter_ids,
+        );
+        let Some(adapter) = self.adapters.get(adapter_id) else {
+            return Err(anyhow!("no adapter configured for '{}'", adapter_id));
+        };
         let transformed_envelope = apply_smash_plugins(adapter_id, plugins, envelope)?;
+        if let Some(faults) = &self.faults {
+            faults.upstream(adapter_id).await?;
+        }
 
         adapter.deliver(adapter_id, &transformed_envelope).await
     }
@@ -303,7 +560,7 @@ impl KafkaConsumer {
             }
             Some(NoOutputSink::Dlq) => {
                 self.dlq
-                    .publish_failed(envelope, &reason)
+                    .publish_failed(envelope, &reason, None)
                     .await
                     .context("publish no-output event to dlq")?;
                 Ok(DeliveryOutcome::Commit)
@@ -315,6 +572,34 @@ impl KafkaConsumer {
     }
 }
 
+/// A tenant's events bound for an OpenClaw gateway go to the tenant's own
+/// gateway instead, when it has one; the route's plugins still apply.
+fn tenant_destination<'a>(
+    adapter_id: &'a str,
+    envelope: &WebhookEnvelope,
+    tenant_gateways: &'a BTreeMap<String, String>,
+    openclaw_adapter_ids: &BTreeSet<String>,
+) -> &'a str {
+    if !openclaw_adapter_ids.contains(adapter_id) {
+        return adapter_id;
+    }
+    envelope
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.tenant.as_deref())
+        .and_then(|tenant| tenant_gateways.get(tenant))
+        .map_or(adapter_id, String::as_str)
+}
+
+/// DLQ reason for an envelope scored above the block threshold. The reason
+/// starts with `risk_blocked` so DLQ tooling can filter on it.
+fn risk_block_reason(threshold: Option<u64>, envelope: &WebhookEnvelope) -> Option<String> {
+    let threshold = threshold?;
+    let score = risk_score(&envelope.payload)?;
+    (score > threshold)
+        .then(|| format!("risk_blocked: risk score {score} exceeds threshold {threshold}"))
+}
+
 fn apply_smash_plugins(
     adapter_id: &str,
     plugins: &[SmashPluginConfig],
@@ -353,9 +638,18 @@ fn apply_smash_plugins(
     Ok(transformed)
 }
 
-fn route_matches(route: &SmashRouteConfig, topic: &str, event_type: &str) -> bool {
+fn route_matches(
+    route: &SmashRouteConfig,
+    topic: &str,
+    event_type: &str,
+    now: DateTime<Utc>,
+) -> bool {
     wildcard_matches(route.source_topic_pattern.as_str(), topic)
         && route_event_filter_match(route, event_type)
+        && route
+            .schedule
+            .as_ref()
+            .is_none_or(|schedule| schedule.is_active(now))
 }
 
 fn route_event_filter_match(route: &SmashRouteConfig, event_type: &str) -> bool {
@@ -450,15 +744,90 @@ fn to_json_string<T: Serialize>(value: &T) -> String {
         .unwrap_or_else(|error| format!("{{\"serialization_error\":\"{}\"}}", error))
 }
 
+/// Resolves on SIGTERM (container stop) or Ctrl-C.
+/// Re-reads adapter secrets on SIGHUP and, when set, every
+/// `RELAY_SECRETS_RELOAD_SECONDS`. A failed read keeps the adapter's
+/// current token.
+fn spawn_secret_reloader(
+    adapters: BTreeMap<String, RuntimeAdapter>,
+    reload_every: Option<Duration>,
+) -> Result<tokio::task::JoinHandle<()>> {
+    let mut hangups = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .context("install SIGHUP handler")?;
+    let mut poll = reload_every.map(|every| {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        interval
+    });
+    Ok(tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                hangup = hangups.recv() => {
+                    if hangup.is_none() {
+                        break;
+                    }
+                    info!("reloading adapter secrets");
+                }
+                _ = async {
+                    match poll.as_mut() {
+                        Some(interval) => {
+                            interval.tick().await;
+                        }
+                        None => std::future::pending().await,
+                    }
+                } => {}
+            }
+            for (adapter_id, adapter) in &adapters {
+                match adapter.reload_secrets() {
+                    Ok(true) => info!(
+                        adapter_id = adapter_id.as_str(),
+                        "adapter secrets changed; reloaded"
+                    ),
+                    Ok(false) => {}
+                    Err(error) => warn!(
+                        adapter_id = adapter_id.as_str(),
+                        error = %format!("{error:#}"),
+                        "adapter secret reload failed; keeping previous secrets"
+                    ),
+                }
+            }
+            register_loaded_secrets();
+        }
+    }))
+}
+
+async fn shutdown_signal() {
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(error) => {
+                warn!(error = %error, "failed to install SIGTERM handler");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{apply_smash_plugins, wildcard_matches};
-    use crate::smash::config::SmashPluginConfig;
-    use relay_core::model::{EventMeta, WebhookEnvelope};
+    use super::{
+        apply_smash_plugins, risk_block_reason, route_matches, tenant_destination, wildcard_matches,
+    };
+    use crate::smash::config::{SmashPluginConfig, SmashRouteConfig};
+    use chrono::DateTime;
+    use relay_core::model::{ENVELOPE_SCHEMA_VERSION, EventMeta, WebhookEnvelope};
     use serde_json::json;
+    use std::collections::{BTreeMap, BTreeSet};
 
     fn fixture_envelope() -> WebhookEnvelope {
         WebhookEnvelope {
+            schema_version: ENVELOPE_SCHEMA_VERSION,
             id: "evt-1".to_string(),
             source: "github".to_string(),
             event_type: "pull_request.opened".to_string(),
@@ -468,6 +837,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tenant_events_use_the_tenant_gateway_for_openclaw_destinations() {
+        let gateways = BTreeMap::from([("payments".to_string(), "tenant-payments".to_string())]);
+        let openclaw = BTreeSet::from(["openclaw-output".to_string()]);
+        let mut envelope = fixture_envelope();
+        assert_eq!(
+            tenant_destination("openclaw-output", &envelope, &gateways, &openclaw),
+            "openclaw-output"
+        );
+
+        envelope.meta = Some(EventMeta {
+            tenant: Some("payments".to_string()),
+            ..EventMeta::default()
+        });
+        assert_eq!(
+            tenant_destination("openclaw-output", &envelope, &gateways, &openclaw),
+            "tenant-payments"
+        );
+        assert_eq!(
+            tenant_destination("kafka-mirror", &envelope, &gateways, &openclaw),
+            "kafka-mirror"
+        );
+
+        envelope.meta = Some(EventMeta {
+            tenant: Some("search".to_string()),
+            ..EventMeta::default()
+        });
+        assert_eq!(
+            tenant_destination("openclaw-output", &envelope, &gateways, &openclaw),
+            "openclaw-output"
+        );
+    }
+
+    #[test]
+    fn risk_block_reason_only_above_threshold() {
+        let mut envelope = fixture_envelope();
+        assert_eq!(risk_block_reason(Some(50), &envelope), None);
+
+        envelope.payload["_risk_score"] = json!(50);
+        assert_eq!(risk_block_reason(Some(50), &envelope), None);
+        assert_eq!(risk_block_reason(None, &envelope), None);
+
+        envelope.payload["_risk_score"] = json!(70);
+        let reason = risk_block_reason(Some(50), &envelope).expect("blocked");
+        assert!(reason.starts_with("risk_blocked"));
+    }
+
     #[test]
     fn wildcard_matches_supports_basic_globs() {
         assert!(wildcard_matches("*", "webhooks.core"));
@@ -476,6 +892,37 @@ mod tests {
         assert!(!wildcard_matches("webhooks.github", "webhooks.core"));
     }
 
+    #[test]
+    fn scheduled_routes_split_business_hours_from_on_call() {
+        let route = |id: &str, outside: bool| -> SmashRouteConfig {
+            serde_json::from_value(json!({
+                "id": id,
+                "source_topic_pattern": "webhooks.core",
+                "schedule": {"hours": "mon-fri 09:00-17:00", "utc_offset": "-05:00", "outside": outside},
+            }))
+            .expect("route")
+        };
+        let team = route("team-channel", false);
+        let on_call = route("on-call", true);
+        let at = |raw: &str| DateTime::parse_from_rfc3339(raw).expect("now").to_utc();
+
+        // Wednesday 10:00 local.
+        let business = at("2026-01-07T15:00:00Z");
+        assert!(route_matches(&team, "webhooks.core", "push", business));
+        assert!(!route_matches(&on_call, "webhooks.core", "push", business));
+        // Saturday 10:00 local.
+        let weekend = at("2026-01-10T15:00:00Z");
+        assert!(!route_matches(&team, "webhooks.core", "push", weekend));
+        assert!(route_matches(&on_call, "webhooks.core", "push", weekend));
+
+        let invalid = serde_json::from_value::<SmashRouteConfig>(json!({
+            "id": "bad",
+            "source_topic_pattern": "webhooks.core",
+            "schedule": {"hours": "9-5"},
+        }));
+        assert!(invalid.is_err());
+    }
+
     #[test]
     fn smash_plugins_alias_event_and_add_flag() {
         let envelope = fixture_envelope();
@@ -499,6 +946,9 @@ mod tests {
                 ingress_adapter: None,
                 route_key: None,
                 flags: vec!["smash.plugin.alias".to_string()],
+                priority: None,
+                replay: None,
+                tenant: None,
             })
         );
     }
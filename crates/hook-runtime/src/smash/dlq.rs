@@ -1,10 +1,12 @@
 use super::config::Config;
+use super::fault::FaultInjector;
 use anyhow::{Context, Result, anyhow};
 use chrono::{SecondsFormat, Utc};
 use rdkafka::ClientConfig;
 use rdkafka::producer::{FutureProducer, FutureRecord};
 use rdkafka::util::Timeout;
-use relay_core::model::{DlqEnvelope, WebhookEnvelope};
+use relay_core::model::{DeliveryFailure, DlqEnvelope, ENVELOPE_SCHEMA_VERSION, WebhookEnvelope};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info};
 
@@ -12,6 +14,7 @@ use tracing::{debug, info};
 pub struct DlqProducer {
     producer: FutureProducer,
     topic: String,
+    faults: Option<Arc<FaultInjector>>,
 }
 
 impl DlqProducer {
@@ -40,18 +43,32 @@ impl DlqProducer {
         Ok(Self {
             producer,
             topic: config.dlq_topic.clone(),
+            faults: None,
         })
     }
 
+    pub fn with_faults(mut self, faults: Option<Arc<FaultInjector>>) -> Self {
+        self.faults = faults;
+        self
+    }
+
     pub async fn publish_failed(
         &self,
         envelope: &WebhookEnvelope,
         error_message: &str,
+        failure: Option<DeliveryFailure>,
     ) -> Result<()> {
+        if let Some(faults) = &self.faults {
+            faults.dlq_write()?;
+        }
+
         let dlq_payload = DlqEnvelope {
+            schema_version: ENVELOPE_SCHEMA_VERSION,
             failed_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
             error: error_message.to_string(),
             envelope: envelope.clone(),
+            failure,
+            tenant: envelope.meta.as_ref().and_then(|meta| meta.tenant.clone()),
         };
 
         let payload = serde_json::to_string(&dlq_payload).context("serialize dlq envelope")?;
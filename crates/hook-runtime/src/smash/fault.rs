@@ -0,0 +1,132 @@
+use super::config::FaultInjectionConfig;
+use anyhow::{Result, anyhow};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::warn;
+
+const SPLITMIX_INCREMENT: u64 = 0x9E37_79B9_7F4A_7C15;
+
+pub struct FaultInjector {
+    config: FaultInjectionConfig,
+    state: Mutex<u64>,
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultInjectionConfig) -> Self {
+        let state = Mutex::new(config.seed);
+        Self { config, state }
+    }
+
+    pub async fn upstream(&self, adapter_id: &str) -> Result<()> {
+        if self.roll(self.config.upstream_error_rate) {
+            warn!(adapter_id, "fault injection: simulated upstream 503");
+            return Err(anyhow!(
+                "fault injection: adapter '{adapter_id}' upstream returned 503"
+            ));
+        }
+        if self.roll(self.config.upstream_timeout_rate) {
+            warn!(adapter_id, "fault injection: simulated upstream timeout");
+            tokio::time::sleep(Duration::from_millis(self.config.upstream_timeout_ms)).await;
+            return Err(anyhow!(
+                "fault injection: adapter '{adapter_id}' upstream timed out"
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn dlq_write(&self) -> Result<()> {
+        if self.roll(self.config.dlq_error_rate) {
+            warn!("fault injection: simulated dlq write failure");
+            return Err(anyhow!("fault injection: dlq write failed"));
+        }
+        Ok(())
+    }
+
+    pub fn wakeup_delay(&self) -> Option<Duration> {
+        if self.config.wakeup_delay_max_ms == 0 {
+            return None;
+        }
+        let delay_ms = self.next_u64() % (self.config.wakeup_delay_max_ms + 1);
+        Some(Duration::from_millis(delay_ms))
+    }
+
+    fn roll(&self, rate: f64) -> bool {
+        if rate <= 0.0 {
+            return false;
+        }
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        unit < rate
+    }
+
+    fn next_u64(&self) -> u64 {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *state = state.wrapping_add(SPLITMIX_INCREMENT);
+        let mut value = *state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FaultInjector;
+    use crate::smash::config::FaultInjectionConfig;
+
+    #[test]
+    fn same_seed_produces_same_fault_sequence() {
+        let config = FaultInjectionConfig {
+            seed: 42,
+            dlq_error_rate: 0.5,
+            ..FaultInjectionConfig::default()
+        };
+        let first = FaultInjector::new(config.clone());
+        let second = FaultInjector::new(config);
+
+        let first_run = (0..64)
+            .map(|_| first.dlq_write().is_err())
+            .collect::<Vec<_>>();
+        let second_run = (0..64)
+            .map(|_| second.dlq_write().is_err())
+            .collect::<Vec<_>>();
+        assert_eq!(first_run, second_run);
+        assert!(first_run.iter().any(|failed| *failed));
+        assert!(first_run.iter().any(|failed| !*failed));
+    }
+
+    #[tokio::test]
+    async fn zero_and_full_rates_are_absolute() {
+        let never = FaultInjector::new(FaultInjectionConfig::default());
+        for _ in 0..32 {
+            assert!(never.upstream("openclaw-output").await.is_ok());
+            assert!(never.dlq_write().is_ok());
+        }
+        assert_eq!(never.wakeup_delay(), None);
+
+        let always = FaultInjector::new(FaultInjectionConfig {
+            upstream_error_rate: 1.0,
+            ..FaultInjectionConfig::default()
+        });
+        let error = always
+            .upstream("openclaw-output")
+            .await
+            .expect_err("full rate must fail");
+        assert!(error.to_string().contains("returned 503"));
+    }
+
+    #[test]
+    fn wakeup_delay_stays_within_bound() {
+        let faults = FaultInjector::new(FaultInjectionConfig {
+            seed: 7,
+            wakeup_delay_max_ms: 25,
+            ..FaultInjectionConfig::default()
+        });
+        for _ in 0..64 {
+            let delay = faults.wakeup_delay().expect("delay configured");
+            assert!(delay.as_millis() <= 25);
+        }
+    }
+}
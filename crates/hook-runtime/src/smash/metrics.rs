@@ -0,0 +1,106 @@
+use super::slo::SloTracker;
+use crate::adapters::HttpClientMetrics;
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::routing::get;
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+/// Serves `GET /metrics` for smash, which otherwise has no HTTP listener.
+pub async fn serve_metrics(
+    bind: &str,
+    slo: Option<Arc<SloTracker>>,
+    http_client: Arc<HttpClientMetrics>,
+    envelope_versions: Arc<EnvelopeVersionMetrics>,
+) -> Result<()> {
+    let app = Router::new().route(
+        "/metrics",
+        get(move || {
+            let slo = slo.clone();
+            let http_client = http_client.clone();
+            let envelope_versions = envelope_versions.clone();
+            async move {
+                let mut output = String::new();
+                if let Some(slo) = slo {
+                    slo.render(&mut output, Utc::now().timestamp());
+                }
+                http_client.render(&mut output);
+                envelope_versions.render(&mut output);
+                output
+            }
+        }),
+    );
+    let listener = TcpListener::bind(bind)
+        .await
+        .with_context(|| format!("bind smash metrics {bind}"))?;
+    info!(bind, "smash metrics listening");
+    tokio::spawn(async move {
+        if let Err(error) = axum::serve(listener, app).await {
+            error!(error = %error, "smash metrics server stopped");
+        }
+    });
+    Ok(())
+}
+
+/// Envelopes consumed with a schema version outside the supported window.
+#[derive(Debug, Default)]
+pub struct EnvelopeVersionMetrics {
+    unsupported: Mutex<BTreeMap<u32, u64>>,
+}
+
+impl EnvelopeVersionMetrics {
+    pub fn record_unsupported(&self, version: u32) {
+        if let Ok(mut unsupported) = self.unsupported.lock() {
+            *unsupported.entry(version).or_default() += 1;
+        }
+    }
+
+    fn render(&self, output: &mut String) {
+        let Ok(unsupported) = self.unsupported.lock() else {
+            return;
+        };
+        write_header(
+            output,
+            "webhook_relay_envelope_unsupported_version_total",
+            "counter",
+            "Envelopes consumed with a schema version smash does not support, by version.",
+        );
+        for (version, count) in unsupported.iter() {
+            let _ = writeln!(
+                output,
+                "webhook_relay_envelope_unsupported_version_total{{version=\"{version}\"}} {count}"
+            );
+        }
+    }
+}
+
+pub(crate) fn write_header(output: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {name} {help}");
+    let _ = writeln!(output, "# TYPE {name} {kind}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_unsupported_envelope_versions_by_version() {
+        let metrics = EnvelopeVersionMetrics::default();
+        metrics.record_unsupported(3);
+        metrics.record_unsupported(3);
+        metrics.record_unsupported(0);
+
+        let mut output = String::new();
+        metrics.render(&mut output);
+        assert!(
+            output.contains("webhook_relay_envelope_unsupported_version_total{version=\"0\"} 1\n")
+        );
+        assert!(
+            output.contains("webhook_relay_envelope_unsupported_version_total{version=\"3\"} 2\n")
+        );
+    }
+}
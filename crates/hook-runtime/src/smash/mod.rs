@@ -1,19 +1,58 @@
 pub(crate) mod config;
 mod consumer;
 mod dlq;
+mod fault;
+pub(crate) mod metrics;
+mod schedule;
+mod slo;
 
 pub use config::Config;
 
+use crate::adapters::HttpClientMetrics;
 use anyhow::{Context, Result};
 use consumer::KafkaConsumer;
 use dlq::DlqProducer;
+use fault::FaultInjector;
+use metrics::EnvelopeVersionMetrics;
+use relay_core::redact::register_loaded_secrets;
+use slo::SloTracker;
+use std::sync::Arc;
+use tracing::warn;
 
 pub async fn run_from_env() -> Result<()> {
+    #[cfg(feature = "vault")]
+    let _vault = relay_core::vault::bootstrap_from_env()
+        .await
+        .context("load secrets from vault")?;
     let config = Config::from_env().context("load smash config")?;
-    let dlq = DlqProducer::from_config(&config).context("initialize dlq producer")?;
-    let consumer = KafkaConsumer::from_config(&config, dlq)
+    let faults = config.fault_injection.clone().map(|faults| {
+        warn!(
+            seed = faults.seed,
+            "fault injection enabled; do not run in production"
+        );
+        Arc::new(FaultInjector::new(faults))
+    });
+    let dlq = DlqProducer::from_config(&config)
+        .context("initialize dlq producer")?
+        .with_faults(faults.clone());
+    let slo = config.slo.clone().map(|slo| Arc::new(SloTracker::new(slo)));
+    let http_metrics = Arc::new(HttpClientMetrics::default());
+    let envelope_versions = Arc::new(EnvelopeVersionMetrics::default());
+    if let Some(bind) = &config.metrics_bind {
+        metrics::serve_metrics(
+            bind,
+            slo.clone(),
+            http_metrics.clone(),
+            envelope_versions.clone(),
+        )
         .await
-        .context("initialize smash consumer")?;
+        .context("start smash metrics")?;
+    }
+    let consumer =
+        KafkaConsumer::from_config(&config, dlq, faults, slo, http_metrics, envelope_versions)
+            .await
+            .context("initialize smash consumer")?;
+    register_loaded_secrets();
 
     consumer.run().await
 }
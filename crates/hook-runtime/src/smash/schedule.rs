@@ -0,0 +1,177 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+
+const DAYS: [(&str, Weekday); 7] = [
+    ("mon", Weekday::Mon),
+    ("tue", Weekday::Tue),
+    ("wed", Weekday::Wed),
+    ("thu", Weekday::Thu),
+    ("fri", Weekday::Fri),
+    ("sat", Weekday::Sat),
+    ("sun", Weekday::Sun),
+];
+
+/// Recurring weekly windows in a fixed UTC offset. A window that ends before
+/// it starts runs past midnight and belongs to its starting day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeeklySchedule {
+    pub windows: Vec<ScheduleWindow>,
+    pub offset: FixedOffset,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleWindow {
+    /// Indexed by `Weekday::num_days_from_monday`.
+    pub days: [bool; 7],
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl WeeklySchedule {
+    /// `raw` is a comma-separated list of `[days ]HH:MM-HH:MM`, where days is
+    /// `mon-fri`, `sat` or similar; without days the window applies daily.
+    pub fn parse(raw: &str, offset: &str) -> Result<Self> {
+        let windows = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|window| !window.is_empty())
+            .map(parse_window)
+            .collect::<Result<Vec<_>>>()?;
+        if windows.is_empty() {
+            return Err(anyhow!("schedule must contain at least one window"));
+        }
+        Ok(Self {
+            windows,
+            offset: parse_offset(offset)?,
+        })
+    }
+
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.window_end(now).is_some()
+    }
+
+    /// End of the window containing `now`, if any.
+    pub fn window_end(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = now.with_timezone(&self.offset);
+        let today = local.date_naive();
+        self.windows
+            .iter()
+            .flat_map(|window| {
+                [today.pred_opt(), Some(today)]
+                    .into_iter()
+                    .flatten()
+                    .filter(|day| window.days[day.weekday().num_days_from_monday() as usize])
+                    .filter_map(|day| {
+                        let start = day.and_time(window.start);
+                        let end_day = if window.end <= window.start {
+                            day.succ_opt()?
+                        } else {
+                            day
+                        };
+                        let end = end_day.and_time(window.end);
+                        let local = local.naive_local();
+                        (start <= local && local < end).then_some(end)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .max()
+            .and_then(|end| self.offset.from_local_datetime(&end).single())
+            .map(|end| end.with_timezone(&Utc))
+    }
+}
+
+fn parse_window(raw: &str) -> Result<ScheduleWindow> {
+    let (days, times) = match raw.split_once(' ') {
+        Some((days, times)) => (parse_days(days.trim())?, times.trim()),
+        None => ([true; 7], raw),
+    };
+    let (start, end) = times
+        .split_once('-')
+        .ok_or_else(|| anyhow!("schedule window '{raw}' must use HH:MM-HH:MM"))?;
+    let parse_time = |value: &str| {
+        NaiveTime::parse_from_str(value.trim(), "%H:%M")
+            .with_context(|| format!("invalid time '{value}' in schedule window '{raw}'"))
+    };
+    let (start, end) = (parse_time(start)?, parse_time(end)?);
+    if start == end {
+        return Err(anyhow!("schedule window '{raw}' is empty"));
+    }
+    Ok(ScheduleWindow { days, start, end })
+}
+
+fn parse_days(raw: &str) -> Result<[bool; 7]> {
+    let index = |name: &str| {
+        DAYS.iter()
+            .position(|(day, _)| day.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| anyhow!("unknown day '{name}' in schedule"))
+    };
+    let mut days = [false; 7];
+    match raw.split_once('-') {
+        Some((from, to)) => {
+            let (from, to) = (index(from)?, index(to)?);
+            let mut day = from;
+            loop {
+                days[day] = true;
+                if day == to {
+                    break;
+                }
+                day = (day + 1) % 7;
+            }
+        }
+        None => days[index(raw)?] = true,
+    }
+    Ok(days)
+}
+
+fn parse_offset(raw: &str) -> Result<FixedOffset> {
+    let raw = raw.trim();
+    let (sign, rest) = match raw.as_bytes().first() {
+        Some(b'+') => (1, &raw[1..]),
+        Some(b'-') => (-1, &raw[1..]),
+        _ => return Err(anyhow!("utc offset must look like +05:30")),
+    };
+    let time =
+        NaiveTime::parse_from_str(rest, "%H:%M").context("utc offset must look like +05:30")?;
+    FixedOffset::east_opt(sign * time.num_seconds_from_midnight() as i32)
+        .ok_or_else(|| anyhow!("utc offset is out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(raw: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(raw)
+            .expect("timestamp")
+            .to_utc()
+    }
+
+    #[test]
+    fn overnight_window_ends_next_morning_in_local_time() {
+        let schedule = WeeklySchedule::parse("mon-fri 22:00-07:00", "+02:00").expect("parse");
+
+        // Tuesday 01:30 local is inside Monday's window.
+        assert_eq!(
+            schedule.window_end(at("2026-01-05T23:30:00Z")),
+            Some(at("2026-01-06T05:00:00Z"))
+        );
+        // Saturday 01:30 local still belongs to Friday's window.
+        assert_eq!(
+            schedule.window_end(at("2026-01-09T23:30:00Z")),
+            Some(at("2026-01-10T05:00:00Z"))
+        );
+        // Sunday 01:30 local: Saturday has no window.
+        assert_eq!(schedule.window_end(at("2026-01-10T23:30:00Z")), None);
+        // Tuesday 12:00 local.
+        assert_eq!(schedule.window_end(at("2026-01-06T10:00:00Z")), None);
+    }
+
+    #[test]
+    fn rejects_malformed_windows() {
+        assert!(WeeklySchedule::parse("22:00", "+00:00").is_err());
+        assert!(WeeklySchedule::parse("funday 22:00-07:00", "+00:00").is_err());
+        assert!(WeeklySchedule::parse("22:00-22:00", "+00:00").is_err());
+        assert!(WeeklySchedule::parse("22:00-07:00", "UTC").is_err());
+        assert!(WeeklySchedule::parse("sat-sun 00:00-23:59", "-05:00").is_ok());
+    }
+}
@@ -0,0 +1,208 @@
+use super::config::SloConfig;
+use super::metrics::write_header;
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+const BUCKET_SECONDS: i64 = 60;
+/// Burn-rate windows from the multiwindow alerting pattern: a fast window to
+/// page on and a slow one to confirm.
+const WINDOWS: &[(&str, i64)] = &[("5m", 5 * 60), ("1h", 60 * 60)];
+const RETAINED_BUCKETS: usize = 60;
+
+/// Counts deliveries against a latency objective measured from when serve
+/// received the event. Failures that end in the DLQ always count as bad.
+pub struct SloTracker {
+    config: SloConfig,
+    state: Mutex<SloState>,
+}
+
+#[derive(Debug, Default)]
+struct SloState {
+    good_total: u64,
+    bad_total: u64,
+    /// (bucket start epoch seconds, good, bad), oldest first.
+    buckets: VecDeque<(i64, u64, u64)>,
+}
+
+impl SloTracker {
+    pub fn new(config: SloConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(SloState::default()),
+        }
+    }
+
+    pub fn record_delivered(&self, received_at: &str, now: DateTime<Utc>) {
+        let Ok(received_at) = DateTime::parse_from_rfc3339(received_at) else {
+            return;
+        };
+        let latency = now.signed_duration_since(received_at);
+        let good = latency.num_milliseconds() as f64 / 1_000.0 <= self.config.latency_seconds;
+        self.record(good, now.timestamp());
+    }
+
+    pub fn record_failed(&self, now: DateTime<Utc>) {
+        self.record(false, now.timestamp());
+    }
+
+    fn record(&self, good: bool, now_epoch_seconds: i64) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let bucket_start = now_epoch_seconds - now_epoch_seconds.rem_euclid(BUCKET_SECONDS);
+        if state.buckets.back().map(|bucket| bucket.0) != Some(bucket_start) {
+            state.buckets.push_back((bucket_start, 0, 0));
+            while state.buckets.len() > RETAINED_BUCKETS {
+                state.buckets.pop_front();
+            }
+        }
+        let Some(bucket) = state.buckets.back_mut() else {
+            return;
+        };
+        if good {
+            bucket.1 += 1;
+            state.good_total += 1;
+        } else {
+            bucket.2 += 1;
+            state.bad_total += 1;
+        }
+    }
+
+    /// Share of good deliveries in the trailing window; 1.0 when idle.
+    fn compliance(state: &SloState, window_seconds: i64, now_epoch_seconds: i64) -> f64 {
+        let since = now_epoch_seconds - window_seconds;
+        let (good, bad) = state
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.0 + BUCKET_SECONDS > since)
+            .fold((0u64, 0u64), |(good, bad), bucket| {
+                (good + bucket.1, bad + bucket.2)
+            });
+        if good + bad == 0 {
+            return 1.0;
+        }
+        good as f64 / (good + bad) as f64
+    }
+
+    pub fn render(&self, output: &mut String, now_epoch_seconds: i64) {
+        let Ok(state) = self.state.lock() else {
+            return;
+        };
+        let target = self.config.target;
+
+        write_header(
+            output,
+            "webhook_relay_delivery_slo_target",
+            "gauge",
+            "Delivery SLO target ratio and latency objective.",
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_delivery_slo_target{{latency_seconds=\"{}\"}} {target}",
+            self.config.latency_seconds
+        );
+
+        write_header(
+            output,
+            "webhook_relay_delivery_slo_events_total",
+            "counter",
+            "Deliveries counted against the SLO, by result.",
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_delivery_slo_events_total{{result=\"good\"}} {}",
+            state.good_total
+        );
+        let _ = writeln!(
+            output,
+            "webhook_relay_delivery_slo_events_total{{result=\"bad\"}} {}",
+            state.bad_total
+        );
+
+        write_header(
+            output,
+            "webhook_relay_delivery_slo_compliance_ratio",
+            "gauge",
+            "Share of deliveries within the latency objective over the window.",
+        );
+        let windows = WINDOWS
+            .iter()
+            .map(|(name, seconds)| (*name, Self::compliance(&state, *seconds, now_epoch_seconds)))
+            .collect::<Vec<_>>();
+        for (name, compliance) in &windows {
+            let _ = writeln!(
+                output,
+                "webhook_relay_delivery_slo_compliance_ratio{{window=\"{name}\"}} {compliance}"
+            );
+        }
+
+        write_header(
+            output,
+            "webhook_relay_delivery_slo_burn_rate",
+            "gauge",
+            "Error-budget burn rate over the window; 1 spends the budget exactly on schedule.",
+        );
+        for (name, compliance) in &windows {
+            let burn_rate = (1.0 - compliance) / (1.0 - target);
+            let _ = writeln!(
+                output,
+                "webhook_relay_delivery_slo_burn_rate{{window=\"{name}\"}} {burn_rate}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> SloTracker {
+        SloTracker::new(SloConfig {
+            target: 0.99,
+            latency_seconds: 60.0,
+        })
+    }
+
+    #[test]
+    fn slow_and_failed_deliveries_burn_budget() {
+        let tracker = tracker();
+        let now = DateTime::parse_from_rfc3339("2026-01-01T01:00:00Z")
+            .expect("now")
+            .to_utc();
+        for _ in 0..8 {
+            tracker.record_delivered("2026-01-01T00:59:30Z", now);
+        }
+        tracker.record_delivered("2026-01-01T00:58:00Z", now);
+        tracker.record_failed(now);
+
+        let mut output = String::new();
+        tracker.render(&mut output, now.timestamp());
+        assert!(output.contains("webhook_relay_delivery_slo_events_total{result=\"good\"} 8"));
+        assert!(output.contains("webhook_relay_delivery_slo_events_total{result=\"bad\"} 2"));
+        assert!(output.contains("webhook_relay_delivery_slo_compliance_ratio{window=\"5m\"} 0.8"));
+        let burn_rate = output
+            .lines()
+            .find_map(|line| {
+                line.strip_prefix("webhook_relay_delivery_slo_burn_rate{window=\"1h\"} ")
+            })
+            .and_then(|value| value.parse::<f64>().ok())
+            .expect("burn rate");
+        assert!((burn_rate - 20.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn old_buckets_fall_out_of_short_window() {
+        let tracker = tracker();
+        let earlier = DateTime::parse_from_rfc3339("2026-01-01T00:30:00Z")
+            .expect("earlier")
+            .to_utc();
+        tracker.record_failed(earlier);
+
+        let now = earlier.timestamp() + 30 * 60;
+        let state = tracker.state.lock().expect("lock");
+        assert_eq!(SloTracker::compliance(&state, 5 * 60, now), 1.0);
+        assert_eq!(SloTracker::compliance(&state, 60 * 60, now), 0.0);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{env_bool, optional_env, parse_csv, required_env, secret_env};
+use anyhow::{Result, anyhow};
+
+/// Broker connection settings every Kafka client reads: `KAFKA_BROKERS`,
+/// `KAFKA_SECURITY_PROTOCOL`, `KAFKA_ALLOW_PLAINTEXT`, `KAFKA_TLS_*` and
+/// `KAFKA_SASL_*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaClientConfig {
+    /// `KAFKA_BROKERS` as given, for `bootstrap.servers`.
+    pub brokers: String,
+    /// Lowercased; the default differs between binaries.
+    pub security_protocol: String,
+    pub allow_plaintext: bool,
+    pub tls_cert: Option<String>,
+    pub tls_key: Option<String>,
+    pub tls_ca: Option<String>,
+    pub sasl_mechanism: Option<String>,
+    pub sasl_username: Option<String>,
+    pub sasl_password: Option<String>,
+}
+
+impl KafkaClientConfig {
+    pub fn from_env(default_security_protocol: &str) -> Result<Self> {
+        let brokers = required_env("KAFKA_BROKERS")?.trim().to_string();
+        if parse_csv(&brokers).is_empty() {
+            return Err(anyhow!("KAFKA_BROKERS cannot be empty"));
+        }
+        Ok(Self {
+            brokers,
+            security_protocol: optional_env("KAFKA_SECURITY_PROTOCOL")
+                .unwrap_or_else(|| default_security_protocol.to_string())
+                .to_ascii_lowercase(),
+            allow_plaintext: env_bool("KAFKA_ALLOW_PLAINTEXT", false),
+            tls_cert: optional_env("KAFKA_TLS_CERT"),
+            tls_key: optional_env("KAFKA_TLS_KEY"),
+            tls_ca: optional_env("KAFKA_TLS_CA"),
+            sasl_mechanism: optional_env("KAFKA_SASL_MECHANISM"),
+            sasl_username: optional_env("KAFKA_SASL_USERNAME"),
+            sasl_password: secret_env("KAFKA_SASL_PASSWORD")?,
+        })
+    }
+
+    pub fn broker_list(&self) -> Vec<String> {
+        parse_csv(&self.brokers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KafkaClientConfig;
+    use crate::tests::with_vars;
+
+    #[test]
+    fn reads_connection_settings_with_the_callers_default_protocol() {
+        with_vars(
+            &[
+                ("KAFKA_BROKERS", Some(" a:9092, b:9092 ")),
+                ("KAFKA_SECURITY_PROTOCOL", None),
+                ("KAFKA_TLS_CERT", Some("/tls/cert.pem")),
+                ("KAFKA_TLS_KEY", None),
+                ("KAFKA_SASL_MECHANISM", Some("  ")),
+            ],
+            || {
+                let config = KafkaClientConfig::from_env("PLAINTEXT").expect("kafka config");
+                assert_eq!(config.brokers, "a:9092, b:9092");
+                assert_eq!(config.broker_list(), vec!["a:9092", "b:9092"]);
+                assert_eq!(config.security_protocol, "plaintext");
+                assert_eq!(config.tls_cert.as_deref(), Some("/tls/cert.pem"));
+                assert_eq!(config.tls_key, None);
+                assert_eq!(config.sasl_mechanism, None);
+            },
+        );
+        with_vars(
+            &[
+                ("KAFKA_BROKERS", Some("a:9092")),
+                ("KAFKA_SECURITY_PROTOCOL", Some("SSL")),
+            ],
+            || {
+                let config = KafkaClientConfig::from_env("plaintext").expect("kafka config");
+                assert_eq!(config.security_protocol, "ssl");
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_missing_or_blank_brokers() {
+        with_vars(&[("KAFKA_BROKERS", None)], || {
+            assert!(KafkaClientConfig::from_env("ssl").is_err());
+        });
+        with_vars(&[("KAFKA_BROKERS", Some(" , "))], || {
+            let error = KafkaClientConfig::from_env("ssl").expect_err("blank brokers");
+            assert_eq!(error.to_string(), "KAFKA_BROKERS cannot be empty");
+        });
+    }
+}
@@ -0,0 +1,326 @@
+use anyhow::{Context, Result, anyhow};
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+mod kafka;
+mod openclaw;
+
+pub use kafka::KafkaClientConfig;
+pub use openclaw::OpenclawGatewayConfig;
+
+/// Secrets fetched from an external store, keyed by env var name.
+static SECRET_OVERRIDES: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// Every value `secret_env` has returned, for log redaction.
+static LOADED_SECRETS: RwLock<BTreeSet<String>> = RwLock::new(BTreeSet::new());
+
+/// Replaces the secrets `secret_env` prefers over `<name>_FILE` and `<name>`.
+pub fn set_secret_overrides(secrets: BTreeMap<String, String>) {
+    *SECRET_OVERRIDES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = secrets;
+}
+
+pub fn required_env(name: &str) -> Result<String> {
+    let value = env::var(name).with_context(|| format!("missing required env var: {name}"))?;
+    if value.trim().is_empty() {
+        return Err(anyhow!("required env var {name} cannot be empty"));
+    }
+    Ok(value)
+}
+
+pub fn optional_env(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+pub fn conditional_env(name: &str, required: bool) -> Result<Option<String>> {
+    if required {
+        return required_env(name).map(Some);
+    }
+
+    Ok(optional_env(name))
+}
+
+/// Secrets loaded so far, including ones since rotated out.
+pub fn loaded_secrets() -> Vec<String> {
+    LOADED_SECRETS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Reads a secret from the overrides, then `<name>_FILE` when set, otherwise
+/// from `<name>`.
+pub fn secret_env(name: &str) -> Result<Option<String>> {
+    let value = read_secret(name)?;
+    if let Some(value) = &value {
+        LOADED_SECRETS
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(value.clone());
+    }
+    Ok(value)
+}
+
+fn read_secret(name: &str) -> Result<Option<String>> {
+    if let Some(value) = SECRET_OVERRIDES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(name)
+        .filter(|value| !value.trim().is_empty())
+    {
+        return Ok(Some(value.trim().to_string()));
+    }
+    let file_var = format!("{name}_FILE");
+    let Some(path) = optional_env(&file_var) else {
+        return Ok(optional_env(name));
+    };
+    let value =
+        std::fs::read_to_string(&path).with_context(|| format!("read {file_var} '{path}'"))?;
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(anyhow!("{file_var} '{path}' is empty"));
+    }
+    Ok(Some(value.to_string()))
+}
+
+pub fn conditional_secret_env(name: &str, required: bool) -> Result<Option<String>> {
+    if required {
+        return required_secret_env(name).map(Some);
+    }
+    secret_env(name)
+}
+
+pub fn required_secret_env(name: &str) -> Result<String> {
+    secret_env(name)?.ok_or_else(|| anyhow!("missing required env var: {name} (or {name}_FILE)"))
+}
+
+pub fn parse_csv(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+pub fn env_u32(name: &str, default: u32) -> Result<u32> {
+    env_parse(name, default, "u32")
+}
+
+pub fn env_u64(name: &str, default: u64) -> Result<u64> {
+    env_parse(name, default, "u64")
+}
+
+pub fn env_i32(name: &str, default: i32) -> Result<i32> {
+    env_parse(name, default, "i32")
+}
+
+pub fn env_i64(name: &str, default: i64) -> Result<i64> {
+    env_parse(name, default, "i64")
+}
+
+pub fn env_usize(name: &str, default: usize) -> Result<usize> {
+    env_parse(name, default, "usize")
+}
+
+pub fn env_f64(name: &str, default: f64) -> Result<f64> {
+    env_parse(name, default, "f64")
+}
+
+pub fn env_bool(name: &str, default: bool) -> bool {
+    match env::var(name) {
+        Ok(value) => matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        ),
+        Err(_) => default,
+    }
+}
+
+fn env_parse<T>(name: &str, default: T, type_name: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| {
+            value
+                .trim()
+                .parse::<T>()
+                .with_context(|| format!("invalid {type_name} for {name}"))
+        })
+        .transpose()
+        .map(|value| value.unwrap_or(default))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{LazyLock, Mutex};
+
+    static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+    fn with_var(name: &str, value: Option<&str>, test_fn: impl FnOnce()) {
+        with_vars(&[(name, value)], test_fn);
+    }
+
+    pub(crate) fn with_vars(vars: &[(&str, Option<&str>)], test_fn: impl FnOnce()) {
+        let _lock = ENV_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Safety: tests serialize all env access through ENV_LOCK.
+        unsafe {
+            for (name, value) in vars {
+                match value {
+                    Some(value) => env::set_var(name, value),
+                    None => env::remove_var(name),
+                }
+            }
+        }
+        test_fn();
+        // Safety: tests serialize all env access through ENV_LOCK.
+        unsafe {
+            for (name, _) in vars {
+                env::remove_var(name);
+            }
+        }
+    }
+
+    #[test]
+    fn required_env_rejects_missing_and_blank_values() {
+        with_var("RELAY_CONFIG_TEST_REQUIRED", None, || {
+            let error = required_env("RELAY_CONFIG_TEST_REQUIRED").expect_err("missing var");
+            assert!(
+                error
+                    .to_string()
+                    .contains("missing required env var: RELAY_CONFIG_TEST_REQUIRED")
+            );
+        });
+        with_var("RELAY_CONFIG_TEST_REQUIRED", Some("  "), || {
+            assert!(required_env("RELAY_CONFIG_TEST_REQUIRED").is_err());
+        });
+    }
+
+    #[test]
+    fn conditional_env_only_requires_when_asked() {
+        with_var("RELAY_CONFIG_TEST_CONDITIONAL", Some(" value "), || {
+            assert_eq!(
+                conditional_env("RELAY_CONFIG_TEST_CONDITIONAL", false)
+                    .expect("optional value")
+                    .as_deref(),
+                Some("value")
+            );
+        });
+        with_var("RELAY_CONFIG_TEST_CONDITIONAL", None, || {
+            assert_eq!(
+                conditional_env("RELAY_CONFIG_TEST_CONDITIONAL", false).expect("unset"),
+                None
+            );
+            assert!(conditional_env("RELAY_CONFIG_TEST_CONDITIONAL", true).is_err());
+        });
+    }
+
+    #[test]
+    fn numeric_helpers_use_default_for_blank_and_reject_garbage() {
+        with_var("RELAY_CONFIG_TEST_NUMBER", Some(""), || {
+            assert_eq!(env_u64("RELAY_CONFIG_TEST_NUMBER", 7).expect("default"), 7);
+        });
+        with_var("RELAY_CONFIG_TEST_NUMBER", Some(" -3 "), || {
+            assert_eq!(env_i64("RELAY_CONFIG_TEST_NUMBER", 0).expect("parsed"), -3);
+            let error = env_u32("RELAY_CONFIG_TEST_NUMBER", 0).expect_err("negative u32");
+            assert!(
+                error
+                    .to_string()
+                    .contains("invalid u32 for RELAY_CONFIG_TEST_NUMBER")
+            );
+        });
+    }
+
+    #[test]
+    fn bool_helper_accepts_truthy_spellings() {
+        for value in ["1", "true", "YES", " on "] {
+            with_var("RELAY_CONFIG_TEST_BOOL", Some(value), || {
+                assert!(env_bool("RELAY_CONFIG_TEST_BOOL", false));
+            });
+        }
+        with_var("RELAY_CONFIG_TEST_BOOL", Some("nope"), || {
+            assert!(!env_bool("RELAY_CONFIG_TEST_BOOL", true));
+        });
+        with_var("RELAY_CONFIG_TEST_BOOL", None, || {
+            assert!(env_bool("RELAY_CONFIG_TEST_BOOL", true));
+        });
+    }
+
+    #[test]
+    fn secret_env_prefers_file_and_rejects_empty_files() {
+        let dir = std::env::temp_dir().join(format!("relay-config-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let secret_path = dir.join("secret");
+        let empty_path = dir.join("empty");
+        std::fs::write(&secret_path, "from-file\n").expect("write secret");
+        std::fs::write(&empty_path, "  \n").expect("write empty");
+
+        with_var("RELAY_CONFIG_TEST_SECRET", Some("from-env"), || {
+            assert_eq!(
+                secret_env("RELAY_CONFIG_TEST_SECRET").expect("env value"),
+                Some("from-env".to_string())
+            );
+            with_file_var(&secret_path, || {
+                assert_eq!(
+                    secret_env("RELAY_CONFIG_TEST_SECRET").expect("file value"),
+                    Some("from-file".to_string())
+                );
+                assert_eq!(
+                    required_secret_env("RELAY_CONFIG_TEST_SECRET").expect("file value"),
+                    "from-file"
+                );
+                assert!(loaded_secrets().contains(&"from-file".to_string()));
+            });
+            with_file_var(&empty_path, || {
+                assert!(secret_env("RELAY_CONFIG_TEST_SECRET").is_err());
+            });
+            set_secret_overrides(BTreeMap::from([(
+                "RELAY_CONFIG_TEST_SECRET".to_string(),
+                "from-store".to_string(),
+            )]));
+            with_file_var(&secret_path, || {
+                assert_eq!(
+                    secret_env("RELAY_CONFIG_TEST_SECRET").expect("override value"),
+                    Some("from-store".to_string())
+                );
+            });
+            set_secret_overrides(BTreeMap::new());
+        });
+        with_var("RELAY_CONFIG_TEST_SECRET", None, || {
+            assert!(conditional_secret_env("RELAY_CONFIG_TEST_SECRET", true).is_err());
+            assert!(required_secret_env("RELAY_CONFIG_TEST_SECRET").is_err());
+        });
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    fn with_file_var(path: &std::path::Path, test_fn: impl FnOnce()) {
+        // Safety: only called while holding ENV_LOCK via with_var.
+        unsafe {
+            env::set_var("RELAY_CONFIG_TEST_SECRET_FILE", path);
+        }
+        test_fn();
+        // Safety: only called while holding ENV_LOCK via with_var.
+        unsafe {
+            env::remove_var("RELAY_CONFIG_TEST_SECRET_FILE");
+        }
+    }
+
+    #[test]
+    fn parse_csv_drops_blank_entries() {
+        assert_eq!(parse_csv(" a, ,b ,,"), vec!["a", "b"]);
+    }
+}
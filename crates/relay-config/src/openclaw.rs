@@ -0,0 +1,99 @@
+use crate::{env_u32, env_u64, env_usize, optional_env, secret_env};
+use anyhow::{Result, anyhow};
+
+/// The OpenClaw gateway settings smash reads: `OPENCLAW_WEBHOOK_URL`, its
+/// token and signing secret, `OPENCLAW_HTTP_TIMEOUT_SECONDS`,
+/// `OPENCLAW_MESSAGE_MAX_BYTES` and the `CONSUMER_MAX_RETRIES` delivery
+/// budget. Tenant gateways share the timeout and retry settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenclawGatewayConfig {
+    pub url: Option<String>,
+    /// `OPENCLAW_WEBHOOK_SIGNING_SECRET`, named only when it is set.
+    pub signing_secret_env: Option<String>,
+    pub timeout_seconds: u64,
+    pub max_retries: u32,
+    pub message_max_bytes: usize,
+}
+
+impl OpenclawGatewayConfig {
+    /// Read when a delivery is made, so it can be rotated without a restart.
+    pub const TOKEN_ENV: &'static str = "OPENCLAW_WEBHOOK_TOKEN";
+    const SIGNING_SECRET_ENV: &'static str = "OPENCLAW_WEBHOOK_SIGNING_SECRET";
+
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            url: optional_env("OPENCLAW_WEBHOOK_URL"),
+            signing_secret_env: secret_env(Self::SIGNING_SECRET_ENV)?
+                .map(|_| Self::SIGNING_SECRET_ENV.to_string()),
+            timeout_seconds: env_u64("OPENCLAW_HTTP_TIMEOUT_SECONDS", 20)?,
+            max_retries: env_u32("CONSUMER_MAX_RETRIES", 5)?,
+            message_max_bytes: env_usize("OPENCLAW_MESSAGE_MAX_BYTES", 4_000)?,
+        })
+    }
+
+    pub fn required_url(&self) -> Result<&str> {
+        self.url
+            .as_deref()
+            .ok_or_else(|| anyhow!("missing required env var: OPENCLAW_WEBHOOK_URL"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OpenclawGatewayConfig;
+    use crate::tests::with_vars;
+
+    #[test]
+    fn reads_gateway_settings_with_defaults() {
+        with_vars(
+            &[
+                ("OPENCLAW_WEBHOOK_URL", None),
+                ("OPENCLAW_WEBHOOK_SIGNING_SECRET", None),
+                ("OPENCLAW_HTTP_TIMEOUT_SECONDS", None),
+                ("OPENCLAW_MESSAGE_MAX_BYTES", Some("512")),
+            ],
+            || {
+                let config = OpenclawGatewayConfig::from_env().expect("gateway config");
+                assert_eq!(config.url, None);
+                assert!(config.required_url().is_err());
+                assert_eq!(config.signing_secret_env, None);
+                assert_eq!(config.timeout_seconds, 20);
+                assert_eq!(config.max_retries, 5);
+                assert_eq!(config.message_max_bytes, 512);
+            },
+        );
+        with_vars(
+            &[
+                (
+                    "OPENCLAW_WEBHOOK_URL",
+                    Some("https://gateway.example/hooks"),
+                ),
+                ("OPENCLAW_WEBHOOK_SIGNING_SECRET", Some("signing")),
+                ("OPENCLAW_HTTP_TIMEOUT_SECONDS", Some("nope")),
+            ],
+            || {
+                assert!(OpenclawGatewayConfig::from_env().is_err());
+            },
+        );
+        with_vars(
+            &[
+                (
+                    "OPENCLAW_WEBHOOK_URL",
+                    Some("https://gateway.example/hooks"),
+                ),
+                ("OPENCLAW_WEBHOOK_SIGNING_SECRET", Some("signing")),
+            ],
+            || {
+                let config = OpenclawGatewayConfig::from_env().expect("gateway config");
+                assert_eq!(
+                    config.required_url().expect("url"),
+                    "https://gateway.example/hooks"
+                );
+                assert_eq!(
+                    config.signing_secret_env.as_deref(),
+                    Some("OPENCLAW_WEBHOOK_SIGNING_SECRET")
+                );
+            },
+        );
+    }
+}
@@ -126,6 +126,19 @@ pub struct SmashRoute {
     #[serde(default)]
     pub event_filters: Vec<String>,
     pub destinations: Vec<RouteDestination>,
+    #[serde(default)]
+    pub schedule: Option<RouteSchedule>,
+}
+
+/// Weekly windows that gate a smash route; smash validates the syntax.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteSchedule {
+    pub hours: String,
+    #[serde(default)]
+    pub utc_offset: Option<String>,
+    #[serde(default)]
+    pub outside: bool,
 }
 
 fn default_required_destination() -> bool {
@@ -308,7 +321,7 @@ smash_routes = ["core-to-openclaw"]
         assert_eq!(contract.app.id, "default-openclaw");
         assert_eq!(contract.policies.validation_mode, ValidationMode::Strict);
         assert_eq!(contract.policies.no_output_sink, None);
-        assert_eq!(contract.policies.allow_no_output, false);
+        assert!(!contract.policies.allow_no_output);
     }
 
     #[test]
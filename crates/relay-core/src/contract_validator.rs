@@ -263,7 +263,7 @@ fn validate_egress_adapter_schemas(
                     "openclaw_http_output",
                     &adapter.config,
                     &["url", "token_env", "timeout_seconds", "max_retries"],
-                    &["plugins"],
+                    &["plugins", "signing_secret_env"],
                     errors,
                 );
             }
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result, anyhow};
+use relay_config::{KafkaClientConfig, env_bool, env_i32, env_u32, env_u64, env_usize};
 use serde::Deserialize;
 use std::env;
 use std::fs;
@@ -78,22 +79,10 @@ impl KafkaCoreConfig {
     }
 
     pub fn from_env() -> Result<Self> {
-        let brokers = required_env("KAFKA_BROKERS")?
-            .split(',')
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .map(ToString::to_string)
-            .collect::<Vec<_>>();
-
-        if brokers.is_empty() {
-            return Err(anyhow!("KAFKA_BROKERS cannot be empty"));
-        }
-
+        let kafka = KafkaClientConfig::from_env("ssl")?;
         let config = Self {
-            brokers,
-            security_protocol: env::var("KAFKA_SECURITY_PROTOCOL")
-                .unwrap_or_else(|_| "ssl".to_string())
-                .to_ascii_lowercase(),
+            brokers: kafka.broker_list(),
+            security_protocol: kafka.security_protocol.clone(),
             topic_prefix_core: env::var("RELAY_SOURCE_TOPIC_PREFIX")
                 .unwrap_or_else(|_| "webhooks".to_string()),
             dlq_topic: env::var("KAFKA_DLQ_TOPIC").unwrap_or_else(|_| "webhooks.dlq".to_string()),
@@ -110,9 +99,9 @@ impl KafkaCoreConfig {
             auto_create_topics: Some(env_bool("KAFKA_AUTO_CREATE_TOPICS", true)),
             topic_partitions: Some(env_i32("KAFKA_TOPIC_PARTITIONS", 3)?),
             topic_replication_factor: Some(env_i32("KAFKA_TOPIC_REPLICATION_FACTOR", 1)?),
-            allow_plaintext: Some(env_bool("KAFKA_ALLOW_PLAINTEXT", false)),
-            tls: env_tls_config(),
-            sasl: env_sasl_config(),
+            allow_plaintext: Some(kafka.allow_plaintext),
+            tls: tls_config(&kafka),
+            sasl: sasl_config(kafka),
         };
 
         config.validate()?;
@@ -144,98 +133,22 @@ impl KafkaCoreConfig {
     }
 }
 
-fn env_tls_config() -> Option<TlsConfig> {
-    let cert = env::var("KAFKA_TLS_CERT").ok()?;
-    let key = env::var("KAFKA_TLS_KEY").ok()?;
-    let ca = env::var("KAFKA_TLS_CA").ok()?;
+fn tls_config(kafka: &KafkaClientConfig) -> Option<TlsConfig> {
     Some(TlsConfig {
-        cert_path: cert,
-        key_path: key,
-        ca_path: ca,
+        cert_path: kafka.tls_cert.clone()?,
+        key_path: kafka.tls_key.clone()?,
+        ca_path: kafka.tls_ca.clone()?,
     })
 }
 
-fn env_sasl_config() -> Option<SaslConfig> {
-    let mechanism = env::var("KAFKA_SASL_MECHANISM").ok()?;
-    let username = env::var("KAFKA_SASL_USERNAME").ok();
-    let password = env::var("KAFKA_SASL_PASSWORD").ok();
+fn sasl_config(kafka: KafkaClientConfig) -> Option<SaslConfig> {
     Some(SaslConfig {
-        mechanism,
-        username,
-        password,
+        mechanism: kafka.sasl_mechanism?,
+        username: kafka.sasl_username,
+        password: kafka.sasl_password,
     })
 }
 
-fn required_env(name: &str) -> Result<String> {
-    let value = env::var(name).with_context(|| format!("missing required env var: {name}"))?;
-    if value.trim().is_empty() {
-        return Err(anyhow!("{name} cannot be empty"));
-    }
-    Ok(value)
-}
-
-fn env_bool(name: &str, default: bool) -> bool {
-    match env::var(name) {
-        Ok(value) => matches!(
-            value.trim().to_ascii_lowercase().as_str(),
-            "1" | "true" | "yes" | "on"
-        ),
-        Err(_) => default,
-    }
-}
-
-fn env_i32(name: &str, default: i32) -> Result<i32> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<i32>()
-                .with_context(|| format!("invalid i32 for {name}"))
-        })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
-}
-
-fn env_u32(name: &str, default: u32) -> Result<u32> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<u32>()
-                .with_context(|| format!("invalid u32 for {name}"))
-        })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
-}
-
-fn env_u64(name: &str, default: u64) -> Result<u64> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<u64>()
-                .with_context(|| format!("invalid u64 for {name}"))
-        })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
-}
-
-fn env_usize(name: &str, default: usize) -> Result<usize> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<usize>()
-                .with_context(|| format!("invalid usize for {name}"))
-        })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
-}
-
 #[cfg(test)]
 mod tests {
     use super::KafkaCoreConfig;
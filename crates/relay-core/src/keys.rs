@@ -1,3 +1,6 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
 pub fn github_dedup_key(delivery_id: &str, action: &str, entity_id: &str) -> String {
     format!("github:{delivery_id}:{action}:{entity_id}")
 }
@@ -6,18 +9,117 @@ pub fn linear_dedup_key(delivery_id: &str, action: &str, entity_id: &str) -> Str
     format!("linear:{delivery_id}:{action}:{entity_id}")
 }
 
+pub fn gitlab_dedup_key(event_uuid: &str, action: &str, entity_id: &str) -> String {
+    format!("gitlab:{event_uuid}:{action}:{entity_id}")
+}
+
+pub fn bitbucket_dedup_key(request_id: &str, event_type: &str, entity_id: &str) -> String {
+    format!("bitbucket:{request_id}:{event_type}:{entity_id}")
+}
+
+pub fn jira_dedup_key(webhook_id: &str, event_type: &str, issue_key: &str) -> String {
+    format!("jira:{webhook_id}:{event_type}:{issue_key}")
+}
+
 pub fn github_cooldown_key(repo: &str, entity_id: &str) -> String {
     let repo_token = repo.replace('/', "-");
     format!("cooldown-github-{repo_token}-{entity_id}")
 }
 
+pub fn gitlab_cooldown_key(project: &str, entity_id: &str) -> String {
+    let project_token = project.replace('/', "-");
+    format!("cooldown-gitlab-{project_token}-{entity_id}")
+}
+
+pub fn bitbucket_cooldown_key(repo: &str, entity_id: &str) -> String {
+    let repo_token = repo.replace('/', "-");
+    format!("cooldown-bitbucket-{repo_token}-{entity_id}")
+}
+
+pub fn jira_cooldown_key(issue_key: &str) -> String {
+    format!("cooldown-jira-{issue_key}")
+}
+
 pub fn linear_cooldown_key(team_key: &str, entity_id: &str) -> String {
     format!("cooldown-linear-{team_key}-{entity_id}")
 }
 
+/// Hash of the payload with object keys sorted and timestamp-like fields
+/// dropped, so a redelivery under a new delivery id still collides.
+pub fn content_dedup_key(source: &str, payload: &Value) -> String {
+    let mut canonical = String::new();
+    write_canonical(payload, &mut canonical);
+    let digest = Sha256::digest(canonical.as_bytes());
+    format!("content:{source}:{}", hex::encode(digest))
+}
+
+fn is_timestamp_field(key: &str) -> bool {
+    key.ends_with("_at") || key.ends_with("At") || key.to_ascii_lowercase().contains("timestamp")
+}
+
+fn write_canonical(value: &Value, output: &mut String) {
+    match value {
+        Value::Object(fields) => {
+            let mut keys = fields
+                .keys()
+                .filter(|key| !is_timestamp_field(key))
+                .collect::<Vec<_>>();
+            keys.sort();
+            output.push('{');
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                output.push_str(&Value::String(key.clone()).to_string());
+                output.push(':');
+                write_canonical(&fields[key], output);
+            }
+            output.push('}');
+        }
+        Value::Array(items) => {
+            output.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_canonical(item, output);
+            }
+            output.push(']');
+        }
+        scalar => output.push_str(&scalar.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn content_dedup_key_ignores_key_order_and_timestamps() {
+        let first = json!({
+            "action": "opened",
+            "issue": {"number": 42, "updated_at": "2026-01-01T00:00:00Z"},
+            "createdAt": "2026-01-01T00:00:00Z",
+        });
+        let redelivered = json!({
+            "issue": {"updated_at": "2026-01-01T00:05:00Z", "number": 42},
+            "createdAt": "2026-01-01T00:05:00Z",
+            "action": "opened",
+        });
+        assert_eq!(
+            content_dedup_key("github", &first),
+            content_dedup_key("github", &redelivered)
+        );
+        assert_ne!(
+            content_dedup_key("github", &first),
+            content_dedup_key("github", &json!({"action":"closed","issue":{"number":42}}))
+        );
+        assert_ne!(
+            content_dedup_key("github", &first),
+            content_dedup_key("linear", &first)
+        );
+    }
 
     #[test]
     fn github_dedup_key_matches_current_script_shape() {
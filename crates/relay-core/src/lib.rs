@@ -1,8 +1,15 @@
 pub mod contract;
 pub mod contract_validator;
+pub mod jwt;
 pub mod kafka_config;
 pub mod keys;
 pub mod model;
+pub mod redact;
 pub mod sanitize;
 pub mod signatures;
+pub mod tenants;
+#[cfg(test)]
+mod test_keys;
 pub mod timestamps;
+#[cfg(feature = "vault")]
+pub mod vault;
@@ -4,6 +4,12 @@ use std::str::FromStr;
 
 pub const DEFAULT_SOURCE_TOPIC_PREFIX: &str = "webhooks";
 
+/// Envelope layout written by this build. Version 1 is every envelope
+/// written before the field existed. Adding an optional field does not
+/// need a bump; renaming, removing or reinterpreting one does.
+pub const ENVELOPE_SCHEMA_VERSION: u32 = 2;
+const LEGACY_ENVELOPE_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Source {
@@ -57,8 +63,20 @@ impl FromStr for Source {
     }
 }
 
+/// Consumers read the current version and the one before it, so producers
+/// and consumers can be upgraded one deploy apart.
+pub fn is_supported_envelope_version(version: u32) -> bool {
+    (ENVELOPE_SCHEMA_VERSION - 1..=ENVELOPE_SCHEMA_VERSION).contains(&version)
+}
+
+fn legacy_envelope_schema_version() -> u32 {
+    LEGACY_ENVELOPE_SCHEMA_VERSION
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventEnvelope {
+    #[serde(default = "legacy_envelope_schema_version")]
+    pub schema_version: u32,
     pub id: String,
     pub source: String,
     pub event_type: String,
@@ -78,22 +96,84 @@ pub struct EventMeta {
     pub route_key: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub flags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay: Option<ReplayMeta>,
+    /// Set for events received on a tenant's path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+/// Set when an event is requeued from the DLQ; `patch` is the merge-patch
+/// applied to the payload, if any.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayMeta {
+    pub replayed_at: String,
+    pub dlq_failed_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<Value>,
 }
 
 pub type WebhookEnvelope = EventEnvelope;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DlqEnvelope {
+    #[serde(default = "legacy_envelope_schema_version")]
+    pub schema_version: u32,
     pub failed_at: String,
     pub error: String,
     pub envelope: EventEnvelope,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<DeliveryFailure>,
+    /// Copied from the envelope so DLQ entries can be filtered per tenant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+/// What the upstream said, and when, across every attempt of a delivery.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveryFailure {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<String>,
+    #[serde(default)]
+    pub attempts: Vec<DeliveryAttempt>,
+    pub retry_duration_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveryAttempt {
+    pub at: String,
+    #[serde(default)]
+    pub duration_ms: u64,
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    /// Absent on entries written before attempts were classified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<AttemptOutcome>,
+}
+
+/// Coarse class of a failed attempt, for grouping a retry history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptOutcome {
+    Timeout,
+    ConnectError,
+    /// 5xx or 429; retried.
+    RetryableStatus,
+    /// Any other non-2xx status; not retried.
+    RejectedStatus,
+    Error,
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        DEFAULT_SOURCE_TOPIC_PREFIX, EventEnvelope, EventMeta, normalize_source_name,
-        source_topic_name,
+        DEFAULT_SOURCE_TOPIC_PREFIX, ENVELOPE_SCHEMA_VERSION, EventEnvelope, EventMeta,
+        is_supported_envelope_version, normalize_source_name, source_topic_name,
     };
     use serde_json::json;
 
@@ -112,9 +192,27 @@ mod tests {
         assert!(source_topic_name("", "linear").is_none());
     }
 
+    #[test]
+    fn unversioned_envelopes_read_as_the_previous_version() {
+        let envelope: EventEnvelope = serde_json::from_value(json!({
+            "id": "id-1",
+            "source": "github",
+            "event_type": "push",
+            "received_at": "2026-01-01T00:00:00Z",
+            "payload": {},
+        }))
+        .expect("deserialize legacy envelope");
+
+        assert_eq!(envelope.schema_version, ENVELOPE_SCHEMA_VERSION - 1);
+        assert!(is_supported_envelope_version(envelope.schema_version));
+        assert!(is_supported_envelope_version(ENVELOPE_SCHEMA_VERSION));
+        assert!(!is_supported_envelope_version(ENVELOPE_SCHEMA_VERSION + 1));
+    }
+
     #[test]
     fn omits_meta_field_when_none() {
         let envelope = EventEnvelope {
+            schema_version: ENVELOPE_SCHEMA_VERSION,
             id: "id-1".to_string(),
             source: "github".to_string(),
             event_type: "pull_request.opened".to_string(),
@@ -130,6 +228,7 @@ mod tests {
     #[test]
     fn serializes_meta_when_present() {
         let envelope = EventEnvelope {
+            schema_version: ENVELOPE_SCHEMA_VERSION,
             id: "id-1".to_string(),
             source: "github".to_string(),
             event_type: "pull_request.opened".to_string(),
@@ -140,6 +239,9 @@ mod tests {
                 ingress_adapter: Some("http-ingress".to_string()),
                 route_key: Some("all-to-core".to_string()),
                 flags: vec!["sanitized".to_string()],
+                priority: None,
+                replay: None,
+                tenant: None,
             }),
         };
 
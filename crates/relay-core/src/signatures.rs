@@ -1,17 +1,247 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use hmac::{Hmac, Mac};
+use ring::signature::{ED25519, UnparsedPublicKey};
+use rustls_pki_types::CertificateDer;
+use sha1::Sha1;
 use sha2::Sha256;
 use subtle::ConstantTimeEq;
+use webpki::EndEntityCert;
 
-pub fn verify_github_signature(secret: &str, payload: &[u8], signature_header: &str) -> bool {
-    let expected = compute_hmac_sha256_hex(secret, payload);
+/// Digest used by a generic HMAC source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HmacAlgorithm {
+    Sha256,
+    Sha1,
+}
+
+impl HmacAlgorithm {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "sha256" => Some(Self::Sha256),
+            "sha1" => Some(Self::Sha1),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha1 => "sha1",
+        }
+    }
+}
+
+/// Hex HMAC of the body, bare or prefixed with the algorithm name
+/// (`sha256=<hex>`).
+pub fn verify_hmac_hex_signature(
+    algorithm: HmacAlgorithm,
+    secret: &str,
+    payload: &[u8],
+    signature_header: &str,
+) -> bool {
+    let trimmed = signature_header.trim();
+    let provided = trimmed
+        .strip_prefix(algorithm.as_str())
+        .and_then(|rest| rest.strip_prefix('='))
+        .unwrap_or(trimmed);
+    let expected = match algorithm {
+        HmacAlgorithm::Sha256 => compute_hmac_sha256_hex(secret, payload),
+        HmacAlgorithm::Sha1 => compute_hmac_sha1_hex(secret, payload),
+    };
+    constant_time_hex_equals(&provided.trim().to_ascii_lowercase(), &expected)
+}
+
+/// `secrets` may list several comma-separated secrets during a rotation;
+/// any of them verifies.
+pub fn verify_github_signature(secrets: &str, payload: &[u8], signature_header: &str) -> bool {
     let provided = normalize_signature(signature_header);
-    constant_time_hex_equals(&provided, &expected)
+    any_secret(secrets, |secret| {
+        constant_time_hex_equals(&provided, &compute_hmac_sha256_hex(secret, payload))
+    })
 }
 
-pub fn verify_linear_signature(secret: &str, payload: &[u8], signature_header: &str) -> bool {
+/// Legacy `X-Hub-Signature` (HMAC-SHA1) check for older GitHub Enterprise
+/// Server. Takes the same comma-separated secrets as the SHA256 check.
+pub fn verify_github_sha1_signature(secrets: &str, payload: &[u8], signature_header: &str) -> bool {
+    let Some(provided) = signature_header.trim().strip_prefix("sha1=") else {
+        return false;
+    };
+    let provided = provided.trim().to_ascii_lowercase();
+    any_secret(secrets, |secret| {
+        constant_time_hex_equals(&provided, &compute_hmac_sha1_hex(secret, payload))
+    })
+}
+
+/// `X-Hub-Signature: sha256=<hex>` as sent by Bitbucket Cloud and Jira
+/// Cloud. Unlike GitHub's check the prefix is mandatory.
+pub fn verify_prefixed_sha256_signature(
+    secret: &str,
+    payload: &[u8],
+    signature_header: &str,
+) -> bool {
+    let Some(provided) = signature_header.trim().strip_prefix("sha256=") else {
+        return false;
+    };
     let expected = compute_hmac_sha256_hex(secret, payload);
+    constant_time_hex_equals(&provided.trim().to_ascii_lowercase(), &expected)
+}
+
+/// `secrets` may list several comma-separated secrets during a rotation.
+pub fn verify_linear_signature(secrets: &str, payload: &[u8], signature_header: &str) -> bool {
     let provided = normalize_signature(signature_header);
-    constant_time_hex_equals(&provided, &expected)
+    any_secret(secrets, |secret| {
+        constant_time_hex_equals(&provided, &compute_hmac_sha256_hex(secret, payload))
+    })
+}
+
+/// Sentry integration webhooks: bare hex HMAC-SHA256 of the body, keyed with
+/// the integration's client secret.
+pub fn verify_sentry_signature(secret: &str, payload: &[u8], signature_header: &str) -> bool {
+    let expected = compute_hmac_sha256_hex(secret, payload);
+    constant_time_hex_equals(&signature_header.trim().to_ascii_lowercase(), &expected)
+}
+
+/// Ed25519 signature over `message`, with the key and signature hex-encoded
+/// as Discord publishes and sends them.
+pub fn verify_ed25519_signature(public_key_hex: &str, message: &[u8], signature_hex: &str) -> bool {
+    let (Ok(public_key), Ok(signature)) = (
+        hex::decode(public_key_hex.trim()),
+        hex::decode(signature_hex.trim()),
+    ) else {
+        return false;
+    };
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(message, &signature)
+        .is_ok()
+}
+
+/// First `CERTIFICATE` block of a PEM document, DER-decoded.
+pub fn decode_pem_certificate(pem: &str) -> Option<Vec<u8>> {
+    decode_pem_block(pem, "CERTIFICATE")
+}
+
+/// First PEM block with the given label (`CERTIFICATE`, `PRIVATE KEY`, ...),
+/// DER-decoded.
+pub fn decode_pem_block(pem: &str, label: &str) -> Option<Vec<u8>> {
+    let (_, rest) = pem.split_once(&format!("-----BEGIN {label}-----"))?;
+    let (body, _) = rest.split_once(&format!("-----END {label}-----"))?;
+    let body: String = body
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect();
+    BASE64.decode(body).ok()
+}
+
+/// RSA PKCS#1 v1.5 SHA-256 signature over `message`, checked against the
+/// public key of a DER certificate. Only the signature is verified; the
+/// caller decides why the certificate is trusted.
+pub fn verify_certificate_sha256_signature(
+    certificate_der: &[u8],
+    message: &[u8],
+    signature_base64: &str,
+) -> bool {
+    let Ok(signature) = BASE64.decode(signature_base64.trim()) else {
+        return false;
+    };
+    let certificate = CertificateDer::from(certificate_der);
+    let Ok(certificate) = EndEntityCert::try_from(&certificate) else {
+        return false;
+    };
+    certificate
+        .verify_signature(
+            webpki::ring::RSA_PKCS1_2048_8192_SHA256,
+            message,
+            &signature,
+        )
+        .is_ok()
+}
+
+/// Shopify: base64 (not hex) HMAC-SHA256 of the body.
+pub fn verify_base64_sha256_signature(
+    secret: &str,
+    payload: &[u8],
+    signature_header: &str,
+) -> bool {
+    let Ok(provided) = BASE64.decode(signature_header.trim()) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts variable-length keys");
+    mac.update(payload);
+    mac.verify_slice(&provided).is_ok()
+}
+
+/// Stripe: any `v1=<hex>` entry in `Stripe-Signature` may match
+/// HMAC-SHA256 over `{t}.{body}`, keyed with the `whsec_` secret as-is.
+pub fn verify_stripe_signature(
+    secret: &str,
+    timestamp: &str,
+    payload: &[u8],
+    signature_header: &str,
+) -> bool {
+    let expected = compute_stripe_signature(secret, timestamp, payload);
+    signature_header
+        .split(',')
+        .filter_map(|entry| entry.trim().strip_prefix("v1="))
+        .any(|provided| constant_time_hex_equals(&provided.to_ascii_lowercase(), &expected))
+}
+
+pub fn compute_stripe_signature(secret: &str, timestamp: &str, payload: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts variable-length keys");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Standard Webhooks: any space-separated `v1,<base64>` entry in
+/// `webhook-signature` may match HMAC-SHA256 over `{id}.{timestamp}.{body}`.
+pub fn verify_standard_webhook_signature(
+    secret: &str,
+    message_id: &str,
+    timestamp: &str,
+    payload: &[u8],
+    signature_header: &str,
+) -> bool {
+    let Some(expected) = compute_standard_webhook_signature(secret, message_id, timestamp, payload)
+    else {
+        return false;
+    };
+    signature_header
+        .split_whitespace()
+        .filter_map(|entry| entry.strip_prefix("v1,"))
+        .any(|provided| {
+            provided.len() == expected.len()
+                && bool::from(provided.as_bytes().ct_eq(expected.as_bytes()))
+        })
+}
+
+/// Returns `None` when the secret is not valid base64 (after an optional
+/// `whsec_` prefix).
+pub fn compute_standard_webhook_signature(
+    secret: &str,
+    message_id: &str,
+    timestamp: &str,
+    payload: &[u8],
+) -> Option<String> {
+    let key = decode_standard_webhook_secret(secret)?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).expect("HMAC accepts variable-length keys");
+    mac.update(message_id.as_bytes());
+    mac.update(b".");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+    Some(BASE64.encode(mac.finalize().into_bytes()))
+}
+
+pub fn decode_standard_webhook_secret(secret: &str) -> Option<Vec<u8>> {
+    let encoded = secret.trim();
+    BASE64
+        .decode(encoded.strip_prefix("whsec_").unwrap_or(encoded))
+        .ok()
+        .filter(|key| !key.is_empty())
 }
 
 pub fn verify_shared_token(expected_token: &str, header_value: &str) -> bool {
@@ -20,6 +250,12 @@ pub fn verify_shared_token(expected_token: &str, header_value: &str) -> bool {
     constant_time_hex_equals(&provided, &expected)
 }
 
+pub fn verify_bearer_token(expected_token: &str, provided_token: &str) -> bool {
+    let expected = expected_token.as_bytes();
+    let provided = provided_token.as_bytes();
+    expected.len() == provided.len() && bool::from(expected.ct_eq(provided))
+}
+
 pub fn compute_hmac_sha256_hex(secret: &str, payload: &[u8]) -> String {
     let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
         .expect("HMAC accepts variable-length keys");
@@ -27,6 +263,23 @@ pub fn compute_hmac_sha256_hex(secret: &str, payload: &[u8]) -> String {
     hex::encode(mac.finalize().into_bytes())
 }
 
+pub fn compute_hmac_sha1_hex(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha1>::new_from_slice(secret.as_bytes()).expect("HMAC accepts variable-length keys");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Tries every comma-separated secret, without stopping at the first match,
+/// so timing does not reveal which one verified.
+fn any_secret(secrets: &str, verify: impl Fn(&str) -> bool) -> bool {
+    secrets
+        .split(',')
+        .map(str::trim)
+        .filter(|secret| !secret.is_empty())
+        .fold(false, |matched, secret| verify(secret) | matched)
+}
+
 fn normalize_signature(raw: &str) -> String {
     raw.trim()
         .strip_prefix("sha256=")
@@ -48,6 +301,112 @@ fn constant_time_hex_equals(left: &str, right: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn verifies_base64_sha256_signature() {
+        let payload = br#"{"id":820982911946154508}"#;
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"shopify-secret").expect("hmac key");
+        mac.update(payload);
+        let signature = BASE64.encode(mac.finalize().into_bytes());
+
+        assert!(verify_base64_sha256_signature(
+            "shopify-secret",
+            payload,
+            &signature
+        ));
+        assert!(!verify_base64_sha256_signature(
+            "other-secret",
+            payload,
+            &signature
+        ));
+        assert!(!verify_base64_sha256_signature(
+            "shopify-secret",
+            payload,
+            "%%%"
+        ));
+    }
+
+    #[test]
+    fn verifies_ed25519_signature() {
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).expect("key pair");
+        let public_key = hex::encode(key_pair.public_key().as_ref());
+        let message = br#"1700000000{"type":1}"#;
+        let signature = hex::encode(key_pair.sign(message).as_ref());
+
+        assert!(verify_ed25519_signature(&public_key, message, &signature));
+        assert!(!verify_ed25519_signature(
+            &public_key,
+            br#"1700000001{"type":1}"#,
+            &signature
+        ));
+        assert!(!verify_ed25519_signature(&public_key, message, "not-hex"));
+    }
+
+    #[test]
+    fn verifies_certificate_signature_from_pem() {
+        use crate::test_keys::{TEST_CERT_DER, TEST_RSA_DER};
+        use ring::rand::SystemRandom;
+        use ring::signature::{RSA_PKCS1_SHA256, RsaKeyPair};
+
+        let key_pair =
+            RsaKeyPair::from_der(&BASE64.decode(TEST_RSA_DER).expect("key")).expect("key pair");
+        let message = b"Message\nhello\nType\nNotification\n";
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                message,
+                &mut signature,
+            )
+            .expect("sign");
+        let signature = BASE64.encode(signature);
+        let pem =
+            format!("-----BEGIN CERTIFICATE-----\n{TEST_CERT_DER}\n-----END CERTIFICATE-----\n");
+        let certificate = decode_pem_certificate(&pem).expect("pem certificate");
+
+        assert!(verify_certificate_sha256_signature(
+            &certificate,
+            message,
+            &signature
+        ));
+        assert!(!verify_certificate_sha256_signature(
+            &certificate,
+            b"Message\nforged\nType\nNotification\n",
+            &signature
+        ));
+        assert_eq!(decode_pem_certificate("no certificate here"), None);
+    }
+
+    #[test]
+    fn verifies_generic_hmac_with_or_without_prefix() {
+        let payload = br#"{"id":"evt-1"}"#;
+        let sha256 = compute_hmac_sha256_hex("custom-secret", payload);
+        let sha1 = compute_hmac_sha1_hex("custom-secret", payload);
+
+        assert!(verify_hmac_hex_signature(
+            HmacAlgorithm::Sha256,
+            "custom-secret",
+            payload,
+            &sha256
+        ));
+        assert!(verify_hmac_hex_signature(
+            HmacAlgorithm::Sha1,
+            "custom-secret",
+            payload,
+            &format!("sha1={sha1}")
+        ));
+        assert!(!verify_hmac_hex_signature(
+            HmacAlgorithm::Sha1,
+            "custom-secret",
+            payload,
+            &format!("sha256={sha256}")
+        ));
+        assert_eq!(HmacAlgorithm::parse("SHA256"), Some(HmacAlgorithm::Sha256));
+        assert_eq!(HmacAlgorithm::parse("md5"), None);
+    }
+
     #[test]
     fn verifies_github_signature_with_sha256_prefix() {
         let secret = "super-secret";
@@ -59,6 +418,58 @@ mod tests {
         assert!(!verify_github_signature(secret, payload, "sha256=deadbeef"));
     }
 
+    #[test]
+    fn accepts_any_secret_in_a_rotation_list() {
+        let payload = br#"{"action":"opened"}"#;
+        let old = compute_hmac_sha256_hex("old-secret", payload);
+        let new = compute_hmac_sha256_hex("new-secret", payload);
+        let secrets = "new-secret, old-secret";
+
+        assert!(verify_github_signature(
+            secrets,
+            payload,
+            &format!("sha256={old}")
+        ));
+        assert!(verify_github_signature(
+            secrets,
+            payload,
+            &format!("sha256={new}")
+        ));
+        assert!(verify_linear_signature(secrets, payload, &old));
+        assert!(verify_github_sha1_signature(
+            secrets,
+            payload,
+            &format!("sha1={}", compute_hmac_sha1_hex("old-secret", payload))
+        ));
+        assert!(!verify_github_signature(
+            "new-secret",
+            payload,
+            &format!("sha256={old}")
+        ));
+        assert!(!verify_linear_signature(
+            ",",
+            payload,
+            &compute_hmac_sha256_hex("", payload)
+        ));
+    }
+
+    #[test]
+    fn prefixed_sha256_signature_requires_prefix() {
+        let payload = br#"{"pullrequest":{"id":7}}"#;
+        let digest = compute_hmac_sha256_hex("bitbucket-secret", payload);
+
+        assert!(verify_prefixed_sha256_signature(
+            "bitbucket-secret",
+            payload,
+            &format!("sha256={digest}")
+        ));
+        assert!(!verify_prefixed_sha256_signature(
+            "bitbucket-secret",
+            payload,
+            &digest
+        ));
+    }
+
     #[test]
     fn verifies_linear_signature_without_prefix() {
         let secret = "linear-secret";
@@ -80,4 +491,75 @@ mod tests {
         assert!(verify_shared_token("token-value", " sha256=token-value "));
         assert!(!verify_shared_token("token-value", "different"));
     }
+
+    #[test]
+    fn verifies_github_sha1_signature_with_prefix_only() {
+        let payload = br#"{"zen":"hi"}"#;
+        let digest = compute_hmac_sha1_hex("legacy-secret", payload);
+        assert_eq!(digest, "983cd1cef3ce3ce00e64800188ee3a887fe91c1b");
+
+        assert!(verify_github_sha1_signature(
+            "legacy-secret",
+            payload,
+            &format!("sha1={digest}")
+        ));
+        assert!(!verify_github_sha1_signature(
+            "legacy-secret",
+            payload,
+            &digest
+        ));
+        assert!(!verify_github_sha1_signature(
+            "other-secret",
+            payload,
+            &format!("sha1={digest}")
+        ));
+    }
+
+    #[test]
+    fn verifies_standard_webhook_reference_vector() {
+        let secret = "whsec_MfKQ9r8GKYqrTwjUPD8ILPZIo2LaLaSw";
+        let id = "msg_p5jXN8AQM9LWM0D4loKWxJek";
+        let timestamp = "1614265330";
+        let payload = br#"{"test": 2432232314}"#;
+        let signature = "v1,g0hM9SsE+OTPJTGt/tmIKtSyZlE3uFJELVlNIOLJ1OE=";
+
+        assert!(verify_standard_webhook_signature(
+            secret, id, timestamp, payload, signature
+        ));
+        assert!(verify_standard_webhook_signature(
+            secret,
+            id,
+            timestamp,
+            payload,
+            &format!("v1,b3Rocm9zaWduYXR1cmU= {signature}")
+        ));
+        assert!(!verify_standard_webhook_signature(
+            secret,
+            id,
+            "1614265331",
+            payload,
+            signature
+        ));
+        assert!(!verify_standard_webhook_signature(
+            secret,
+            id,
+            timestamp,
+            payload,
+            "v2,g0hM9SsE+OTPJTGt/tmIKtSyZlE3uFJELVlNIOLJ1OE="
+        ));
+        assert!(!verify_standard_webhook_signature(
+            "whsec_not base64!",
+            id,
+            timestamp,
+            payload,
+            signature
+        ));
+    }
+
+    #[test]
+    fn bearer_token_match_is_exact() {
+        assert!(verify_bearer_token("Admin-Token", "Admin-Token"));
+        assert!(!verify_bearer_token("Admin-Token", "admin-token"));
+        assert!(!verify_bearer_token("Admin-Token", "Admin-Token "));
+    }
 }
@@ -0,0 +1,117 @@
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// One team sharing a relay. Serve reads the secrets and cooldown; smash
+/// reads the gateway. Secrets and tokens are referenced by env var name, as
+/// in contracts.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenantConfig {
+    pub id: String,
+    /// Source name to the env var holding the tenant's secret for it. Only
+    /// these sources are served under the tenant's path.
+    #[serde(default)]
+    pub secret_envs: BTreeMap<String, String>,
+    /// Overrides `RELAY_COOLDOWN_SECONDS` for the tenant's events.
+    #[serde(default)]
+    pub cooldown_seconds: Option<i64>,
+    /// OpenClaw gateway that replaces `openclaw_http_output` destinations
+    /// for the tenant's events.
+    #[serde(default)]
+    pub gateway_url: Option<String>,
+    #[serde(default)]
+    pub gateway_token_env: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TenantsFile {
+    #[serde(default)]
+    tenants: Vec<TenantConfig>,
+}
+
+pub fn load_tenants(path: &Path) -> Result<Vec<TenantConfig>> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("read tenants file: {}", path.display()))?;
+    parse_tenants(&raw).with_context(|| format!("parse tenants file: {}", path.display()))
+}
+
+pub fn parse_tenants(raw: &str) -> Result<Vec<TenantConfig>> {
+    let tenants = toml::from_str::<TenantsFile>(raw)?.tenants;
+    let mut ids = BTreeSet::new();
+    for tenant in &tenants {
+        let valid_id = !tenant.id.is_empty()
+            && tenant.id.chars().all(|character| {
+                character.is_ascii_lowercase()
+                    || character.is_ascii_digit()
+                    || character == '-'
+                    || character == '_'
+            });
+        if !valid_id {
+            return Err(anyhow!(
+                "tenant id '{}' must be lowercase letters, digits, '-' or '_'",
+                tenant.id
+            ));
+        }
+        if !ids.insert(tenant.id.as_str()) {
+            return Err(anyhow!("duplicate tenant id '{}'", tenant.id));
+        }
+        if tenant.secret_envs.is_empty() {
+            return Err(anyhow!("tenant '{}' has no secret_envs", tenant.id));
+        }
+        if tenant.cooldown_seconds.is_some_and(|seconds| seconds < 0) {
+            return Err(anyhow!(
+                "tenant '{}' cooldown_seconds cannot be negative",
+                tenant.id
+            ));
+        }
+        if tenant.gateway_url.is_some() != tenant.gateway_token_env.is_some() {
+            return Err(anyhow!(
+                "tenant '{}' needs both gateway_url and gateway_token_env, or neither",
+                tenant.id
+            ));
+        }
+    }
+    Ok(tenants)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tenants_and_rejects_ambiguous_entries() {
+        let tenants = parse_tenants(
+            r#"
+            [[tenants]]
+            id = "payments"
+            cooldown_seconds = 120
+            gateway_url = "https://openclaw.payments.example.com/hooks/agent"
+            gateway_token_env = "PAYMENTS_OPENCLAW_TOKEN"
+
+            [tenants.secret_envs]
+            github = "PAYMENTS_HMAC_SECRET_GITHUB"
+            "#,
+        )
+        .expect("parse tenants");
+        assert_eq!(tenants.len(), 1);
+        assert_eq!(tenants[0].cooldown_seconds, Some(120));
+        assert_eq!(
+            tenants[0].secret_envs.get("github").map(String::as_str),
+            Some("PAYMENTS_HMAC_SECRET_GITHUB")
+        );
+
+        for invalid in [
+            "[[tenants]]\nid = \"Payments\"\nsecret_envs = { github = \"A\" }",
+            "[[tenants]]\nid = \"a\"\nsecret_envs = { github = \"A\" }\n[[tenants]]\nid = \"a\"\nsecret_envs = { github = \"B\" }",
+            "[[tenants]]\nid = \"a\"",
+            "[[tenants]]\nid = \"a\"\nsecret_envs = { github = \"A\" }\ngateway_url = \"https://gw\"",
+            "[[tenants]]\nid = \"a\"\nsecret_envs = { github = \"A\" }\nteam = \"x\"",
+        ] {
+            assert!(parse_tenants(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Throwaway key material for tests. Never trusted outside them.
+
+/// 2048-bit RSA key (PKCS#1 DER, base64) that only ever signs test tokens.
+pub const TEST_RSA_DER: &str = concat!(
+    "MIIEowIBAAKCAQEAvC2U6yX2g/TaAv9KrszzBViD7SdNy2DCAhH08x/CV4EYu0tzpdAtupGJK0wr",
+    "8odKIe8WMO22poYxt+AuRNtWoe2UTRQxpZCy50pSLffxbuUCbZIS5FG1euL945xNuR2houBYzArs",
+    "RtD24MNvrE/p3NuHoBwKFBPCWQUdLx1DKTakP+V2ouMbXi4mAd9gP9lSApnkt7E1/oeuyNViJCkT",
+    "27BdkyNoevAAP0KG5zEnQDcwMNhLF5j8nKLn6TLBpaJc59qLDGGxrik/ZKYJ2e2haj7nQOrFKVU5",
+    "CYPhphONhqh+uj9HeePcNhJq6eRaPqXb4FbqXscR4n5Ku5r0T0hJFwIDAQABAoIBAAGrb95URGcw",
+    "VXzgUgPSCrf57JW+mwiab65ABYngtsM7AIKs7TZD7hvxLaPmNZ+dxrEdY3eErHG20BsfEJoIzjB6",
+    "oK28Z6Jd8dBujuMch5Zhzs81FBjVhsQz3mfWG7RwSF5fGjqZQDZypTYiVcvUG7Gbew0XIpnCAR/0",
+    "FSm1W0Ky1vrVZYFTpggQg7mQreg7fP0pH1KDtnwh1u3fA8BkkM4i8RSJer59AhmLsaDEuY+VxUni",
+    "tA6KICsqGQRQy8mLm8LnYEuYHpyCn4EkB983Uv9pawSoBp6zoWz6i0SdRggjmQHE4JD2xXXWFM9D",
+    "EXPI+aoMq6RnbmnyVw7FzUalc2ECgYEA/x3eSKFt9B12r2rL39eebgb2TKCccIX1fO5bIPWl2jOT",
+    "hSKPnbDVaIusttBpkrVLnSoGDf8MLj5e1p5mtgWd1JVBXrUba2BtfvTkLeFTpmSdpebfSCHOZL1E",
+    "+YuFcyAPp/zKbRW+SeZ+ADWyV9is4WWhizBgM3Hh6GiLtCo/sGECgYEAvNRhR6lXf94VG2za8V4m",
+    "isTHs4uCZD76EIXBhQT9hmPEQvzEiPzXMmwjDb1BYcdOryIepVN2Qx/axSsunTHIhlYPSkpTK9UB",
+    "FUlw2XDrxW3IsAofLXc80BFETRG7YjLPzf5xZ1aSH80HsheGAEMBXYXJidoQOL4zmtiDrD8gzHcC",
+    "gYEA/FZXwjFiJ35pIXMZ+usz+pKKVhrbAfWObqb4ni47gQtY0+vub8Q0Ifddu2mDsyf9hZPGb1uh",
+    "ebAZcGE3KL1/coC7KukqGNhFFbk2ZScl7URCJpGospk1o7EOI2JcehXxRnCk7KcAE+qXIK/8GiXo",
+    "Qf2bfqg3z0rKb23RGUBcxKECgYBskO8ddI+KRPV9vyhife9K+biX0i411L+RoFWTVTsueU3t9cZJ",
+    "vJU9uE6vJ5Df1ue2abeU9leYYEg39IvodScFlZVU8UbAfMcSphbr7bCg4m3wSOGEEno0ZrkOVpOV",
+    "8gDoPtKUx4FcJ1W1XzD8FYpDaxhk2atZ9IFXFgUek20YIQKBgEnTWmJcT6Rz0Xj99QaUtiL4FNeY",
+    "3Ly3uyRy6oyJZwXYK1PI0L+CE+DgKjxWv1DjoYZrhMLyUKc0Of1xvnirnzgjPd81lkO4K11eoNYP",
+    "f6JYKDHZl3th03ExoUfWRNIP3vryzThQOt2a3pWuIOCLtijJ7P6PtQIcrVNoHYpLc7Mp",
+);
+
+/// Self-signed certificate (DER, base64) for [`TEST_RSA_DER`], with
+/// `CN=sns.us-east-1.amazonaws.com`.
+pub const TEST_CERT_DER: &str = concat!(
+    "MIIDLzCCAhegAwIBAgIUMittbTZhlZSA3J5WMTyUqrFnLecwDQYJKoZIhvcNAQELBQAwJjEkMCIG",
+    "A1UEAwwbc25zLnVzLWVhc3QtMS5hbWF6b25hd3MuY29tMCAXDTI2MTAxNTA3NTAyMFoYDzIxMjYw",
+    "OTIxMDc1MDIwWjAmMSQwIgYDVQQDDBtzbnMudXMtZWFzdC0xLmFtYXpvbmF3cy5jb20wggEiMA0G",
+    "CSqGSIb3DQEBAQUAA4IBDwAwggEKAoIBAQC8LZTrJfaD9NoC/0quzPMFWIPtJ03LYMICEfTzH8JX",
+    "gRi7S3Ol0C26kYkrTCvyh0oh7xYw7bamhjG34C5E21ah7ZRNFDGlkLLnSlIt9/Fu5QJtkhLkUbV6",
+    "4v3jnE25HaGi4FjMCuxG0Pbgw2+sT+nc24egHAoUE8JZBR0vHUMpNqQ/5Xai4xteLiYB32A/2VIC",
+    "meS3sTX+h67I1WIkKRPbsF2TI2h68AA/QobnMSdANzAw2EsXmPycoufpMsGlolzn2osMYbGuKT9k",
+    "pgnZ7aFqPudA6sUpVTkJg+GmE42GqH66P0d549w2Emrp5Fo+pdvgVupexxHifkq7mvRPSEkXAgMB",
+    "AAGjUzBRMB0GA1UdDgQWBBTAIWTD0LaybYBnr9Uzy6hkaS2dYTAfBgNVHSMEGDAWgBTAIWTD0Lay",
+    "bYBnr9Uzy6hkaS2dYTAPBgNVHRMBAf8EBTADAQH/MA0GCSqGSIb3DQEBCwUAA4IBAQCx0OfsA0Y/",
+    "vW11EYcdyItdj7KqjfiInHf+WqWU5ToqNsheOuhqS0F1XKUn2jNbS9EOig+tgn2qBb6QpyiKugF/",
+    "c/c3RGSxbLCG9Z1ZhkAGxXAcg4G/kszeicdX80+Wy+0bYBc5BHxlolyzBcCvoXIM/5tYVPEg9DZg",
+    "AU9LSYsS76zQra1e4oM8QUm6VLSFww9bc90F3cBb2GoU113LUE8LMFaBYKSXAHQz1vK1Rg+zSHn6",
+    "YC6SiV0LFEewitLohQJ77bXR49vUE2DUGEnYIdAqpTtLBvyAVmHOXvDL+DndbomFjBGO/nfSuDZJ",
+    "1R2jwHUXlMqFRuFh3UP8FjSxPJuI",
+);
@@ -0,0 +1,419 @@
+use crate::redact::Redactor;
+use anyhow::{Context, Result, anyhow};
+use relay_config::{
+    env_u64, optional_env, required_env, required_secret_env, set_secret_overrides,
+};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_KUBERNETES_TOKEN_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+/// Retry delay after a failed refresh when the token is close to expiry.
+const MIN_REFRESH_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VaultAuth {
+    AppRole {
+        mount: String,
+        role_id: String,
+        secret_id: String,
+    },
+    Kubernetes {
+        mount: String,
+        role: String,
+        token_path: String,
+    },
+}
+
+/// Where to log in and which KV secret to read, from `VAULT_*` env vars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultConfig {
+    addr: String,
+    namespace: Option<String>,
+    auth: VaultAuth,
+    kv_mount: String,
+    kv_path: String,
+    kv_version: u8,
+    refresh: Duration,
+}
+
+impl VaultConfig {
+    /// `None` unless `VAULT_ADDR` is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(addr) = optional_env("VAULT_ADDR") else {
+            return Ok(None);
+        };
+        let method = optional_env("VAULT_AUTH_METHOD").unwrap_or_else(|| "approle".to_string());
+        let mount = optional_env("VAULT_AUTH_MOUNT").unwrap_or_else(|| method.clone());
+        let auth = match method.as_str() {
+            "approle" => VaultAuth::AppRole {
+                mount,
+                role_id: required_env("VAULT_ROLE_ID")?,
+                secret_id: required_secret_env("VAULT_SECRET_ID")?,
+            },
+            "kubernetes" => VaultAuth::Kubernetes {
+                mount,
+                role: required_env("VAULT_KUBERNETES_ROLE")?,
+                token_path: optional_env("VAULT_KUBERNETES_TOKEN_PATH")
+                    .unwrap_or_else(|| DEFAULT_KUBERNETES_TOKEN_PATH.to_string()),
+            },
+            other => {
+                return Err(anyhow!(
+                    "VAULT_AUTH_METHOD must be approle or kubernetes, got '{other}'"
+                ));
+            }
+        };
+        let kv_version = match env_u64("VAULT_KV_VERSION", 2)? {
+            1 => 1,
+            2 => 2,
+            other => return Err(anyhow!("VAULT_KV_VERSION must be 1 or 2, got {other}")),
+        };
+        let refresh_seconds = env_u64("VAULT_REFRESH_SECONDS", 300)?;
+        if refresh_seconds == 0 {
+            return Err(anyhow!("VAULT_REFRESH_SECONDS must be a positive integer"));
+        }
+        Ok(Some(Self {
+            addr: addr.trim_end_matches('/').to_string(),
+            namespace: optional_env("VAULT_NAMESPACE"),
+            auth,
+            kv_mount: optional_env("VAULT_KV_MOUNT").unwrap_or_else(|| "secret".to_string()),
+            kv_path: required_env("VAULT_KV_PATH")?,
+            kv_version,
+            refresh: Duration::from_secs(refresh_seconds),
+        }))
+    }
+
+    fn kv_url(&self) -> String {
+        let mount = self.kv_mount.trim_matches('/');
+        let path = self.kv_path.trim_matches('/');
+        match self.kv_version {
+            1 => format!("{}/v1/{mount}/{path}", self.addr),
+            _ => format!("{}/v1/{mount}/data/{path}", self.addr),
+        }
+    }
+}
+
+struct Session {
+    token: String,
+    lease: Duration,
+    renewable: bool,
+    renewed_at: Instant,
+}
+
+impl Session {
+    /// Renew once two thirds of the lease has passed. Root-like tokens with
+    /// no lease never need it.
+    fn renew_in(&self) -> Option<Duration> {
+        (!self.lease.is_zero())
+            .then(|| (self.lease * 2 / 3).saturating_sub(self.renewed_at.elapsed()))
+    }
+}
+
+/// Keeps a Vault token alive and the KV secret mirrored into
+/// `relay_config`'s secret overrides.
+pub struct VaultClient {
+    config: VaultConfig,
+    http: reqwest::Client,
+    session: Option<Session>,
+}
+
+/// Logs in, loads the KV secret and spawns the renewal task. `None` when
+/// `VAULT_ADDR` is unset.
+pub async fn bootstrap_from_env() -> Result<Option<tokio::task::JoinHandle<()>>> {
+    let Some(config) = VaultConfig::from_env()? else {
+        return Ok(None);
+    };
+    let mut client = VaultClient::new(config)?;
+    client.refresh().await?;
+    Ok(Some(tokio::spawn(client.run())))
+}
+
+impl VaultClient {
+    pub fn new(config: VaultConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .context("build vault client")?;
+        Ok(Self {
+            config,
+            http,
+            session: None,
+        })
+    }
+
+    /// Renews or replaces the token as needed, then reloads the secret.
+    pub async fn refresh(&mut self) -> Result<()> {
+        let due = self.session.as_ref().map(|session| {
+            (
+                session.renew_in().is_some_and(|wait| wait.is_zero()),
+                session.renewable,
+            )
+        });
+        match due {
+            None | Some((true, false)) => self.login().await?,
+            Some((true, true)) => {
+                if let Err(error) = self.renew().await {
+                    warn!(error = %format!("{error:#}"), "vault token renewal failed; logging in again");
+                    self.login().await?;
+                }
+            }
+            Some((false, _)) => {}
+        }
+        let secrets = self.read_secrets().await?;
+        info!(
+            path = %self.config.kv_path,
+            keys = secrets.len(),
+            "loaded secrets from vault"
+        );
+        apply_secrets(secrets);
+        Ok(())
+    }
+
+    async fn run(mut self) {
+        loop {
+            let wait = self
+                .session
+                .as_ref()
+                .and_then(Session::renew_in)
+                .map_or(self.config.refresh, |renew_in| {
+                    renew_in.min(self.config.refresh).max(MIN_REFRESH_DELAY)
+                });
+            tokio::time::sleep(wait).await;
+            if let Err(error) = self.refresh().await {
+                warn!(error = %format!("{error:#}"), "vault refresh failed; keeping previous secrets");
+            }
+        }
+    }
+
+    async fn login(&mut self) -> Result<()> {
+        let (mount, body) = match &self.config.auth {
+            VaultAuth::AppRole {
+                mount,
+                role_id,
+                secret_id,
+            } => (mount, json!({"role_id": role_id, "secret_id": secret_id})),
+            VaultAuth::Kubernetes {
+                mount,
+                role,
+                token_path,
+            } => {
+                let jwt = fs::read_to_string(token_path).with_context(|| {
+                    format!("read kubernetes service account token {token_path}")
+                })?;
+                (mount, json!({"role": role, "jwt": jwt.trim()}))
+            }
+        };
+        let url = format!(
+            "{}/v1/auth/{}/login",
+            self.config.addr,
+            mount.trim_matches('/')
+        );
+        let response = self.send(self.http.post(url).json(&body), None).await?;
+        self.session = Some(session_from_auth(&response).context("vault login")?);
+        Ok(())
+    }
+
+    async fn renew(&mut self) -> Result<()> {
+        let token = self.token()?.to_string();
+        let url = format!("{}/v1/auth/token/renew-self", self.config.addr);
+        let response = self
+            .send(self.http.post(url).json(&json!({})), Some(&token))
+            .await?;
+        self.session = Some(session_from_auth(&response).context("vault token renewal")?);
+        Ok(())
+    }
+
+    async fn read_secrets(&self) -> Result<BTreeMap<String, String>> {
+        let token = self.token()?;
+        let response = self
+            .send(self.http.get(self.config.kv_url()), Some(token))
+            .await?;
+        kv_secrets(&response, self.config.kv_version)
+    }
+
+    fn token(&self) -> Result<&str> {
+        self.session
+            .as_ref()
+            .map(|session| session.token.as_str())
+            .ok_or_else(|| anyhow!("not logged in to vault"))
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, token: Option<&str>) -> Result<Value> {
+        let mut request = request;
+        if let Some(namespace) = &self.config.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        if let Some(token) = token {
+            request = request.header("X-Vault-Token", token);
+        }
+        let response = request.send().await.context("vault request")?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("vault returned {status}"));
+        }
+        response.json().await.context("decode vault response")
+    }
+}
+
+/// Masks the secrets in logs before anything can read them.
+fn apply_secrets(secrets: BTreeMap<String, String>) {
+    Redactor::shared().add_secrets(secrets.values().cloned());
+    set_secret_overrides(secrets);
+}
+
+fn session_from_auth(response: &Value) -> Result<Session> {
+    let auth = response
+        .get("auth")
+        .ok_or_else(|| anyhow!("response has no auth block"))?;
+    let token = auth
+        .get("client_token")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("response has no client_token"))?;
+    Ok(Session {
+        token: token.to_string(),
+        lease: Duration::from_secs(
+            auth.get("lease_duration")
+                .and_then(Value::as_u64)
+                .unwrap_or(0),
+        ),
+        renewable: auth
+            .get("renewable")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        renewed_at: Instant::now(),
+    })
+}
+
+/// The secret's key/value pairs. KV v2 nests them one level deeper, under
+/// `data.data`.
+fn kv_secrets(response: &Value, kv_version: u8) -> Result<BTreeMap<String, String>> {
+    let pointer = if kv_version == 1 {
+        "/data"
+    } else {
+        "/data/data"
+    };
+    let data = response
+        .pointer(pointer)
+        .and_then(Value::as_object)
+        .ok_or_else(|| anyhow!("vault secret has no {pointer} object"))?;
+    data.iter()
+        .map(|(key, value)| match value {
+            Value::String(value) => Ok((key.clone(), value.clone())),
+            Value::Number(_) | Value::Bool(_) => Ok((key.clone(), value.to_string())),
+            _ => Err(anyhow!("vault secret key '{key}' must be a string")),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().expect("log buffer").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn config(kv_version: u8) -> VaultConfig {
+        VaultConfig {
+            addr: "https://vault.example.com".to_string(),
+            namespace: None,
+            auth: VaultAuth::AppRole {
+                mount: "approle".to_string(),
+                role_id: "role".to_string(),
+                secret_id: "secret".to_string(),
+            },
+            kv_mount: "secret".to_string(),
+            kv_path: "/webhook-relay/prod".to_string(),
+            kv_version,
+            refresh: Duration::from_secs(300),
+        }
+    }
+
+    #[test]
+    fn reads_kv_v1_and_v2_secrets() {
+        assert_eq!(
+            config(2).kv_url(),
+            "https://vault.example.com/v1/secret/data/webhook-relay/prod"
+        );
+        assert_eq!(
+            config(1).kv_url(),
+            "https://vault.example.com/v1/secret/webhook-relay/prod"
+        );
+
+        let v2 = json!({"data": {"data": {"HMAC_SECRET_GITHUB": "gh", "RELAY_PORT": 8080}, "metadata": {"version": 3}}});
+        let secrets = kv_secrets(&v2, 2).expect("kv v2");
+        assert_eq!(secrets["HMAC_SECRET_GITHUB"], "gh");
+        assert_eq!(secrets["RELAY_PORT"], "8080");
+
+        let v1 = json!({"lease_duration": 2764800, "data": {"RELAY_ADMIN_TOKEN": "admin"}});
+        assert_eq!(
+            kv_secrets(&v1, 1).expect("kv v1")["RELAY_ADMIN_TOKEN"],
+            "admin"
+        );
+        assert!(kv_secrets(&v1, 2).is_err());
+        assert!(kv_secrets(&json!({"data": {"data": {"NESTED": {"a": 1}}}}), 2).is_err());
+    }
+
+    #[test]
+    fn renews_after_two_thirds_of_the_lease() {
+        let session = session_from_auth(&json!({
+            "auth": {"client_token": "hvs.token", "lease_duration": 3600, "renewable": true}
+        }))
+        .expect("session");
+        assert_eq!(session.token, "hvs.token");
+        assert!(session.renewable);
+        let renew_in = session.renew_in().expect("lease");
+        assert!(renew_in <= Duration::from_secs(2400) && renew_in > Duration::from_secs(2390));
+
+        let root =
+            session_from_auth(&json!({"auth": {"client_token": "root", "lease_duration": 0}}))
+                .expect("session");
+        assert_eq!(root.renew_in(), None);
+        assert!(session_from_auth(&json!({"data": {}})).is_err());
+    }
+
+    #[test]
+    fn vault_secrets_are_masked_in_logs_across_refreshes() {
+        let buffer = LogBuffer::default();
+        let sink = buffer.clone();
+        let redactor = Redactor::shared();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || redactor.writer(sink.clone()))
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            for value in ["vault-sourced-token-v1", "vault-sourced-token-v2"] {
+                apply_secrets(BTreeMap::from([(
+                    "RELAY_VAULT_TEST_TOKEN".to_string(),
+                    value.to_string(),
+                )]));
+                let token = relay_config::secret_env("RELAY_VAULT_TEST_TOKEN")
+                    .expect("read override")
+                    .expect("override set");
+                assert_eq!(token, value);
+                info!("delivering with {token}");
+            }
+        });
+        set_secret_overrides(BTreeMap::new());
+
+        let logged = String::from_utf8(buffer.0.lock().expect("log buffer").clone()).expect("utf8");
+        assert_eq!(logged.matches("delivering with [REDACTED]").count(), 2);
+        assert!(!logged.contains("vault-sourced"), "{logged}");
+    }
+}
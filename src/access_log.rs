@@ -0,0 +1,108 @@
+use crate::client_ip::TrustedClientIpKeyExtractor;
+use axum::body::HttpBody;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::header::CONTENT_LENGTH;
+use axum::middleware::Next;
+use axum::response::Response;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::time::Instant;
+use tracing::info;
+
+/// Logs one line per HTTP request. Successful requests are sampled at
+/// `sample_rate`; 4xx and 5xx responses are always logged so auth probes
+/// stay visible.
+#[derive(Debug, Clone)]
+pub struct AccessLog {
+    client_ip: TrustedClientIpKeyExtractor,
+    sampler: Arc<Sampler>,
+}
+
+impl AccessLog {
+    pub fn new(client_ip: TrustedClientIpKeyExtractor, sample_rate: f64) -> Self {
+        Self {
+            client_ip,
+            sampler: Arc::new(Sampler::new(sample_rate)),
+        }
+    }
+}
+
+pub async fn log_requests(
+    State(access_log): State<AccessLog>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let started = Instant::now();
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let request_bytes = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    let client_ip =
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(peer)| {
+                access_log
+                    .client_ip
+                    .client_ip(peer.ip(), request.headers())
+                    .to_string()
+            });
+
+    let response = next.run(request).await;
+
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() || access_log.sampler.sample() {
+        info!(
+            method = %method,
+            path = path.as_str(),
+            status = status.as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            client_ip = client_ip.as_deref().unwrap_or("unknown"),
+            request_bytes,
+            response_bytes = response.body().size_hint().exact(),
+            "http request"
+        );
+    }
+    response
+}
+
+/// Keeps an evenly spaced `rate` share of calls, without randomness.
+#[derive(Debug)]
+struct Sampler {
+    rate: f64,
+    seen: AtomicU64,
+}
+
+impl Sampler {
+    fn new(rate: f64) -> Self {
+        Self {
+            rate,
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    fn sample(&self) -> bool {
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+        ((seen + 1) as f64 * self.rate).floor() > (seen as f64 * self.rate).floor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampler_keeps_the_configured_share() {
+        let count = |rate: f64| {
+            let sampler = Sampler::new(rate);
+            (0..1_000).filter(|_| sampler.sample()).count()
+        };
+        assert_eq!(count(1.0), 1_000);
+        assert_eq!(count(0.1), 100);
+        assert_eq!(count(0.0), 0);
+    }
+}
@@ -0,0 +1,391 @@
+use crate::config::{Config, custom_secret_env};
+use relay_config::optional_env;
+use relay_core::redact::REDACTED;
+use relay_core::signatures::verify_bearer_token;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::env;
+
+pub fn admin_token_matches(expected: &str, provided: Option<&str>) -> bool {
+    provided.is_some_and(|provided| verify_bearer_token(expected, provided))
+}
+
+pub fn effective_config(config: &Config) -> Value {
+    let entries = [
+        ("RELAY_BIND", json!(config.bind_addr)),
+        ("RELAY_ADMIN_BIND", json!(config.admin_bind_addr)),
+        (
+            "RELAY_UNIX_SOCKET_MODE",
+            json!(format!("{:o}", config.unix_socket_mode)),
+        ),
+        ("RELAY_ENABLED_SOURCES", json!(config.enabled_sources)),
+        (
+            "RELAY_SOURCE_TOPIC_PREFIX",
+            json!(config.source_topic_prefix),
+        ),
+        ("RELAY_SOURCE_TOPICS", json!(config.relay_source_topics)),
+        ("KAFKA_BROKERS", json!(config.kafka_brokers)),
+        (
+            "KAFKA_SECURITY_PROTOCOL",
+            json!(config.kafka_security_protocol),
+        ),
+        ("KAFKA_ALLOW_PLAINTEXT", json!(config.kafka_allow_plaintext)),
+        ("KAFKA_TLS_CERT", json!(config.kafka_tls_cert)),
+        ("KAFKA_TLS_KEY", json!(config.kafka_tls_key)),
+        ("KAFKA_TLS_CA", json!(config.kafka_tls_ca)),
+        ("KAFKA_DLQ_TOPIC", json!(config.kafka_dlq_topic)),
+        (
+            "KAFKA_DLQ_RETENTION_BYTES",
+            json!(config.kafka_dlq_retention_bytes),
+        ),
+        (
+            "KAFKA_DLQ_RETENTION_MS",
+            json!(config.kafka_dlq_retention_ms),
+        ),
+        (
+            "KAFKA_AUTO_CREATE_TOPICS",
+            json!(config.kafka_auto_create_topics),
+        ),
+        (
+            "KAFKA_TOPIC_PARTITIONS",
+            json!(config.kafka_topic_partitions),
+        ),
+        ("KAFKA_PRODUCER_ACKS", json!(config.kafka_producer_acks)),
+        (
+            "KAFKA_PRODUCER_IDEMPOTENCE",
+            json!(config.kafka_producer_idempotence),
+        ),
+        (
+            "KAFKA_PRODUCER_DELIVERY_TIMEOUT_MS",
+            json!(config.kafka_producer_delivery_timeout_ms),
+        ),
+        (
+            "KAFKA_PRODUCER_RETRIES",
+            json!(config.kafka_producer_retries),
+        ),
+        (
+            "KAFKA_PRODUCER_COMPRESSION",
+            json!(config.kafka_producer_compression),
+        ),
+        (
+            "KAFKA_PRODUCER_TRANSACTIONAL_ID",
+            json!(config.kafka_producer_transactional_id),
+        ),
+        (
+            "KAFKA_PRODUCER_TRANSACTION_BATCH_SIZE",
+            json!(config.kafka_producer_transaction_batch_size),
+        ),
+        (
+            "KAFKA_TOPIC_REPLICATION_FACTOR",
+            json!(config.kafka_topic_replication_factor),
+        ),
+        ("HMAC_SECRET_GITHUB", masked(&config.hmac_secret_github)),
+        ("HMAC_SECRET_LINEAR", masked(&config.hmac_secret_linear)),
+        ("HMAC_SECRET_EXAMPLE", masked(&config.hmac_secret_example)),
+        (
+            "HMAC_SECRET_STANDARD_WEBHOOKS",
+            masked(&config.hmac_secret_standard_webhooks),
+        ),
+        ("HMAC_SECRET_GITLAB", masked(&config.hmac_secret_gitlab)),
+        (
+            "HMAC_SECRET_BITBUCKET",
+            masked(&config.hmac_secret_bitbucket),
+        ),
+        ("HMAC_SECRET_JIRA", masked(&config.hmac_secret_jira)),
+        ("HMAC_SECRET_STRIPE", masked(&config.hmac_secret_stripe)),
+        ("HMAC_SECRET_SENTRY", masked(&config.hmac_secret_sentry)),
+        ("HMAC_SECRET_GMAIL", masked(&config.hmac_secret_gmail)),
+        ("HMAC_SECRET_SHOPIFY", masked(&config.hmac_secret_shopify)),
+        ("HMAC_SECRET_REGISTRY", masked(&config.hmac_secret_registry)),
+        ("HMAC_SECRET_CI", masked(&config.hmac_secret_ci)),
+        ("RELAY_DISCORD_PUBLIC_KEY", json!(config.discord_public_key)),
+        (
+            "RELAY_STRIPE_TOLERANCE_SECONDS",
+            json!(config.stripe_tolerance_seconds),
+        ),
+        ("RELAY_PUBSUB_AUDIENCE", json!(config.pubsub_audience)),
+        (
+            "RELAY_PUBSUB_SERVICE_ACCOUNT",
+            json!(config.pubsub_service_account),
+        ),
+        ("RELAY_PUBSUB_JWKS_URL", json!(config.pubsub_jwks_url)),
+        ("RELAY_SNS_TOPIC_ARNS", json!(config.sns_topic_arns)),
+        (
+            "RELAY_SNS_TOLERANCE_SECONDS",
+            json!(config.sns_tolerance_seconds),
+        ),
+        (
+            "RELAY_STANDARD_WEBHOOKS_TOLERANCE_SECONDS",
+            json!(config.standard_webhooks_tolerance_seconds),
+        ),
+        ("RELAY_ADMIN_TOKEN", masked(&config.admin_token)),
+        (
+            "RELAY_SECRETS_RELOAD_SECONDS",
+            json!(config.secrets_reload_seconds),
+        ),
+        ("RELAY_GITHUB_ALLOW_SHA1", json!(config.github_allow_sha1)),
+        ("RELAY_MAX_PAYLOAD_BYTES", json!(config.max_payload_bytes)),
+        (
+            "RELAY_SOURCE_MAX_PAYLOAD_BYTES",
+            json!(config.source_max_payload_bytes),
+        ),
+        ("RELAY_SOURCE_PATHS", json!(config.source_paths)),
+        (
+            "RELAY_CUSTOM_SOURCES",
+            json!(
+                config
+                    .custom_sources
+                    .iter()
+                    .map(|(name, source)| {
+                        (
+                            name.clone(),
+                            format!("{}:{}", source.signature_header, source.algorithm.as_str()),
+                        )
+                    })
+                    .collect::<BTreeMap<_, _>>()
+            ),
+        ),
+        (
+            "RELAY_OVERSIZED_PAYLOAD_MODE",
+            json!(config.oversized_payload_mode.as_str()),
+        ),
+        ("RELAY_BLOB_DIR", json!(config.blob_dir)),
+        (
+            "RELAY_MAX_IN_FLIGHT_REQUESTS",
+            json!(config.max_in_flight_requests),
+        ),
+        ("RELAY_AUDIT_DIR", json!(config.audit_dir)),
+        ("RELAY_AUDIT_URL", json!(config.audit_url)),
+        ("RELAY_AUDIT_TOKEN", masked(&config.audit_token)),
+        ("RELAY_ACCESS_LOG", json!(config.access_log)),
+        (
+            "RELAY_ACCESS_LOG_SAMPLE_RATE",
+            json!(config.access_log_sample_rate),
+        ),
+        (
+            "RELAY_BLOB_THRESHOLD_BYTES",
+            json!(config.blob_threshold_bytes),
+        ),
+        (
+            "RELAY_IP_RATE_PER_MINUTE",
+            json!(config.ip_limit_per_minute),
+        ),
+        (
+            "RELAY_SOURCE_RATE_PER_MINUTE",
+            json!(config.source_limit_per_minute),
+        ),
+        (
+            "RELAY_TRUST_PROXY_HEADERS",
+            json!(config.trust_proxy_headers),
+        ),
+        ("RELAY_PROXY_PROTOCOL", json!(config.proxy_protocol)),
+        (
+            "RELAY_TRUSTED_PROXY_CIDRS",
+            json!(
+                config
+                    .trusted_proxy_cidrs
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            ),
+        ),
+        ("RELAY_DEDUP_TTL_SECONDS", json!(config.dedup_ttl_seconds)),
+        ("RELAY_COOLDOWN_SECONDS", json!(config.cooldown_seconds)),
+        (
+            "RELAY_CONTENT_DEDUP_TTL_SECONDS",
+            json!(config.content_dedup_ttl_seconds),
+        ),
+        ("RELAY_DEBOUNCE_WINDOW_MS", json!(config.debounce_window_ms)),
+        (
+            "RELAY_GITHUB_SYNCHRONIZE_COALESCE_MS",
+            json!(config.github_synchronize_coalesce_ms),
+        ),
+        ("RELAY_GITHUB_APP_ID", json!(config.github_app_id)),
+        (
+            "RELAY_GITHUB_APP_PRIVATE_KEY",
+            masked(&config.github_app_private_key),
+        ),
+        ("RELAY_GITHUB_API_URL", json!(config.github_api_url)),
+        (
+            "RELAY_ENFORCE_LINEAR_TIMESTAMP_WINDOW",
+            json!(config.enforce_linear_timestamp_window),
+        ),
+        (
+            "RELAY_LINEAR_TIMESTAMP_WINDOW_SECONDS",
+            json!(config.linear_timestamp_window_seconds),
+        ),
+        (
+            "RELAY_LINEAR_STATE_TRANSITIONS",
+            json!(
+                config
+                    .linear_state_transitions
+                    .iter()
+                    .map(|rule| format!(
+                        "{}->{}",
+                        rule.from.as_deref().unwrap_or("*"),
+                        rule.to.as_deref().unwrap_or("*")
+                    ))
+                    .collect::<Vec<_>>()
+            ),
+        ),
+        (
+            "RELAY_LINEAR_VERIFY_WITH_API",
+            json!(config.linear_verify_with_api),
+        ),
+        ("RELAY_LINEAR_API_TOKEN", masked(&config.linear_api_token)),
+        (
+            "RELAY_SAMPLING_RULES",
+            json!(
+                config
+                    .sampling_rules
+                    .iter()
+                    .map(|rule| match &rule.scope {
+                        Some(scope) =>
+                            format!("{}:{}@{scope}={}", rule.source, rule.event_type, rule.rate),
+                        None => format!("{}:{}={}", rule.source, rule.event_type, rule.rate),
+                    })
+                    .collect::<Vec<_>>()
+            ),
+        ),
+        (
+            "RELAY_PUBLISH_QUEUE_CAPACITY",
+            json!(config.publish_queue_capacity),
+        ),
+        (
+            "RELAY_PUBLISH_MAX_RETRIES",
+            json!(config.publish_max_retries),
+        ),
+        (
+            "RELAY_PUBLISH_BACKOFF_BASE_MS",
+            json!(config.publish_backoff_base_ms),
+        ),
+        (
+            "RELAY_PUBLISH_BACKOFF_MAX_MS",
+            json!(config.publish_backoff_max_ms),
+        ),
+        ("RELAY_PUBLISH_SPILL_DIR", json!(config.publish_spill_dir)),
+        (
+            "RELAY_PUBLISH_SPILL_REPLAY_SECONDS",
+            json!(config.publish_spill_replay_seconds),
+        ),
+        (
+            "RELAY_SHUTDOWN_DRAIN_SECONDS",
+            json!(config.shutdown_drain_seconds),
+        ),
+        ("RELAY_VALIDATION_MODE", json!(config.validation_mode)),
+        ("RELAY_PROFILE", json!(config.active_profile)),
+        ("RELAY_CONTRACT_PATH", json!(config.contract_path)),
+        (
+            "RELAY_ENQUEUE_SCRIPT_PATH",
+            json!(config.enqueue_script_path),
+        ),
+        ("RELAY_DIGEST_TOPIC", json!(config.digest_topic)),
+        ("RELAY_DIGEST_HOUR_UTC", json!(config.digest_hour_utc)),
+        ("RELAY_ALERT_TOPIC", json!(config.alert_topic)),
+        (
+            "RELAY_DLQ_ALERT_THRESHOLD",
+            json!(config.dlq_alert_threshold),
+        ),
+        (
+            "RELAY_DLQ_ALERT_WINDOW_SECONDS",
+            json!(config.dlq_alert_window_seconds),
+        ),
+        (
+            "RELAY_DLQ_ALERT_COOLDOWN_SECONDS",
+            json!(config.dlq_alert_cooldown_seconds),
+        ),
+        (
+            "RELAY_INGRESS_ADAPTER_ID",
+            json!(config.active_ingress_adapter_id),
+        ),
+        (
+            "RELAY_INGRESS_ADAPTERS_JSON",
+            json!({"count": config.ingress_adapters.len()}),
+        ),
+        (
+            "RELAY_SERVE_ROUTES_JSON",
+            json!(
+                config
+                    .serve_routes
+                    .iter()
+                    .map(|route| route.id.as_str())
+                    .collect::<Vec<_>>()
+            ),
+        ),
+    ];
+
+    let mut output = Map::new();
+    for (key, value) in entries {
+        output.insert(
+            key.to_string(),
+            json!({"value": value, "source": value_source(key)}),
+        );
+    }
+    output.insert(
+        "RELAY_INTERNAL_TOKENS".to_string(),
+        json!({
+            "value": config
+                .internal_tokens
+                .keys()
+                .map(|producer| format!("{producer}={REDACTED}"))
+                .collect::<Vec<_>>(),
+            "source": value_source("RELAY_INTERNAL_TOKENS"),
+        }),
+    );
+    // Env var names only; secret values never leave the process.
+    output.insert(
+        "RELAY_TENANTS_FILE".to_string(),
+        json!({
+            "value": config.tenants_file,
+            "source": value_source("RELAY_TENANTS_FILE"),
+            "tenants": config
+                .tenants
+                .iter()
+                .map(|tenant| {
+                    json!({
+                        "id": tenant.id,
+                        "cooldown_seconds": tenant.cooldown_seconds.unwrap_or(config.cooldown_seconds),
+                        "cooldown_source": if tenant.cooldown_seconds.is_some() { "tenant" } else { "RELAY_COOLDOWN_SECONDS" },
+                        "secret_envs": tenant.secret_envs,
+                        "gateway_url": tenant.gateway_url,
+                        "gateway_token_env": tenant.gateway_token_env,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        }),
+    );
+    // Smash's tenants file, when it shares serve's env.
+    output.insert(
+        "HOOK_TENANTS_FILE".to_string(),
+        json!({
+            "value": optional_env("HOOK_TENANTS_FILE"),
+            "source": value_source("HOOK_TENANTS_FILE"),
+        }),
+    );
+    for name in config.custom_sources.keys() {
+        let key = custom_secret_env(name);
+        let value = masked(&config.hmac_secret_custom.get(name).cloned());
+        output.insert(
+            key.clone(),
+            json!({"value": value, "source": value_source(&key)}),
+        );
+    }
+    Value::Object(output)
+}
+
+fn masked(value: &Option<String>) -> Value {
+    match value {
+        Some(_) => json!(REDACTED),
+        None => Value::Null,
+    }
+}
+
+/// Secrets read from `<key>_FILE` count as set from the environment.
+fn value_source(key: &str) -> &'static str {
+    let set = |name: &str| env::var(name).is_ok_and(|value| !value.trim().is_empty());
+    if set(key) || set(&format!("{key}_FILE")) {
+        "env"
+    } else {
+        "default"
+    }
+}
@@ -0,0 +1,134 @@
+use crate::clock::SharedClock;
+use crate::config::Config;
+use crate::digest::{DIGEST_SOURCE, dlq_depth};
+use crate::envelope::build_envelope;
+use crate::producer::PublishJob;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, MissedTickBehavior, interval};
+use tracing::{info, warn};
+
+pub const DLQ_GROWTH_EVENT_TYPE: &str = "relay.alert.dlq_growth";
+
+#[derive(Debug, Clone)]
+pub struct DlqGrowthMonitor {
+    threshold: i64,
+    window_seconds: i64,
+    cooldown_seconds: i64,
+    previous_depth: Option<i64>,
+    last_alert_at: Option<i64>,
+}
+
+impl DlqGrowthMonitor {
+    pub fn new(threshold: i64, window_seconds: i64, cooldown_seconds: i64) -> Self {
+        Self {
+            threshold,
+            window_seconds,
+            cooldown_seconds,
+            previous_depth: None,
+            last_alert_at: None,
+        }
+    }
+
+    pub fn observe(&mut self, now_epoch_seconds: i64, depth: i64) -> Option<Value> {
+        let previous = self.previous_depth.replace(depth)?;
+        let growth = depth.saturating_sub(previous);
+        if growth <= self.threshold {
+            return None;
+        }
+        if let Some(last_alert_at) = self.last_alert_at
+            && now_epoch_seconds - last_alert_at < self.cooldown_seconds
+        {
+            return None;
+        }
+
+        self.last_alert_at = Some(now_epoch_seconds);
+        Some(json!({
+            "growth": growth,
+            "threshold": self.threshold,
+            "window_seconds": self.window_seconds,
+            "dlq_depth": depth,
+        }))
+    }
+}
+
+pub async fn run_dlq_growth_worker(
+    config: Config,
+    topic: String,
+    threshold: i64,
+    publish_tx: mpsc::Sender<PublishJob>,
+    clock: SharedClock,
+) {
+    let mut monitor = DlqGrowthMonitor::new(
+        threshold,
+        config.dlq_alert_window_seconds,
+        config.dlq_alert_cooldown_seconds,
+    );
+    let mut ticker = interval(Duration::from_secs(
+        config.dlq_alert_window_seconds.unsigned_abs(),
+    ));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        let Some(depth) = dlq_depth(&config).await else {
+            continue;
+        };
+        let Some(mut payload) = monitor.observe(clock.now_epoch_seconds(), depth) else {
+            continue;
+        };
+        payload["dlq_topic"] = json!(config.kafka_dlq_topic);
+
+        warn!(
+            dlq_topic = config.kafka_dlq_topic.as_str(),
+            alert = %payload,
+            "dlq growth exceeded threshold"
+        );
+        let envelope = build_envelope(
+            DIGEST_SOURCE,
+            DLQ_GROWTH_EVENT_TYPE.to_string(),
+            payload,
+            None,
+        );
+        let event_id = envelope.id.clone();
+        let job = PublishJob {
+            topic: topic.clone(),
+            envelope,
+        };
+        match publish_tx.send(job).await {
+            Ok(()) => info!(
+                topic = topic.as_str(),
+                event_id = event_id.as_str(),
+                "queued dlq growth alert"
+            ),
+            Err(_) => {
+                warn!("publisher unavailable; stopping dlq growth worker");
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DlqGrowthMonitor;
+
+    #[test]
+    fn first_sample_only_sets_baseline() {
+        let mut monitor = DlqGrowthMonitor::new(5, 300, 3_600);
+        assert!(monitor.observe(1_000, 500).is_none());
+        assert!(monitor.observe(1_300, 505).is_none());
+        let alert = monitor.observe(1_600, 520).expect("growth above threshold");
+        assert_eq!(alert["growth"], 15);
+        assert_eq!(alert["dlq_depth"], 520);
+    }
+
+    #[test]
+    fn alerts_are_rate_limited_by_cooldown() {
+        let mut monitor = DlqGrowthMonitor::new(0, 60, 600);
+        monitor.observe(0, 0);
+        assert!(monitor.observe(60, 10).is_some());
+        assert!(monitor.observe(120, 20).is_none());
+        assert!(monitor.observe(660, 30).is_some());
+    }
+}
@@ -0,0 +1,2640 @@
+use crate::access_log::{AccessLog, log_requests};
+use crate::admin::{admin_token_matches, effective_config};
+use crate::alerts::run_dlq_growth_worker;
+use crate::audit::{AuditSink, audit_record};
+use crate::blob::BlobStore;
+use crate::build_info::{BuildInfo, VERSION};
+use crate::client_ip::{TrustedClientIpKeyExtractor, ip_bucket};
+use crate::clock::{SharedClock, SystemClock};
+use crate::config::{
+    Config, OversizedPayloadMode, RuntimeIngressAdapter, RuntimeServePluginConfig, ServeRouteRule,
+    SourceSecrets, load_tenant_secrets, report_problems,
+};
+use crate::debounce::Debouncer;
+use crate::digest::{ActivityOutcome, ActivityTracker, activity_scope, run_digest_worker};
+use crate::envelope::build_envelope;
+use crate::idempotency::{IdempotencyDecision, IdempotencyStore};
+use crate::listener::{BindTarget, BoundListener};
+use crate::metrics::{Metrics, SignatureResult};
+use crate::middleware::SourceRateLimiter;
+use crate::openapi::{self, ErrorBody, WebhookAccepted};
+use crate::producer::{KafkaPublisher, PublishJob, ensure_required_topics, run_publish_worker};
+use crate::sampling::sampled_out;
+use crate::script::{EnqueueScript, ScriptDecision};
+use crate::sources::pubsub::run_google_keys_refresher;
+use crate::sources::{
+    ConfirmError, QUERY_TOKEN_HEADER, SignatureKind, SourceServices, ValidationError, handler_for,
+    known_source_names, normalize_source_name,
+};
+use crate::spill::PublishSpill;
+use crate::truncate::truncate_payload;
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::error_handling::HandleErrorLayer;
+use axum::extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State};
+use axum::http::header::{CONTENT_TYPE, RETRY_AFTER};
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::{MethodRouter, get, post};
+use axum::{Json, Router};
+use chrono::{SecondsFormat, Utc};
+use futures_util::StreamExt;
+use rdkafka::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::message::Message;
+use relay_config::{optional_env, secret_env};
+use relay_core::keys::content_dedup_key;
+use relay_core::model::EventMeta;
+use relay_core::redact::register_loaded_secrets;
+use relay_core::sanitize::sanitize_payload;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{mpsc, watch};
+use tokio::time::{Duration, Instant, timeout};
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::{BoxError, ServiceBuilder};
+use tower_governor::GovernorLayer;
+use tower_governor::governor::GovernorConfigBuilder;
+use tracing::{Level, debug, error, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Clone)]
+struct AppState {
+    config: Config,
+    metrics: Arc<Metrics>,
+    client_ip: TrustedClientIpKeyExtractor,
+    secrets: Arc<RwLock<Arc<SourceSecrets>>>,
+    publish_tx: mpsc::Sender<PublishJob>,
+    source_rate_limiter: SourceRateLimiter,
+    idempotency_store: IdempotencyStore,
+    debouncer: Option<Debouncer>,
+    synchronize_coalescer: Option<Debouncer>,
+    blob_store: Option<BlobStore>,
+    audit_sink: Option<AuditSink>,
+    publish_spill: Option<PublishSpill>,
+    enqueue_script: Option<Arc<EnqueueScript>>,
+    source_services: Arc<SourceServices>,
+    clock: SharedClock,
+    activity: Arc<ActivityTracker>,
+    publish_worker_alive: Arc<AtomicBool>,
+    /// Set on SIGTERM; hook routes answer 503 until the listeners close.
+    draining: Arc<AtomicBool>,
+    http_ingress_adapter_id: Option<String>,
+    http_ingress_plugins: Vec<RuntimeServePluginConfig>,
+    websocket_ingress: Option<WebsocketIngressRuntime>,
+    mcp_ingress: Option<McpIngressRuntime>,
+}
+
+impl AppState {
+    fn source_secrets(&self) -> Arc<SourceSecrets> {
+        self.secrets
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Re-reads source secrets; the previous set stays active on error.
+    fn reload_secrets(&self) -> Result<()> {
+        let secrets = self.read_secrets()?;
+        self.swap_secrets(secrets);
+        info!("source secrets reloaded");
+        Ok(())
+    }
+
+    /// Like `reload_secrets`, but quiet when nothing changed.
+    fn refresh_secrets(&self) -> Result<()> {
+        let secrets = self.read_secrets()?;
+        if *self.source_secrets() != secrets {
+            self.swap_secrets(secrets);
+            info!("source secrets changed; reloaded");
+        }
+        Ok(())
+    }
+
+    fn read_secrets(&self) -> Result<SourceSecrets> {
+        let mut secrets =
+            SourceSecrets::load(&self.config.enabled_sources, &self.config.custom_sources)
+                .context("reload source secrets")?;
+        secrets.tenants = load_tenant_secrets(&self.config.tenants, &self.config.custom_sources)
+            .context("reload tenant secrets")?;
+        register_loaded_secrets();
+        Ok(secrets)
+    }
+
+    fn swap_secrets(&self, secrets: SourceSecrets) {
+        *self
+            .secrets
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(secrets);
+    }
+}
+
+const MAX_RAW_BODY_PREVIEW_CHARS: usize = 4_096;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const GITHUB_SYNCHRONIZE_EVENT: &str = "pull_request.synchronize";
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+const DEFAULT_ACTIVITY_LIMIT: usize = 20;
+/// Routes `RELAY_SOURCE_PATHS` entries may not shadow.
+const RESERVED_PATHS: &[&str] = &["/health", "/ready", "/version", "/metrics", "/openapi.json"];
+/// Each `RELAY_CUSTOM_SOURCES` name is also served at this prefix plus its name.
+const CUSTOM_SOURCE_PATH_PREFIX: &str = "/hooks/custom/";
+
+#[derive(Debug, Clone)]
+struct WebsocketIngressRuntime {
+    id: String,
+    path_template: String,
+    auth_mode: String,
+    auth_token: Option<String>,
+    plugins: Vec<RuntimeServePluginConfig>,
+}
+
+#[derive(Debug, Clone)]
+struct McpIngressRuntime {
+    id: String,
+    tool_name: String,
+    path: String,
+    auth_mode: String,
+    auth_token: Option<String>,
+    max_payload_bytes: usize,
+    plugins: Vec<RuntimeServePluginConfig>,
+}
+
+#[derive(Debug, Clone)]
+struct KafkaIngressRuntime {
+    id: String,
+    topics: Vec<String>,
+    group_id: String,
+    brokers: String,
+    plugins: Vec<RuntimeServePluginConfig>,
+}
+
+#[derive(Debug, Clone)]
+struct IngressRuntimeSelection {
+    http_path: String,
+    http_ingress_adapter_id: Option<String>,
+    http_ingress_plugins: Vec<RuntimeServePluginConfig>,
+    websocket_ingress: Option<WebsocketIngressRuntime>,
+    mcp_ingress: Option<McpIngressRuntime>,
+    kafka_ingress_adapters: Vec<KafkaIngressRuntime>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub(crate) struct McpIngestRequest {
+    source: String,
+    payload: Value,
+    #[serde(default)]
+    event_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsIngressFrame {
+    #[serde(default)]
+    payload: Option<Value>,
+    #[serde(default)]
+    event_type: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct EnqueueAccepted {
+    event_id: String,
+    topic: String,
+    event_type: String,
+}
+
+pub fn build_router(
+    config: Config,
+    publish_tx: mpsc::Sender<PublishJob>,
+    publish_worker_alive: Arc<AtomicBool>,
+) -> Result<Router> {
+    build_router_with_clock(
+        config,
+        publish_tx,
+        publish_worker_alive,
+        Arc::new(SystemClock),
+    )
+}
+
+pub fn build_router_with_clock(
+    config: Config,
+    publish_tx: mpsc::Sender<PublishJob>,
+    publish_worker_alive: Arc<AtomicBool>,
+    clock: SharedClock,
+) -> Result<Router> {
+    let (router, _admin_router, _state, _ingress_runtime) =
+        build_app(config, publish_tx, publish_worker_alive, clock)?;
+    Ok(router)
+}
+
+/// Runs every startup check that needs no network and reports all the
+/// problems found, one per line. Takes a config from `Config::load_env`;
+/// the app is only built once the config itself has no problems.
+pub fn check_config(config: &Config) -> Result<()> {
+    let mut problems = config
+        .problems()
+        .iter()
+        .map(|error| format!("{error:#}"))
+        .collect::<Vec<_>>();
+    let consistent = problems.is_empty();
+    let mut check = |result: Result<()>| {
+        if let Err(error) = result {
+            problems.push(format!("{error:#}"));
+        }
+    };
+
+    if config.kafka_security_protocol == "ssl" {
+        for (name, path) in [
+            ("KAFKA_TLS_CERT", &config.kafka_tls_cert),
+            ("KAFKA_TLS_KEY", &config.kafka_tls_key),
+            ("KAFKA_TLS_CA", &config.kafka_tls_ca),
+        ] {
+            // Missing paths are already reported by `Config::problems`.
+            if path.trim().is_empty() {
+                continue;
+            }
+            check(
+                std::fs::File::open(path)
+                    .map(drop)
+                    .with_context(|| format!("{name} '{path}' is not readable")),
+            );
+        }
+    }
+
+    for (name, url) in [
+        ("RELAY_PUBSUB_JWKS_URL", config.pubsub_jwks_url.as_str()),
+        ("RELAY_GITHUB_API_URL", config.github_api_url.as_str()),
+    ] {
+        check(check_http_url(name, url));
+    }
+    for (name, dir) in [
+        (
+            "RELAY_PUBLISH_SPILL_DIR",
+            config.publish_spill_dir.as_deref(),
+        ),
+        ("RELAY_BLOB_DIR", config.blob_dir.as_deref()),
+        ("RELAY_AUDIT_DIR", config.audit_dir.as_deref()),
+    ] {
+        if let Some(dir) = dir {
+            check(check_writable_dir(name, std::path::Path::new(dir)));
+        }
+    }
+
+    if consistent {
+        let (publish_tx, _publish_rx) = mpsc::channel(config.publish_queue_capacity);
+        check(
+            build_app(
+                config.clone(),
+                publish_tx,
+                Arc::new(AtomicBool::new(true)),
+                Arc::new(SystemClock),
+            )
+            .map(drop),
+        );
+    }
+
+    report_problems(problems)
+}
+
+/// Sends `HEAD` to the HTTP endpoints serve and smash will call: the audit
+/// sink, the GitHub API when an app is configured, and `OPENCLAW_WEBHOOK_URL`
+/// when set in the same env. Any response counts as reachable.
+pub async fn probe_endpoints(config: &Config) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .context("build probe client")?;
+    let openclaw_url = optional_env("OPENCLAW_WEBHOOK_URL");
+    let mut problems = Vec::new();
+    for (name, url) in [
+        ("RELAY_AUDIT_URL", config.audit_url.as_deref()),
+        (
+            "RELAY_GITHUB_API_URL",
+            config
+                .github_app_id
+                .as_ref()
+                .map(|_| config.github_api_url.as_str()),
+        ),
+        ("OPENCLAW_WEBHOOK_URL", openclaw_url.as_deref()),
+    ] {
+        let Some(url) = url else {
+            continue;
+        };
+        if let Err(error) = client.head(url).send().await {
+            problems.push(format!("{name} '{url}' is unreachable: {error}"));
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow::anyhow!(
+        "{} endpoint problem(s):\n  - {}",
+        problems.len(),
+        problems.join("\n  - ")
+    ))
+}
+
+fn check_http_url(name: &str, raw: &str) -> Result<()> {
+    let url = reqwest::Url::parse(raw).with_context(|| format!("{name} '{raw}' is not a URL"))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(anyhow::anyhow!("{name} '{raw}' must be an http(s) URL"));
+    }
+    Ok(())
+}
+
+/// Probes the directory, or the nearest existing parent serve would create
+/// it under, with a throwaway file.
+fn check_writable_dir(name: &str, dir: &std::path::Path) -> Result<()> {
+    let existing = dir
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(std::path::Path::new("."));
+    if !existing.is_dir() {
+        return Err(anyhow::anyhow!(
+            "{name} '{}' is not a directory",
+            existing.display()
+        ));
+    }
+    let probe = existing.join(format!(".hook-serve-check-{}", std::process::id()));
+    std::fs::write(&probe, b"")
+        .with_context(|| format!("{name} '{}' is not writable", dir.display()))?;
+    let _ = std::fs::remove_file(probe);
+    Ok(())
+}
+
+pub async fn run(config: Config) -> Result<()> {
+    let (publish_tx, publish_rx) = mpsc::channel(config.publish_queue_capacity);
+    let publish_worker_alive = Arc::new(AtomicBool::new(true));
+    let (app, admin_app, state, ingress_runtime) = build_app(
+        config,
+        publish_tx,
+        publish_worker_alive.clone(),
+        Arc::new(SystemClock),
+    )?;
+    register_loaded_secrets();
+
+    if state.config.kafka_security_protocol == "plaintext" {
+        warn!(
+            "kafka plaintext transport is enabled (KAFKA_ALLOW_PLAINTEXT=true); use only on trusted private links"
+        );
+    }
+    ensure_required_topics(&state.config)
+        .await
+        .context("ensure kafka topics")?;
+    let publisher =
+        KafkaPublisher::from_config(&state.config).context("initialize kafka producer")?;
+
+    let publish_spill = state.publish_spill.clone();
+    let spill_replay_every = Duration::from_secs(state.config.publish_spill_replay_seconds);
+    let (publish_shutdown_tx, publish_shutdown_rx) = watch::channel(false);
+    let publish_worker_handle = tokio::spawn(async move {
+        run_publish_worker(
+            publish_rx,
+            publisher,
+            publish_spill,
+            spill_replay_every,
+            publish_shutdown_rx,
+        )
+        .await;
+        publish_worker_alive.store(false, Ordering::SeqCst);
+    });
+
+    let digest_worker_handle = state.config.digest_topic.clone().map(|topic| {
+        info!(
+            topic = topic.as_str(),
+            hour_utc = state.config.digest_hour_utc,
+            "daily relay digest enabled"
+        );
+        tokio::spawn(run_digest_worker(
+            state.config.clone(),
+            topic,
+            state.activity.clone(),
+            state.publish_tx.clone(),
+            state.clock.clone(),
+        ))
+    });
+
+    let dlq_alert_handle = match (state.config.dlq_alert_threshold, state.config.alert_topic()) {
+        (Some(threshold), Some(topic)) => {
+            info!(
+                topic,
+                threshold,
+                window_seconds = state.config.dlq_alert_window_seconds,
+                "dlq growth alerts enabled"
+            );
+            Some(tokio::spawn(run_dlq_growth_worker(
+                state.config.clone(),
+                topic.to_string(),
+                threshold,
+                state.publish_tx.clone(),
+                state.clock.clone(),
+            )))
+        }
+        _ => None,
+    };
+
+    if state.config.is_source_enabled("pubsub") {
+        tokio::spawn(run_google_keys_refresher(
+            state.config.pubsub_jwks_url.clone(),
+        ));
+    }
+
+    let secret_reload_handle = spawn_secret_reloader(state.clone())?;
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let drain_handle = tokio::spawn(drain_on_shutdown_signal(state.clone(), stop_tx));
+
+    for kafka_ingress in ingress_runtime.kafka_ingress_adapters.clone() {
+        let state_for_worker = state.clone();
+        tokio::spawn(async move {
+            if let Err(error) = run_kafka_ingress_worker(state_for_worker, kafka_ingress).await {
+                warn!(error = %error, "kafka ingress worker exited");
+            }
+        });
+    }
+
+    let unix_socket_mode = state.config.unix_socket_mode;
+    let mut listeners = BoundListener::bind_all(
+        &BindTarget::parse_list(&state.config.bind_addr)?,
+        unix_socket_mode,
+    )
+    .await?;
+    if state.config.proxy_protocol {
+        listeners = listeners
+            .into_iter()
+            .map(BoundListener::with_proxy_protocol)
+            .collect::<Result<Vec<_>>>()
+            .context("enable RELAY_PROXY_PROTOCOL")?;
+    }
+
+    info!(
+        bind = %state.config.bind_addr,
+        http_path = ingress_runtime.http_path.as_str(),
+        websocket_ingress_path = ingress_runtime
+            .websocket_ingress
+            .as_ref()
+            .map(|adapter| adapter.path_template.as_str()),
+        mcp_ingress_path = ingress_runtime
+            .mcp_ingress
+            .as_ref()
+            .map(|adapter| adapter.path.as_str()),
+        trust_proxy_headers = state.config.trust_proxy_headers,
+        proxy_protocol = state.config.proxy_protocol,
+        trusted_proxy_cidrs = ?state.config.trusted_proxy_cidrs,
+        "hook serve listening"
+    );
+
+    let admin_listener = match state.config.admin_bind_addr.as_deref() {
+        Some(admin_bind_addr) => {
+            let admin_listener =
+                BoundListener::bind(&BindTarget::parse(admin_bind_addr)?, unix_socket_mode)
+                    .await
+                    .context("bind admin listener")?;
+            info!(bind = admin_bind_addr, "hook serve admin listening");
+            Some(admin_listener)
+        }
+        None => None,
+    };
+
+    tokio::try_join!(
+        async {
+            futures_util::future::try_join_all(listeners.into_iter().map(|listener| {
+                let app = app.clone();
+                let stop = stopped(stop_rx.clone());
+                async move { listener.serve(app, stop).await.context("serve hook serve") }
+            }))
+            .await
+            .map(|_| ())
+        },
+        async {
+            match (admin_listener, admin_app) {
+                (Some(admin_listener), Some(admin_app)) => admin_listener
+                    .serve(admin_app, stopped(stop_rx.clone()))
+                    .await
+                    .context("serve hook serve admin"),
+                _ => Ok(()),
+            }
+        },
+    )?;
+
+    secret_reload_handle.abort();
+    drain_handle.abort();
+    let _ = publish_shutdown_tx.send(true);
+    for handle in [digest_worker_handle, dlq_alert_handle]
+        .into_iter()
+        .flatten()
+    {
+        handle.abort();
+    }
+    drop(state);
+    match timeout(Duration::from_secs(30), publish_worker_handle).await {
+        Ok(Ok(())) => {}
+        Ok(Err(error)) => {
+            warn!(error = %error, "publish worker exited with join error");
+        }
+        Err(_) => {
+            warn!("timed out waiting for publish worker drain during shutdown");
+        }
+    }
+
+    Ok(())
+}
+
+fn build_app(
+    config: Config,
+    publish_tx: mpsc::Sender<PublishJob>,
+    publish_worker_alive: Arc<AtomicBool>,
+    clock: SharedClock,
+) -> Result<(
+    Router,
+    Option<Router>,
+    Arc<AppState>,
+    IngressRuntimeSelection,
+)> {
+    let ingress_runtime = resolve_ingress_runtime(&config).context("resolve ingress adapters")?;
+    ensure_enabled_sources_have_handlers(&config).context("validate enabled sources")?;
+    let enqueue_script = match config.enqueue_script_path.as_deref() {
+        Some(path) => Some(Arc::new(
+            EnqueueScript::from_file(std::path::Path::new(path)).context("load enqueue script")?,
+        )),
+        None => None,
+    };
+
+    let audit_sink = match (config.audit_dir.as_deref(), config.audit_url.as_deref()) {
+        (Some(dir), _) => Some(AuditSink::file(dir)),
+        (None, Some(url)) => Some(AuditSink::http(url, config.audit_token.clone())?),
+        (None, None) => None,
+    };
+
+    let source_services = Arc::new(SourceServices::from_config(&config)?);
+
+    let state = Arc::new(AppState {
+        source_rate_limiter: SourceRateLimiter::new(config.source_limit_per_minute),
+        idempotency_store: IdempotencyStore::new(config.dedup_ttl_seconds, config.cooldown_seconds)
+            .with_content_dedup(config.content_dedup_ttl_seconds),
+        debouncer: config
+            .debounce_window_ms
+            .map(|window_ms| Debouncer::new(Duration::from_millis(window_ms), publish_tx.clone())),
+        synchronize_coalescer: config
+            .github_synchronize_coalesce_ms
+            .map(|window_ms| Debouncer::new(Duration::from_millis(window_ms), publish_tx.clone())),
+        blob_store: config
+            .blob_dir
+            .as_deref()
+            .map(|dir| BlobStore::new(dir, config.blob_threshold_bytes)),
+        audit_sink,
+        publish_spill: config.publish_spill_dir.as_deref().map(PublishSpill::new),
+        enqueue_script,
+        source_services,
+        secrets: Arc::new(RwLock::new(Arc::new(config.source_secrets()))),
+        metrics: Arc::new(Metrics::new()),
+        client_ip: TrustedClientIpKeyExtractor::new(
+            config.trust_proxy_headers,
+            config.trusted_proxy_cidrs.clone(),
+        ),
+        activity: Arc::new(ActivityTracker::new(clock.now_epoch_seconds())),
+        clock,
+        config,
+        publish_tx,
+        publish_worker_alive,
+        draining: Arc::new(AtomicBool::new(false)),
+        http_ingress_adapter_id: ingress_runtime.http_ingress_adapter_id.clone(),
+        http_ingress_plugins: ingress_runtime.http_ingress_plugins.clone(),
+        websocket_ingress: ingress_runtime.websocket_ingress.clone(),
+        mcp_ingress: ingress_runtime.mcp_ingress.clone(),
+    });
+
+    let period_ms = ip_refill_period_ms(state.config.ip_limit_per_minute);
+    let mut governor_builder = GovernorConfigBuilder::default()
+        .key_extractor(state.client_ip.clone())
+        .use_headers();
+    governor_builder
+        .per_millisecond(period_ms)
+        .burst_size(state.config.ip_limit_per_minute)
+        .methods(vec![Method::POST]);
+    let governor_config = Arc::new(
+        governor_builder
+            .finish()
+            .ok_or_else(|| anyhow::anyhow!("build governor config"))?,
+    );
+
+    let openapi_document = Json(openapi::document(
+        &ingress_runtime.http_path,
+        ingress_runtime
+            .mcp_ingress
+            .as_ref()
+            .map(|mcp_ingress| mcp_ingress.path.as_str()),
+    ));
+    let mut operator_routes = Router::new()
+        .route("/version", get(version))
+        .route("/metrics", get(metrics))
+        .route(
+            "/openapi.json",
+            get(move || async move { openapi_document }),
+        );
+    if state.config.admin_token.is_some() {
+        operator_routes = operator_routes
+            .route("/admin/config", get(admin_config))
+            .route("/admin/activity", get(admin_activity))
+            .route("/admin/reload-secrets", post(admin_reload_secrets));
+    }
+
+    let mut hook_routes =
+        Router::new().route(ingress_runtime.http_path.as_str(), post(webhook_handler));
+    if let Some(mcp_ingress) = ingress_runtime.mcp_ingress.as_ref() {
+        hook_routes = hook_routes.route(mcp_ingress.path.as_str(), post(mcp_ingest_handler));
+    }
+    if !state.config.tenants.is_empty() {
+        let tenant_path = ingress_runtime
+            .http_path
+            .replace("{source}", "{tenant}/{source}");
+        hook_routes = hook_routes.route(&tenant_path, post(tenant_webhook_handler));
+    }
+    for (source, path) in &state.config.source_paths {
+        let taken = path == &ingress_runtime.http_path
+            || ingress_runtime
+                .mcp_ingress
+                .as_ref()
+                .is_some_and(|mcp_ingress| &mcp_ingress.path == path)
+            || RESERVED_PATHS.contains(&path.as_str())
+            || path.starts_with("/admin/");
+        if taken {
+            return Err(anyhow::anyhow!(
+                "RELAY_SOURCE_PATHS path '{path}' for {source} collides with a built-in route"
+            ));
+        }
+        hook_routes = hook_routes.route(path, fixed_source_route(source.clone()));
+    }
+    for name in state.config.custom_sources.keys() {
+        let path = format!("{CUSTOM_SOURCE_PATH_PREFIX}{name}");
+        match state
+            .config
+            .source_paths
+            .iter()
+            .find(|(_, taken)| **taken == path)
+        {
+            Some((source, _)) if source == name => continue,
+            Some((source, _)) => {
+                return Err(anyhow::anyhow!(
+                    "RELAY_SOURCE_PATHS path '{path}' for {source} collides with custom source {name}"
+                ));
+            }
+            None => {}
+        }
+        hook_routes = hook_routes.route(&path, fixed_source_route(name.clone()));
+    }
+    if let Some(max_in_flight) = state.config.max_in_flight_requests {
+        // One semaphore across all hook routes; requests over the limit are
+        // shed before their body is read.
+        hook_routes = hook_routes.route_layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(|_: BoxError| async {
+                    (
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        [(RETRY_AFTER, "1")],
+                        Json(json!({"error": "overloaded"})),
+                    )
+                }))
+                .load_shed()
+                .layer(GlobalConcurrencyLimitLayer::new(max_in_flight)),
+        );
+    }
+
+    hook_routes = hook_routes.route_layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        reject_while_draining,
+    ));
+    let mut app = hook_routes
+        .route("/health", get(health))
+        .route("/ready", get(ready));
+    let admin_app = if state.config.admin_bind_addr.is_some() {
+        Some(
+            operator_routes
+                .route("/health", get(health))
+                .route("/ready", get(ready))
+                .with_state(state.clone()),
+        )
+    } else {
+        app = app.merge(operator_routes);
+        None
+    };
+    if let Some(websocket_ingress) = ingress_runtime.websocket_ingress.as_ref() {
+        app = app.route(
+            websocket_ingress.path_template.as_str(),
+            get(websocket_ingress_handler),
+        );
+    }
+    let mut app = app
+        .layer(DefaultBodyLimit::max(state.config.max_payload_bytes))
+        .layer(GovernorLayer::new(governor_config))
+        .with_state(state.clone());
+    let mut admin_app = admin_app;
+    if state.config.access_log {
+        let access_log =
+            AccessLog::new(state.client_ip.clone(), state.config.access_log_sample_rate);
+        app = app.layer(axum::middleware::from_fn_with_state(
+            access_log.clone(),
+            log_requests,
+        ));
+        admin_app = admin_app.map(|admin_app| {
+            admin_app.layer(axum::middleware::from_fn_with_state(
+                access_log,
+                log_requests,
+            ))
+        });
+    }
+
+    Ok((app, admin_app, state, ingress_runtime))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhook/{source}",
+    tag = "ingest",
+    params(
+        ("source" = String, Path, description = "Enabled source name, e.g. `github`."),
+        ("token" = Option<String>, Query, description = "Shared token for sources that authenticate through the URL, e.g. `gmail`."),
+    ),
+    request_body(
+        content = Value,
+        content_type = "application/json",
+        description = "Provider payload, signed with the provider's scheme. `application/x-www-form-urlencoded` is accepted where the provider uses it."
+    ),
+    responses(
+        (status = 200, description = "Published, or deliberately ignored (`WebhookIgnored`)", body = WebhookAccepted),
+        (status = 400, description = "Malformed request or payload", body = ErrorBody),
+        (status = 401, description = "Signature or payload validation failed", body = ErrorBody),
+        (status = 404, description = "Source unknown or disabled", body = ErrorBody),
+        (status = 413, description = "Body exceeds the source payload limit", body = ErrorBody),
+        (status = 429, description = "Per-IP or per-source rate limit exceeded", body = ErrorBody),
+        (status = 503, description = "Publish queue full or publisher down", body = ErrorBody),
+    )
+)]
+async fn webhook_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    Path(source_path): Path<String>,
+    Query(query): Query<WebhookQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    handle_webhook(state, remote_addr, None, source_path, query, headers, body).await
+}
+
+/// `/webhook/{tenant}/{source}`: verified with the tenant's own secrets.
+async fn tenant_webhook_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    Path((tenant, source_path)): Path<(String, String)>,
+    Query(query): Query<WebhookQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    handle_webhook(
+        state,
+        remote_addr,
+        Some(tenant),
+        source_path,
+        query,
+        headers,
+        body,
+    )
+    .await
+}
+
+/// Route for a `RELAY_SOURCE_PATHS` entry: a static path bound to one source.
+fn fixed_source_route(source: String) -> MethodRouter<Arc<AppState>> {
+    post(
+        move |State(state): State<Arc<AppState>>,
+              ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+              Query(query): Query<WebhookQuery>,
+              headers: HeaderMap,
+              body: Bytes| async move {
+            handle_webhook(state, remote_addr, None, source, query, headers, body).await
+        },
+    )
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WebhookQuery {
+    token: Option<String>,
+}
+
+async fn handle_webhook(
+    state: Arc<AppState>,
+    remote_addr: SocketAddr,
+    tenant: Option<String>,
+    source_path: String,
+    query: WebhookQuery,
+    mut headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<Value>) {
+    headers.remove(QUERY_TOKEN_HEADER);
+    if let Some(token) = query
+        .token
+        .and_then(|token| HeaderValue::from_str(&token).ok())
+    {
+        headers.insert(QUERY_TOKEN_HEADER, token);
+    }
+    let mut scope = None;
+    let (status, Json(response)) = process_webhook(
+        &state,
+        remote_addr,
+        tenant.as_deref(),
+        &source_path,
+        headers,
+        body,
+        &mut scope,
+    )
+    .await;
+    if let Some(source) = normalize_source_name(&source_path)
+        && state.config.is_source_enabled(&source)
+    {
+        let outcome = activity_outcome(status, &response);
+        if let Some(tenant) = tenant
+            .as_deref()
+            .filter(|_| status != StatusCode::NOT_FOUND)
+        {
+            state
+                .metrics
+                .record_tenant_request(tenant, &source, outcome.as_str());
+        }
+        state.activity.record(&source, outcome);
+        if let Some(scope) = scope {
+            state
+                .activity
+                .record_scope_outcome(&scope, outcome, state.clock.now_epoch_seconds());
+        }
+    }
+    (status, Json(response))
+}
+
+fn activity_outcome(status: StatusCode, response: &Value) -> ActivityOutcome {
+    if !status.is_success() {
+        return ActivityOutcome::Rejected;
+    }
+    match response.get("status").and_then(Value::as_str) {
+        Some("ignored") => ActivityOutcome::Dropped,
+        _ => ActivityOutcome::Forwarded,
+    }
+}
+
+async fn process_webhook(
+    state: &Arc<AppState>,
+    remote_addr: SocketAddr,
+    tenant: Option<&str>,
+    source_path: &str,
+    headers: HeaderMap,
+    body: Bytes,
+    scope: &mut Option<String>,
+) -> (StatusCode, Json<Value>) {
+    let Some(normalized_source) = normalize_source_name(source_path) else {
+        return (StatusCode::NOT_FOUND, Json(json!({"error":"not found"})));
+    };
+    if !state.config.is_source_enabled(&normalized_source) {
+        return (StatusCode::NOT_FOUND, Json(json!({"error":"not found"})));
+    }
+    let Some(handler) = handler_for(&state.config, &normalized_source) else {
+        return (StatusCode::NOT_FOUND, Json(json!({"error":"not found"})));
+    };
+    let source = handler.source_name();
+    let all_secrets = state.source_secrets();
+    let secrets = match tenant {
+        Some(tenant) => match all_secrets.tenants.get(tenant).filter(|_| {
+            state
+                .config
+                .tenant(tenant)
+                .is_some_and(|config| config.secret_envs.contains_key(source))
+        }) {
+            Some(secrets) => secrets,
+            None => return (StatusCode::NOT_FOUND, Json(json!({"error":"not found"}))),
+        },
+        None => all_secrets.as_ref(),
+    };
+    let now_epoch_seconds = state.clock.now_epoch_seconds();
+    let client_ip = state.client_ip.client_ip(remote_addr.ip(), &headers);
+    info!(
+        source,
+        tenant,
+        client_ip = %client_ip,
+        body_bytes = body.len(),
+        "webhook request received"
+    );
+
+    if !state.source_rate_limiter.allow(source, now_epoch_seconds) {
+        warn!(
+            source,
+            client_ip = %client_ip,
+            "source rate limit exceeded"
+        );
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({"error":"source rate limit exceeded"})),
+        );
+    }
+
+    let max_payload_bytes = state.config.max_payload_bytes_for(source);
+    if body.len() > max_payload_bytes
+        && state.config.oversized_payload_mode == OversizedPayloadMode::Reject
+    {
+        warn!(
+            source,
+            client_ip = %client_ip,
+            body_bytes = body.len(),
+            max_payload_bytes,
+            "webhook body exceeds source payload limit"
+        );
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(json!({"error":"payload too large"})),
+        );
+    }
+
+    handler
+        .prepare(&state.config, &body, now_epoch_seconds)
+        .await;
+
+    let verify_started = Instant::now();
+    let verification =
+        handler.validate_request(&state.config, secrets, &headers, &body, now_epoch_seconds);
+    record_signature_metrics(
+        state,
+        source,
+        client_ip,
+        &verification,
+        verify_started.elapsed(),
+    );
+    if let Err(error) = verification {
+        match error {
+            ValidationError::Unauthorized(message) => {
+                warn!(
+                    source,
+                    client_ip = %client_ip,
+                    reason = message,
+                    "webhook authentication failed"
+                );
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(json!({"error":"unauthorized"})),
+                );
+            }
+            ValidationError::BadRequest(message) => {
+                return (StatusCode::BAD_REQUEST, Json(json!({"error": message})));
+            }
+        }
+    }
+
+    let mut payload = match handler.parse_payload(&headers, &body) {
+        Ok(payload) => payload,
+        Err(error) => {
+            if tracing::enabled!(Level::DEBUG) {
+                debug!(
+                    source,
+                    client_ip = %client_ip,
+                    raw_body = %body_utf8_preview(&body, MAX_RAW_BODY_PREVIEW_CHARS),
+                    "failed to parse webhook json payload"
+                );
+            }
+            let message = match error {
+                ValidationError::BadRequest(message) | ValidationError::Unauthorized(message) => {
+                    message
+                }
+            };
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": message})));
+        }
+    };
+    debug!(
+        source,
+        client_ip = %client_ip,
+        webhook_payload = %payload,
+        "parsed webhook payload"
+    );
+    *scope = activity_scope(source, &payload);
+
+    if let Err(error) = handler.validate_payload(&state.config, &payload, now_epoch_seconds) {
+        match error {
+            ValidationError::Unauthorized(message) => {
+                warn!(
+                    source,
+                    client_ip = %client_ip,
+                    reason = message,
+                    "webhook payload validation failed"
+                );
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(json!({"error":"unauthorized"})),
+                );
+            }
+            ValidationError::BadRequest(message) => {
+                return (StatusCode::BAD_REQUEST, Json(json!({"error": message})));
+            }
+        }
+    }
+
+    match handler.confirm(&state.config, &payload).await {
+        Ok(()) => {}
+        Err(ConfirmError::Unconfirmed(reason)) => {
+            warn!(
+                source,
+                client_ip = %client_ip,
+                reason,
+                "webhook not confirmed by the provider"
+            );
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error":"unauthorized"})),
+            );
+        }
+        Err(ConfirmError::Unavailable(message, error)) => {
+            warn!(
+                source,
+                error = %format!("{error:#}"),
+                "webhook confirmation with the provider failed"
+            );
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({"error": message})),
+            );
+        }
+    }
+
+    if let Some(reply) = handler.reply(&payload) {
+        debug!(source, "answered webhook handshake without publishing");
+        return (StatusCode::OK, Json(reply));
+    }
+
+    let event_type = match handler.event_type(&headers, &payload) {
+        Ok(event_type) => event_type,
+        Err(ValidationError::BadRequest(message)) => {
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": message})));
+        }
+        Err(ValidationError::Unauthorized(_)) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "unauthorized"})),
+            );
+        }
+    };
+    debug!(
+        source,
+        event_type = event_type.as_str(),
+        "derived webhook event type"
+    );
+
+    if !handler.should_forward(&state.config, &event_type, &payload) {
+        info!(
+            source,
+            event_type = event_type.as_str(),
+            "ignored webhook filtered by source rules"
+        );
+        return (
+            StatusCode::OK,
+            Json(json!({"status":"ignored","reason":"filtered"})),
+        );
+    }
+    if let Some(entity_key) = handler.cooldown_key(&payload)
+        && sampled_out(
+            &state.config.sampling_rules,
+            source,
+            &event_type,
+            &payload,
+            &entity_key,
+        )
+    {
+        info!(
+            source,
+            event_type = event_type.as_str(),
+            entity_key = entity_key.as_str(),
+            "ignored webhook sampled out"
+        );
+        return (
+            StatusCode::OK,
+            Json(json!({"status":"ignored","reason":"sampled"})),
+        );
+    }
+    let priority = match evaluate_enqueue_script(state, source, &event_type, &payload) {
+        Ok(priority) => priority,
+        Err(response) => return response,
+    };
+
+    let dedup_key = match handler.sender_dedup_key(secrets, &headers, &payload) {
+        Ok(key) => key,
+        Err(ValidationError::BadRequest(message)) => {
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": message})));
+        }
+        Err(ValidationError::Unauthorized(_)) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "unauthorized"})),
+            );
+        }
+    };
+    let cooldown_key = handler.cooldown_key(&payload);
+    let content_key = state
+        .idempotency_store
+        .content_dedup_enabled()
+        .then(|| content_dedup_key(source, &payload));
+    // Tenants get their own key space, so one team's deliveries never
+    // dedup or cool down another's.
+    let (dedup_key, cooldown_key, content_key) = match tenant {
+        Some(tenant) => (
+            format!("{tenant}/{dedup_key}"),
+            cooldown_key.map(|key| format!("{tenant}/{key}")),
+            content_key.map(|key| format!("{tenant}/{key}")),
+        ),
+        None => (dedup_key, cooldown_key, content_key),
+    };
+    let cooldown_seconds = tenant
+        .and_then(|tenant| state.config.tenant(tenant))
+        .and_then(|tenant| tenant.cooldown_seconds)
+        .unwrap_or(state.config.cooldown_seconds);
+    debug!(
+        source,
+        dedup_key = dedup_key.as_str(),
+        content_key = ?content_key,
+        cooldown_key = ?cooldown_key,
+        "computed idempotency keys"
+    );
+    // Held events are grouped by their cooldown key instead of being
+    // dropped by the cooldown.
+    let held_by = debouncer_for(state, source, &event_type, cooldown_key.as_deref());
+    let cooldown_check_key = cooldown_key.as_deref().filter(|_| held_by.is_none());
+    match state.idempotency_store.check_with_cooldown(
+        &dedup_key,
+        content_key.as_deref(),
+        cooldown_check_key,
+        cooldown_seconds,
+        now_epoch_seconds,
+    ) {
+        IdempotencyDecision::Accept => {}
+        IdempotencyDecision::Duplicate => {
+            info!(
+                source,
+                dedup_key = dedup_key.as_str(),
+                "ignored duplicate webhook delivery"
+            );
+            return (
+                StatusCode::OK,
+                Json(json!({"status":"ignored","reason":"duplicate"})),
+            );
+        }
+        IdempotencyDecision::DuplicateContent => {
+            info!(
+                source,
+                dedup_key = dedup_key.as_str(),
+                content_key = ?content_key,
+                "ignored webhook with duplicate payload content"
+            );
+            return (
+                StatusCode::OK,
+                Json(json!({"status":"ignored","reason":"duplicate_content"})),
+            );
+        }
+        IdempotencyDecision::Cooldown => {
+            info!(
+                source,
+                cooldown_key = ?cooldown_key,
+                "ignored webhook due to cooldown"
+            );
+            return (
+                StatusCode::OK,
+                Json(json!({"status":"ignored","reason":"cooldown"})),
+            );
+        }
+    }
+
+    if let Err(error) = handler.enrich(&state.source_services, &mut payload).await {
+        warn!(source, error = %format!("{error:#}"), "webhook enrichment failed");
+    }
+
+    let sanitized_payload = match handler.sanitize(&payload) {
+        Ok(sanitized_payload) => sanitized_payload,
+        Err(error) => {
+            warn!(
+                source,
+                client_ip = %client_ip,
+                reason = %error,
+                "payload sanitizer rejected request"
+            );
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error":"invalid payload"})),
+            );
+        }
+    };
+    debug!(
+        source,
+        sanitized_payload = %sanitized_payload,
+        "sanitized webhook payload"
+    );
+
+    let (event_type, mut sanitized_payload, plugin_flags) =
+        match apply_serve_plugins(&state.http_ingress_plugins, event_type, sanitized_payload) {
+            Ok(output) => output,
+            Err(error) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"error": error.to_string()})),
+                );
+            }
+        };
+
+    if state.config.oversized_payload_mode == OversizedPayloadMode::Truncate
+        && let Some(original_bytes) = truncate_payload(&mut sanitized_payload, max_payload_bytes)
+    {
+        warn!(
+            source,
+            event_type = event_type.as_str(),
+            original_bytes,
+            max_payload_bytes,
+            "webhook payload truncated to source payload limit"
+        );
+    }
+
+    let matched_route = match resolve_serve_route(&state.config, source, event_type.as_str()) {
+        Some(route) => Some(route),
+        None if state.config.serve_routes.is_empty() => None,
+        None => {
+            warn!(
+                source,
+                event_type = event_type.as_str(),
+                "no matching serve route for inbound event"
+            );
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error":"no matching serve route"})),
+            );
+        }
+    };
+    let route_key = matched_route.map(|route| route.id.clone());
+    let topic = matched_route
+        .map(|route| route.target_topic.clone())
+        .unwrap_or_else(|| handler.topic_name(&state.config));
+
+    let trace_id = if route_key.is_some() || state.http_ingress_adapter_id.is_some() {
+        Some(Uuid::new_v4().to_string())
+    } else {
+        None
+    };
+    let event_meta = with_tenant(
+        with_priority(
+            build_event_meta(
+                trace_id.clone(),
+                state.http_ingress_adapter_id.clone(),
+                route_key.clone(),
+                plugin_flags,
+            ),
+            priority,
+        ),
+        tenant,
+    );
+    let envelope_source = handler
+        .envelope_source(secrets, &headers)
+        .unwrap_or_else(|| source.to_string());
+    let mut envelope = build_envelope(&envelope_source, event_type, sanitized_payload, event_meta);
+    record_audit(state, source, &envelope.id, &payload, &envelope.payload);
+    if let Some(blob_store) = &state.blob_store {
+        match blob_store
+            .offload(source, &envelope.id, &mut envelope.payload)
+            .await
+        {
+            Ok(Some(path)) => info!(
+                source,
+                event_id = envelope.id.as_str(),
+                path = %path.display(),
+                "offloaded oversized payload to blob store"
+            ),
+            Ok(None) => {}
+            Err(error) => warn!(
+                source,
+                event_id = envelope.id.as_str(),
+                error = %error,
+                "blob offload failed; publishing full payload"
+            ),
+        }
+    }
+    debug!(
+        source,
+        topic = topic.as_str(),
+        route_key = ?route_key,
+        trace_id = ?trace_id,
+        envelope_json = %to_json_string(&envelope),
+        "prepared kafka publish envelope"
+    );
+
+    let event_id = envelope.id.clone();
+    let event_type_for_log = envelope.event_type.clone();
+    let topic_for_log = topic.clone();
+    let publish_job = PublishJob { topic, envelope };
+    if let (Some(debouncer), Some(cooldown_key)) = (held_by, cooldown_key.as_deref()) {
+        let outcome = debouncer.hold(cooldown_key, publish_job);
+        info!(
+            source,
+            event_type = event_type_for_log.as_str(),
+            event_id = event_id.as_str(),
+            cooldown_key,
+            outcome = ?outcome,
+            "webhook event held for debounce"
+        );
+        return (
+            StatusCode::OK,
+            Json(json!({"status":"ok","id": event_id,"debounced": true})),
+        );
+    }
+    match state.publish_tx.try_send(publish_job) {
+        Ok(()) => {
+            info!(
+                source,
+                event_type = event_type_for_log.as_str(),
+                topic = topic_for_log.as_str(),
+                event_id = event_id.as_str(),
+                route_key = ?route_key,
+                trace_id = ?trace_id,
+                client_ip = %client_ip,
+                "webhook event accepted and queued for kafka publish"
+            );
+            (StatusCode::OK, Json(json!({"status":"ok","id": event_id})))
+        }
+        Err(mpsc::error::TrySendError::Full(job) | mpsc::error::TrySendError::Closed(job))
+            if spill_job(state, &job).await =>
+        {
+            warn!(
+                source,
+                topic = topic_for_log.as_str(),
+                event_id = event_id.as_str(),
+                "publisher queue unavailable; webhook envelope spilled to disk"
+            );
+            (
+                StatusCode::OK,
+                Json(json!({"status":"ok","id": event_id,"spilled": true})),
+            )
+        }
+        Err(mpsc::error::TrySendError::Full(_)) => {
+            warn!(
+                source,
+                topic = topic_for_log.as_str(),
+                event_id = event_id.as_str(),
+                "failed to enqueue webhook envelope: publisher queue is full"
+            );
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({"error":"publisher queue is full"})),
+            )
+        }
+        Err(mpsc::error::TrySendError::Closed(_)) => {
+            warn!(
+                source,
+                topic = topic_for_log.as_str(),
+                event_id = event_id.as_str(),
+                "failed to enqueue webhook envelope: publisher unavailable"
+            );
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({"error":"publisher unavailable"})),
+            )
+        }
+    }
+}
+
+/// PR pushes get their own coalescing window so a rebase storm collapses
+/// into one event without debouncing every other event type.
+fn debouncer_for<'a>(
+    state: &'a AppState,
+    source: &str,
+    event_type: &str,
+    cooldown_key: Option<&str>,
+) -> Option<&'a Debouncer> {
+    cooldown_key?;
+    if source == "github" && event_type == GITHUB_SYNCHRONIZE_EVENT {
+        if let Some(coalescer) = &state.synchronize_coalescer {
+            return Some(coalescer);
+        }
+    }
+    state.debouncer.as_ref()
+}
+
+async fn websocket_ingress_handler(
+    State(state): State<Arc<AppState>>,
+    Path(source_path): Path<String>,
+    headers: HeaderMap,
+    websocket: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let Some(adapter) = state.websocket_ingress.clone() else {
+        return (StatusCode::NOT_FOUND, Json(json!({"error":"not found"}))).into_response();
+    };
+    if !authorize_adapter_request(
+        &headers,
+        adapter.auth_mode.as_str(),
+        adapter.auth_token.as_deref(),
+    ) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error":"unauthorized"})),
+        )
+            .into_response();
+    }
+
+    websocket
+        .on_upgrade(move |socket| {
+            run_websocket_ingress_session(state, socket, source_path, adapter)
+        })
+        .into_response()
+}
+
+async fn run_websocket_ingress_session(
+    state: Arc<AppState>,
+    mut socket: WebSocket,
+    source: String,
+    adapter: WebsocketIngressRuntime,
+) {
+    while let Some(frame_result) = socket.next().await {
+        match frame_result {
+            Ok(WsMessage::Text(text)) => {
+                let parsed_frame = parse_ws_frame_payload(text.as_ref());
+                let response = match parsed_frame {
+                    Ok((payload, event_type)) => match enqueue_prevalidated_event(
+                        &state,
+                        source.as_str(),
+                        payload,
+                        event_type,
+                        Some(adapter.id.clone()),
+                        &adapter.plugins,
+                    )
+                    .await
+                    {
+                        Ok(accepted) => json!({
+                            "status": "ok",
+                            "event_id": accepted.event_id,
+                            "kafka_topic": accepted.topic,
+                        }),
+                        Err(error) => json!({
+                            "status": "error",
+                            "message": error.to_string(),
+                        }),
+                    },
+                    Err(error) => json!({
+                        "status": "error",
+                        "message": error.to_string(),
+                    }),
+                };
+                if socket
+                    .send(WsMessage::Text(response.to_string().into()))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            Ok(WsMessage::Close(_)) => break,
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/mcp/ingest",
+    tag = "ingest",
+    description = "Mounted at the `mcp_ingest_exposed` adapter's `path`, only when that adapter is configured.",
+    request_body = McpIngestRequest,
+    responses(
+        (status = 200, description = "Event queued for Kafka", body = Value),
+        (status = 400, description = "Invalid source or payload", body = ErrorBody),
+        (status = 401, description = "Adapter token missing or wrong", body = ErrorBody),
+        (status = 413, description = "Payload exceeds the adapter limit", body = ErrorBody),
+    )
+)]
+async fn mcp_ingest_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<McpIngestRequest>,
+) -> impl IntoResponse {
+    let Some(adapter) = state.mcp_ingress.clone() else {
+        return (StatusCode::NOT_FOUND, Json(json!({"error":"not found"})));
+    };
+    if !authorize_adapter_request(
+        &headers,
+        adapter.auth_mode.as_str(),
+        adapter.auth_token.as_deref(),
+    ) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error":"unauthorized"})),
+        );
+    }
+
+    let payload_bytes = request.payload.to_string().len();
+    if payload_bytes > adapter.max_payload_bytes {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(json!({"error":"payload exceeds adapter max_payload_bytes"})),
+        );
+    }
+
+    let accepted = match enqueue_prevalidated_event(
+        &state,
+        request.source.as_str(),
+        request.payload,
+        request.event_type,
+        Some(adapter.id),
+        &adapter.plugins,
+    )
+    .await
+    {
+        Ok(accepted) => accepted,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": error.to_string()})),
+            );
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "ok",
+            "event_id": accepted.event_id,
+            "source": request.source,
+            "event_type": accepted.event_type,
+            "kafka_topic": accepted.topic,
+            "queued_at": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            "tool_name": adapter.tool_name,
+        })),
+    )
+}
+
+/// Hands flagged events to the audit sink in the background so a slow sink
+/// never delays the ingest response.
+/// Writes a job the publish queue refused to the spill dir, if one is
+/// configured. Returns whether it is now safely on disk.
+async fn spill_job(state: &AppState, job: &PublishJob) -> bool {
+    let Some(spill) = &state.publish_spill else {
+        return false;
+    };
+    match spill.write(job).await {
+        Ok(()) => true,
+        Err(error) => {
+            error!(
+                event_id = job.envelope.id.as_str(),
+                error = %format!("{error:#}"),
+                "failed to spill webhook envelope"
+            );
+            false
+        }
+    }
+}
+
+fn record_audit(
+    state: &AppState,
+    source: &str,
+    event_id: &str,
+    original: &Value,
+    sanitized: &Value,
+) {
+    let Some(sink) = state.audit_sink.clone() else {
+        return;
+    };
+    let Some(record) = audit_record(source, event_id, original, sanitized) else {
+        return;
+    };
+    let source = source.to_string();
+    let event_id = event_id.to_string();
+    tokio::spawn(async move {
+        if let Err(error) = sink.write(&source, &event_id, &record).await {
+            warn!(
+                source = source.as_str(),
+                event_id = event_id.as_str(),
+                error = %error,
+                "failed to write audit record"
+            );
+        }
+    });
+}
+
+async fn enqueue_prevalidated_event(
+    state: &Arc<AppState>,
+    source: &str,
+    payload: Value,
+    event_type_override: Option<String>,
+    ingress_adapter_id: Option<String>,
+    plugins: &[RuntimeServePluginConfig],
+) -> Result<EnqueueAccepted> {
+    let Some(normalized_source) = normalize_source_name(source) else {
+        return Err(anyhow::anyhow!("source cannot be empty"));
+    };
+    if !state.config.is_source_enabled(&normalized_source) {
+        return Err(anyhow::anyhow!(
+            "source '{}' is not enabled",
+            normalized_source
+        ));
+    }
+
+    let event_type = if let Some(value) = event_type_override {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return Err(anyhow::anyhow!("event_type override cannot be empty"));
+        }
+        trimmed.to_string()
+    } else if let Some(handler) = handler_for(&state.config, &normalized_source) {
+        handler
+            .event_type(&HeaderMap::new(), &payload)
+            .map_err(|error| anyhow::anyhow!("derive event_type failed: {:?}", error))?
+    } else {
+        "event".to_string()
+    };
+
+    let priority = match state.enqueue_script.as_ref() {
+        Some(script) => match script.evaluate(&normalized_source, &event_type, &payload)? {
+            ScriptDecision::Accept { priority } => priority,
+            ScriptDecision::Drop { reason } => {
+                return Err(anyhow::anyhow!(
+                    "event dropped by enqueue script: {}",
+                    reason
+                ));
+            }
+        },
+        None => None,
+    };
+
+    let sanitized_payload = match handler_for(&state.config, &normalized_source) {
+        Some(handler) => handler.sanitize(&payload),
+        None => sanitize_payload(&normalized_source, &payload),
+    }
+    .map_err(|error| anyhow::anyhow!("payload sanitizer rejected request: {}", error))?;
+    let (event_type, sanitized_payload, plugin_flags) =
+        apply_serve_plugins(plugins, event_type, sanitized_payload)?;
+    let matched_route = resolve_serve_route(&state.config, &normalized_source, event_type.as_str());
+    let route_key = matched_route.map(|route| route.id.clone());
+    let topic = matched_route
+        .map(|route| route.target_topic.clone())
+        .unwrap_or_else(|| state.config.source_topic_name(&normalized_source));
+    let trace_id = Some(Uuid::new_v4().to_string());
+    let event_meta = with_priority(
+        build_event_meta(
+            trace_id.clone(),
+            ingress_adapter_id.clone(),
+            route_key.clone(),
+            plugin_flags,
+        ),
+        priority,
+    );
+    let envelope = build_envelope(
+        &normalized_source,
+        event_type.clone(),
+        sanitized_payload,
+        event_meta,
+    );
+    record_audit(
+        state,
+        &normalized_source,
+        &envelope.id,
+        &payload,
+        &envelope.payload,
+    );
+    let event_id = envelope.id.clone();
+    match state.publish_tx.try_send(PublishJob {
+        topic: topic.clone(),
+        envelope,
+    }) {
+        Ok(()) => {}
+        Err(mpsc::error::TrySendError::Full(job) | mpsc::error::TrySendError::Closed(job))
+            if spill_job(state, &job).await => {}
+        Err(error) => return Err(anyhow::anyhow!("failed to enqueue event: {}", error)),
+    }
+
+    Ok(EnqueueAccepted {
+        event_id,
+        topic,
+        event_type,
+    })
+}
+
+async fn run_kafka_ingress_worker(
+    state: Arc<AppState>,
+    adapter: KafkaIngressRuntime,
+) -> Result<()> {
+    let mut client_config = ClientConfig::new();
+    client_config
+        .set("bootstrap.servers", &adapter.brokers)
+        .set("group.id", &adapter.group_id)
+        .set("enable.auto.commit", "false")
+        .set("auto.offset.reset", "latest")
+        .set("security.protocol", &state.config.kafka_security_protocol);
+    if state.config.kafka_security_protocol == "ssl" {
+        client_config
+            .set("ssl.certificate.location", &state.config.kafka_tls_cert)
+            .set("ssl.key.location", &state.config.kafka_tls_key)
+            .set("ssl.ca.location", &state.config.kafka_tls_ca);
+    }
+
+    let consumer = client_config
+        .create::<StreamConsumer>()
+        .context("create kafka ingress consumer")?;
+    let topic_refs = adapter
+        .topics
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    consumer
+        .subscribe(&topic_refs)
+        .with_context(|| format!("subscribe kafka ingress topics: {}", topic_refs.join(",")))?;
+    info!(
+        adapter_id = adapter.id.as_str(),
+        topics = ?adapter.topics,
+        group_id = adapter.group_id.as_str(),
+        "kafka ingress worker started"
+    );
+
+    loop {
+        let message = match consumer.recv().await {
+            Ok(message) => message,
+            Err(error) => {
+                warn!(
+                    adapter_id = adapter.id.as_str(),
+                    error = %error,
+                    "kafka ingress receive error"
+                );
+                continue;
+            }
+        };
+
+        let payload_bytes = match message.payload() {
+            Some(payload) => payload,
+            None => {
+                warn!(
+                    adapter_id = adapter.id.as_str(),
+                    topic = message.topic(),
+                    "kafka ingress message missing payload"
+                );
+                let _ = consumer.commit_message(&message, CommitMode::Async);
+                continue;
+            }
+        };
+
+        let parsed = parse_kafka_ingress_payload(payload_bytes);
+        if let Err(error) = &parsed {
+            warn!(
+                adapter_id = adapter.id.as_str(),
+                topic = message.topic(),
+                error = %error,
+                "kafka ingress payload parse failed"
+            );
+        }
+        if let Ok((source, payload, event_type)) = parsed {
+            if let Err(error) = enqueue_prevalidated_event(
+                &state,
+                source.as_str(),
+                payload,
+                event_type,
+                Some(adapter.id.clone()),
+                &adapter.plugins,
+            )
+            .await
+            {
+                warn!(
+                    adapter_id = adapter.id.as_str(),
+                    topic = message.topic(),
+                    error = %error,
+                    "kafka ingress enqueue failed"
+                );
+            }
+        }
+
+        if let Err(error) = consumer.commit_message(&message, CommitMode::Async) {
+            warn!(
+                adapter_id = adapter.id.as_str(),
+                topic = message.topic(),
+                error = %error,
+                "kafka ingress commit failed"
+            );
+        }
+    }
+}
+
+fn parse_kafka_ingress_payload(payload: &[u8]) -> Result<(String, Value, Option<String>)> {
+    #[derive(Debug, Deserialize)]
+    struct ParsedKafkaIngress {
+        source: String,
+        payload: Value,
+        #[serde(default)]
+        event_type: Option<String>,
+    }
+
+    if let Ok(envelope) = serde_json::from_slice::<relay_core::model::EventEnvelope>(payload) {
+        return Ok((envelope.source, envelope.payload, Some(envelope.event_type)));
+    }
+
+    let parsed = serde_json::from_slice::<ParsedKafkaIngress>(payload)
+        .context("parse kafka ingress payload as object")?;
+    Ok((parsed.source, parsed.payload, parsed.event_type))
+}
+
+fn parse_ws_frame_payload(raw: &str) -> Result<(Value, Option<String>)> {
+    let parsed: Value = serde_json::from_str(raw).context("parse websocket frame JSON")?;
+    let frame = serde_json::from_value::<WsIngressFrame>(parsed.clone()).ok();
+    match frame {
+        Some(frame) => Ok((frame.payload.unwrap_or(parsed), frame.event_type)),
+        None => Ok((parsed, None)),
+    }
+}
+
+fn resolve_ingress_runtime(config: &Config) -> Result<IngressRuntimeSelection> {
+    let mut http_path = "/webhook/{source}".to_string();
+    let mut http_ingress_adapter_id = config.active_ingress_adapter_id.clone();
+    let mut http_ingress_plugins = Vec::new();
+    let mut websocket_ingress = None;
+    let mut mcp_ingress = None;
+    let mut kafka_ingress_adapters = Vec::new();
+
+    for adapter in &config.ingress_adapters {
+        match adapter {
+            RuntimeIngressAdapter::HttpWebhookIngress {
+                id,
+                bind,
+                path_template,
+                plugins,
+            } => {
+                if bind.trim() != config.bind_addr.trim() {
+                    warn!(
+                        adapter_id = id.as_str(),
+                        adapter_bind = bind.as_str(),
+                        relay_bind = config.bind_addr.as_str(),
+                        "http_webhook_ingress bind differs from relay bind; relay bind takes precedence"
+                    );
+                }
+                if http_ingress_adapter_id.is_none() {
+                    http_ingress_adapter_id = Some(id.clone());
+                }
+                http_path = path_template.clone();
+                http_ingress_plugins = plugins.clone();
+            }
+            RuntimeIngressAdapter::WebsocketIngress {
+                id,
+                path_template,
+                auth_mode,
+                token_env,
+                plugins,
+            } => {
+                let auth_token =
+                    resolve_auth_token(auth_mode.as_str(), token_env.as_deref(), id.as_str())?;
+                websocket_ingress = Some(WebsocketIngressRuntime {
+                    id: id.clone(),
+                    path_template: path_template.clone(),
+                    auth_mode: auth_mode.clone(),
+                    auth_token,
+                    plugins: plugins.clone(),
+                });
+            }
+            RuntimeIngressAdapter::McpIngestExposed {
+                id,
+                tool_name,
+                bind,
+                path,
+                auth_mode,
+                token_env,
+                max_payload_bytes,
+                plugins,
+                ..
+            } => {
+                if bind.trim() != config.bind_addr.trim() {
+                    warn!(
+                        adapter_id = id.as_str(),
+                        adapter_bind = bind.as_str(),
+                        relay_bind = config.bind_addr.as_str(),
+                        "mcp_ingest_exposed bind differs from relay bind; relay bind takes precedence"
+                    );
+                }
+                let auth_token =
+                    resolve_auth_token(auth_mode.as_str(), token_env.as_deref(), id.as_str())?;
+                mcp_ingress = Some(McpIngressRuntime {
+                    id: id.clone(),
+                    tool_name: tool_name.clone(),
+                    path: path.clone(),
+                    auth_mode: auth_mode.clone(),
+                    auth_token,
+                    max_payload_bytes: *max_payload_bytes,
+                    plugins: plugins.clone(),
+                });
+            }
+            RuntimeIngressAdapter::KafkaIngress {
+                id,
+                topics,
+                group_id,
+                brokers,
+                plugins,
+            } => kafka_ingress_adapters.push(KafkaIngressRuntime {
+                id: id.clone(),
+                topics: topics.clone(),
+                group_id: group_id.clone(),
+                brokers: brokers
+                    .clone()
+                    .unwrap_or_else(|| config.kafka_brokers.clone()),
+                plugins: plugins.clone(),
+            }),
+        }
+    }
+
+    Ok(IngressRuntimeSelection {
+        http_path,
+        http_ingress_adapter_id,
+        http_ingress_plugins,
+        websocket_ingress,
+        mcp_ingress,
+        kafka_ingress_adapters,
+    })
+}
+
+fn resolve_auth_token(
+    auth_mode: &str,
+    token_env: Option<&str>,
+    adapter_id: &str,
+) -> Result<Option<String>> {
+    match auth_mode.trim().to_ascii_lowercase().as_str() {
+        "none" => Ok(None),
+        "bearer" | "hmac" => {
+            let token_env = token_env.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "adapter '{}' auth_mode={} requires token_env",
+                    adapter_id,
+                    auth_mode
+                )
+            })?;
+            let token = secret_env(token_env)?.with_context(|| {
+                format!(
+                    "missing auth token env '{}' for adapter '{}'",
+                    token_env, adapter_id
+                )
+            })?;
+            Ok(Some(token))
+        }
+        other => Err(anyhow::anyhow!(
+            "unsupported auth_mode '{}' for adapter '{}'",
+            other,
+            adapter_id
+        )),
+    }
+}
+
+fn authorize_adapter_request(
+    headers: &HeaderMap,
+    auth_mode: &str,
+    expected_token: Option<&str>,
+) -> bool {
+    match auth_mode.trim().to_ascii_lowercase().as_str() {
+        "none" => true,
+        "bearer" | "hmac" => {
+            let Some(expected_token) = expected_token else {
+                return false;
+            };
+            let provided = extract_bearer_token(headers)
+                .or_else(|| header_value(headers, "x-adapter-token"))
+                .unwrap_or_default();
+            provided == expected_token
+        }
+        _ => false,
+    }
+}
+
+fn extract_bearer_token(headers: &HeaderMap) -> Option<String> {
+    let authorization = headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())?
+        .trim()
+        .to_string();
+    let prefix = "bearer ";
+    if authorization.len() < prefix.len()
+        || !authorization[..prefix.len()].eq_ignore_ascii_case(prefix)
+    {
+        return None;
+    }
+    let token = authorization[prefix.len()..].trim();
+    if token.is_empty() {
+        None
+    } else {
+        Some(token.to_string())
+    }
+}
+
+fn header_value(headers: &HeaderMap, key: &str) -> Option<String> {
+    headers
+        .get(key)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+}
+
+fn apply_serve_plugins(
+    plugins: &[RuntimeServePluginConfig],
+    mut event_type: String,
+    payload: Value,
+) -> Result<(String, Value, Vec<String>)> {
+    let mut flags = Vec::new();
+
+    for plugin in plugins {
+        match plugin {
+            RuntimeServePluginConfig::EventTypeAlias { from, to } => {
+                if event_type == from.as_str() {
+                    event_type = to.clone();
+                }
+            }
+            RuntimeServePluginConfig::RequirePayloadField { pointer } => {
+                if payload.pointer(pointer).is_none() {
+                    return Err(anyhow::anyhow!(
+                        "payload missing required field '{}'",
+                        pointer
+                    ));
+                }
+            }
+            RuntimeServePluginConfig::AddMetaFlag { flag } => {
+                if !flags.iter().any(|existing| existing == flag) {
+                    flags.push(flag.clone());
+                }
+            }
+        }
+    }
+
+    Ok((event_type, payload, flags))
+}
+
+fn build_event_meta(
+    trace_id: Option<String>,
+    ingress_adapter: Option<String>,
+    route_key: Option<String>,
+    flags: Vec<String>,
+) -> Option<EventMeta> {
+    if trace_id.is_none() && ingress_adapter.is_none() && route_key.is_none() && flags.is_empty() {
+        return None;
+    }
+
+    Some(EventMeta {
+        trace_id,
+        ingress_adapter,
+        route_key,
+        flags,
+        priority: None,
+        replay: None,
+        tenant: None,
+    })
+}
+
+fn with_priority(meta: Option<EventMeta>, priority: Option<i64>) -> Option<EventMeta> {
+    let Some(priority) = priority else {
+        return meta;
+    };
+    let mut meta = meta.unwrap_or_default();
+    meta.priority = Some(priority);
+    Some(meta)
+}
+
+fn with_tenant(meta: Option<EventMeta>, tenant: Option<&str>) -> Option<EventMeta> {
+    let Some(tenant) = tenant else {
+        return meta;
+    };
+    let mut meta = meta.unwrap_or_default();
+    meta.tenant = Some(tenant.to_string());
+    Some(meta)
+}
+
+fn evaluate_enqueue_script(
+    state: &AppState,
+    source: &str,
+    event_type: &str,
+    payload: &Value,
+) -> Result<Option<i64>, (StatusCode, Json<Value>)> {
+    let Some(script) = state.enqueue_script.as_ref() else {
+        return Ok(None);
+    };
+
+    match script.evaluate(source, event_type, payload) {
+        Ok(ScriptDecision::Accept { priority }) => Ok(priority),
+        Ok(ScriptDecision::Drop { reason }) => {
+            info!(
+                source,
+                event_type,
+                reason = reason.as_str(),
+                "ignored webhook dropped by enqueue script"
+            );
+            Err((
+                StatusCode::OK,
+                Json(json!({"status":"ignored","reason":"script","detail": reason})),
+            ))
+        }
+        Err(error) => {
+            warn!(source, event_type, error = %error, "enqueue script evaluation failed");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error":"enqueue script failed"})),
+            ))
+        }
+    }
+}
+
+fn resolve_serve_route<'a>(
+    config: &'a Config,
+    source: &str,
+    event_type: &str,
+) -> Option<&'a ServeRouteRule> {
+    config.serve_routes.iter().find(|route| {
+        wildcard_matches(route.source_match.as_str(), source)
+            && wildcard_matches(route.event_type_pattern.as_str(), event_type)
+    })
+}
+
+fn wildcard_matches(pattern: &str, value: &str) -> bool {
+    let normalized_pattern = pattern.trim();
+    if normalized_pattern.is_empty() {
+        return false;
+    }
+    if normalized_pattern == "*" {
+        return true;
+    }
+    if !normalized_pattern.contains('*') {
+        return normalized_pattern == value;
+    }
+
+    let mut remainder = value;
+    let requires_prefix = !normalized_pattern.starts_with('*');
+    let requires_suffix = !normalized_pattern.ends_with('*');
+    let segments = normalized_pattern
+        .split('*')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    if segments.is_empty() {
+        return true;
+    }
+
+    for (index, segment) in segments.iter().enumerate() {
+        if index == 0 && requires_prefix {
+            if !remainder.starts_with(segment) {
+                return false;
+            }
+            remainder = &remainder[segment.len()..];
+            continue;
+        }
+
+        if index == segments.len() - 1 && requires_suffix {
+            return remainder.ends_with(segment);
+        }
+
+        match remainder.find(segment) {
+            Some(position) => {
+                let next_index = position + segment.len();
+                remainder = &remainder[next_index..];
+            }
+            None => return false,
+        }
+    }
+
+    true
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "operator",
+    responses((status = 200, description = "Process is up", body = Value, example = json!({"status": "ok"})))
+)]
+async fn health() -> impl IntoResponse {
+    (StatusCode::OK, Json(json!({"status": "ok"})))
+}
+
+#[utoipa::path(
+    get,
+    path = "/version",
+    tag = "operator",
+    description = "Served on `RELAY_ADMIN_BIND` instead when it is set.",
+    responses((status = 200, description = "Build and runtime identity", body = Value))
+)]
+async fn version(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (StatusCode::OK, Json(json!(BuildInfo::new(&state.config))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "operator",
+    description = "Served on `RELAY_ADMIN_BIND` instead when it is set.",
+    responses((status = 200, description = "Prometheus text exposition", body = String, content_type = "text/plain"))
+)]
+async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut body = BuildInfo::new(&state.config).render_metric();
+    state.metrics.render(&mut body);
+    (
+        StatusCode::OK,
+        [(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+        body,
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/config",
+    tag = "admin",
+    description = "Mounted only when `RELAY_ADMIN_TOKEN` is set.",
+    responses(
+        (status = 200, description = "Effective configuration with secrets masked", body = Value),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorBody),
+    ),
+    security(("admin_token" = []))
+)]
+async fn admin_config(State(state): State<Arc<AppState>>, headers: HeaderMap) -> impl IntoResponse {
+    if let Err(rejection) = authorize_admin(&state, &headers) {
+        return rejection;
+    }
+
+    (StatusCode::OK, Json(effective_config(&state.config)))
+}
+
+#[derive(Debug, Deserialize)]
+struct ActivityQuery {
+    limit: Option<usize>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/activity",
+    tag = "admin",
+    description = "Mounted only when `RELAY_ADMIN_TOKEN` is set. Per-repository and per-team counters since startup, busiest first.",
+    params(("limit" = Option<usize>, Query, description = "Number of scopes to return. Defaults to 20.")),
+    responses(
+        (status = 200, description = "Top scopes by events received", body = Value),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorBody),
+    ),
+    security(("admin_token" = []))
+)]
+async fn admin_activity(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ActivityQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = authorize_admin(&state, &headers) {
+        return rejection;
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_ACTIVITY_LIMIT);
+    (
+        StatusCode::OK,
+        Json(json!(state.activity.top_scope_activity(limit))),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/reload-secrets",
+    tag = "admin",
+    description = "Mounted only when `RELAY_ADMIN_TOKEN` is set. Same as sending SIGHUP.",
+    responses(
+        (status = 200, description = "Secrets re-read from the environment and files", body = Value),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorBody),
+        (status = 500, description = "Reload failed; previous secrets kept", body = ErrorBody),
+    ),
+    security(("admin_token" = []))
+)]
+async fn admin_reload_secrets(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = authorize_admin(&state, &headers) {
+        return rejection;
+    }
+
+    match state.reload_secrets() {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(json!({"status":"reloaded","sources": state.config.enabled_sources})),
+        ),
+        Err(error) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"status":"error","reason": format!("{error:#}")})),
+        ),
+    }
+}
+
+fn authorize_admin(state: &AppState, headers: &HeaderMap) -> Result<(), (StatusCode, Json<Value>)> {
+    let expected = state.config.admin_token.as_deref().unwrap_or_default();
+    let provided = extract_bearer_token(headers);
+    if admin_token_matches(expected, provided.as_deref()) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error":"unauthorized"})),
+        ))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "operator",
+    responses(
+        (status = 200, description = "Publisher worker running", body = Value),
+        (status = 503, description = "Publisher worker not running", body = Value),
+    )
+)]
+async fn ready(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    if state.draining.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"status":"not_ready","reason":"shutting down"})),
+        );
+    }
+    if !state.publish_worker_alive.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"status":"not_ready","reason":"publisher worker not running"})),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "ready",
+            "bind": state.config.bind_addr,
+            "version": VERSION,
+            "validation_mode": state.config.validation_mode,
+            "profile": state.config.active_profile,
+            "contract_path": state.config.contract_path,
+        })),
+    )
+}
+
+/// Reloads secrets on SIGHUP, and every `RELAY_SECRETS_RELOAD_SECONDS` when
+/// set.
+fn spawn_secret_reloader(state: Arc<AppState>) -> Result<tokio::task::JoinHandle<()>> {
+    let mut hangups = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .context("install SIGHUP handler")?;
+    let mut poll = state.config.secrets_reload_seconds.map(|seconds| {
+        let mut interval = tokio::time::interval(Duration::from_secs(seconds));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        interval
+    });
+    Ok(tokio::spawn(async move {
+        loop {
+            let result = tokio::select! {
+                hangup = hangups.recv() => match hangup {
+                    Some(()) => state.reload_secrets(),
+                    None => break,
+                },
+                _ = async {
+                    match poll.as_mut() {
+                        Some(interval) => {
+                            interval.tick().await;
+                        }
+                        None => std::future::pending().await,
+                    }
+                } => state.refresh_secrets(),
+            };
+            if let Err(error) = result {
+                warn!(error = %format!("{error:#}"), "secret reload failed; keeping previous secrets");
+            }
+        }
+    }))
+}
+
+fn record_signature_metrics(
+    state: &AppState,
+    source: &str,
+    client_ip: IpAddr,
+    verification: &Result<SignatureKind, ValidationError>,
+    elapsed: Duration,
+) {
+    let (result, reason) = match verification {
+        Ok(SignatureKind::Primary) => (SignatureResult::Valid, None),
+        Ok(SignatureKind::LegacySha1) => (SignatureResult::ValidLegacySha1, None),
+        Err(ValidationError::Unauthorized(message)) => (
+            SignatureResult::Invalid,
+            Some(signature_failure_reason(message)),
+        ),
+        Err(ValidationError::BadRequest(_)) => (SignatureResult::Malformed, Some("malformed")),
+    };
+    let failure = reason.map(|reason| (reason, ip_bucket(client_ip)));
+    state
+        .metrics
+        .record_signature(source, result, failure, elapsed);
+}
+
+fn signature_failure_reason(message: &str) -> &'static str {
+    if message.contains("secret") {
+        "secret_unset"
+    } else if message.starts_with("missing") {
+        "missing"
+    } else if message.contains("timestamp") {
+        "stale_timestamp"
+    } else {
+        "mismatch"
+    }
+}
+
+/// Resolves on SIGTERM (container stop) or Ctrl-C.
+async fn shutdown_signal() {
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(error) => {
+                warn!(error = %error, "failed to install SIGTERM handler");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
+
+/// On shutdown, fails readiness and rejects new webhooks with 503 for
+/// `RELAY_SHUTDOWN_DRAIN_SECONDS` so load balancers move traffic away, then
+/// closes the listeners. Requests already being handled finish either way.
+async fn drain_on_shutdown_signal(state: Arc<AppState>, stop: watch::Sender<bool>) {
+    shutdown_signal().await;
+    state.draining.store(true, Ordering::SeqCst);
+    info!(
+        drain_seconds = state.config.shutdown_drain_seconds,
+        "shutting down; rejecting new webhooks"
+    );
+    tokio::time::sleep(Duration::from_secs(state.config.shutdown_drain_seconds)).await;
+    let _ = stop.send(true);
+}
+
+async fn stopped(mut stop: watch::Receiver<bool>) {
+    let _ = stop.wait_for(|stop| *stop).await;
+}
+
+async fn reject_while_draining(
+    State(state): State<Arc<AppState>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    if state.draining.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(RETRY_AFTER, "1")],
+            Json(json!({"error": "shutting down"})),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+fn ensure_enabled_sources_have_handlers(config: &Config) -> Result<()> {
+    let unsupported = config
+        .enabled_sources
+        .iter()
+        .filter(|source| handler_for(config, source).is_none())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if unsupported.is_empty() {
+        return Ok(());
+    }
+
+    let built_ins = known_source_names().join(", ");
+    Err(anyhow::anyhow!(
+        "enabled sources without handlers: {} (built-in handlers: {})",
+        unsupported.join(", "),
+        built_ins
+    ))
+}
+
+fn ip_refill_period_ms(limit_per_minute: u32) -> u64 {
+    if limit_per_minute == 0 {
+        return 1;
+    }
+
+    let period = 60_000u64 / u64::from(limit_per_minute);
+    period.max(1)
+}
+
+fn body_utf8_preview(body: &Bytes, max_chars: usize) -> String {
+    let raw = String::from_utf8_lossy(body);
+    if raw.chars().count() <= max_chars {
+        return raw.into_owned();
+    }
+
+    let preview_limit = max_chars.saturating_sub(3);
+    let mut output = String::new();
+    let mut char_count = 0usize;
+    for character in raw.chars() {
+        if char_count >= preview_limit {
+            break;
+        }
+        output.push(character);
+        char_count = char_count.saturating_add(1);
+    }
+    output.push_str("...");
+    output
+}
+
+fn to_json_string<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value)
+        .unwrap_or_else(|error| format!("{{\"serialization_error\":\"{}\"}}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_serve_plugins, build_event_meta, ip_refill_period_ms, wildcard_matches};
+    use crate::config::RuntimeServePluginConfig;
+    use relay_core::model::EventMeta;
+
+    #[test]
+    fn ip_limit_refill_period_matches_100_per_minute() {
+        assert_eq!(ip_refill_period_ms(100), 600);
+    }
+
+    #[test]
+    fn ip_limit_refill_period_has_minimum_one_millisecond() {
+        assert_eq!(ip_refill_period_ms(100_000), 1);
+    }
+
+    #[test]
+    fn ip_limit_refill_period_handles_zero_limit() {
+        assert_eq!(ip_refill_period_ms(0), 1);
+    }
+
+    #[test]
+    fn wildcard_matches_exact_and_glob_patterns() {
+        assert!(wildcard_matches("github", "github"));
+        assert!(wildcard_matches("*", "github"));
+        assert!(wildcard_matches("pull_request.*", "pull_request.opened"));
+        assert!(wildcard_matches("*.opened", "pull_request.opened"));
+        assert!(!wildcard_matches("linear", "github"));
+        assert!(!wildcard_matches("pull_request.*", "issues.opened"));
+    }
+
+    #[test]
+    fn build_event_meta_returns_none_without_values() {
+        assert_eq!(build_event_meta(None, None, None, Vec::new()), None);
+    }
+
+    #[test]
+    fn build_event_meta_includes_trace_and_route() {
+        let meta = build_event_meta(
+            Some("trace-1".to_string()),
+            Some("http-ingress".to_string()),
+            Some("all-to-core".to_string()),
+            vec!["plugin.tag".to_string()],
+        )
+        .expect("meta");
+        assert_eq!(
+            meta,
+            EventMeta {
+                trace_id: Some("trace-1".to_string()),
+                ingress_adapter: Some("http-ingress".to_string()),
+                route_key: Some("all-to-core".to_string()),
+                flags: vec!["plugin.tag".to_string()],
+                priority: None,
+                replay: None,
+                tenant: None,
+            }
+        );
+    }
+
+    #[test]
+    fn apply_serve_plugins_alias_and_flag() {
+        let plugins = vec![
+            RuntimeServePluginConfig::EventTypeAlias {
+                from: "pull_request.opened".to_string(),
+                to: "pr.opened".to_string(),
+            },
+            RuntimeServePluginConfig::AddMetaFlag {
+                flag: "serve.plugin.alias".to_string(),
+            },
+        ];
+
+        let (event_type, payload, flags) = apply_serve_plugins(
+            &plugins,
+            "pull_request.opened".to_string(),
+            serde_json::json!({"action":"opened"}),
+        )
+        .expect("apply plugins");
+
+        assert_eq!(event_type, "pr.opened");
+        assert_eq!(payload["action"].as_str(), Some("opened"));
+        assert_eq!(flags, vec!["serve.plugin.alias".to_string()]);
+    }
+
+    #[test]
+    fn apply_serve_plugins_require_payload_field_fails_closed() {
+        let plugins = vec![RuntimeServePluginConfig::RequirePayloadField {
+            pointer: "/action".to_string(),
+        }];
+
+        let error = apply_serve_plugins(
+            &plugins,
+            "pull_request.opened".to_string(),
+            serde_json::json!({}),
+        )
+        .expect_err("missing pointer should fail");
+        assert!(error.to_string().contains("/action"));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::truncate::truncate_payload;
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+/// Payload field pointing at the offloaded full payload.
+const BLOB_FIELD: &str = "_blob";
+
+/// Writes payloads over `threshold_bytes` to `dir/<source>/<event id>.json`
+/// and leaves a truncated summary plus a `_blob` pointer in the envelope.
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    dir: PathBuf,
+    threshold_bytes: usize,
+}
+
+impl BlobStore {
+    pub fn new(dir: impl Into<PathBuf>, threshold_bytes: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            threshold_bytes,
+        }
+    }
+
+    /// Returns the written path when the payload was offloaded.
+    pub async fn offload(
+        &self,
+        source: &str,
+        event_id: &str,
+        payload: &mut Value,
+    ) -> Result<Option<PathBuf>> {
+        let encoded = serde_json::to_vec(payload).context("encode payload for blob store")?;
+        if encoded.len() <= self.threshold_bytes {
+            return Ok(None);
+        }
+
+        let dir = self.dir.join(source);
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("create blob dir {}", dir.display()))?;
+        let path = dir.join(format!("{event_id}.json"));
+        let partial = dir.join(format!("{event_id}.json.partial"));
+        tokio::fs::write(&partial, &encoded)
+            .await
+            .with_context(|| format!("write blob {}", partial.display()))?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .with_context(|| format!("rename blob to {}", path.display()))?;
+
+        truncate_payload(payload, self.threshold_bytes);
+        if let Some(object) = payload.as_object_mut() {
+            object.insert(
+                BLOB_FIELD.to_string(),
+                json!({"path": path.display().to_string(), "bytes": encoded.len()}),
+            );
+        }
+        Ok(Some(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn offloads_only_payloads_over_threshold() {
+        let dir = std::env::temp_dir().join(format!("hook-serve-blob-{}", std::process::id()));
+        let store = BlobStore::new(&dir, 1_024);
+
+        let mut small = json!({"action": "opened"});
+        assert!(
+            store
+                .offload("github", "evt-small", &mut small)
+                .await
+                .expect("offload")
+                .is_none()
+        );
+        assert!(small.get("_blob").is_none());
+
+        let body = "x".repeat(10_000);
+        let mut large = json!({"action": "opened", "pull_request": {"body": body}});
+        let path = store
+            .offload("github", "evt-large", &mut large)
+            .await
+            .expect("offload")
+            .expect("offloaded");
+
+        let stored: Value =
+            serde_json::from_slice(&std::fs::read(&path).expect("read blob")).expect("blob json");
+        assert_eq!(
+            stored["pull_request"]["body"].as_str().map(str::len),
+            Some(10_000)
+        );
+        assert_eq!(large["_blob"]["path"], path.display().to_string());
+        assert_eq!(large["action"], "opened");
+        assert!(large["pull_request"]["body"].as_str().map(str::len) < Some(10_000));
+
+        std::fs::remove_dir_all(&dir).expect("clean up");
+    }
+}
@@ -0,0 +1,113 @@
+use crate::config::Config;
+use crate::metrics::{escape_label_value, write_header};
+use chrono::{DateTime, SecondsFormat};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const GIT_COMMIT: &str = env!("HOOK_SERVE_GIT_COMMIT");
+const BUILD_EPOCH: &str = env!("HOOK_SERVE_BUILD_EPOCH");
+const BUILD_INFO_METRIC: &str = "webhook_relay_build_info";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_commit: &'static str,
+    pub build_timestamp: String,
+    pub features: BTreeMap<&'static str, String>,
+}
+
+impl BuildInfo {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            version: VERSION,
+            git_commit: GIT_COMMIT,
+            build_timestamp: build_timestamp(),
+            features: enabled_features(config),
+        }
+    }
+
+    pub fn render_metric(&self) -> String {
+        let mut labels = vec![
+            ("version", self.version.to_string()),
+            ("git_commit", self.git_commit.to_string()),
+            ("build_timestamp", self.build_timestamp.clone()),
+        ];
+        labels.extend(
+            self.features
+                .iter()
+                .map(|(name, value)| (*name, value.clone())),
+        );
+
+        let mut output = String::new();
+        write_header(
+            &mut output,
+            BUILD_INFO_METRIC,
+            "gauge",
+            "Build and feature information for this relay instance.",
+        );
+        let rendered = labels
+            .iter()
+            .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let _ = writeln!(output, "{BUILD_INFO_METRIC}{{{rendered}}} 1");
+        output
+    }
+}
+
+fn enabled_features(config: &Config) -> BTreeMap<&'static str, String> {
+    BTreeMap::from([
+        (
+            "kafka_security_protocol",
+            config.kafka_security_protocol.clone(),
+        ),
+        ("sources", config.enabled_sources.join(",")),
+        ("validation_mode", config.validation_mode.clone()),
+        ("profile", config.active_profile.clone()),
+        (
+            "ingress_adapter",
+            config.active_ingress_adapter_id.clone().unwrap_or_default(),
+        ),
+        (
+            "enqueue_script",
+            config.enqueue_script_path.is_some().to_string(),
+        ),
+        ("digest", config.digest_topic.is_some().to_string()),
+        (
+            "dlq_alert",
+            config.dlq_alert_threshold.is_some().to_string(),
+        ),
+        ("admin", config.admin_token.is_some().to_string()),
+    ])
+}
+
+fn build_timestamp() -> String {
+    BUILD_EPOCH
+        .parse::<i64>()
+        .ok()
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+        .map(|timestamp| timestamp.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metric_carries_build_fields_as_labels() {
+        let info = BuildInfo {
+            version: "0.2.0",
+            git_commit: "abc123",
+            build_timestamp: "2026-01-01T00:00:00Z".to_string(),
+            features: BTreeMap::from([("sources", "github,linear".to_string())]),
+        };
+        let rendered = info.render_metric();
+        assert!(rendered.contains("# TYPE webhook_relay_build_info gauge"));
+        assert!(rendered.contains(
+            "webhook_relay_build_info{version=\"0.2.0\",git_commit=\"abc123\",build_timestamp=\"2026-01-01T00:00:00Z\",sources=\"github,linear\"} 1"
+        ));
+    }
+}
@@ -41,23 +41,35 @@ impl KeyExtractor for TrustedClientIpKeyExtractor {
             .or_else(|| req.extensions().get::<SocketAddr>().map(|addr| addr.ip()))
             .ok_or(GovernorError::UnableToExtractKey)?;
 
-        if !self.trust_proxy_headers {
-            return Ok(peer_ip);
-        }
+        Ok(self.client_ip(peer_ip, req.headers()))
+    }
+}
 
-        if !self.is_trusted_proxy(peer_ip) {
-            return Ok(peer_ip);
+impl TrustedClientIpKeyExtractor {
+    /// Resolves the client address, honouring proxy headers only from trusted peers.
+    pub fn client_ip(&self, peer_ip: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.trust_proxy_headers || !self.is_trusted_proxy(peer_ip) {
+            return peer_ip;
         }
 
-        let headers = req.headers();
         parse_x_forwarded_for(headers)
             .or_else(|| parse_x_real_ip(headers))
             .or_else(|| parse_forwarded(headers))
-            .or(Some(peer_ip))
-            .ok_or(GovernorError::UnableToExtractKey)
+            .unwrap_or(peer_ip)
     }
 }
 
+/// Coarse network bucket for metrics: /24 for IPv4, /48 for IPv6.
+pub fn ip_bucket(ip: IpAddr) -> String {
+    let prefix = match ip {
+        IpAddr::V4(_) => 24,
+        IpAddr::V6(_) => 48,
+    };
+    IpNet::new(ip, prefix)
+        .map(|net| net.trunc().to_string())
+        .unwrap_or_else(|_| ip.to_string())
+}
+
 fn parse_x_forwarded_for(headers: &HeaderMap) -> Option<IpAddr> {
     headers
         .get(X_FORWARDED_FOR)
@@ -147,4 +159,16 @@ mod tests {
             IpAddr::from([1, 2, 3, 4])
         );
     }
+
+    #[test]
+    fn buckets_are_coarse() {
+        assert_eq!(
+            ip_bucket("203.0.113.77".parse().expect("ip")),
+            "203.0.113.0/24"
+        );
+        assert_eq!(
+            ip_bucket("2001:db8:abcd:12::1".parse().expect("ip")),
+            "2001:db8:abcd::/48"
+        );
+    }
 }
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub type SharedClock = Arc<dyn Clock>;
+
+pub trait Clock: Send + Sync {
+    fn now_epoch_seconds(&self) -> i64;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_epoch_seconds(&self) -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    epoch_seconds: AtomicI64,
+}
+
+impl ManualClock {
+    pub fn new(epoch_seconds: i64) -> Self {
+        Self {
+            epoch_seconds: AtomicI64::new(epoch_seconds),
+        }
+    }
+
+    pub fn set(&self, epoch_seconds: i64) {
+        self.epoch_seconds.store(epoch_seconds, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, seconds: i64) {
+        self.epoch_seconds.fetch_add(seconds, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_epoch_seconds(&self) -> i64 {
+        self.epoch_seconds.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clock, ManualClock};
+
+    #[test]
+    fn manual_clock_only_moves_when_told() {
+        let clock = ManualClock::new(1_700_000_000);
+        assert_eq!(clock.now_epoch_seconds(), 1_700_000_000);
+        clock.advance(45);
+        assert_eq!(clock.now_epoch_seconds(), 1_700_000_045);
+        clock.set(10);
+        assert_eq!(clock.now_epoch_seconds(), 10);
+    }
+}
@@ -1,8 +1,219 @@
+use crate::listener::BindTarget;
 use anyhow::{Context, Result, anyhow};
 use ipnet::IpNet;
+use relay_config::{
+    KafkaClientConfig, conditional_secret_env, env_bool, env_f64, env_i32, env_i64, env_u32,
+    env_u64, env_usize, optional_env, parse_csv, secret_env,
+};
+use relay_core::signatures::HmacAlgorithm;
+use relay_core::tenants::{TenantConfig, load_tenants};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::env;
 
+/// Source HMAC secrets; reloadable at runtime from env or `*_FILE` paths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceSecrets {
+    pub github: Option<String>,
+    pub linear: Option<String>,
+    pub example: Option<String>,
+    pub standard_webhooks: Option<String>,
+    pub gitlab: Option<String>,
+    pub bitbucket: Option<String>,
+    pub jira: Option<String>,
+    pub stripe: Option<String>,
+    pub sentry: Option<String>,
+    pub gmail: Option<String>,
+    pub shopify: Option<String>,
+    pub registry: Option<String>,
+    pub ci: Option<String>,
+    /// Ed25519 public key, not a secret; loaded here so it reloads with them.
+    pub discord: Option<String>,
+    /// Keyed by `RELAY_CUSTOM_SOURCES` name.
+    pub custom: BTreeMap<String, String>,
+    /// Bearer token per internal producer, from `RELAY_INTERNAL_TOKENS`.
+    pub internal: BTreeMap<String, String>,
+    /// Each tenant's own secrets, keyed by tenant id.
+    pub tenants: BTreeMap<String, SourceSecrets>,
+}
+
+impl SourceSecrets {
+    pub fn load(
+        enabled_sources: &[String],
+        custom_sources: &BTreeMap<String, CustomSource>,
+    ) -> Result<Self> {
+        let mut custom = BTreeMap::new();
+        for name in custom_sources.keys() {
+            if let Some(secret) = conditional_secret_env(
+                &custom_secret_env(name),
+                contains_source(enabled_sources, name),
+            )? {
+                custom.insert(name.clone(), secret);
+            }
+        }
+        Ok(Self {
+            github: conditional_secret_env(
+                "HMAC_SECRET_GITHUB",
+                contains_source(enabled_sources, "github"),
+            )?,
+            linear: conditional_secret_env(
+                "HMAC_SECRET_LINEAR",
+                contains_source(enabled_sources, "linear"),
+            )?,
+            example: conditional_secret_env(
+                "HMAC_SECRET_EXAMPLE",
+                contains_source(enabled_sources, "example"),
+            )?,
+            standard_webhooks: conditional_secret_env(
+                "HMAC_SECRET_STANDARD_WEBHOOKS",
+                contains_source(enabled_sources, "standard-webhooks"),
+            )?,
+            gitlab: conditional_secret_env(
+                "HMAC_SECRET_GITLAB",
+                contains_source(enabled_sources, "gitlab"),
+            )?,
+            bitbucket: conditional_secret_env(
+                "HMAC_SECRET_BITBUCKET",
+                contains_source(enabled_sources, "bitbucket"),
+            )?,
+            jira: conditional_secret_env(
+                "HMAC_SECRET_JIRA",
+                contains_source(enabled_sources, "jira"),
+            )?,
+            stripe: conditional_secret_env(
+                "HMAC_SECRET_STRIPE",
+                contains_source(enabled_sources, "stripe"),
+            )?,
+            sentry: conditional_secret_env(
+                "HMAC_SECRET_SENTRY",
+                contains_source(enabled_sources, "sentry"),
+            )?,
+            gmail: conditional_secret_env(
+                "HMAC_SECRET_GMAIL",
+                contains_source(enabled_sources, "gmail"),
+            )?,
+            shopify: conditional_secret_env(
+                "HMAC_SECRET_SHOPIFY",
+                contains_source(enabled_sources, "shopify"),
+            )?,
+            registry: conditional_secret_env(
+                "HMAC_SECRET_REGISTRY",
+                contains_source(enabled_sources, "registry"),
+            )?,
+            ci: conditional_secret_env("HMAC_SECRET_CI", contains_source(enabled_sources, "ci"))?,
+            discord: conditional_secret_env(
+                "RELAY_DISCORD_PUBLIC_KEY",
+                contains_source(enabled_sources, "discord"),
+            )?,
+            custom,
+            internal: conditional_secret_env(
+                "RELAY_INTERNAL_TOKENS",
+                contains_source(enabled_sources, "internal"),
+            )?
+            .map(|raw| parse_internal_tokens(&raw))
+            .transpose()?
+            .unwrap_or_default(),
+            tenants: BTreeMap::new(),
+        })
+    }
+
+    /// A tenant's secrets, read from the env vars named in its
+    /// `secret_envs` (each also accepts `_FILE`). Keys must be a built-in
+    /// source with a shared secret or a `RELAY_CUSTOM_SOURCES` name.
+    pub fn for_tenant(
+        tenant: &TenantConfig,
+        custom_sources: &BTreeMap<String, CustomSource>,
+    ) -> Result<Self> {
+        let mut secrets = Self::default();
+        for (source, env_name) in &tenant.secret_envs {
+            if !TENANT_SECRET_SOURCES.contains(&source.as_str())
+                && !custom_sources.contains_key(source)
+            {
+                return Err(anyhow!(
+                    "tenant '{}' secret_envs key '{source}' is not a source with a shared secret; expected one of {} or a RELAY_CUSTOM_SOURCES name",
+                    tenant.id,
+                    TENANT_SECRET_SOURCES.join(", ")
+                ));
+            }
+            let secret = conditional_secret_env(env_name, true)?.unwrap_or_default();
+            let slot = match source.as_str() {
+                "github" => &mut secrets.github,
+                "linear" => &mut secrets.linear,
+                "example" => &mut secrets.example,
+                "standard-webhooks" => &mut secrets.standard_webhooks,
+                "gitlab" => &mut secrets.gitlab,
+                "bitbucket" => &mut secrets.bitbucket,
+                "jira" => &mut secrets.jira,
+                "stripe" => &mut secrets.stripe,
+                "sentry" => &mut secrets.sentry,
+                "gmail" => &mut secrets.gmail,
+                "shopify" => &mut secrets.shopify,
+                "registry" => &mut secrets.registry,
+                "ci" => &mut secrets.ci,
+                "discord" => &mut secrets.discord,
+                "internal" => {
+                    secrets.internal = parse_internal_tokens(&secret)?;
+                    continue;
+                }
+                custom => {
+                    secrets.custom.insert(custom.to_string(), secret);
+                    continue;
+                }
+            };
+            *slot = Some(secret);
+        }
+        Ok(secrets)
+    }
+}
+
+/// Built-in sources a tenant can hold its own secret for.
+const TENANT_SECRET_SOURCES: &[&str] = &[
+    "github",
+    "linear",
+    "example",
+    "standard-webhooks",
+    "gitlab",
+    "bitbucket",
+    "jira",
+    "stripe",
+    "sentry",
+    "gmail",
+    "shopify",
+    "registry",
+    "ci",
+    "discord",
+    "internal",
+];
+
+pub fn load_tenant_secrets(
+    tenants: &[TenantConfig],
+    custom_sources: &BTreeMap<String, CustomSource>,
+) -> Result<BTreeMap<String, SourceSecrets>> {
+    tenants
+        .iter()
+        .map(|tenant| {
+            SourceSecrets::for_tenant(tenant, custom_sources)
+                .with_context(|| format!("load secrets for tenant {}", tenant.id))
+                .map(|secrets| (tenant.id.clone(), secrets))
+        })
+        .collect()
+}
+
+/// A generic HMAC source declared in `RELAY_CUSTOM_SOURCES`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomSource {
+    pub signature_header: String,
+    pub algorithm: HmacAlgorithm,
+}
+
+/// `HMAC_SECRET_<NAME>`, with dashes in the name turned into underscores.
+pub fn custom_secret_env(name: &str) -> String {
+    format!(
+        "HMAC_SECRET_{}",
+        name.to_ascii_uppercase().replace('-', "_")
+    )
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ServeRouteRule {
     pub id: String,
@@ -11,6 +222,39 @@ pub struct ServeRouteRule {
     pub target_topic: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearStateTransition {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Forward only `rate` of `source:event_type` events, optionally limited to
+/// one repo or team (`scope`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplingRule {
+    pub source: String,
+    pub event_type: String,
+    pub scope: Option<String>,
+    pub rate: f64,
+}
+
+/// What serve does with a body over its source's payload limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizedPayloadMode {
+    Reject,
+    /// Accept it and cut the payload down; see `truncate::truncate_payload`.
+    Truncate,
+}
+
+impl OversizedPayloadMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Truncate => "truncate",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "driver", rename_all = "snake_case")]
 pub enum RuntimeServePluginConfig {
@@ -65,6 +309,8 @@ pub enum RuntimeIngressAdapter {
 #[derive(Debug, Clone)]
 pub struct Config {
     pub bind_addr: String,
+    pub admin_bind_addr: Option<String>,
+    pub unix_socket_mode: u32,
     pub enabled_sources: Vec<String>,
     pub source_topic_prefix: String,
     pub relay_source_topics: Vec<String>,
@@ -75,39 +321,138 @@ pub struct Config {
     pub kafka_tls_key: String,
     pub kafka_tls_ca: String,
     pub kafka_dlq_topic: String,
+    pub kafka_dlq_retention_bytes: Option<i64>,
+    pub kafka_dlq_retention_ms: Option<i64>,
     pub kafka_auto_create_topics: bool,
     pub kafka_topic_partitions: i32,
     pub kafka_topic_replication_factor: i32,
+    pub kafka_producer_acks: String,
+    pub kafka_producer_idempotence: bool,
+    pub kafka_producer_delivery_timeout_ms: u64,
+    /// `None` keeps librdkafka's default, effectively unlimited within the
+    /// delivery timeout.
+    pub kafka_producer_retries: Option<u32>,
+    pub kafka_producer_compression: String,
+    /// Setting it publishes in transactional batches.
+    pub kafka_producer_transactional_id: Option<String>,
+    pub kafka_producer_transaction_batch_size: usize,
     pub hmac_secret_github: Option<String>,
     pub hmac_secret_linear: Option<String>,
     pub hmac_secret_example: Option<String>,
+    pub hmac_secret_standard_webhooks: Option<String>,
+    /// GitLab's `X-Gitlab-Token` secret; compared, not used as an HMAC key.
+    pub hmac_secret_gitlab: Option<String>,
+    pub hmac_secret_bitbucket: Option<String>,
+    pub hmac_secret_jira: Option<String>,
+    pub hmac_secret_stripe: Option<String>,
+    pub hmac_secret_sentry: Option<String>,
+    pub hmac_secret_gmail: Option<String>,
+    pub hmac_secret_shopify: Option<String>,
+    pub hmac_secret_registry: Option<String>,
+    pub hmac_secret_ci: Option<String>,
+    pub discord_public_key: Option<String>,
+    pub hmac_secret_custom: BTreeMap<String, String>,
+    pub internal_tokens: BTreeMap<String, String>,
+    /// Teams served under `/webhook/{tenant}/{source}`, from
+    /// `RELAY_TENANTS_FILE`.
+    pub tenants: Vec<TenantConfig>,
+    pub tenants_file: Option<String>,
+    pub tenant_secrets: BTreeMap<String, SourceSecrets>,
+    pub custom_sources: BTreeMap<String, CustomSource>,
+    pub stripe_tolerance_seconds: i64,
+    /// Expected `aud` of Pub/Sub push OIDC tokens.
+    pub pubsub_audience: Option<String>,
+    /// When set, the token's `email` must be this service account.
+    pub pubsub_service_account: Option<String>,
+    pub pubsub_jwks_url: String,
+    /// SNS topics whose messages are accepted.
+    pub sns_topic_arns: Vec<String>,
+    /// Maximum age of an SNS message's `Timestamp`.
+    pub sns_tolerance_seconds: i64,
+    pub standard_webhooks_tolerance_seconds: i64,
+    pub github_allow_sha1: bool,
+    pub admin_token: Option<String>,
+    /// Re-read source secrets this often, for files rotated in place.
+    pub secrets_reload_seconds: Option<u64>,
     pub max_payload_bytes: usize,
+    pub source_max_payload_bytes: BTreeMap<String, usize>,
+    /// Extra static ingest paths, keyed by source.
+    pub source_paths: BTreeMap<String, String>,
+    pub oversized_payload_mode: OversizedPayloadMode,
+    pub blob_dir: Option<String>,
+    pub blob_threshold_bytes: usize,
+    pub audit_dir: Option<String>,
+    pub audit_url: Option<String>,
+    pub audit_token: Option<String>,
+    pub max_in_flight_requests: Option<usize>,
+    pub access_log: bool,
+    pub access_log_sample_rate: f64,
     pub ip_limit_per_minute: u32,
     pub source_limit_per_minute: u32,
     pub trust_proxy_headers: bool,
+    pub proxy_protocol: bool,
     pub trusted_proxy_cidrs: Vec<IpNet>,
     pub dedup_ttl_seconds: i64,
     pub cooldown_seconds: i64,
+    pub content_dedup_ttl_seconds: Option<i64>,
+    pub debounce_window_ms: Option<u64>,
+    pub github_synchronize_coalesce_ms: Option<u64>,
+    /// GitHub App used to attach changed files to pull request events.
+    pub github_app_id: Option<String>,
+    pub github_app_private_key: Option<String>,
+    pub github_api_url: String,
     pub enforce_linear_timestamp_window: bool,
     pub linear_timestamp_window_seconds: i64,
+    pub linear_state_transitions: Vec<LinearStateTransition>,
+    /// Look each Linear delivery's entity up through the API before
+    /// forwarding it.
+    pub linear_verify_with_api: bool,
+    pub linear_api_token: Option<String>,
+    pub sampling_rules: Vec<SamplingRule>,
     pub publish_queue_capacity: usize,
     pub publish_max_retries: u32,
     pub publish_backoff_base_ms: u64,
     pub publish_backoff_max_ms: u64,
+    pub publish_spill_dir: Option<String>,
+    pub publish_spill_replay_seconds: u64,
+    pub shutdown_drain_seconds: u64,
     pub validation_mode: String,
     pub active_profile: String,
     pub contract_path: Option<String>,
+    pub enqueue_script_path: Option<String>,
+    pub digest_topic: Option<String>,
+    pub digest_hour_utc: u32,
+    pub alert_topic: Option<String>,
+    pub dlq_alert_threshold: Option<i64>,
+    pub dlq_alert_window_seconds: i64,
+    pub dlq_alert_cooldown_seconds: i64,
     pub active_ingress_adapter_id: Option<String>,
     pub ingress_adapters: Vec<RuntimeIngressAdapter>,
     pub serve_routes: Vec<ServeRouteRule>,
 }
 
 impl Config {
+    /// Loads and validates the config, failing on the first unparsable value
+    /// or with every range and consistency problem at once.
     pub fn from_env() -> Result<Self> {
+        let config = Self::load_env()?;
+        report_problems(
+            config
+                .problems()
+                .iter()
+                .map(|error| format!("{error:#}"))
+                .collect(),
+        )?;
+        Ok(config)
+    }
+
+    /// Parses the env without the checks in `problems`.
+    pub fn load_env() -> Result<Self> {
         let enabled_sources = env_csv_lower("RELAY_ENABLED_SOURCES", "github,linear")?;
         if enabled_sources.is_empty() {
             return Err(anyhow!("RELAY_ENABLED_SOURCES cannot be empty"));
         }
+        let kafka = KafkaClientConfig::from_env("ssl")?;
 
         let source_topic_prefix = env::var("RELAY_SOURCE_TOPIC_PREFIX")
             .unwrap_or_else(|_| "webhooks".to_string())
@@ -119,11 +464,7 @@ impl Config {
             ));
         }
 
-        let relay_source_topics = match env::var("RELAY_SOURCE_TOPICS")
-            .ok()
-            .map(|value| value.trim().to_string())
-            .filter(|value| !value.is_empty())
-        {
+        let relay_source_topics = match optional_env("RELAY_SOURCE_TOPICS") {
             Some(raw_topics) => {
                 let parsed_topics = parse_csv(&raw_topics);
                 if parsed_topics.is_empty() {
@@ -147,48 +488,178 @@ impl Config {
                 .collect(),
         };
 
-        let github_enabled = contains_source(&enabled_sources, "github");
-        let linear_enabled = contains_source(&enabled_sources, "linear");
-        let example_enabled = contains_source(&enabled_sources, "example");
+        let linear_verify_with_api = env_bool("RELAY_LINEAR_VERIFY_WITH_API", false);
+        let github_app_id = optional_env("RELAY_GITHUB_APP_ID");
+
+        let custom_sources =
+            parse_custom_sources(&env::var("RELAY_CUSTOM_SOURCES").unwrap_or_default())?;
+        let secrets = SourceSecrets::load(&enabled_sources, &custom_sources)?;
+        let tenants_file = optional_env("RELAY_TENANTS_FILE");
+        let tenants = match &tenants_file {
+            Some(path) => load_tenants(std::path::Path::new(path))?,
+            None => Vec::new(),
+        };
+        let tenant_secrets = load_tenant_secrets(&tenants, &custom_sources)?;
 
         let config = Self {
             bind_addr: env::var("RELAY_BIND").unwrap_or_else(|_| "0.0.0.0:8080".to_string()),
+            admin_bind_addr: optional_env("RELAY_ADMIN_BIND"),
+            unix_socket_mode: parse_unix_socket_mode(
+                &env::var("RELAY_UNIX_SOCKET_MODE").unwrap_or_else(|_| "660".to_string()),
+            )?,
             enabled_sources,
             source_topic_prefix,
             relay_source_topics,
-            kafka_brokers: required_env("KAFKA_BROKERS")?,
-            kafka_security_protocol: env::var("KAFKA_SECURITY_PROTOCOL")
-                .unwrap_or_else(|_| "ssl".to_string())
-                .trim()
-                .to_ascii_lowercase(),
-            kafka_allow_plaintext: env_bool("KAFKA_ALLOW_PLAINTEXT", false),
-            kafka_tls_cert: env::var("KAFKA_TLS_CERT").unwrap_or_default(),
-            kafka_tls_key: env::var("KAFKA_TLS_KEY").unwrap_or_default(),
-            kafka_tls_ca: env::var("KAFKA_TLS_CA").unwrap_or_default(),
+            kafka_brokers: kafka.brokers,
+            kafka_security_protocol: kafka.security_protocol,
+            kafka_allow_plaintext: kafka.allow_plaintext,
+            kafka_tls_cert: kafka.tls_cert.unwrap_or_default(),
+            kafka_tls_key: kafka.tls_key.unwrap_or_default(),
+            kafka_tls_ca: kafka.tls_ca.unwrap_or_default(),
             kafka_dlq_topic: env::var("KAFKA_DLQ_TOPIC")
                 .unwrap_or_else(|_| "webhooks.dlq".to_string()),
+            kafka_dlq_retention_bytes: optional_env("KAFKA_DLQ_RETENTION_BYTES")
+                .map(|raw| raw.parse::<i64>())
+                .transpose()
+                .context("invalid i64 for KAFKA_DLQ_RETENTION_BYTES")?,
+            kafka_dlq_retention_ms: optional_env("KAFKA_DLQ_RETENTION_MS")
+                .map(|raw| raw.parse::<i64>())
+                .transpose()
+                .context("invalid i64 for KAFKA_DLQ_RETENTION_MS")?,
             kafka_auto_create_topics: env_bool("KAFKA_AUTO_CREATE_TOPICS", true),
             kafka_topic_partitions: env_i32("KAFKA_TOPIC_PARTITIONS", 3)?,
             kafka_topic_replication_factor: env_i32("KAFKA_TOPIC_REPLICATION_FACTOR", 1)?,
-            hmac_secret_github: conditional_env("HMAC_SECRET_GITHUB", github_enabled)?,
-            hmac_secret_linear: conditional_env("HMAC_SECRET_LINEAR", linear_enabled)?,
-            hmac_secret_example: conditional_env("HMAC_SECRET_EXAMPLE", example_enabled)?,
+            kafka_producer_acks: env::var("KAFKA_PRODUCER_ACKS")
+                .unwrap_or_else(|_| "all".to_string())
+                .trim()
+                .to_ascii_lowercase(),
+            kafka_producer_idempotence: env_bool("KAFKA_PRODUCER_IDEMPOTENCE", true),
+            kafka_producer_delivery_timeout_ms: env_u64(
+                "KAFKA_PRODUCER_DELIVERY_TIMEOUT_MS",
+                30_000,
+            )?,
+            kafka_producer_retries: optional_env("KAFKA_PRODUCER_RETRIES")
+                .map(|raw| raw.parse::<u32>())
+                .transpose()
+                .context("invalid u32 for KAFKA_PRODUCER_RETRIES")?,
+            kafka_producer_compression: env::var("KAFKA_PRODUCER_COMPRESSION")
+                .unwrap_or_else(|_| "none".to_string())
+                .trim()
+                .to_ascii_lowercase(),
+            kafka_producer_transactional_id: optional_env("KAFKA_PRODUCER_TRANSACTIONAL_ID"),
+            kafka_producer_transaction_batch_size: env_usize(
+                "KAFKA_PRODUCER_TRANSACTION_BATCH_SIZE",
+                100,
+            )?,
+            hmac_secret_github: secrets.github,
+            hmac_secret_linear: secrets.linear,
+            hmac_secret_example: secrets.example,
+            hmac_secret_standard_webhooks: secrets.standard_webhooks,
+            hmac_secret_gitlab: secrets.gitlab,
+            hmac_secret_bitbucket: secrets.bitbucket,
+            hmac_secret_jira: secrets.jira,
+            hmac_secret_stripe: secrets.stripe,
+            hmac_secret_sentry: secrets.sentry,
+            hmac_secret_gmail: secrets.gmail,
+            hmac_secret_shopify: secrets.shopify,
+            hmac_secret_registry: secrets.registry,
+            hmac_secret_ci: secrets.ci,
+            discord_public_key: secrets.discord,
+            hmac_secret_custom: secrets.custom,
+            internal_tokens: secrets.internal,
+            tenants,
+            tenants_file,
+            tenant_secrets,
+            custom_sources,
+            stripe_tolerance_seconds: env_i64("RELAY_STRIPE_TOLERANCE_SECONDS", 300)?,
+            pubsub_audience: optional_env("RELAY_PUBSUB_AUDIENCE"),
+            pubsub_service_account: optional_env("RELAY_PUBSUB_SERVICE_ACCOUNT"),
+            pubsub_jwks_url: env::var("RELAY_PUBSUB_JWKS_URL")
+                .unwrap_or_else(|_| "https://www.googleapis.com/oauth2/v3/certs".to_string()),
+            sns_topic_arns: parse_csv(&env::var("RELAY_SNS_TOPIC_ARNS").unwrap_or_default()),
+            sns_tolerance_seconds: env_i64("RELAY_SNS_TOLERANCE_SECONDS", 3600)?,
+            standard_webhooks_tolerance_seconds: env_i64(
+                "RELAY_STANDARD_WEBHOOKS_TOLERANCE_SECONDS",
+                300,
+            )?,
+            github_allow_sha1: env_bool("RELAY_GITHUB_ALLOW_SHA1", false),
+            admin_token: secret_env("RELAY_ADMIN_TOKEN")?,
+            secrets_reload_seconds: optional_env("RELAY_SECRETS_RELOAD_SECONDS")
+                .map(|raw| raw.parse::<u64>())
+                .transpose()
+                .context("invalid u64 for RELAY_SECRETS_RELOAD_SECONDS")?,
             max_payload_bytes: env_usize("RELAY_MAX_PAYLOAD_BYTES", 1_048_576)?,
+            source_max_payload_bytes: parse_source_max_payload_bytes(
+                &env::var("RELAY_SOURCE_MAX_PAYLOAD_BYTES").unwrap_or_default(),
+            )?,
+            source_paths: parse_source_paths(&env::var("RELAY_SOURCE_PATHS").unwrap_or_default())?,
+            oversized_payload_mode: parse_oversized_payload_mode(
+                &env::var("RELAY_OVERSIZED_PAYLOAD_MODE").unwrap_or_else(|_| "reject".to_string()),
+            )?,
+            blob_dir: optional_env("RELAY_BLOB_DIR"),
+            blob_threshold_bytes: env_usize("RELAY_BLOB_THRESHOLD_BYTES", 262_144)?,
+            audit_dir: optional_env("RELAY_AUDIT_DIR"),
+            audit_url: optional_env("RELAY_AUDIT_URL"),
+            audit_token: secret_env("RELAY_AUDIT_TOKEN")?,
+            max_in_flight_requests: optional_env("RELAY_MAX_IN_FLIGHT_REQUESTS")
+                .map(|raw| {
+                    raw.parse::<usize>()
+                        .context("invalid usize for RELAY_MAX_IN_FLIGHT_REQUESTS")
+                })
+                .transpose()?,
+            access_log: env_bool("RELAY_ACCESS_LOG", false),
+            access_log_sample_rate: env_f64("RELAY_ACCESS_LOG_SAMPLE_RATE", 1.0)?,
             ip_limit_per_minute: env_u32("RELAY_IP_RATE_PER_MINUTE", 100)?,
             source_limit_per_minute: env_u32("RELAY_SOURCE_RATE_PER_MINUTE", 500)?,
             trust_proxy_headers: env_bool("RELAY_TRUST_PROXY_HEADERS", false),
+            proxy_protocol: env_bool("RELAY_PROXY_PROTOCOL", false),
             trusted_proxy_cidrs: env_cidrs("RELAY_TRUSTED_PROXY_CIDRS", "127.0.0.1/32,::1/128")?,
             dedup_ttl_seconds: env_i64("RELAY_DEDUP_TTL_SECONDS", 604_800)?,
             cooldown_seconds: env_i64("RELAY_COOLDOWN_SECONDS", 30)?,
+            content_dedup_ttl_seconds: optional_env("RELAY_CONTENT_DEDUP_TTL_SECONDS")
+                .map(|raw| raw.parse::<i64>())
+                .transpose()
+                .context("invalid i64 for RELAY_CONTENT_DEDUP_TTL_SECONDS")?,
+            debounce_window_ms: optional_env("RELAY_DEBOUNCE_WINDOW_MS")
+                .map(|raw| raw.parse::<u64>())
+                .transpose()
+                .context("invalid u64 for RELAY_DEBOUNCE_WINDOW_MS")?,
+            github_synchronize_coalesce_ms: optional_env("RELAY_GITHUB_SYNCHRONIZE_COALESCE_MS")
+                .map(|raw| raw.parse::<u64>())
+                .transpose()
+                .context("invalid u64 for RELAY_GITHUB_SYNCHRONIZE_COALESCE_MS")?,
+            github_app_private_key: conditional_secret_env(
+                "RELAY_GITHUB_APP_PRIVATE_KEY",
+                github_app_id.is_some(),
+            )?,
+            github_app_id,
+            github_api_url: optional_env("RELAY_GITHUB_API_URL")
+                .unwrap_or_else(|| "https://api.github.com".to_string())
+                .trim_end_matches('/')
+                .to_string(),
             enforce_linear_timestamp_window: env_bool(
                 "RELAY_ENFORCE_LINEAR_TIMESTAMP_WINDOW",
                 true,
             ),
             linear_timestamp_window_seconds: env_i64("RELAY_LINEAR_TIMESTAMP_WINDOW_SECONDS", 60)?,
+            linear_state_transitions: parse_linear_state_transitions(
+                &env::var("RELAY_LINEAR_STATE_TRANSITIONS").unwrap_or_default(),
+            )?,
+            linear_verify_with_api,
+            linear_api_token: conditional_secret_env(
+                "RELAY_LINEAR_API_TOKEN",
+                linear_verify_with_api,
+            )?,
+            sampling_rules: parse_sampling_rules(
+                &env::var("RELAY_SAMPLING_RULES").unwrap_or_default(),
+            )?,
             publish_queue_capacity: env_usize("RELAY_PUBLISH_QUEUE_CAPACITY", 4096)?,
             publish_max_retries: env_u32("RELAY_PUBLISH_MAX_RETRIES", 5)?,
             publish_backoff_base_ms: env_u64("RELAY_PUBLISH_BACKOFF_BASE_MS", 200)?,
             publish_backoff_max_ms: env_u64("RELAY_PUBLISH_BACKOFF_MAX_MS", 5_000)?,
+            publish_spill_dir: optional_env("RELAY_PUBLISH_SPILL_DIR"),
+            publish_spill_replay_seconds: env_u64("RELAY_PUBLISH_SPILL_REPLAY_SECONDS", 30)?,
+            shutdown_drain_seconds: env_u64("RELAY_SHUTDOWN_DRAIN_SECONDS", 0)?,
             validation_mode: env::var("RELAY_VALIDATION_MODE")
                 .unwrap_or_else(|_| "strict".to_string())
                 .trim()
@@ -197,113 +668,379 @@ impl Config {
                 .unwrap_or_else(|_| "default-openclaw".to_string())
                 .trim()
                 .to_string(),
-            contract_path: env::var("RELAY_CONTRACT_PATH")
-                .ok()
-                .map(|value| value.trim().to_string())
-                .filter(|value| !value.is_empty()),
-            active_ingress_adapter_id: env::var("RELAY_INGRESS_ADAPTER_ID")
-                .ok()
-                .map(|value| value.trim().to_string())
-                .filter(|value| !value.is_empty()),
+            contract_path: optional_env("RELAY_CONTRACT_PATH"),
+            enqueue_script_path: optional_env("RELAY_ENQUEUE_SCRIPT_PATH"),
+            digest_topic: optional_env("RELAY_DIGEST_TOPIC"),
+            digest_hour_utc: env_u32("RELAY_DIGEST_HOUR_UTC", 9)?,
+            alert_topic: optional_env("RELAY_ALERT_TOPIC"),
+            dlq_alert_threshold: optional_env("RELAY_DLQ_ALERT_THRESHOLD")
+                .map(|raw| raw.parse::<i64>())
+                .transpose()
+                .context("invalid i64 for RELAY_DLQ_ALERT_THRESHOLD")?,
+            dlq_alert_window_seconds: env_i64("RELAY_DLQ_ALERT_WINDOW_SECONDS", 300)?,
+            dlq_alert_cooldown_seconds: env_i64("RELAY_DLQ_ALERT_COOLDOWN_SECONDS", 3_600)?,
+            active_ingress_adapter_id: optional_env("RELAY_INGRESS_ADAPTER_ID"),
             ingress_adapters: parse_ingress_adapters_from_env()?,
             serve_routes: parse_serve_routes_from_env()?,
         };
 
-        if config.kafka_topic_partitions <= 0 {
-            return Err(anyhow!("KAFKA_TOPIC_PARTITIONS must be a positive integer"));
+        Ok(config)
+    }
+
+    /// Range and consistency problems, all of them rather than the first.
+    pub fn problems(&self) -> Vec<anyhow::Error> {
+        let mut problems = Vec::new();
+
+        if self.kafka_topic_partitions <= 0 {
+            problems.push(anyhow!("KAFKA_TOPIC_PARTITIONS must be a positive integer"));
         }
 
-        if config.kafka_topic_replication_factor <= 0 {
-            return Err(anyhow!(
+        if self
+            .kafka_dlq_retention_bytes
+            .is_some_and(|bytes| bytes <= 0)
+        {
+            problems.push(anyhow!(
+                "KAFKA_DLQ_RETENTION_BYTES must be a positive integer"
+            ));
+        }
+
+        if self.kafka_dlq_retention_ms.is_some_and(|ms| ms <= 0) {
+            problems.push(anyhow!("KAFKA_DLQ_RETENTION_MS must be a positive integer"));
+        }
+
+        if self.kafka_topic_replication_factor <= 0 {
+            problems.push(anyhow!(
                 "KAFKA_TOPIC_REPLICATION_FACTOR must be a positive integer"
             ));
         }
 
-        if config.dedup_ttl_seconds <= 0 {
-            return Err(anyhow!(
+        if !matches!(self.kafka_producer_acks.as_str(), "all" | "-1" | "0" | "1") {
+            problems.push(anyhow!(
+                "unsupported KAFKA_PRODUCER_ACKS={}; expected all, 1 or 0",
+                self.kafka_producer_acks
+            ));
+        }
+        if self.kafka_producer_idempotence
+            && !matches!(self.kafka_producer_acks.as_str(), "all" | "-1")
+        {
+            problems.push(anyhow!(
+                "KAFKA_PRODUCER_IDEMPOTENCE=true requires KAFKA_PRODUCER_ACKS=all"
+            ));
+        }
+        if !matches!(
+            self.kafka_producer_compression.as_str(),
+            "none" | "gzip" | "snappy" | "lz4" | "zstd"
+        ) {
+            problems.push(anyhow!(
+                "unsupported KAFKA_PRODUCER_COMPRESSION={}; expected none, gzip, snappy, lz4 or zstd",
+                self.kafka_producer_compression
+            ));
+        }
+        if self.kafka_producer_transactional_id.is_some() {
+            if !self.kafka_producer_idempotence {
+                problems.push(anyhow!(
+                    "KAFKA_PRODUCER_TRANSACTIONAL_ID requires KAFKA_PRODUCER_IDEMPOTENCE=true"
+                ));
+            }
+            if self.kafka_producer_transaction_batch_size == 0 {
+                problems.push(anyhow!(
+                    "KAFKA_PRODUCER_TRANSACTION_BATCH_SIZE must be a positive integer"
+                ));
+            }
+        }
+        if self.kafka_producer_delivery_timeout_ms == 0 {
+            problems.push(anyhow!(
+                "KAFKA_PRODUCER_DELIVERY_TIMEOUT_MS must be a positive integer"
+            ));
+        }
+
+        if let Some(public_key) = &self.discord_public_key
+            && (public_key.len() != 64
+                || !public_key
+                    .chars()
+                    .all(|character| character.is_ascii_hexdigit()))
+        {
+            problems.push(anyhow!(
+                "RELAY_DISCORD_PUBLIC_KEY must be 64 hex characters"
+            ));
+        }
+
+        for name in self.custom_sources.keys() {
+            if !self.is_source_enabled(name) {
+                problems.push(anyhow!(
+                    "RELAY_CUSTOM_SOURCES declares {name}, which is not in RELAY_ENABLED_SOURCES"
+                ));
+            }
+        }
+
+        for source in self.source_paths.keys() {
+            if !self.is_source_enabled(source) {
+                problems.push(anyhow!(
+                    "RELAY_SOURCE_PATHS names {source}, which is not in RELAY_ENABLED_SOURCES"
+                ));
+            }
+        }
+
+        for (source, limit) in &self.source_max_payload_bytes {
+            if *limit == 0 || *limit > self.max_payload_bytes {
+                problems.push(anyhow!(
+                    "RELAY_SOURCE_MAX_PAYLOAD_BYTES limit for {source} must be between 1 and RELAY_MAX_PAYLOAD_BYTES ({})",
+                    self.max_payload_bytes
+                ));
+            }
+        }
+
+        if self.blob_dir.is_some() && self.blob_threshold_bytes == 0 {
+            problems.push(anyhow!(
+                "RELAY_BLOB_THRESHOLD_BYTES must be a positive integer"
+            ));
+        }
+
+        if self.max_in_flight_requests == Some(0) {
+            problems.push(anyhow!(
+                "RELAY_MAX_IN_FLIGHT_REQUESTS must be a positive integer"
+            ));
+        }
+
+        if self.audit_dir.is_some() && self.audit_url.is_some() {
+            problems.push(anyhow!(
+                "RELAY_AUDIT_DIR and RELAY_AUDIT_URL are mutually exclusive"
+            ));
+        }
+        if let Some(url) = self.audit_url.as_deref()
+            && !(url.starts_with("https://") || url.starts_with("http://"))
+        {
+            problems.push(anyhow!("RELAY_AUDIT_URL must be an http(s) URL"));
+        }
+
+        if !(0.0..=1.0).contains(&self.access_log_sample_rate) {
+            problems.push(anyhow!(
+                "RELAY_ACCESS_LOG_SAMPLE_RATE must be between 0 and 1"
+            ));
+        }
+
+        for tenant in &self.tenants {
+            if let Some(source) = tenant
+                .secret_envs
+                .keys()
+                .find(|source| !self.is_source_enabled(source))
+            {
+                problems.push(anyhow!(
+                    "tenant '{}' configures {source}, which is not in RELAY_ENABLED_SOURCES",
+                    tenant.id
+                ));
+            }
+        }
+
+        if self.dedup_ttl_seconds <= 0 {
+            problems.push(anyhow!(
                 "RELAY_DEDUP_TTL_SECONDS must be a positive integer"
             ));
         }
 
-        if config.cooldown_seconds <= 0 {
-            return Err(anyhow!("RELAY_COOLDOWN_SECONDS must be a positive integer"));
+        if self.cooldown_seconds <= 0 {
+            problems.push(anyhow!("RELAY_COOLDOWN_SECONDS must be a positive integer"));
         }
 
-        if config.linear_timestamp_window_seconds <= 0 {
-            return Err(anyhow!(
+        if self.debounce_window_ms == Some(0) {
+            problems.push(anyhow!(
+                "RELAY_DEBOUNCE_WINDOW_MS must be a positive integer"
+            ));
+        }
+
+        if self.github_synchronize_coalesce_ms == Some(0) {
+            problems.push(anyhow!(
+                "RELAY_GITHUB_SYNCHRONIZE_COALESCE_MS must be a positive integer"
+            ));
+        }
+
+        if self.secrets_reload_seconds == Some(0) {
+            problems.push(anyhow!(
+                "RELAY_SECRETS_RELOAD_SECONDS must be a positive integer"
+            ));
+        }
+
+        if self.content_dedup_ttl_seconds.is_some_and(|ttl| ttl <= 0) {
+            problems.push(anyhow!(
+                "RELAY_CONTENT_DEDUP_TTL_SECONDS must be a positive integer"
+            ));
+        }
+
+        if self.linear_timestamp_window_seconds <= 0 {
+            problems.push(anyhow!(
                 "RELAY_LINEAR_TIMESTAMP_WINDOW_SECONDS must be a positive integer"
             ));
         }
 
-        if config.trust_proxy_headers && config.trusted_proxy_cidrs.is_empty() {
-            return Err(anyhow!(
+        if self.standard_webhooks_tolerance_seconds <= 0 {
+            problems.push(anyhow!(
+                "RELAY_STANDARD_WEBHOOKS_TOLERANCE_SECONDS must be a positive integer"
+            ));
+        }
+
+        if self.stripe_tolerance_seconds <= 0 {
+            problems.push(anyhow!(
+                "RELAY_STRIPE_TOLERANCE_SECONDS must be a positive integer"
+            ));
+        }
+
+        if self.sns_tolerance_seconds <= 0 {
+            problems.push(anyhow!(
+                "RELAY_SNS_TOLERANCE_SECONDS must be a positive integer"
+            ));
+        }
+
+        if self.is_source_enabled("pubsub") && self.pubsub_audience.is_none() {
+            problems.push(anyhow!(
+                "RELAY_PUBSUB_AUDIENCE is required when the pubsub source is enabled"
+            ));
+        }
+
+        if self.is_source_enabled("pubsub") && self.pubsub_service_account.is_none() {
+            problems.push(anyhow!(
+                "RELAY_PUBSUB_SERVICE_ACCOUNT is required when the pubsub source is enabled"
+            ));
+        }
+
+        if self.is_source_enabled("sns") && self.sns_topic_arns.is_empty() {
+            problems.push(anyhow!(
+                "RELAY_SNS_TOPIC_ARNS is required when the sns source is enabled"
+            ));
+        }
+
+        if self.publish_queue_capacity == 0 {
+            problems.push(anyhow!(
+                "RELAY_PUBLISH_QUEUE_CAPACITY must be a positive integer"
+            ));
+        }
+
+        if self.publish_spill_replay_seconds == 0 {
+            problems.push(anyhow!(
+                "RELAY_PUBLISH_SPILL_REPLAY_SECONDS must be a positive integer"
+            ));
+        }
+
+        if self.publish_backoff_max_ms < self.publish_backoff_base_ms {
+            problems.push(anyhow!(
+                "RELAY_PUBLISH_BACKOFF_MAX_MS ({}) must be >= RELAY_PUBLISH_BACKOFF_BASE_MS ({})",
+                self.publish_backoff_max_ms,
+                self.publish_backoff_base_ms
+            ));
+        }
+
+        match BindTarget::parse_list(&self.bind_addr).context("invalid RELAY_BIND") {
+            Ok(bind_targets) => {
+                if self.proxy_protocol
+                    && bind_targets
+                        .iter()
+                        .any(|target| matches!(target, BindTarget::Unix(_)))
+                {
+                    problems.push(anyhow!(
+                        "RELAY_PROXY_PROTOCOL requires TCP targets in RELAY_BIND"
+                    ));
+                }
+                if let Some(admin_bind_addr) = self.admin_bind_addr.as_deref() {
+                    match BindTarget::parse(admin_bind_addr).context("invalid RELAY_ADMIN_BIND") {
+                        Ok(admin_target) if bind_targets.contains(&admin_target) => {
+                            problems.push(anyhow!("RELAY_ADMIN_BIND must differ from RELAY_BIND"));
+                        }
+                        Ok(_) => {}
+                        Err(error) => problems.push(error),
+                    }
+                }
+            }
+            Err(error) => problems.push(error),
+        }
+
+        if self.digest_hour_utc > 23 {
+            problems.push(anyhow!("RELAY_DIGEST_HOUR_UTC must be between 0 and 23"));
+        }
+
+        if let Some(threshold) = self.dlq_alert_threshold {
+            if threshold < 0 {
+                problems.push(anyhow!("RELAY_DLQ_ALERT_THRESHOLD cannot be negative"));
+            }
+            if self.dlq_alert_window_seconds <= 0 {
+                problems.push(anyhow!(
+                    "RELAY_DLQ_ALERT_WINDOW_SECONDS must be a positive integer"
+                ));
+            }
+            if self.alert_topic().is_none() {
+                problems.push(anyhow!(
+                    "RELAY_DLQ_ALERT_THRESHOLD requires RELAY_ALERT_TOPIC or RELAY_DIGEST_TOPIC"
+                ));
+            }
+        }
+
+        if self.trust_proxy_headers && self.trusted_proxy_cidrs.is_empty() {
+            problems.push(anyhow!(
                 "RELAY_TRUSTED_PROXY_CIDRS cannot be empty when RELAY_TRUST_PROXY_HEADERS is enabled"
             ));
         }
 
-        match config.kafka_security_protocol.as_str() {
+        match self.kafka_security_protocol.as_str() {
             "ssl" => {
-                if config.kafka_tls_cert.trim().is_empty() {
-                    return Err(anyhow!(
+                if self.kafka_tls_cert.trim().is_empty() {
+                    problems.push(anyhow!(
                         "KAFKA_TLS_CERT is required when KAFKA_SECURITY_PROTOCOL=ssl"
                     ));
                 }
-                if config.kafka_tls_key.trim().is_empty() {
-                    return Err(anyhow!(
+                if self.kafka_tls_key.trim().is_empty() {
+                    problems.push(anyhow!(
                         "KAFKA_TLS_KEY is required when KAFKA_SECURITY_PROTOCOL=ssl"
                     ));
                 }
-                if config.kafka_tls_ca.trim().is_empty() {
-                    return Err(anyhow!(
+                if self.kafka_tls_ca.trim().is_empty() {
+                    problems.push(anyhow!(
                         "KAFKA_TLS_CA is required when KAFKA_SECURITY_PROTOCOL=ssl"
                     ));
                 }
             }
             "plaintext" => {
-                if !config.kafka_allow_plaintext {
-                    return Err(anyhow!(
+                if !self.kafka_allow_plaintext {
+                    problems.push(anyhow!(
                         "KAFKA_SECURITY_PROTOCOL=plaintext requires KAFKA_ALLOW_PLAINTEXT=true"
                     ));
                 }
             }
             other => {
-                return Err(anyhow!(
+                problems.push(anyhow!(
                     "unsupported KAFKA_SECURITY_PROTOCOL={other}; expected ssl or plaintext"
                 ));
             }
         }
 
-        match config.validation_mode.as_str() {
+        match self.validation_mode.as_str() {
             "strict" | "debug" => {}
             other => {
-                return Err(anyhow!(
+                problems.push(anyhow!(
                     "unsupported RELAY_VALIDATION_MODE={other}; expected strict or debug"
                 ));
             }
         }
 
-        for route in &config.serve_routes {
+        for route in &self.serve_routes {
             if route.id.trim().is_empty() {
-                return Err(anyhow!("RELAY_SERVE_ROUTES_JSON route id cannot be empty"));
+                problems.push(anyhow!("RELAY_SERVE_ROUTES_JSON route id cannot be empty"));
             }
             if route.source_match.trim().is_empty() {
-                return Err(anyhow!(
+                problems.push(anyhow!(
                     "RELAY_SERVE_ROUTES_JSON route source_match cannot be empty"
                 ));
             }
             if route.event_type_pattern.trim().is_empty() {
-                return Err(anyhow!(
+                problems.push(anyhow!(
                     "RELAY_SERVE_ROUTES_JSON route event_type_pattern cannot be empty"
                 ));
             }
             if route.target_topic.trim().is_empty() {
-                return Err(anyhow!(
+                problems.push(anyhow!(
                     "RELAY_SERVE_ROUTES_JSON route target_topic cannot be empty"
                 ));
             }
         }
 
-        for adapter in &config.ingress_adapters {
+        for adapter in &self.ingress_adapters {
             match adapter {
                 RuntimeIngressAdapter::HttpWebhookIngress {
                     id,
@@ -312,21 +1049,23 @@ impl Config {
                     plugins,
                 } => {
                     if id.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON http_webhook_ingress id cannot be empty"
                         ));
                     }
                     if bind.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON http_webhook_ingress bind cannot be empty"
                         ));
                     }
                     if path_template.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON http_webhook_ingress path_template cannot be empty"
                         ));
                     }
-                    validate_serve_plugins(plugins, id)?;
+                    if let Err(error) = validate_serve_plugins(plugins, id) {
+                        problems.push(error);
+                    }
                 }
                 RuntimeIngressAdapter::WebsocketIngress {
                     id,
@@ -336,21 +1075,23 @@ impl Config {
                     ..
                 } => {
                     if id.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON websocket_ingress id cannot be empty"
                         ));
                     }
                     if path_template.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON websocket_ingress path_template cannot be empty"
                         ));
                     }
                     if auth_mode.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON websocket_ingress auth_mode cannot be empty"
                         ));
                     }
-                    validate_serve_plugins(plugins, id)?;
+                    if let Err(error) = validate_serve_plugins(plugins, id) {
+                        problems.push(error);
+                    }
                 }
                 RuntimeIngressAdapter::McpIngestExposed {
                     id,
@@ -364,41 +1105,43 @@ impl Config {
                     plugins,
                 } => {
                     if id.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON mcp_ingest_exposed id cannot be empty"
                         ));
                     }
                     if tool_name.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON mcp_ingest_exposed tool_name cannot be empty"
                         ));
                     }
                     if transport_driver.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON mcp_ingest_exposed transport_driver cannot be empty"
                         ));
                     }
                     if bind.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON mcp_ingest_exposed bind cannot be empty"
                         ));
                     }
                     if auth_mode.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON mcp_ingest_exposed auth_mode cannot be empty"
                         ));
                     }
                     if *max_payload_bytes == 0 {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON mcp_ingest_exposed max_payload_bytes must be positive"
                         ));
                     }
                     if path.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON mcp_ingest_exposed path cannot be empty"
                         ));
                     }
-                    validate_serve_plugins(plugins, id)?;
+                    if let Err(error) = validate_serve_plugins(plugins, id) {
+                        problems.push(error);
+                    }
                 }
                 RuntimeIngressAdapter::KafkaIngress {
                     id,
@@ -408,26 +1151,54 @@ impl Config {
                     ..
                 } => {
                     if id.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON kafka_ingress id cannot be empty"
                         ));
                     }
                     if topics.is_empty() || topics.iter().any(|topic| topic.trim().is_empty()) {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON kafka_ingress topics must be non-empty"
                         ));
                     }
                     if group_id.trim().is_empty() {
-                        return Err(anyhow!(
+                        problems.push(anyhow!(
                             "RELAY_INGRESS_ADAPTERS_JSON kafka_ingress group_id cannot be empty"
                         ));
                     }
-                    validate_serve_plugins(plugins, id)?;
+                    if let Err(error) = validate_serve_plugins(plugins, id) {
+                        problems.push(error);
+                    }
                 }
             }
         }
 
-        Ok(config)
+        problems
+    }
+
+    pub fn source_secrets(&self) -> SourceSecrets {
+        SourceSecrets {
+            github: self.hmac_secret_github.clone(),
+            linear: self.hmac_secret_linear.clone(),
+            example: self.hmac_secret_example.clone(),
+            standard_webhooks: self.hmac_secret_standard_webhooks.clone(),
+            gitlab: self.hmac_secret_gitlab.clone(),
+            bitbucket: self.hmac_secret_bitbucket.clone(),
+            jira: self.hmac_secret_jira.clone(),
+            stripe: self.hmac_secret_stripe.clone(),
+            sentry: self.hmac_secret_sentry.clone(),
+            gmail: self.hmac_secret_gmail.clone(),
+            shopify: self.hmac_secret_shopify.clone(),
+            registry: self.hmac_secret_registry.clone(),
+            ci: self.hmac_secret_ci.clone(),
+            discord: self.discord_public_key.clone(),
+            custom: self.hmac_secret_custom.clone(),
+            internal: self.internal_tokens.clone(),
+            tenants: self.tenant_secrets.clone(),
+        }
+    }
+
+    pub fn tenant(&self, id: &str) -> Option<&TenantConfig> {
+        self.tenants.iter().find(|tenant| tenant.id == id)
     }
 
     pub fn is_source_enabled(&self, source: &str) -> bool {
@@ -437,6 +1208,19 @@ impl Config {
             .any(|candidate| candidate == &normalized)
     }
 
+    /// Payload limit for `source`; bodies up to `max_payload_bytes` are read
+    /// regardless so truncate mode can still verify their signature.
+    pub fn max_payload_bytes_for(&self, source: &str) -> usize {
+        self.source_max_payload_bytes
+            .get(source)
+            .copied()
+            .unwrap_or(self.max_payload_bytes)
+    }
+
+    pub fn alert_topic(&self) -> Option<&str> {
+        self.alert_topic.as_deref().or(self.digest_topic.as_deref())
+    }
+
     pub fn source_topic_name(&self, source: &str) -> String {
         let normalized_source = source.trim().to_ascii_lowercase();
         if let Some(topic) = self
@@ -451,25 +1235,6 @@ impl Config {
     }
 }
 
-fn required_env(name: &str) -> Result<String> {
-    let value = env::var(name).with_context(|| format!("missing required env var: {name}"))?;
-    if value.trim().is_empty() {
-        return Err(anyhow!("required env var {name} cannot be empty"));
-    }
-    Ok(value)
-}
-
-fn conditional_env(name: &str, required: bool) -> Result<Option<String>> {
-    if required {
-        return required_env(name).map(Some);
-    }
-
-    Ok(env::var(name)
-        .ok()
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty()))
-}
-
 fn env_csv_lower(name: &str, default: &str) -> Result<Vec<String>> {
     let raw = env::var(name).unwrap_or_else(|_| default.to_string());
     let values = parse_csv(&raw)
@@ -482,14 +1247,6 @@ fn env_csv_lower(name: &str, default: &str) -> Result<Vec<String>> {
     Ok(values)
 }
 
-fn parse_csv(raw: &str) -> Vec<String> {
-    raw.split(',')
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .map(ToString::to_string)
-        .collect()
-}
-
 fn contains_source(values: &[String], source: &str) -> bool {
     values.iter().any(|value| value == source)
 }
@@ -499,92 +1256,224 @@ fn topic_matches_source(topic: &str, source: &str) -> bool {
     normalized_topic == source || normalized_topic.ends_with(&format!(".{source}"))
 }
 
-fn env_u32(name: &str, default: u32) -> Result<u32> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
+fn env_cidrs(name: &str, default: &str) -> Result<Vec<IpNet>> {
+    let raw = env::var(name).unwrap_or_else(|_| default.to_string());
+    raw.split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
         .map(|value| {
             value
-                .parse::<u32>()
-                .with_context(|| format!("invalid u32 for {name}"))
+                .parse::<IpNet>()
+                .with_context(|| format!("invalid CIDR for {name}: {value}"))
         })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
+        .collect()
 }
 
-fn env_u64(name: &str, default: u64) -> Result<u64> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<u64>()
-                .with_context(|| format!("invalid u64 for {name}"))
-        })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
+fn parse_unix_socket_mode(raw: &str) -> Result<u32> {
+    let mode = u32::from_str_radix(raw.trim().trim_start_matches("0o"), 8)
+        .with_context(|| format!("invalid octal mode for RELAY_UNIX_SOCKET_MODE: {raw}"))?;
+    if mode > 0o777 {
+        return Err(anyhow!("RELAY_UNIX_SOCKET_MODE must be at most 777"));
+    }
+    Ok(mode)
 }
 
-fn env_i32(name: &str, default: i32) -> Result<i32> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<i32>()
-                .with_context(|| format!("invalid i32 for {name}"))
+fn parse_linear_state_transitions(raw: &str) -> Result<Vec<LinearStateTransition>> {
+    parse_csv(raw)
+        .into_iter()
+        .map(|rule| {
+            let (from, to) = rule.split_once("->").ok_or_else(|| {
+                anyhow!("RELAY_LINEAR_STATE_TRANSITIONS rule '{rule}' must use from->to")
+            })?;
+            Ok(LinearStateTransition {
+                from: parse_state_selector(from, &rule)?,
+                to: parse_state_selector(to, &rule)?,
+            })
         })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
+        .collect()
 }
 
-fn env_i64(name: &str, default: i64) -> Result<i64> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<i64>()
-                .with_context(|| format!("invalid i64 for {name}"))
+fn parse_sampling_rules(raw: &str) -> Result<Vec<SamplingRule>> {
+    parse_csv(raw)
+        .into_iter()
+        .map(|rule| {
+            let malformed = || {
+                anyhow!(
+                    "RELAY_SAMPLING_RULES rule '{rule}' must use source:event_type[@scope]=rate"
+                )
+            };
+            let (selector, rate) = rule.split_once('=').ok_or_else(malformed)?;
+            let (typed, scope) = match selector.split_once('@') {
+                Some((typed, scope)) => (typed, Some(scope.trim().to_string())),
+                None => (selector, None),
+            };
+            let (source, event_type) = typed.split_once(':').ok_or_else(malformed)?;
+            let (source, event_type) = (source.trim(), event_type.trim());
+            if source.is_empty() || event_type.is_empty() || scope.as_deref() == Some("") {
+                return Err(malformed());
+            }
+            let rate = rate
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|rate| (0.0..=1.0).contains(rate))
+                .ok_or_else(|| {
+                    anyhow!("RELAY_SAMPLING_RULES rule '{rule}' rate must be between 0 and 1")
+                })?;
+            Ok(SamplingRule {
+                source: source.to_ascii_lowercase(),
+                event_type: event_type.to_string(),
+                scope,
+                rate,
+            })
         })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
+        .collect()
 }
 
-fn env_usize(name: &str, default: usize) -> Result<usize> {
-    env::var(name)
-        .ok()
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| {
-            value
-                .parse::<usize>()
-                .with_context(|| format!("invalid usize for {name}"))
+fn parse_source_max_payload_bytes(raw: &str) -> Result<BTreeMap<String, usize>> {
+    parse_csv(raw)
+        .into_iter()
+        .map(|entry| {
+            let (source, limit) = entry.split_once('=').ok_or_else(|| {
+                anyhow!("RELAY_SOURCE_MAX_PAYLOAD_BYTES entry '{entry}' must use source=bytes")
+            })?;
+            let limit = limit.trim().parse::<usize>().with_context(|| {
+                format!("invalid usize in RELAY_SOURCE_MAX_PAYLOAD_BYTES entry '{entry}'")
+            })?;
+            Ok((source.trim().to_ascii_lowercase(), limit))
         })
-        .transpose()
-        .map(|value| value.unwrap_or(default))
+        .collect()
 }
 
-fn env_bool(name: &str, default: bool) -> bool {
-    match env::var(name) {
-        Ok(value) => matches!(
-            value.trim().to_ascii_lowercase().as_str(),
-            "1" | "true" | "yes" | "on"
-        ),
-        Err(_) => default,
+fn parse_source_paths(raw: &str) -> Result<BTreeMap<String, String>> {
+    let mut paths = BTreeMap::new();
+    for entry in parse_csv(raw) {
+        let (source, path) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow!("RELAY_SOURCE_PATHS entry '{entry}' must use source=/path"))?;
+        let path = path.trim();
+        if !path.starts_with('/') || path.contains(['{', '}', '*']) {
+            return Err(anyhow!(
+                "RELAY_SOURCE_PATHS path '{path}' must be a literal path starting with '/'"
+            ));
+        }
+        if paths.values().any(|existing| existing == path) {
+            return Err(anyhow!("RELAY_SOURCE_PATHS path '{path}' is used twice"));
+        }
+        paths.insert(source.trim().to_ascii_lowercase(), path.to_string());
     }
+    Ok(paths)
 }
 
-fn env_cidrs(name: &str, default: &str) -> Result<Vec<IpNet>> {
-    let raw = env::var(name).unwrap_or_else(|_| default.to_string());
-    raw.split(',')
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .map(|value| {
-            value
-                .parse::<IpNet>()
-                .with_context(|| format!("invalid CIDR for {name}: {value}"))
-        })
-        .collect()
+/// `name=Header-Name[:algorithm]` entries; the algorithm defaults to sha256.
+fn parse_custom_sources(raw: &str) -> Result<BTreeMap<String, CustomSource>> {
+    let mut sources = BTreeMap::new();
+    for entry in parse_csv(raw) {
+        let (name, spec) = entry.split_once('=').ok_or_else(|| {
+            anyhow!("RELAY_CUSTOM_SOURCES entry '{entry}' must use name=Header[:algorithm]")
+        })?;
+        let name = name.trim().to_ascii_lowercase();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '-')
+        {
+            return Err(anyhow!(
+                "RELAY_CUSTOM_SOURCES name '{name}' may only use letters, digits and '-'"
+            ));
+        }
+        if crate::sources::has_handler(&name) {
+            return Err(anyhow!(
+                "RELAY_CUSTOM_SOURCES name '{name}' shadows a built-in source"
+            ));
+        }
+        let (header, algorithm) = match spec.split_once(':') {
+            Some((header, algorithm)) => (
+                header.trim(),
+                HmacAlgorithm::parse(algorithm).ok_or_else(|| {
+                    anyhow!(
+                        "RELAY_CUSTOM_SOURCES algorithm '{}' for {name} must be sha256 or sha1",
+                        algorithm.trim()
+                    )
+                })?,
+            ),
+            None => (spec.trim(), HmacAlgorithm::Sha256),
+        };
+        if axum::http::HeaderName::from_bytes(header.as_bytes()).is_err() {
+            return Err(anyhow!(
+                "RELAY_CUSTOM_SOURCES header '{header}' for {name} is not a valid header name"
+            ));
+        }
+        if sources
+            .insert(
+                name.clone(),
+                CustomSource {
+                    signature_header: header.to_string(),
+                    algorithm,
+                },
+            )
+            .is_some()
+        {
+            return Err(anyhow!("RELAY_CUSTOM_SOURCES declares {name} twice"));
+        }
+    }
+    Ok(sources)
+}
+
+/// `producer=token` entries. Producers are identified by their token, so
+/// tokens must be unique.
+fn parse_internal_tokens(raw: &str) -> Result<BTreeMap<String, String>> {
+    let mut tokens = BTreeMap::new();
+    for entry in parse_csv(raw) {
+        let (producer, token) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow!("RELAY_INTERNAL_TOKENS entries must use producer=token"))?;
+        let producer = producer.trim().to_ascii_lowercase();
+        let token = token.trim();
+        if producer.is_empty()
+            || !producer.chars().all(|character| {
+                character.is_ascii_alphanumeric() || character == '-' || character == '_'
+            })
+        {
+            return Err(anyhow!(
+                "RELAY_INTERNAL_TOKENS producer '{producer}' may only use letters, digits, '-' and '_'"
+            ));
+        }
+        if token.is_empty() {
+            return Err(anyhow!(
+                "RELAY_INTERNAL_TOKENS token for {producer} is empty"
+            ));
+        }
+        if tokens.values().any(|existing| existing == token) {
+            return Err(anyhow!(
+                "RELAY_INTERNAL_TOKENS token for {producer} is shared with another producer"
+            ));
+        }
+        if tokens.insert(producer.clone(), token.to_string()).is_some() {
+            return Err(anyhow!("RELAY_INTERNAL_TOKENS declares {producer} twice"));
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_oversized_payload_mode(raw: &str) -> Result<OversizedPayloadMode> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "reject" => Ok(OversizedPayloadMode::Reject),
+        "truncate" => Ok(OversizedPayloadMode::Truncate),
+        other => Err(anyhow!(
+            "unsupported RELAY_OVERSIZED_PAYLOAD_MODE={other}; expected reject or truncate"
+        )),
+    }
+}
+
+fn parse_state_selector(raw: &str, rule: &str) -> Result<Option<String>> {
+    match raw.trim() {
+        "" => Err(anyhow!(
+            "RELAY_LINEAR_STATE_TRANSITIONS rule '{rule}' cannot have an empty state"
+        )),
+        "*" => Ok(None),
+        state => Ok(Some(state.to_string())),
+    }
 }
 
 fn parse_serve_routes_from_env() -> Result<Vec<ServeRouteRule>> {
@@ -615,6 +1504,20 @@ fn parse_ingress_adapters_from_env() -> Result<Vec<RuntimeIngressAdapter>> {
         .with_context(|| "parse RELAY_INGRESS_ADAPTERS_JSON as adapter list".to_string())
 }
 
+/// `Ok` when there are no problems; a single problem is returned as is,
+/// several as one numbered list.
+pub fn report_problems(problems: Vec<String>) -> Result<()> {
+    match problems.as_slice() {
+        [] => Ok(()),
+        [problem] => Err(anyhow!("{problem}")),
+        _ => Err(anyhow!(
+            "{} config problems:\n  - {}",
+            problems.len(),
+            problems.join("\n  - ")
+        )),
+    }
+}
+
 fn validate_serve_plugins(plugins: &[RuntimeServePluginConfig], adapter_id: &str) -> Result<()> {
     for plugin in plugins {
         match plugin {
@@ -656,7 +1559,11 @@ fn validate_serve_plugins(plugins: &[RuntimeServePluginConfig], adapter_id: &str
 
 #[cfg(test)]
 mod tests {
-    use super::Config;
+    use super::{
+        Config, CustomSource, HmacAlgorithm, LinearStateTransition, OversizedPayloadMode,
+        SamplingRule, SourceSecrets, TenantConfig, custom_secret_env, parse_custom_sources,
+        parse_internal_tokens, parse_source_paths, parse_unix_socket_mode,
+    };
     use std::env;
     use std::sync::{LazyLock, Mutex};
 
@@ -674,28 +1581,114 @@ mod tests {
         "KAFKA_TLS_KEY",
         "KAFKA_TLS_CA",
         "KAFKA_DLQ_TOPIC",
+        "KAFKA_DLQ_RETENTION_BYTES",
+        "KAFKA_DLQ_RETENTION_MS",
         "KAFKA_AUTO_CREATE_TOPICS",
         "KAFKA_TOPIC_PARTITIONS",
+        "KAFKA_PRODUCER_ACKS",
+        "KAFKA_PRODUCER_IDEMPOTENCE",
+        "KAFKA_PRODUCER_DELIVERY_TIMEOUT_MS",
+        "KAFKA_PRODUCER_RETRIES",
+        "KAFKA_PRODUCER_COMPRESSION",
+        "KAFKA_PRODUCER_TRANSACTIONAL_ID",
+        "KAFKA_PRODUCER_TRANSACTION_BATCH_SIZE",
         "KAFKA_TOPIC_REPLICATION_FACTOR",
         "HMAC_SECRET_GITHUB",
         "HMAC_SECRET_LINEAR",
         "HMAC_SECRET_EXAMPLE",
+        "HMAC_SECRET_STANDARD_WEBHOOKS",
+        "HMAC_SECRET_GITLAB",
+        "HMAC_SECRET_BITBUCKET",
+        "HMAC_SECRET_JIRA",
+        "HMAC_SECRET_STRIPE",
+        "HMAC_SECRET_SENTRY",
+        "RELAY_DISCORD_PUBLIC_KEY",
+        "HMAC_SECRET_GMAIL",
+        "HMAC_SECRET_SHOPIFY",
+        "HMAC_SECRET_REGISTRY",
+        "HMAC_SECRET_CI",
+        "HMAC_SECRET_GITHUB_FILE",
+        "HMAC_SECRET_LINEAR_FILE",
+        "HMAC_SECRET_EXAMPLE_FILE",
+        "HMAC_SECRET_STANDARD_WEBHOOKS_FILE",
+        "HMAC_SECRET_GITLAB_FILE",
+        "HMAC_SECRET_BITBUCKET_FILE",
+        "HMAC_SECRET_JIRA_FILE",
+        "HMAC_SECRET_STRIPE_FILE",
+        "HMAC_SECRET_SENTRY_FILE",
+        "HMAC_SECRET_GMAIL_FILE",
+        "HMAC_SECRET_SHOPIFY_FILE",
+        "HMAC_SECRET_REGISTRY_FILE",
+        "HMAC_SECRET_CI_FILE",
+        "RELAY_DISCORD_PUBLIC_KEY_FILE",
+        "RELAY_STRIPE_TOLERANCE_SECONDS",
+        "RELAY_PUBSUB_AUDIENCE",
+        "RELAY_PUBSUB_SERVICE_ACCOUNT",
+        "RELAY_PUBSUB_JWKS_URL",
+        "RELAY_SNS_TOPIC_ARNS",
+        "RELAY_SNS_TOLERANCE_SECONDS",
+        "RELAY_STANDARD_WEBHOOKS_TOLERANCE_SECONDS",
+        "RELAY_GITHUB_ALLOW_SHA1",
         "RELAY_MAX_PAYLOAD_BYTES",
+        "RELAY_SOURCE_MAX_PAYLOAD_BYTES",
+        "RELAY_SOURCE_PATHS",
+        "RELAY_CUSTOM_SOURCES",
+        "RELAY_INTERNAL_TOKENS",
+        "RELAY_INTERNAL_TOKENS_FILE",
+        "RELAY_TENANTS_FILE",
+        "RELAY_OVERSIZED_PAYLOAD_MODE",
+        "RELAY_BLOB_DIR",
+        "RELAY_MAX_IN_FLIGHT_REQUESTS",
+        "RELAY_AUDIT_DIR",
+        "RELAY_AUDIT_URL",
+        "RELAY_AUDIT_TOKEN",
+        "RELAY_AUDIT_TOKEN_FILE",
+        "RELAY_BLOB_THRESHOLD_BYTES",
+        "RELAY_ACCESS_LOG",
+        "RELAY_ACCESS_LOG_SAMPLE_RATE",
         "RELAY_IP_RATE_PER_MINUTE",
         "RELAY_SOURCE_RATE_PER_MINUTE",
         "RELAY_TRUST_PROXY_HEADERS",
+        "RELAY_PROXY_PROTOCOL",
         "RELAY_TRUSTED_PROXY_CIDRS",
         "RELAY_DEDUP_TTL_SECONDS",
         "RELAY_COOLDOWN_SECONDS",
+        "RELAY_CONTENT_DEDUP_TTL_SECONDS",
+        "RELAY_DEBOUNCE_WINDOW_MS",
+        "RELAY_GITHUB_SYNCHRONIZE_COALESCE_MS",
+        "RELAY_GITHUB_APP_ID",
+        "RELAY_GITHUB_APP_PRIVATE_KEY",
+        "RELAY_GITHUB_APP_PRIVATE_KEY_FILE",
+        "RELAY_GITHUB_API_URL",
         "RELAY_ENFORCE_LINEAR_TIMESTAMP_WINDOW",
         "RELAY_LINEAR_TIMESTAMP_WINDOW_SECONDS",
+        "RELAY_LINEAR_STATE_TRANSITIONS",
+        "RELAY_LINEAR_VERIFY_WITH_API",
+        "RELAY_LINEAR_API_TOKEN",
+        "RELAY_LINEAR_API_TOKEN_FILE",
+        "RELAY_SAMPLING_RULES",
         "RELAY_PUBLISH_QUEUE_CAPACITY",
         "RELAY_PUBLISH_MAX_RETRIES",
         "RELAY_PUBLISH_BACKOFF_BASE_MS",
         "RELAY_PUBLISH_BACKOFF_MAX_MS",
+        "RELAY_PUBLISH_SPILL_DIR",
+        "RELAY_PUBLISH_SPILL_REPLAY_SECONDS",
+        "RELAY_SHUTDOWN_DRAIN_SECONDS",
         "RELAY_VALIDATION_MODE",
         "RELAY_PROFILE",
         "RELAY_CONTRACT_PATH",
+        "RELAY_ENQUEUE_SCRIPT_PATH",
+        "RELAY_ADMIN_TOKEN",
+        "RELAY_ADMIN_TOKEN_FILE",
+        "RELAY_SECRETS_RELOAD_SECONDS",
+        "RELAY_ADMIN_BIND",
+        "RELAY_UNIX_SOCKET_MODE",
+        "RELAY_DIGEST_TOPIC",
+        "RELAY_DIGEST_HOUR_UTC",
+        "RELAY_ALERT_TOPIC",
+        "RELAY_DLQ_ALERT_THRESHOLD",
+        "RELAY_DLQ_ALERT_WINDOW_SECONDS",
+        "RELAY_DLQ_ALERT_COOLDOWN_SECONDS",
         "RELAY_INGRESS_ADAPTER_ID",
         "RELAY_INGRESS_ADAPTERS_JSON",
         "RELAY_SERVE_ROUTES_JSON",
@@ -860,6 +1853,65 @@ mod tests {
         });
     }
 
+    #[test]
+    fn from_env_reports_every_consistency_problem() {
+        let env_vars = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+            ("RELAY_PUBLISH_BACKOFF_BASE_MS", "500"),
+            ("RELAY_PUBLISH_BACKOFF_MAX_MS", "100"),
+            ("RELAY_DIGEST_HOUR_UTC", "24"),
+        ];
+        with_env(&env_vars, || {
+            let report = Config::from_env().expect_err("two problems").to_string();
+            assert!(report.starts_with("2 config problems:"), "{report}");
+            assert!(
+                report.contains("RELAY_PUBLISH_BACKOFF_MAX_MS (100)"),
+                "{report}"
+            );
+            assert!(report.contains("RELAY_DIGEST_HOUR_UTC"), "{report}");
+
+            let config = Config::load_env().expect("values parse");
+            assert_eq!(config.problems().len(), 2);
+        });
+    }
+
+    #[test]
+    fn pubsub_requires_a_service_account() {
+        let without_account = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("RELAY_ENABLED_SOURCES", "pubsub"),
+            (
+                "RELAY_PUBSUB_AUDIENCE",
+                "https://relay.example.com/webhook/pubsub",
+            ),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+        ];
+        with_env(&without_account, || {
+            let error = Config::from_env().expect_err("pubsub without service account");
+            assert!(error.to_string().contains(
+                "RELAY_PUBSUB_SERVICE_ACCOUNT is required when the pubsub source is enabled"
+            ));
+        });
+
+        let mut with_account = without_account.to_vec();
+        with_account.push((
+            "RELAY_PUBSUB_SERVICE_ACCOUNT",
+            "push@acme.iam.gserviceaccount.com",
+        ));
+        with_env(&with_account, || {
+            let config = Config::from_env().expect("pubsub with service account");
+            assert_eq!(
+                config.pubsub_service_account.as_deref(),
+                Some("push@acme.iam.gserviceaccount.com")
+            );
+        });
+    }
+
     #[test]
     fn accepts_explicit_source_topics_override() {
         let env_vars = [
@@ -898,4 +1950,375 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn parses_linear_state_transitions() {
+        let env_vars = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+            (
+                "RELAY_LINEAR_STATE_TRANSITIONS",
+                "*->In Progress, todo-id -> Done",
+            ),
+        ];
+        with_env(&env_vars, || {
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(
+                config.linear_state_transitions,
+                vec![
+                    LinearStateTransition {
+                        from: None,
+                        to: Some("In Progress".to_string()),
+                    },
+                    LinearStateTransition {
+                        from: Some("todo-id".to_string()),
+                        to: Some("Done".to_string()),
+                    },
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn rejects_malformed_linear_state_transitions() {
+        let env_vars = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+            ("RELAY_LINEAR_STATE_TRANSITIONS", "Done"),
+        ];
+        with_env(&env_vars, || {
+            let error = Config::from_env().expect_err("rule without arrow should fail");
+            assert!(error.to_string().contains("must use from->to"));
+        });
+    }
+
+    #[test]
+    fn parses_sampling_rules() {
+        let base = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+        ];
+        let mut valid = base.to_vec();
+        valid.push((
+            "RELAY_SAMPLING_RULES",
+            "github:issue_comment.created@acme/api=0.1, linear:comment.create=0.5",
+        ));
+        with_env(&valid, || {
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(
+                config.sampling_rules,
+                vec![
+                    SamplingRule {
+                        source: "github".to_string(),
+                        event_type: "issue_comment.created".to_string(),
+                        scope: Some("acme/api".to_string()),
+                        rate: 0.1,
+                    },
+                    SamplingRule {
+                        source: "linear".to_string(),
+                        event_type: "comment.create".to_string(),
+                        scope: None,
+                        rate: 0.5,
+                    },
+                ]
+            );
+        });
+
+        for raw in [
+            "github:push=1.5",
+            "issue_comment.created=0.1",
+            "github:push",
+        ] {
+            let mut invalid = base.to_vec();
+            invalid.push(("RELAY_SAMPLING_RULES", raw));
+            with_env(&invalid, || {
+                let error = Config::from_env().expect_err("malformed rule should fail");
+                assert!(error.to_string().contains("RELAY_SAMPLING_RULES"), "{raw}");
+            });
+        }
+    }
+
+    #[test]
+    fn source_payload_limits_must_fit_the_global_limit() {
+        let base = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+            ("RELAY_MAX_PAYLOAD_BYTES", "4194304"),
+        ];
+        let mut valid = base.to_vec();
+        valid.push(("RELAY_SOURCE_MAX_PAYLOAD_BYTES", "GitHub=1048576"));
+        valid.push(("RELAY_OVERSIZED_PAYLOAD_MODE", "truncate"));
+        with_env(&valid, || {
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(config.max_payload_bytes_for("github"), 1_048_576);
+            assert_eq!(config.max_payload_bytes_for("linear"), 4_194_304);
+            assert_eq!(
+                config.oversized_payload_mode,
+                OversizedPayloadMode::Truncate
+            );
+        });
+
+        for (key, raw) in [
+            ("RELAY_SOURCE_MAX_PAYLOAD_BYTES", "github=8388608"),
+            ("RELAY_SOURCE_MAX_PAYLOAD_BYTES", "github"),
+            ("RELAY_OVERSIZED_PAYLOAD_MODE", "drop"),
+        ] {
+            let mut invalid = base.to_vec();
+            invalid.push((key, raw));
+            with_env(&invalid, || {
+                let error = Config::from_env().expect_err("invalid limit should fail");
+                assert!(error.to_string().contains(key), "{raw}");
+            });
+        }
+    }
+
+    #[test]
+    fn dlq_alert_threshold_requires_a_topic() {
+        let base = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+            ("RELAY_DLQ_ALERT_THRESHOLD", "10"),
+        ];
+        with_env(&base, || {
+            let error = Config::from_env().expect_err("alert without topic should fail");
+            assert!(error.to_string().contains("RELAY_ALERT_TOPIC"));
+        });
+
+        let mut with_digest = base.to_vec();
+        with_digest.push(("RELAY_DIGEST_TOPIC", "webhooks.relay"));
+        with_env(&with_digest, || {
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(config.dlq_alert_threshold, Some(10));
+            assert_eq!(config.alert_topic(), Some("webhooks.relay"));
+        });
+    }
+
+    #[test]
+    fn dlq_retention_caps_must_be_positive() {
+        let base = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+        ];
+        let mut capped = base.to_vec();
+        capped.push(("KAFKA_DLQ_RETENTION_BYTES", "1073741824"));
+        with_env(&capped, || {
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(config.kafka_dlq_retention_bytes, Some(1_073_741_824));
+            assert_eq!(config.kafka_dlq_retention_ms, None);
+        });
+
+        let mut zero = base.to_vec();
+        zero.push(("KAFKA_DLQ_RETENTION_MS", "0"));
+        with_env(&zero, || {
+            let error = Config::from_env().expect_err("zero retention should fail");
+            assert!(error.to_string().contains("KAFKA_DLQ_RETENTION_MS"));
+        });
+    }
+
+    #[test]
+    fn rejects_backoff_max_below_base() {
+        let env_vars = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+            ("RELAY_PUBLISH_BACKOFF_BASE_MS", "1000"),
+            ("RELAY_PUBLISH_BACKOFF_MAX_MS", "500"),
+        ];
+        with_env(&env_vars, || {
+            let error = Config::from_env().expect_err("inverted backoff should fail");
+            assert!(
+                error
+                    .to_string()
+                    .contains("RELAY_PUBLISH_BACKOFF_MAX_MS (500)")
+            );
+        });
+    }
+
+    #[test]
+    fn producer_idempotence_requires_acks_all() {
+        let base = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+        ];
+        with_env(&base, || {
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(config.kafka_producer_acks, "all");
+            assert!(config.kafka_producer_idempotence);
+            assert_eq!(config.kafka_producer_delivery_timeout_ms, 30_000);
+        });
+
+        let mut leader_only = base.to_vec();
+        leader_only.push(("KAFKA_PRODUCER_ACKS", "1"));
+        with_env(&leader_only, || {
+            let error = Config::from_env().expect_err("idempotence with acks=1 should fail");
+            assert!(error.to_string().contains("KAFKA_PRODUCER_ACKS=all"));
+        });
+
+        leader_only.push(("KAFKA_PRODUCER_IDEMPOTENCE", "false"));
+        with_env(&leader_only, || {
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(config.kafka_producer_acks, "1");
+        });
+
+        let mut transactional = leader_only.clone();
+        transactional.push(("KAFKA_PRODUCER_TRANSACTIONAL_ID", "hook-serve-0"));
+        with_env(&transactional, || {
+            let error = Config::from_env().expect_err("transactions need idempotence");
+            assert!(
+                error
+                    .to_string()
+                    .contains("KAFKA_PRODUCER_TRANSACTIONAL_ID")
+            );
+        });
+    }
+
+    #[test]
+    fn producer_compression_must_be_a_known_codec() {
+        let base = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+        ];
+        let mut zstd = base.to_vec();
+        zstd.push(("KAFKA_PRODUCER_COMPRESSION", "ZSTD"));
+        with_env(&zstd, || {
+            let config = Config::from_env().expect("config should load");
+            assert_eq!(config.kafka_producer_compression, "zstd");
+        });
+
+        let mut brotli = base.to_vec();
+        brotli.push(("KAFKA_PRODUCER_COMPRESSION", "brotli"));
+        with_env(&brotli, || {
+            let error = Config::from_env().expect_err("unknown codec should fail");
+            assert!(error.to_string().contains("KAFKA_PRODUCER_COMPRESSION"));
+        });
+    }
+
+    #[test]
+    fn rejects_admin_bind_equal_to_public_bind() {
+        let env_vars = [
+            ("KAFKA_BROKERS", "broker:9093"),
+            ("HMAC_SECRET_GITHUB", "github-secret"),
+            ("HMAC_SECRET_LINEAR", "linear-secret"),
+            ("KAFKA_SECURITY_PROTOCOL", "plaintext"),
+            ("KAFKA_ALLOW_PLAINTEXT", "true"),
+            ("RELAY_BIND", "0.0.0.0:8080"),
+            ("RELAY_ADMIN_BIND", "0.0.0.0:8080"),
+        ];
+        with_env(&env_vars, || {
+            let error = Config::from_env().expect_err("shared bind should fail");
+            assert!(error.to_string().contains("RELAY_ADMIN_BIND"));
+        });
+    }
+
+    #[test]
+    fn parses_custom_sources() {
+        let sources = parse_custom_sources("Billing=X-Billing-Signature, deploys=X-Sig:SHA1")
+            .expect("custom sources");
+        assert_eq!(
+            sources.get("billing"),
+            Some(&CustomSource {
+                signature_header: "X-Billing-Signature".to_string(),
+                algorithm: HmacAlgorithm::Sha256,
+            })
+        );
+        assert_eq!(sources["deploys"].algorithm, HmacAlgorithm::Sha1);
+        assert_eq!(custom_secret_env("my-service"), "HMAC_SECRET_MY_SERVICE");
+
+        assert!(parse_custom_sources("billing").is_err());
+        assert!(parse_custom_sources("github=X-Sig").is_err());
+        assert!(parse_custom_sources("billing=X-Sig:md5").is_err());
+        assert!(parse_custom_sources("billing=Bad Header").is_err());
+        assert!(parse_custom_sources("billing=X-A,billing=X-B").is_err());
+        assert!(parse_custom_sources("bill_ing=X-Sig").is_err());
+    }
+
+    #[test]
+    fn tenant_secret_envs_must_name_a_secret_bearing_source() {
+        let custom_sources = parse_custom_sources("billing=X-Billing-Signature").expect("custom");
+        let tenant = |source: &str| TenantConfig {
+            id: "payments".to_string(),
+            secret_envs: [(source.to_string(), "HMAC_SECRET_GITHUB".to_string())].into(),
+            cooldown_seconds: None,
+            gateway_url: None,
+            gateway_token_env: None,
+        };
+        for source in ["githb", "pubsub", "sns"] {
+            let error = SourceSecrets::for_tenant(&tenant(source), &custom_sources)
+                .expect_err("unknown secret source");
+            assert!(
+                error
+                    .to_string()
+                    .contains(&format!("tenant 'payments' secret_envs key '{source}'")),
+                "{error}"
+            );
+        }
+
+        with_env(&[("HMAC_SECRET_GITHUB", "tenant-secret")], || {
+            let secrets =
+                SourceSecrets::for_tenant(&tenant("billing"), &custom_sources).expect("custom");
+            assert_eq!(secrets.custom["billing"], "tenant-secret");
+            let secrets =
+                SourceSecrets::for_tenant(&tenant("github"), &custom_sources).expect("github");
+            assert_eq!(secrets.github.as_deref(), Some("tenant-secret"));
+        });
+    }
+
+    #[test]
+    fn parses_internal_tokens() {
+        let tokens = parse_internal_tokens("Billing=tok-1, deploy_bot=tok-2").expect("tokens");
+        assert_eq!(tokens.get("billing").map(String::as_str), Some("tok-1"));
+        assert_eq!(tokens.get("deploy_bot").map(String::as_str), Some("tok-2"));
+
+        assert!(parse_internal_tokens("billing").is_err());
+        assert!(parse_internal_tokens("billing=").is_err());
+        assert!(parse_internal_tokens("bill ing=tok").is_err());
+        assert!(parse_internal_tokens("billing=tok-1,deploys=tok-1").is_err());
+        assert!(parse_internal_tokens("billing=tok-1,billing=tok-2").is_err());
+    }
+
+    #[test]
+    fn parses_source_paths() {
+        let paths =
+            parse_source_paths("GitHub=/hooks/github-pr, linear=/legacy/linear").expect("paths");
+        assert_eq!(paths["github"], "/hooks/github-pr");
+        assert_eq!(paths["linear"], "/legacy/linear");
+        assert!(parse_source_paths("github=hooks").is_err());
+        assert!(parse_source_paths("github=/hooks/{source}").is_err());
+        assert!(parse_source_paths("github=/hooks,linear=/hooks").is_err());
+    }
+
+    #[test]
+    fn parses_octal_unix_socket_mode() {
+        assert_eq!(parse_unix_socket_mode("660").expect("mode"), 0o660);
+        assert_eq!(parse_unix_socket_mode("0o600").expect("mode"), 0o600);
+        assert_eq!(parse_unix_socket_mode("0770").expect("mode"), 0o770);
+        assert!(parse_unix_socket_mode("888").is_err());
+        assert!(parse_unix_socket_mode("1777").is_err());
+    }
 }
@@ -0,0 +1,151 @@
+use crate::producer::PublishJob;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+/// Payload field recording how many events were folded into the published one.
+const COALESCED_FIELD: &str = "_coalesced";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebounceOutcome {
+    /// First event for the key; a flush is scheduled after the window.
+    Held,
+    /// An event was already waiting and has been superseded by this one.
+    Replaced,
+}
+
+struct PendingJob {
+    job: PublishJob,
+    superseded: u32,
+}
+
+/// Holds the first event per cooldown key for `window`, keeping only the
+/// latest arrival, then queues it for publish. When arrivals were folded,
+/// the payload carries `_coalesced.count`.
+#[derive(Clone)]
+pub struct Debouncer {
+    window: Duration,
+    publish_tx: mpsc::Sender<PublishJob>,
+    pending: Arc<Mutex<HashMap<String, PendingJob>>>,
+}
+
+impl Debouncer {
+    pub fn new(window: Duration, publish_tx: mpsc::Sender<PublishJob>) -> Self {
+        Self {
+            window,
+            publish_tx,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn hold(&self, key: &str, job: PublishJob) -> DebounceOutcome {
+        let mut pending = self
+            .pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(waiting) = pending.get_mut(key) {
+            waiting.job = job;
+            waiting.superseded += 1;
+            return DebounceOutcome::Replaced;
+        }
+        pending.insert(key.to_string(), PendingJob { job, superseded: 0 });
+        drop(pending);
+
+        let debouncer = self.clone();
+        let key = key.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(debouncer.window).await;
+            debouncer.flush(&key).await;
+        });
+        DebounceOutcome::Held
+    }
+
+    async fn flush(&self, key: &str) {
+        let Some(PendingJob {
+            mut job,
+            superseded,
+        }) = self
+            .pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(key)
+        else {
+            return;
+        };
+        if superseded > 0
+            && let Some(payload) = job.envelope.payload.as_object_mut()
+        {
+            payload.insert(
+                COALESCED_FIELD.to_string(),
+                json!({"count": superseded + 1}),
+            );
+        }
+        let event_id = job.envelope.id.clone();
+        match self.publish_tx.send(job).await {
+            Ok(()) => info!(
+                cooldown_key = key,
+                event_id = event_id.as_str(),
+                superseded,
+                "debounced webhook event queued for kafka publish"
+            ),
+            Err(_) => warn!(
+                cooldown_key = key,
+                event_id = event_id.as_str(),
+                "dropping debounced webhook event: publisher unavailable"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use relay_core::model::{ENVELOPE_SCHEMA_VERSION, EventEnvelope};
+
+    fn job(id: &str) -> PublishJob {
+        PublishJob {
+            topic: "webhooks.github".to_string(),
+            envelope: EventEnvelope {
+                schema_version: ENVELOPE_SCHEMA_VERSION,
+                id: id.to_string(),
+                source: "github".to_string(),
+                event_type: "issues.edited".to_string(),
+                received_at: "2026-01-01T00:00:00.000Z".to_string(),
+                payload: json!({}),
+                meta: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn publishes_only_the_latest_event_per_key() {
+        let (publish_tx, mut publish_rx) = mpsc::channel(8);
+        let debouncer = Debouncer::new(Duration::from_millis(50), publish_tx);
+
+        assert_eq!(debouncer.hold("issue-1", job("a")), DebounceOutcome::Held);
+        assert_eq!(
+            debouncer.hold("issue-1", job("b")),
+            DebounceOutcome::Replaced
+        );
+        assert_eq!(debouncer.hold("issue-2", job("c")), DebounceOutcome::Held);
+        assert!(publish_rx.try_recv().is_err());
+
+        let mut published = Vec::new();
+        for _ in 0..2 {
+            let job = tokio::time::timeout(Duration::from_secs(2), publish_rx.recv())
+                .await
+                .expect("flush within window")
+                .expect("job");
+            published.push(job);
+        }
+        published.sort_by(|left, right| left.envelope.id.cmp(&right.envelope.id));
+        assert_eq!(published[0].envelope.id, "b");
+        assert_eq!(published[0].envelope.payload["_coalesced"]["count"], 2);
+        assert!(published[1].envelope.payload.get("_coalesced").is_none());
+
+        assert_eq!(debouncer.hold("issue-1", job("d")), DebounceOutcome::Held);
+    }
+}
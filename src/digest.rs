@@ -0,0 +1,397 @@
+use crate::clock::SharedClock;
+use crate::config::Config;
+use crate::envelope::build_envelope;
+use crate::producer::{PublishJob, topic_high_watermark};
+use chrono::{DateTime, SecondsFormat};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, sleep};
+use tracing::{info, warn};
+
+pub const DIGEST_SOURCE: &str = "relay";
+pub const DIGEST_EVENT_TYPE: &str = "relay.digest";
+const TOP_SCOPES_LIMIT: usize = 5;
+const SECONDS_PER_HOUR: i64 = 3_600;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityOutcome {
+    Forwarded,
+    Dropped,
+    Rejected,
+}
+
+impl ActivityOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Forwarded => "forwarded",
+            Self::Dropped => "dropped",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SourceActivity {
+    pub received: u64,
+    pub forwarded: u64,
+    pub dropped: u64,
+    pub rejected: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScopeCount {
+    pub scope: String,
+    pub forwarded: u64,
+}
+
+/// Lifetime counters for one repo or team, kept across digest windows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScopeActivity {
+    pub scope: String,
+    #[serde(flatten)]
+    pub counts: SourceActivity,
+    pub last_event_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScopeActivityReport {
+    pub since: String,
+    pub scopes: Vec<ScopeActivity>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivitySnapshot {
+    pub window_start: String,
+    pub window_end: String,
+    pub sources: BTreeMap<String, SourceActivity>,
+    pub top_scopes: Vec<ScopeCount>,
+}
+
+#[derive(Debug, Default)]
+struct ActivityWindow {
+    started_at: i64,
+    sources: BTreeMap<String, SourceActivity>,
+    scopes: BTreeMap<String, u64>,
+}
+
+#[derive(Debug)]
+pub struct ActivityTracker {
+    window: Mutex<ActivityWindow>,
+    started_at: i64,
+    scope_totals: Mutex<BTreeMap<String, (SourceActivity, i64)>>,
+}
+
+impl ActivityTracker {
+    pub fn new(now_epoch_seconds: i64) -> Self {
+        Self {
+            window: Mutex::new(ActivityWindow {
+                started_at: now_epoch_seconds,
+                ..ActivityWindow::default()
+            }),
+            started_at: now_epoch_seconds,
+            scope_totals: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn record(&self, source: &str, outcome: ActivityOutcome) {
+        let Ok(mut window) = self.window.lock() else {
+            return;
+        };
+        let activity = window.sources.entry(source.to_string()).or_default();
+        activity.received = activity.received.saturating_add(1);
+        let counter = match outcome {
+            ActivityOutcome::Forwarded => &mut activity.forwarded,
+            ActivityOutcome::Dropped => &mut activity.dropped,
+            ActivityOutcome::Rejected => &mut activity.rejected,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    pub fn record_scope(&self, scope: String) {
+        let Ok(mut window) = self.window.lock() else {
+            return;
+        };
+        let count = window.scopes.entry(scope).or_default();
+        *count = count.saturating_add(1);
+    }
+
+    /// Counts an outcome against a repo or team. Forwarded events also feed
+    /// the digest's top scopes.
+    pub fn record_scope_outcome(
+        &self,
+        scope: &str,
+        outcome: ActivityOutcome,
+        now_epoch_seconds: i64,
+    ) {
+        if outcome == ActivityOutcome::Forwarded {
+            self.record_scope(scope.to_string());
+        }
+        let Ok(mut totals) = self.scope_totals.lock() else {
+            return;
+        };
+        let (counts, last_event_at) = totals.entry(scope.to_string()).or_default();
+        counts.received = counts.received.saturating_add(1);
+        let counter = match outcome {
+            ActivityOutcome::Forwarded => &mut counts.forwarded,
+            ActivityOutcome::Dropped => &mut counts.dropped,
+            ActivityOutcome::Rejected => &mut counts.rejected,
+        };
+        *counter = counter.saturating_add(1);
+        *last_event_at = now_epoch_seconds;
+    }
+
+    /// Busiest scopes since startup, by events received.
+    pub fn top_scope_activity(&self, limit: usize) -> ScopeActivityReport {
+        let mut scopes = match self.scope_totals.lock() {
+            Ok(totals) => totals
+                .iter()
+                .map(|(scope, (counts, last_event_at))| ScopeActivity {
+                    scope: scope.clone(),
+                    counts: counts.clone(),
+                    last_event_at: rfc3339(*last_event_at),
+                })
+                .collect::<Vec<_>>(),
+            Err(_) => Vec::new(),
+        };
+        scopes.sort_by(|left, right| {
+            right
+                .counts
+                .received
+                .cmp(&left.counts.received)
+                .then_with(|| left.scope.cmp(&right.scope))
+        });
+        scopes.truncate(limit);
+        ScopeActivityReport {
+            since: rfc3339(self.started_at),
+            scopes,
+        }
+    }
+
+    pub fn take(&self, now_epoch_seconds: i64) -> ActivitySnapshot {
+        let window = match self.window.lock() {
+            Ok(mut guard) => std::mem::replace(
+                &mut *guard,
+                ActivityWindow {
+                    started_at: now_epoch_seconds,
+                    ..ActivityWindow::default()
+                },
+            ),
+            Err(_) => ActivityWindow::default(),
+        };
+
+        let mut top_scopes = window
+            .scopes
+            .into_iter()
+            .map(|(scope, forwarded)| ScopeCount { scope, forwarded })
+            .collect::<Vec<_>>();
+        top_scopes.sort_by(|left, right| {
+            right
+                .forwarded
+                .cmp(&left.forwarded)
+                .then_with(|| left.scope.cmp(&right.scope))
+        });
+        top_scopes.truncate(TOP_SCOPES_LIMIT);
+
+        ActivitySnapshot {
+            window_start: rfc3339(window.started_at),
+            window_end: rfc3339(now_epoch_seconds),
+            sources: window.sources,
+            top_scopes,
+        }
+    }
+}
+
+pub fn activity_scope(source: &str, payload: &Value) -> Option<String> {
+    let scope = match source {
+        "github" => payload.pointer("/repository/full_name"),
+        "gitlab" => payload.pointer("/project/path_with_namespace"),
+        "bitbucket" => payload.pointer("/repository/full_name"),
+        "jira" => payload.pointer("/issue/fields/project/key"),
+        "sentry" => payload.pointer("/data/issue/project/slug"),
+        "discord" => payload.pointer("/guild_id"),
+        "registry" => payload.pointer("/_registry/repository"),
+        "linear" => payload.pointer("/data/team/key"),
+        _ => None,
+    }?
+    .as_str()?;
+    Some(format!("{source}:{scope}"))
+}
+
+pub fn seconds_until_next_digest(now_epoch_seconds: i64, hour_utc: u32) -> u64 {
+    let target_offset = i64::from(hour_utc) * SECONDS_PER_HOUR;
+    let day_offset = now_epoch_seconds.rem_euclid(SECONDS_PER_DAY);
+    let wait = (target_offset - day_offset).rem_euclid(SECONDS_PER_DAY);
+    if wait == 0 {
+        SECONDS_PER_DAY as u64
+    } else {
+        wait as u64
+    }
+}
+
+pub async fn run_digest_worker(
+    config: Config,
+    topic: String,
+    tracker: Arc<ActivityTracker>,
+    publish_tx: mpsc::Sender<PublishJob>,
+    clock: SharedClock,
+) {
+    let mut dlq_baseline = dlq_depth(&config).await;
+    loop {
+        let wait = seconds_until_next_digest(clock.now_epoch_seconds(), config.digest_hour_utc);
+        sleep(Duration::from_secs(wait)).await;
+
+        let snapshot = tracker.take(clock.now_epoch_seconds());
+        let dlq_current = dlq_depth(&config).await;
+        let dlq_additions = match (dlq_baseline, dlq_current) {
+            (Some(previous), Some(current)) => Some(current.saturating_sub(previous).max(0)),
+            _ => None,
+        };
+        if dlq_current.is_some() {
+            dlq_baseline = dlq_current;
+        }
+
+        let mut payload = json!(snapshot);
+        payload["dlq_additions"] = json!(dlq_additions);
+        let envelope = build_envelope(DIGEST_SOURCE, DIGEST_EVENT_TYPE.to_string(), payload, None);
+        let event_id = envelope.id.clone();
+        let job = PublishJob {
+            topic: topic.clone(),
+            envelope,
+        };
+        match publish_tx.send(job).await {
+            Ok(()) => info!(
+                topic = topic.as_str(),
+                event_id = event_id.as_str(),
+                "queued daily relay digest"
+            ),
+            Err(_) => {
+                warn!("publisher unavailable; stopping digest worker");
+                return;
+            }
+        }
+    }
+}
+
+pub(crate) async fn dlq_depth(config: &Config) -> Option<i64> {
+    let config = config.clone();
+    let topic = config.kafka_dlq_topic.clone();
+    let result = tokio::task::spawn_blocking(move || topic_high_watermark(&config, &topic)).await;
+    match result {
+        Ok(Ok(depth)) => Some(depth),
+        Ok(Err(error)) => {
+            warn!(error = %error, "failed to read dlq depth");
+            None
+        }
+        Err(error) => {
+            warn!(error = %error, "dlq depth task failed");
+            None
+        }
+    }
+}
+
+fn rfc3339(epoch_seconds: i64) -> String {
+    DateTime::from_timestamp(epoch_seconds, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_counts_outcomes_and_resets_on_take() {
+        let tracker = ActivityTracker::new(1_700_000_000);
+        tracker.record("github", ActivityOutcome::Forwarded);
+        tracker.record("github", ActivityOutcome::Dropped);
+        tracker.record("linear", ActivityOutcome::Rejected);
+        for scope in ["github:acme/api", "github:acme/web", "github:acme/api"] {
+            tracker.record_scope(scope.to_string());
+        }
+
+        let snapshot = tracker.take(1_700_086_400);
+        assert_eq!(snapshot.window_start, "2023-11-14T22:13:20Z");
+        assert_eq!(
+            snapshot.sources["github"],
+            SourceActivity {
+                received: 2,
+                forwarded: 1,
+                dropped: 1,
+                rejected: 0,
+            }
+        );
+        assert_eq!(snapshot.sources["linear"].rejected, 1);
+        assert_eq!(snapshot.top_scopes[0].scope, "github:acme/api");
+        assert_eq!(snapshot.top_scopes[0].forwarded, 2);
+
+        let next = tracker.take(1_700_086_500);
+        assert_eq!(next.window_start, snapshot.window_end);
+        assert!(next.sources.is_empty());
+        assert!(next.top_scopes.is_empty());
+    }
+
+    #[test]
+    fn scope_totals_survive_digest_and_rank_by_received() {
+        let tracker = ActivityTracker::new(1_700_000_000);
+        tracker.record_scope_outcome("github:acme/api", ActivityOutcome::Forwarded, 1_700_000_010);
+        tracker.record_scope_outcome("github:acme/api", ActivityOutcome::Dropped, 1_700_000_020);
+        tracker.record_scope_outcome("linear:ENG", ActivityOutcome::Forwarded, 1_700_000_030);
+
+        let snapshot = tracker.take(1_700_086_400);
+        assert_eq!(snapshot.top_scopes.len(), 2);
+
+        let report = tracker.top_scope_activity(1);
+        assert_eq!(report.since, "2023-11-14T22:13:20Z");
+        assert_eq!(report.scopes.len(), 1);
+        let api = &report.scopes[0];
+        assert_eq!(api.scope, "github:acme/api");
+        assert_eq!(
+            api.counts,
+            SourceActivity {
+                received: 2,
+                forwarded: 1,
+                dropped: 1,
+                rejected: 0,
+            }
+        );
+        assert_eq!(api.last_event_at, "2023-11-14T22:13:40Z");
+    }
+
+    #[test]
+    fn activity_scope_uses_repo_or_team() {
+        let github = json!({"repository":{"full_name":"acme/api"}});
+        let linear = json!({"data":{"team":{"key":"ENG"}}});
+        assert_eq!(
+            activity_scope("github", &github).as_deref(),
+            Some("github:acme/api")
+        );
+        assert_eq!(
+            activity_scope("linear", &linear).as_deref(),
+            Some("linear:ENG")
+        );
+        assert_eq!(
+            activity_scope(
+                "gitlab",
+                &json!({"project":{"path_with_namespace":"acme/relay"}})
+            )
+            .as_deref(),
+            Some("gitlab:acme/relay")
+        );
+        assert_eq!(activity_scope("example", &github), None);
+    }
+
+    #[test]
+    fn next_digest_waits_until_configured_hour() {
+        let midnight = 1_700_006_400;
+        assert_eq!(seconds_until_next_digest(midnight, 9), 9 * 3_600);
+        assert_eq!(
+            seconds_until_next_digest(midnight + 10 * 3_600, 9),
+            23 * 3_600
+        );
+        assert_eq!(seconds_until_next_digest(midnight + 9 * 3_600, 9), 86_400);
+    }
+}
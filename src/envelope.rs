@@ -1,5 +1,5 @@
 use chrono::{SecondsFormat, Utc};
-use relay_core::model::{EventEnvelope, EventMeta};
+use relay_core::model::{ENVELOPE_SCHEMA_VERSION, EventEnvelope, EventMeta};
 use serde_json::Value;
 use uuid::Uuid;
 
@@ -10,6 +10,7 @@ pub fn build_envelope(
     meta: Option<EventMeta>,
 ) -> EventEnvelope {
     EventEnvelope {
+        schema_version: ENVELOPE_SCHEMA_VERSION,
         id: Uuid::new_v4().to_string(),
         source: source.to_string(),
         event_type,
@@ -0,0 +1,295 @@
+use crate::config::Config;
+use anyhow::{Context, Result, anyhow};
+use chrono::Utc;
+use relay_core::jwt::sign_rs256_jwt;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+const USER_AGENT: &str = "hook-serve";
+const GITHUB_ACCEPT: &str = "application/vnd.github+json";
+/// Backdates `iat` against clock drift, as GitHub recommends.
+const JWT_BACKDATE_SECONDS: i64 = 60;
+/// GitHub rejects app JWTs that live longer than ten minutes.
+const JWT_LIFETIME_SECONDS: i64 = 540;
+/// Installation tokens expire after an hour; refresh them ten minutes early.
+const INSTALLATION_TOKEN_TTL: Duration = Duration::from_secs(50 * 60);
+const FILES_PER_PAGE: usize = 100;
+const MAX_FILE_PAGES: usize = 3;
+/// Pull request actions that change the diff.
+const ENRICHED_ACTIONS: &[&str] = &["opened", "reopened", "synchronize", "ready_for_review"];
+pub const ENRICHMENT_FIELD: &str = "_enrichment";
+
+/// GitHub App installation used to attach the changed files of a pull
+/// request to its events.
+pub struct GithubApp {
+    app_id: String,
+    private_key_pem: String,
+    api_url: String,
+    client: reqwest::Client,
+    installation_tokens: Mutex<HashMap<u64, (String, Instant)>>,
+}
+
+#[derive(Deserialize)]
+struct InstallationToken {
+    token: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct PullRequestTarget {
+    installation_id: u64,
+    repository: String,
+    number: u64,
+}
+
+impl GithubApp {
+    /// `None` unless `RELAY_GITHUB_APP_ID` is set.
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let (Some(app_id), Some(private_key)) = (
+            config.github_app_id.as_deref(),
+            config.github_app_private_key.as_deref(),
+        ) else {
+            return Ok(None);
+        };
+        // Keys pasted into env files often carry literal `\n`s.
+        let private_key_pem = private_key.replace("\\n", "\n");
+        sign_rs256_jwt(&private_key_pem, &json!({}))
+            .ok_or_else(|| anyhow!("RELAY_GITHUB_APP_PRIVATE_KEY is not an RSA private key"))?;
+        let client = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .context("build github api client")?;
+        Ok(Some(Self {
+            app_id: app_id.to_string(),
+            private_key_pem,
+            api_url: config.github_api_url.clone(),
+            client,
+            installation_tokens: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// `_enrichment` for a pull request event that changes the diff, or
+    /// `None` for any other event.
+    pub async fn pull_request_enrichment(&self, payload: &Value) -> Result<Option<Value>> {
+        let Some(target) = pull_request_target(payload) else {
+            return Ok(None);
+        };
+        let token = self.installation_token(target.installation_id).await?;
+        let mut files = Vec::new();
+        for page in 1..=MAX_FILE_PAGES {
+            let url = format!(
+                "{}/repos/{}/pulls/{}/files?per_page={FILES_PER_PAGE}&page={page}",
+                self.api_url, target.repository, target.number
+            );
+            let response = self
+                .client
+                .get(url)
+                .bearer_auth(&token)
+                .header("Accept", GITHUB_ACCEPT)
+                .send()
+                .await
+                .context("list pull request files")?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "github api returned {} listing pull request files",
+                    response.status()
+                ));
+            }
+            let batch: Vec<Value> = response.json().await.context("decode pull request files")?;
+            let last_page = batch.len() < FILES_PER_PAGE;
+            files.extend(batch);
+            if last_page {
+                break;
+            }
+        }
+        Ok(Some(enrichment(payload, &files)))
+    }
+
+    async fn installation_token(&self, installation_id: u64) -> Result<String> {
+        if let Some((token, minted_at)) = self
+            .installation_tokens
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&installation_id)
+            && minted_at.elapsed() < INSTALLATION_TOKEN_TTL
+        {
+            return Ok(token.clone());
+        }
+
+        let now = Utc::now().timestamp();
+        // Numeric app ids go out as numbers; client ids stay strings.
+        let issuer = self
+            .app_id
+            .parse::<u64>()
+            .map_or_else(|_| json!(self.app_id), |id| json!(id));
+        let jwt = sign_rs256_jwt(
+            &self.private_key_pem,
+            &json!({
+                "iss": issuer,
+                "iat": now - JWT_BACKDATE_SECONDS,
+                "exp": now + JWT_LIFETIME_SECONDS,
+            }),
+        )
+        .ok_or_else(|| anyhow!("sign github app jwt"))?;
+        let response = self
+            .client
+            .post(format!(
+                "{}/app/installations/{installation_id}/access_tokens",
+                self.api_url
+            ))
+            .bearer_auth(jwt)
+            .header("Accept", GITHUB_ACCEPT)
+            .send()
+            .await
+            .context("request github installation token")?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "github api returned {} minting an installation token",
+                response.status()
+            ));
+        }
+        let InstallationToken { token } = response
+            .json()
+            .await
+            .context("decode github installation token")?;
+        self.installation_tokens
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(installation_id, (token.clone(), Instant::now()));
+        Ok(token)
+    }
+}
+
+fn pull_request_target(payload: &Value) -> Option<PullRequestTarget> {
+    let action = payload.get("action")?.as_str()?;
+    if !ENRICHED_ACTIONS.contains(&action) {
+        return None;
+    }
+    let pull_request = payload.get("pull_request")?;
+    Some(PullRequestTarget {
+        installation_id: payload.pointer("/installation/id")?.as_u64()?,
+        repository: payload
+            .pointer("/repository/full_name")?
+            .as_str()?
+            .to_string(),
+        number: pull_request
+            .get("number")
+            .or_else(|| payload.get("number"))?
+            .as_u64()?,
+    })
+}
+
+/// Names, statuses and line counts of the changed files. Patches are left
+/// out; they are large and entirely author-controlled.
+fn enrichment(payload: &Value, files: &[Value]) -> Value {
+    let files: Vec<Value> = files
+        .iter()
+        .map(|file| {
+            json!({
+                "filename": file.get("filename"),
+                "status": file.get("status"),
+                "additions": file.get("additions"),
+                "deletions": file.get("deletions"),
+                "changes": file.get("changes"),
+            })
+        })
+        .collect();
+    let stat = |field: &str| {
+        payload
+            .pointer(&format!("/pull_request/{field}"))
+            .and_then(Value::as_u64)
+            .unwrap_or_else(|| files.iter().filter_map(|file| file[field].as_u64()).sum())
+    };
+    let changed_files = payload
+        .pointer("/pull_request/changed_files")
+        .and_then(Value::as_u64)
+        .unwrap_or(files.len() as u64);
+    json!({
+        "stats": {
+            "changed_files": changed_files,
+            "additions": stat("additions"),
+            "deletions": stat("deletions"),
+        },
+        "truncated": changed_files > files.len() as u64,
+        "files": files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pull_request_event(action: &str) -> Value {
+        json!({
+            "action": action,
+            "number": 42,
+            "installation": {"id": 7},
+            "repository": {"full_name": "acme/api"},
+            "pull_request": {"number": 42, "additions": 12, "deletions": 3, "changed_files": 2}
+        })
+    }
+
+    #[test]
+    fn targets_pull_request_actions_that_change_the_diff() {
+        assert_eq!(
+            pull_request_target(&pull_request_event("synchronize")),
+            Some(PullRequestTarget {
+                installation_id: 7,
+                repository: "acme/api".to_string(),
+                number: 42,
+            })
+        );
+        assert_eq!(pull_request_target(&pull_request_event("labeled")), None);
+
+        let mut without_installation = pull_request_event("opened");
+        without_installation
+            .as_object_mut()
+            .expect("object")
+            .remove("installation");
+        assert_eq!(pull_request_target(&without_installation), None);
+    }
+
+    #[test]
+    fn keeps_file_stats_and_drops_patches() {
+        let files = vec![json!({
+            "sha": "abc123",
+            "filename": "src/lib.rs",
+            "status": "modified",
+            "additions": 10,
+            "deletions": 3,
+            "changes": 13,
+            "patch": "@@ -1,3 +1,10 @@",
+            "contents_url": "https://api.github.com/repos/acme/api/contents/src/lib.rs"
+        })];
+
+        let enrichment = enrichment(&pull_request_event("opened"), &files);
+
+        assert_eq!(
+            enrichment["files"],
+            json!([{
+                "filename": "src/lib.rs",
+                "status": "modified",
+                "additions": 10,
+                "deletions": 3,
+                "changes": 13
+            }])
+        );
+        assert_eq!(
+            enrichment["stats"],
+            json!({"changed_files": 2, "additions": 12, "deletions": 3})
+        );
+        assert_eq!(enrichment["truncated"], json!(true));
+
+        let without_counts = json!({"pull_request": {"number": 42}});
+        let enrichment = super::enrichment(&without_counts, &files);
+        assert_eq!(
+            enrichment["stats"],
+            json!({"changed_files": 1, "additions": 10, "deletions": 3})
+        );
+        assert_eq!(enrichment["truncated"], json!(false));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::listener::BindTarget;
+use anyhow::{Context, Result, anyhow};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::time::{Duration, timeout};
+
+const READY_REQUEST: &[u8] =
+    b"GET /ready HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nUser-Agent: hook-serve-healthcheck\r\n\r\n";
+const MAX_RESPONSE_BYTES: u64 = 16 * 1024;
+
+/// Probes `/ready` on the local listener and fails unless it answers 200.
+pub async fn probe_ready(target: &BindTarget, deadline: Duration) -> Result<()> {
+    timeout(deadline, probe(target))
+        .await
+        .map_err(|_| anyhow!("/ready did not answer within {}s", deadline.as_secs()))?
+}
+
+async fn probe(target: &BindTarget) -> Result<()> {
+    let response = match target {
+        BindTarget::Tcp(addr) => {
+            let addr = loopback_for(*addr);
+            let stream = TcpStream::connect(addr)
+                .await
+                .with_context(|| format!("connect {addr}"))?;
+            exchange(stream).await?
+        }
+        BindTarget::Unix(path) => {
+            let stream = UnixStream::connect(path)
+                .await
+                .with_context(|| format!("connect unix:{}", path.display()))?;
+            exchange(stream).await?
+        }
+        BindTarget::Systemd(_) => {
+            return Err(anyhow!(
+                "cannot probe a systemd-provided socket; set RELAY_HEALTHCHECK_ADDR"
+            ));
+        }
+    };
+
+    let status_line = response.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status == "200" {
+        Ok(())
+    } else {
+        Err(anyhow!("/ready returned '{status_line}'"))
+    }
+}
+
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S) -> Result<String> {
+    stream
+        .write_all(READY_REQUEST)
+        .await
+        .context("send /ready request")?;
+    let mut response = Vec::new();
+    stream
+        .take(MAX_RESPONSE_BYTES)
+        .read_to_end(&mut response)
+        .await
+        .context("read /ready response")?;
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+fn loopback_for(addr: SocketAddr) -> SocketAddr {
+    match addr.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => (Ipv4Addr::LOCALHOST, addr.port()).into(),
+        IpAddr::V6(ip) if ip.is_unspecified() => (Ipv6Addr::LOCALHOST, addr.port()).into(),
+        _ => addr,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+
+    async fn serve_ready(status: StatusCode) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        let app = Router::new().route("/ready", get(move || async move { status }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        addr
+    }
+
+    #[tokio::test]
+    async fn passes_only_on_200() {
+        let ready = serve_ready(StatusCode::OK).await;
+        probe_ready(&BindTarget::Tcp(ready), Duration::from_secs(5))
+            .await
+            .expect("ready");
+
+        let not_ready = serve_ready(StatusCode::SERVICE_UNAVAILABLE).await;
+        let error = probe_ready(&BindTarget::Tcp(not_ready), Duration::from_secs(5))
+            .await
+            .expect_err("503 must fail");
+        assert!(error.to_string().contains("503"));
+    }
+
+    #[test]
+    fn wildcard_binds_are_probed_on_loopback() {
+        assert_eq!(
+            loopback_for("0.0.0.0:8080".parse().expect("addr")),
+            "127.0.0.1:8080".parse::<SocketAddr>().expect("addr")
+        );
+        assert_eq!(
+            loopback_for("[::]:8080".parse().expect("addr")),
+            "[::1]:8080".parse::<SocketAddr>().expect("addr")
+        );
+    }
+}
@@ -5,6 +5,7 @@ use std::sync::{Arc, Mutex};
 pub enum IdempotencyDecision {
     Accept,
     Duplicate,
+    DuplicateContent,
     Cooldown,
 }
 
@@ -14,6 +15,8 @@ pub struct IdempotencyStore {
     cooldown_seconds: i64,
     dedup_expirations: Arc<Mutex<HashMap<String, i64>>>,
     cooldown_expirations: Arc<Mutex<HashMap<String, i64>>>,
+    content_ttl_seconds: Option<i64>,
+    content_expirations: Arc<Mutex<HashMap<String, i64>>>,
 }
 
 impl IdempotencyStore {
@@ -23,14 +26,57 @@ impl IdempotencyStore {
             cooldown_seconds,
             dedup_expirations: Arc::new(Mutex::new(HashMap::new())),
             cooldown_expirations: Arc::new(Mutex::new(HashMap::new())),
+            content_ttl_seconds: None,
+            content_expirations: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Enables the payload-hash dedup tier with its own retention window.
+    pub fn with_content_dedup(mut self, ttl_seconds: Option<i64>) -> Self {
+        self.content_ttl_seconds = ttl_seconds;
+        self
+    }
+
+    pub fn content_dedup_enabled(&self) -> bool {
+        self.content_ttl_seconds.is_some()
+    }
+
     pub fn check(
         &self,
         dedup_key: &str,
         cooldown_key: Option<&str>,
         now_epoch: i64,
+    ) -> IdempotencyDecision {
+        self.check_with_content(dedup_key, None, cooldown_key, now_epoch)
+    }
+
+    /// Content is checked after the delivery id and before cooldown, so an
+    /// identical redelivery never starts a cooldown window of its own.
+    pub fn check_with_content(
+        &self,
+        dedup_key: &str,
+        content_key: Option<&str>,
+        cooldown_key: Option<&str>,
+        now_epoch: i64,
+    ) -> IdempotencyDecision {
+        self.check_with_cooldown(
+            dedup_key,
+            content_key,
+            cooldown_key,
+            self.cooldown_seconds,
+            now_epoch,
+        )
+    }
+
+    /// [`Self::check_with_content`] with a cooldown window other than the
+    /// store's own, e.g. a tenant's.
+    pub fn check_with_cooldown(
+        &self,
+        dedup_key: &str,
+        content_key: Option<&str>,
+        cooldown_key: Option<&str>,
+        cooldown_seconds: i64,
+        now_epoch: i64,
     ) -> IdempotencyDecision {
         if dedup_key.is_empty() {
             return IdempotencyDecision::Accept;
@@ -52,6 +98,22 @@ impl IdempotencyStore {
             dedup_guard.insert(dedup_key.to_string(), now_epoch + self.dedup_ttl_seconds);
         }
 
+        if let (Some(content_key), Some(ttl_seconds)) = (content_key, self.content_ttl_seconds) {
+            let mut content_guard = match self.content_expirations.lock() {
+                Ok(guard) => guard,
+                Err(_) => return IdempotencyDecision::DuplicateContent,
+            };
+
+            prune_expired(&mut content_guard, now_epoch);
+            if let Some(expires_at) = content_guard.get(content_key)
+                && *expires_at > now_epoch
+            {
+                return IdempotencyDecision::DuplicateContent;
+            }
+
+            content_guard.insert(content_key.to_string(), now_epoch + ttl_seconds);
+        }
+
         let Some(cooldown_key) = cooldown_key else {
             return IdempotencyDecision::Accept;
         };
@@ -72,7 +134,7 @@ impl IdempotencyStore {
             return IdempotencyDecision::Cooldown;
         }
 
-        cooldown_guard.insert(cooldown_key.to_string(), now_epoch + self.cooldown_seconds);
+        cooldown_guard.insert(cooldown_key.to_string(), now_epoch + cooldown_seconds);
         IdempotencyDecision::Accept
     }
 }
@@ -111,6 +173,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn identical_content_under_new_delivery_id_is_rejected() {
+        let store = IdempotencyStore::new(600, 30).with_content_dedup(Some(120));
+        assert_eq!(
+            store.check_with_content("dedup-1", Some("content-a"), None, 1_700_000_000),
+            IdempotencyDecision::Accept
+        );
+        assert_eq!(
+            store.check_with_content("dedup-2", Some("content-a"), None, 1_700_000_010),
+            IdempotencyDecision::DuplicateContent
+        );
+        assert_eq!(
+            store.check_with_content("dedup-3", Some("content-a"), None, 1_700_000_121),
+            IdempotencyDecision::Accept
+        );
+
+        let disabled = IdempotencyStore::new(600, 30);
+        disabled.check_with_content("dedup-1", Some("content-a"), None, 1_700_000_000);
+        assert_eq!(
+            disabled.check_with_content("dedup-2", Some("content-a"), None, 1_700_000_010),
+            IdempotencyDecision::Accept
+        );
+    }
+
+    #[test]
+    fn cooldown_window_can_be_overridden_per_check() {
+        let store = IdempotencyStore::new(600, 30);
+        store.check_with_cooldown("dedup-1", None, Some("cooldown-1"), 120, 1_700_000_000);
+        assert_eq!(
+            store.check("dedup-2", Some("cooldown-1"), 1_700_000_090),
+            IdempotencyDecision::Cooldown
+        );
+        assert_eq!(
+            store.check("dedup-3", Some("cooldown-1"), 1_700_000_121),
+            IdempotencyDecision::Accept
+        );
+    }
+
     #[test]
     fn keys_expire_and_accept_again() {
         let store = IdempotencyStore::new(60, 30);
@@ -1,7 +1,29 @@
+pub mod access_log;
+pub mod admin;
+pub mod alerts;
+pub mod app;
+pub mod audit;
+pub mod blob;
+pub mod build_info;
 pub mod client_ip;
+pub mod clock;
 pub mod config;
+pub mod debounce;
+pub mod digest;
 pub mod envelope;
+pub mod github_app;
+pub mod healthcheck;
 pub mod idempotency;
+pub mod listener;
+pub mod metrics;
 pub mod middleware;
+pub mod openapi;
 pub mod producer;
+pub mod proxy_protocol;
+pub mod sampling;
+pub mod script;
 pub mod sources;
+pub mod spill;
+pub mod truncate;
+
+pub use app::{build_router, build_router_with_clock, check_config, run};
@@ -0,0 +1,409 @@
+use crate::proxy_protocol::{ProxiedPeer, ProxyProtocolListener, insert_proxied_peer};
+use anyhow::{Context, Result, anyhow};
+use axum::Router;
+use axum::extract::{ConnectInfo, Request};
+use axum::middleware::map_request;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::fs;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::os::fd::{FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use tokio::net::{TcpListener, UnixListener};
+use tracing::warn;
+
+const UNIX_PREFIX: &str = "unix:";
+const SYSTEMD_TARGET: &str = "systemd";
+const SD_LISTEN_FDS_START: RawFd = 3;
+const UNIX_PEER: ([u8; 4], u16) = ([127, 0, 0, 1], 0);
+const TCP_BACKLOG: i32 = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindTarget {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+    /// Index into the sockets passed by systemd socket activation.
+    Systemd(usize),
+}
+
+impl BindTarget {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        if raw == SYSTEMD_TARGET {
+            return Ok(Self::Systemd(0));
+        }
+        if let Some(index) = raw
+            .strip_prefix(SYSTEMD_TARGET)
+            .and_then(|rest| rest.strip_prefix(':'))
+        {
+            return index
+                .parse::<usize>()
+                .map(Self::Systemd)
+                .with_context(|| format!("invalid systemd socket index in '{raw}'"));
+        }
+        if let Some(path) = raw.strip_prefix(UNIX_PREFIX) {
+            let path = PathBuf::from(path);
+            if !path.is_absolute() {
+                return Err(anyhow!(
+                    "unix socket path '{}' must be absolute",
+                    path.display()
+                ));
+            }
+            return Ok(Self::Unix(path));
+        }
+        raw.parse::<SocketAddr>()
+            .map(Self::Tcp)
+            .with_context(|| format!("'{raw}' is not a socket address or unix:<path>"))
+    }
+
+    /// Comma-separated targets, e.g. `0.0.0.0:8080,[::]:8080`.
+    pub fn parse_list(raw: &str) -> Result<Vec<Self>> {
+        let mut targets = Vec::new();
+        for target in raw.split(',').map(str::trim).filter(|raw| !raw.is_empty()) {
+            let target = Self::parse(target)?;
+            if targets.contains(&target) {
+                return Err(anyhow!("bind target '{}' is listed twice", raw.trim()));
+            }
+            targets.push(target);
+        }
+        if targets.is_empty() {
+            return Err(anyhow!("no bind target given"));
+        }
+        Ok(targets)
+    }
+}
+
+pub enum BoundListener {
+    Tcp(TcpListener),
+    Unix(UnixListener, Option<UnixSocketGuard>),
+    ProxyProtocol(ProxyProtocolListener),
+}
+
+impl BoundListener {
+    pub async fn bind(target: &BindTarget, unix_socket_mode: u32) -> Result<Self> {
+        Self::bind_with(target, unix_socket_mode, false).await
+    }
+
+    /// Binds every target. With more than one, IPv6 sockets are made
+    /// IPv6-only so `[::]:port` can sit next to `0.0.0.0:port`.
+    pub async fn bind_all(targets: &[BindTarget], unix_socket_mode: u32) -> Result<Vec<Self>> {
+        let v6_only = targets.len() > 1;
+        let mut listeners = Vec::with_capacity(targets.len());
+        for target in targets {
+            listeners.push(Self::bind_with(target, unix_socket_mode, v6_only).await?);
+        }
+        Ok(listeners)
+    }
+
+    async fn bind_with(target: &BindTarget, unix_socket_mode: u32, v6_only: bool) -> Result<Self> {
+        match target {
+            BindTarget::Tcp(addr) => bind_tcp(*addr, v6_only)
+                .map(Self::Tcp)
+                .with_context(|| format!("bind {addr}")),
+            BindTarget::Unix(path) => {
+                let (listener, guard) = bind_unix(path, unix_socket_mode)?;
+                Ok(Self::Unix(listener, Some(guard)))
+            }
+            BindTarget::Systemd(index) => {
+                let fd = systemd_listen_fd(
+                    std::env::var("LISTEN_PID").ok().as_deref(),
+                    std::env::var("LISTEN_FDS").ok().as_deref(),
+                    std::process::id(),
+                    *index,
+                )?;
+                // Safety: systemd hands this fd to the process for it to own,
+                // and each index is claimed by at most one listener.
+                unsafe { Self::from_inherited_fd(fd) }
+            }
+        }
+    }
+
+    /// Requires a PROXY protocol header on every TCP connection.
+    pub fn with_proxy_protocol(self) -> Result<Self> {
+        match self {
+            Self::Tcp(listener) => ProxyProtocolListener::new(listener)
+                .map(Self::ProxyProtocol)
+                .context("wrap listener for PROXY protocol"),
+            Self::Unix(..) => Err(anyhow!("PROXY protocol is only supported on TCP listeners")),
+            Self::ProxyProtocol(_) => Ok(self),
+        }
+    }
+
+    /// # Safety
+    /// `fd` must be an open listening socket not owned by anything else.
+    unsafe fn from_inherited_fd(fd: RawFd) -> Result<Self> {
+        let tcp = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+        if tcp.local_addr().is_ok() {
+            tcp.set_nonblocking(true)
+                .context("set inherited socket non-blocking")?;
+            return TcpListener::from_std(tcp)
+                .map(Self::Tcp)
+                .context("adopt inherited tcp socket");
+        }
+        let unix = unsafe { std::os::unix::net::UnixListener::from_raw_fd(tcp.into_raw_fd()) };
+        unix.set_nonblocking(true)
+            .context("set inherited socket non-blocking")?;
+        UnixListener::from_std(unix)
+            .map(|listener| Self::Unix(listener, None))
+            .context("adopt inherited unix socket")
+    }
+
+    /// Unix socket peers are reported as loopback so rate limiting and
+    /// trusted-proxy handling treat the local reverse proxy like 127.0.0.1.
+    pub async fn serve(
+        self,
+        app: Router,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> std::io::Result<()> {
+        match self {
+            Self::Tcp(listener) => {
+                axum::serve(
+                    listener,
+                    app.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(shutdown)
+                .await
+            }
+            Self::Unix(listener, _guard) => {
+                axum::serve(listener, app.layer(map_request(insert_unix_peer)))
+                    .with_graceful_shutdown(shutdown)
+                    .await
+            }
+            Self::ProxyProtocol(listener) => {
+                axum::serve(
+                    listener,
+                    app.layer(map_request(insert_proxied_peer))
+                        .into_make_service_with_connect_info::<ProxiedPeer>(),
+                )
+                .with_graceful_shutdown(shutdown)
+                .await
+            }
+        }
+    }
+}
+
+fn bind_tcp(addr: SocketAddr, v6_only: bool) -> std::io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    if v6_only && addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(TCP_BACKLOG)?;
+    TcpListener::from_std(socket.into())
+}
+
+async fn insert_unix_peer(mut request: Request) -> Request {
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(UNIX_PEER)));
+    request
+}
+
+fn systemd_listen_fd(
+    listen_pid: Option<&str>,
+    listen_fds: Option<&str>,
+    pid: u32,
+    index: usize,
+) -> Result<RawFd> {
+    let listen_pid = listen_pid
+        .and_then(|raw| raw.trim().parse::<u32>().ok())
+        .ok_or_else(|| anyhow!("systemd socket activation requested but LISTEN_PID is not set"))?;
+    if listen_pid != pid {
+        return Err(anyhow!(
+            "LISTEN_PID {listen_pid} does not match this process ({pid})"
+        ));
+    }
+    let listen_fds = listen_fds
+        .and_then(|raw| raw.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    if index >= listen_fds {
+        return Err(anyhow!(
+            "systemd socket {index} requested but only {listen_fds} passed"
+        ));
+    }
+    Ok(SD_LISTEN_FDS_START + index as RawFd)
+}
+
+/// Removes the socket file when dropped so restarts can rebind the path.
+#[derive(Debug)]
+pub struct UnixSocketGuard {
+    path: PathBuf,
+}
+
+impl Drop for UnixSocketGuard {
+    fn drop(&mut self) {
+        if let Err(error) = fs::remove_file(&self.path) {
+            warn!(path = %self.path.display(), error = %error, "failed to remove unix socket");
+        }
+    }
+}
+
+pub fn bind_unix(path: &Path, mode: u32) -> Result<(UnixListener, UnixSocketGuard)> {
+    remove_stale_socket(path)?;
+    let listener =
+        UnixListener::bind(path).with_context(|| format!("bind unix:{}", path.display()))?;
+    let guard = UnixSocketGuard {
+        path: path.to_path_buf(),
+    };
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .with_context(|| format!("set permissions on {}", path.display()))?;
+    Ok((listener, guard))
+}
+
+fn remove_stale_socket(path: &Path) -> Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            fs::remove_file(path).with_context(|| format!("remove stale socket {}", path.display()))
+        }
+        Ok(_) => Err(anyhow!("{} exists and is not a socket", path.display())),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tcp_and_unix_targets() {
+        assert_eq!(
+            BindTarget::parse("127.0.0.1:8080").expect("tcp"),
+            BindTarget::Tcp(SocketAddr::from(([127, 0, 0, 1], 8080)))
+        );
+        assert_eq!(
+            BindTarget::parse("unix:/run/hook-serve.sock").expect("unix"),
+            BindTarget::Unix(PathBuf::from("/run/hook-serve.sock"))
+        );
+        assert_eq!(
+            BindTarget::parse("systemd").expect("systemd"),
+            BindTarget::Systemd(0)
+        );
+        assert_eq!(
+            BindTarget::parse("systemd:1").expect("systemd index"),
+            BindTarget::Systemd(1)
+        );
+        assert!(BindTarget::parse("systemd:admin").is_err());
+        assert!(BindTarget::parse("unix:relative.sock").is_err());
+        assert!(BindTarget::parse("localhost").is_err());
+    }
+
+    #[test]
+    fn parses_bind_lists() {
+        assert_eq!(
+            BindTarget::parse_list("0.0.0.0:9000, [::]:9000").expect("list"),
+            vec![
+                BindTarget::Tcp(SocketAddr::from(([0, 0, 0, 0], 9000))),
+                BindTarget::Tcp("[::]:9000".parse().expect("v6")),
+            ]
+        );
+        assert!(BindTarget::parse_list("127.0.0.1:1,127.0.0.1:1").is_err());
+        assert!(BindTarget::parse_list(" , ").is_err());
+    }
+
+    #[tokio::test]
+    async fn binds_ipv4_and_ipv6_wildcards_on_one_port() {
+        let v4 = std::net::TcpListener::bind("0.0.0.0:0").expect("probe port");
+        let port = v4.local_addr().expect("addr").port();
+        drop(v4);
+        let targets =
+            BindTarget::parse_list(&format!("0.0.0.0:{port},[::]:{port}")).expect("targets");
+
+        match BoundListener::bind_all(&targets, 0o600).await {
+            Ok(listeners) => assert_eq!(listeners.len(), 2),
+            // Hosts without IPv6 cannot create the second socket.
+            Err(error) if format!("{error:#}").contains("Address family not supported") => {}
+            Err(error) => panic!("bind dual stack: {error:#}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn binds_with_mode_and_cleans_up() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("relay.sock");
+
+        let (listener, guard) = bind_unix(&path, 0o660).expect("bind");
+        let mode = fs::metadata(&path).expect("metadata").permissions().mode();
+        assert_eq!(mode & 0o777, 0o660);
+        drop(listener);
+        let (_listener, guard_again) = bind_unix(&path, 0o600).expect("rebind over stale socket");
+
+        drop(guard);
+        assert!(!path.exists());
+        drop(guard_again);
+    }
+
+    #[tokio::test]
+    async fn unix_requests_see_loopback_peer() {
+        use axum::routing::get;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("relay.sock");
+        let listener = BoundListener::bind(&BindTarget::Unix(path.clone()), 0o600)
+            .await
+            .expect("bind");
+        let app = Router::new().route(
+            "/peer",
+            get(|ConnectInfo(peer): ConnectInfo<SocketAddr>| async move { peer.to_string() }),
+        );
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(listener.serve(app, async {
+            let _ = stop_rx.await;
+        }));
+
+        let mut stream = tokio::net::UnixStream::connect(&path)
+            .await
+            .expect("connect");
+        stream
+            .write_all(b"GET /peer HTTP/1.1\r\nHost: relay\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("write request");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("read response");
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("127.0.0.1:0"), "{response}");
+
+        let _ = stop_tx.send(());
+        server.await.expect("join").expect("serve");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn systemd_fds_follow_listen_env() {
+        assert_eq!(
+            systemd_listen_fd(Some("42"), Some("2"), 42, 1).expect("fd"),
+            4
+        );
+        assert!(systemd_listen_fd(None, Some("1"), 42, 0).is_err());
+        assert!(systemd_listen_fd(Some("41"), Some("1"), 42, 0).is_err());
+        assert!(systemd_listen_fd(Some("42"), Some("1"), 42, 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn inherited_fds_are_classified_by_family() {
+        let tcp = std::net::TcpListener::bind("127.0.0.1:0").expect("bind tcp");
+        let adopted = unsafe { BoundListener::from_inherited_fd(tcp.into_raw_fd()) };
+        assert!(matches!(adopted, Ok(BoundListener::Tcp(_))));
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let unix = std::os::unix::net::UnixListener::bind(dir.path().join("inherited.sock"))
+            .expect("bind");
+        let adopted = unsafe { BoundListener::from_inherited_fd(unix.into_raw_fd()) };
+        assert!(matches!(adopted, Ok(BoundListener::Unix(_, None))));
+    }
+
+    #[test]
+    fn refuses_to_replace_regular_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("not-a-socket");
+        fs::write(&path, b"data").expect("write");
+        assert!(bind_unix(&path, 0o660).is_err());
+        assert!(path.exists());
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const SIGNATURE_DURATION_BUCKETS: &[f64] = &[0.000_05, 0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05];
+const MAX_FAILURE_SERIES: usize = 1_024;
+const OVERFLOW_BUCKET: &str = "other";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SignatureResult {
+    Valid,
+    ValidLegacySha1,
+    Invalid,
+    Malformed,
+}
+
+impl SignatureResult {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::ValidLegacySha1 => "valid_sha1",
+            Self::Invalid => "invalid",
+            Self::Malformed => "malformed",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    signatures: Mutex<SignatureMetrics>,
+    /// Requests on tenant paths by tenant, source and outcome.
+    tenant_requests: Mutex<BTreeMap<(String, String, &'static str), u64>>,
+}
+
+#[derive(Debug, Default)]
+struct SignatureMetrics {
+    results: BTreeMap<(String, SignatureResult), u64>,
+    failures: BTreeMap<(String, &'static str, String), u64>,
+    durations: BTreeMap<String, Histogram>,
+}
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &[f64]) -> Self {
+        Self {
+            buckets: vec![0; bounds.len()],
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, bounds: &[f64], value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(bounds) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Failures also count per client network so probing shows up separately
+    /// from a provider with a stale secret.
+    pub fn record_signature(
+        &self,
+        source: &str,
+        result: SignatureResult,
+        failure: Option<(&'static str, String)>,
+        elapsed: Duration,
+    ) {
+        let Ok(mut signatures) = self.signatures.lock() else {
+            return;
+        };
+        *signatures
+            .results
+            .entry((source.to_string(), result))
+            .or_default() += 1;
+        if let Some((reason, ip_bucket)) = failure {
+            let at_capacity = signatures.failures.len() >= MAX_FAILURE_SERIES;
+            let mut key = (source.to_string(), reason, ip_bucket);
+            if at_capacity && !signatures.failures.contains_key(&key) {
+                key.2 = OVERFLOW_BUCKET.to_string();
+            }
+            *signatures.failures.entry(key).or_default() += 1;
+        }
+        signatures
+            .durations
+            .entry(source.to_string())
+            .or_insert_with(|| Histogram::new(SIGNATURE_DURATION_BUCKETS))
+            .observe(SIGNATURE_DURATION_BUCKETS, elapsed.as_secs_f64());
+    }
+
+    pub fn record_tenant_request(&self, tenant: &str, source: &str, outcome: &'static str) {
+        if let Ok(mut tenant_requests) = self.tenant_requests.lock() {
+            *tenant_requests
+                .entry((tenant.to_string(), source.to_string(), outcome))
+                .or_default() += 1;
+        }
+    }
+
+    pub fn render(&self, output: &mut String) {
+        if let Ok(tenant_requests) = self.tenant_requests.lock()
+            && !tenant_requests.is_empty()
+        {
+            write_header(
+                output,
+                "webhook_relay_tenant_requests_total",
+                "counter",
+                "Webhook requests on tenant paths by tenant, source, and outcome.",
+            );
+            for ((tenant, source, outcome), count) in tenant_requests.iter() {
+                let _ = writeln!(
+                    output,
+                    "webhook_relay_tenant_requests_total{{tenant=\"{}\",source=\"{}\",outcome=\"{outcome}\"}} {count}",
+                    escape_label_value(tenant),
+                    escape_label_value(source)
+                );
+            }
+        }
+
+        let Ok(signatures) = self.signatures.lock() else {
+            return;
+        };
+
+        write_header(
+            output,
+            "webhook_relay_signature_verifications_total",
+            "counter",
+            "Webhook signature checks by source and result.",
+        );
+        for ((source, result), count) in &signatures.results {
+            let _ = writeln!(
+                output,
+                "webhook_relay_signature_verifications_total{{source=\"{}\",result=\"{}\"}} {count}",
+                escape_label_value(source),
+                result.as_str()
+            );
+        }
+
+        write_header(
+            output,
+            "webhook_relay_signature_failures_total",
+            "counter",
+            "Failed signature checks by source, reason, and client network (/24 or /48).",
+        );
+        for ((source, reason, ip_bucket), count) in &signatures.failures {
+            let _ = writeln!(
+                output,
+                "webhook_relay_signature_failures_total{{source=\"{}\",reason=\"{reason}\",client_ip_bucket=\"{}\"}} {count}",
+                escape_label_value(source),
+                escape_label_value(ip_bucket)
+            );
+        }
+
+        let name = "webhook_relay_signature_verification_duration_seconds";
+        write_header(
+            output,
+            name,
+            "histogram",
+            "Time spent verifying webhook signatures.",
+        );
+        for (source, histogram) in &signatures.durations {
+            let source = escape_label_value(source);
+            for (bound, count) in SIGNATURE_DURATION_BUCKETS.iter().zip(&histogram.buckets) {
+                let _ = writeln!(
+                    output,
+                    "{name}_bucket{{source=\"{source}\",le=\"{bound}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                output,
+                "{name}_bucket{{source=\"{source}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                output,
+                "{name}_sum{{source=\"{source}\"}} {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                output,
+                "{name}_count{{source=\"{source}\"}} {}",
+                histogram.count
+            );
+        }
+    }
+}
+
+pub(crate) fn write_header(output: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {name} {help}");
+    let _ = writeln!(output, "# TYPE {name} {kind}");
+}
+
+pub(crate) fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_histogram() {
+        let metrics = Metrics::new();
+        metrics.record_signature(
+            "github",
+            SignatureResult::Valid,
+            None,
+            Duration::from_micros(80),
+        );
+        metrics.record_signature(
+            "github",
+            SignatureResult::Invalid,
+            Some(("mismatch", "203.0.113.0/24".to_string())),
+            Duration::from_millis(2),
+        );
+
+        let mut output = String::new();
+        metrics.render(&mut output);
+        assert!(output.contains(
+            "webhook_relay_signature_verifications_total{source=\"github\",result=\"valid\"} 1"
+        ));
+        assert!(output.contains(
+            "webhook_relay_signature_failures_total{source=\"github\",reason=\"mismatch\",client_ip_bucket=\"203.0.113.0/24\"} 1"
+        ));
+        assert!(output.contains(
+            "webhook_relay_signature_verification_duration_seconds_bucket{source=\"github\",le=\"0.0001\"} 1"
+        ));
+        assert!(output.contains(
+            "webhook_relay_signature_verification_duration_seconds_count{source=\"github\"} 2"
+        ));
+    }
+
+    #[test]
+    fn failure_series_are_capped() {
+        let metrics = Metrics::new();
+        for index in 0..(MAX_FAILURE_SERIES + 5) {
+            metrics.record_signature(
+                "github",
+                SignatureResult::Invalid,
+                Some((
+                    "mismatch",
+                    format!("10.{}.{}.0/24", index / 256, index % 256),
+                )),
+                Duration::ZERO,
+            );
+        }
+        let signatures = metrics.signatures.lock().expect("lock");
+        assert_eq!(signatures.failures.len(), MAX_FAILURE_SERIES + 1);
+        assert_eq!(
+            signatures.failures[&(
+                "github".to_string(),
+                "mismatch",
+                OVERFLOW_BUCKET.to_string()
+            )],
+            5
+        );
+    }
+
+    #[test]
+    fn counts_tenant_requests() {
+        let metrics = Metrics::new();
+        metrics.record_tenant_request("payments", "github", "forwarded");
+        metrics.record_tenant_request("payments", "github", "forwarded");
+
+        let mut output = String::new();
+        metrics.render(&mut output);
+        assert!(output.contains(
+            "webhook_relay_tenant_requests_total{tenant=\"payments\",source=\"github\",outcome=\"forwarded\"} 2"
+        ));
+
+        let mut untenanted = String::new();
+        Metrics::new().render(&mut untenanted);
+        assert!(!untenanted.contains("webhook_relay_tenant_requests_total"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label_value("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
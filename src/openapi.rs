@@ -0,0 +1,108 @@
+use crate::app;
+use serde::Serialize;
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::openapi::{OpenApi as OpenApiDocument, PathItem};
+use utoipa::{Modify, OpenApi, ToSchema};
+
+/// Path the ingest operation is declared under before the configured
+/// ingress path replaces it.
+const DEFAULT_WEBHOOK_PATH: &str = "/webhook/{source}";
+const DEFAULT_MCP_INGEST_PATH: &str = "/mcp/ingest";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "hook serve",
+        description = "Webhook ingress relay: verifies provider signatures and publishes envelopes to Kafka."
+    ),
+    paths(
+        app::webhook_handler,
+        app::mcp_ingest_handler,
+        app::health,
+        app::ready,
+        app::version,
+        app::metrics,
+        app::admin_config,
+        app::admin_activity,
+        app::admin_reload_secrets,
+    ),
+    components(schemas(WebhookAccepted, WebhookIgnored, ErrorBody, app::McpIngestRequest)),
+    modifiers(&AdminBearerAuth),
+    tags(
+        (name = "ingest", description = "Provider webhooks and adapter ingest."),
+        (name = "operator", description = "Health, readiness, version and metrics."),
+        (name = "admin", description = "Requires `RELAY_ADMIN_TOKEN` as a bearer token."),
+    )
+)]
+struct ApiDoc;
+
+/// Builds the document for the routes this process actually mounts.
+pub fn document(webhook_path: &str, mcp_ingest_path: Option<&str>) -> OpenApiDocument {
+    let mut document = ApiDoc::openapi();
+    document.info.version = env!("CARGO_PKG_VERSION").to_string();
+    move_path(&mut document, DEFAULT_WEBHOOK_PATH, Some(webhook_path));
+    move_path(&mut document, DEFAULT_MCP_INGEST_PATH, mcp_ingest_path);
+    document
+}
+
+fn move_path(document: &mut OpenApiDocument, from: &str, to: Option<&str>) {
+    let Some(item): Option<PathItem> = document.paths.paths.remove(from) else {
+        return;
+    };
+    if let Some(to) = to {
+        document.paths.paths.insert(to.to_string(), item);
+    }
+}
+
+struct AdminBearerAuth;
+
+impl Modify for AdminBearerAuth {
+    fn modify(&self, document: &mut OpenApiDocument) {
+        let components = document.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+    }
+}
+
+/// Event accepted and queued for Kafka.
+#[derive(Serialize, ToSchema)]
+#[schema(example = json!({"status": "ok", "id": "550e8400-e29b-41d4-a716-446655440000"}))]
+pub struct WebhookAccepted {
+    pub status: String,
+    /// Envelope id.
+    pub id: String,
+}
+
+/// Event verified but deliberately not published, e.g. a duplicate or a
+/// filtered event type.
+#[derive(Serialize, ToSchema)]
+#[schema(example = json!({"status": "ignored", "reason": "duplicate"}))]
+pub struct WebhookIgnored {
+    pub status: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[schema(example = json!({"error": "unauthorized"}))]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_uses_mounted_ingest_paths() {
+        let document = document("/hooks/{source}", None);
+        let paths = document.paths.paths.keys().collect::<Vec<_>>();
+
+        assert!(paths.contains(&&"/hooks/{source}".to_string()));
+        assert!(!paths.contains(&&DEFAULT_WEBHOOK_PATH.to_string()));
+        assert!(!paths.contains(&&DEFAULT_MCP_INGEST_PATH.to_string()));
+        assert!(paths.contains(&&"/admin/reload-secrets".to_string()));
+        assert_eq!(document.info.version, env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -1,18 +1,25 @@
 use crate::config::Config;
+use crate::spill::PublishSpill;
 use anyhow::{Context, Result, anyhow};
 use rdkafka::ClientConfig;
 use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
 use rdkafka::client::DefaultClientContext;
-use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
 use rdkafka::types::RDKafkaErrorCode;
 use rdkafka::util::Timeout;
 use relay_core::model::WebhookEnvelope;
 use serde::Serialize;
 use std::collections::BTreeSet;
-use tokio::sync::mpsc;
-use tokio::time::{Duration, sleep};
+use tokio::sync::{mpsc, watch};
+use tokio::time::{Duration, Instant, interval, sleep};
 use tracing::{debug, error, info, warn};
 
+const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(30);
+/// Time the worker spends publishing the queue after shutdown before it
+/// spills the rest.
+const SHUTDOWN_PUBLISH_DEADLINE: Duration = Duration::from_secs(20);
+
 #[derive(Debug, Clone)]
 pub struct PublishJob {
     pub topic: String,
@@ -25,13 +32,13 @@ pub struct KafkaPublisher {
     max_retries: u32,
     backoff_base_ms: u64,
     backoff_max_ms: u64,
+    /// Set when `KAFKA_PRODUCER_TRANSACTIONAL_ID` is configured.
+    transaction_batch_size: Option<usize>,
 }
 
 impl KafkaPublisher {
     pub fn from_config(config: &Config) -> Result<Self> {
-        let producer = base_client_config(config)
-            .set("message.timeout.ms", "5000")
-            .set("queue.buffering.max.ms", "5")
+        let producer = producer_client_config(config)
             .create::<FutureProducer>()
             .context("create kafka future producer")?;
 
@@ -40,9 +47,99 @@ impl KafkaPublisher {
             max_retries: config.publish_max_retries,
             backoff_base_ms: config.publish_backoff_base_ms,
             backoff_max_ms: config.publish_backoff_max_ms,
+            transaction_batch_size: config
+                .kafka_producer_transactional_id
+                .as_ref()
+                .map(|_| config.kafka_producer_transaction_batch_size),
         })
     }
 
+    /// Registers the transactional id with the coordinator, fencing any
+    /// older producer that used it. Blocks, so it runs off the runtime.
+    async fn init_transactions(&self) -> Result<()> {
+        let producer = self.producer.clone();
+        tokio::task::spawn_blocking(move || producer.init_transactions(TRANSACTION_TIMEOUT))
+            .await
+            .context("join kafka init_transactions")?
+            .context("init kafka transactions")
+    }
+
+    /// Publishes `jobs` in one transaction, retrying the whole batch with
+    /// the usual backoff when it aborts.
+    pub async fn publish_batch(&self, jobs: &[PublishJob]) -> Result<()> {
+        let mut attempt = 0u32;
+        loop {
+            match self.publish_transaction(jobs).await {
+                Ok(()) => {
+                    info!(
+                        envelopes = jobs.len(),
+                        "committed kafka transaction of webhook envelopes"
+                    );
+                    return Ok(());
+                }
+                Err(error) => {
+                    let producer = self.producer.clone();
+                    tokio::task::spawn_blocking(move || {
+                        producer.abort_transaction(TRANSACTION_TIMEOUT)
+                    })
+                    .await
+                    .context("join kafka abort_transaction")?
+                    .context("abort kafka transaction")?;
+
+                    attempt = attempt.saturating_add(1);
+                    if attempt >= self.max_retries {
+                        return Err(anyhow!(
+                            "kafka transaction failed after {attempt} attempts: {error:#}"
+                        ));
+                    }
+                    let backoff = retry_backoff_ms(
+                        self.backoff_base_ms,
+                        self.backoff_max_ms,
+                        attempt.saturating_sub(1),
+                    );
+                    warn!(
+                        envelopes = jobs.len(),
+                        attempt,
+                        backoff_ms = backoff,
+                        error = %format!("{error:#}"),
+                        "kafka transaction aborted; retrying"
+                    );
+                    sleep(Duration::from_millis(backoff)).await;
+                }
+            }
+        }
+    }
+
+    async fn publish_transaction(&self, jobs: &[PublishJob]) -> Result<()> {
+        self.producer
+            .begin_transaction()
+            .context("begin kafka transaction")?;
+        let mut deliveries = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            let payload =
+                serde_json::to_string(&job.envelope).context("serialize webhook envelope")?;
+            let record = FutureRecord::to(&job.topic)
+                .key(job.envelope.id.as_str())
+                .payload(&payload);
+            let delivery = self
+                .producer
+                .send_result(record)
+                .map_err(|(error, _record)| anyhow!("enqueue {}: {error}", job.envelope.id))?;
+            deliveries.push(delivery);
+        }
+        for (job, delivery) in jobs.iter().zip(deliveries) {
+            delivery
+                .await
+                .map_err(|_| anyhow!("delivery of {} cancelled", job.envelope.id))?
+                .map_err(|(error, _message)| anyhow!("deliver {}: {error}", job.envelope.id))?;
+        }
+        let producer = self.producer.clone();
+        tokio::task::spawn_blocking(move || producer.commit_transaction(TRANSACTION_TIMEOUT))
+            .await
+            .context("join kafka commit_transaction")?
+            .context("commit kafka transaction")
+    }
+
     pub async fn publish(&self, job: &PublishJob) -> Result<()> {
         let payload = serde_json::to_string(&job.envelope).context("serialize webhook envelope")?;
         let key = job.envelope.id.as_str();
@@ -123,15 +220,33 @@ pub async fn ensure_required_topics(config: &Config) -> Result<()> {
         .map(ToString::to_string)
         .collect::<BTreeSet<_>>();
     topic_names.insert(config.kafka_dlq_topic.clone());
+    if let Some(digest_topic) = &config.digest_topic {
+        topic_names.insert(digest_topic.clone());
+    }
+    if let Some(alert_topic) = config.alert_topic()
+        && config.dlq_alert_threshold.is_some()
+    {
+        topic_names.insert(alert_topic.to_string());
+    }
 
+    let dlq_configs = dlq_topic_configs(config);
     let topics = topic_names
         .iter()
         .map(|topic| {
-            NewTopic::new(
+            let new_topic = NewTopic::new(
                 topic,
                 config.kafka_topic_partitions,
                 TopicReplication::Fixed(config.kafka_topic_replication_factor),
-            )
+            );
+            if *topic == config.kafka_dlq_topic {
+                dlq_configs
+                    .iter()
+                    .fold(new_topic, |new_topic, (name, value)| {
+                        new_topic.set(name, value)
+                    })
+            } else {
+                new_topic
+            }
         })
         .collect::<Vec<_>>();
 
@@ -147,6 +262,12 @@ pub async fn ensure_required_topics(config: &Config) -> Result<()> {
             }
             Err((topic_name, RDKafkaErrorCode::TopicAlreadyExists)) => {
                 info!(topic = %topic_name, "kafka topic already exists");
+                if topic_name == config.kafka_dlq_topic && !dlq_configs.is_empty() {
+                    warn!(
+                        topic = %topic_name,
+                        "dlq retention caps only apply when serve creates the topic; set them on the existing topic with kafka-configs"
+                    );
+                }
             }
             Err((topic_name, error_code)) => {
                 return Err(anyhow!(
@@ -161,15 +282,200 @@ pub async fn ensure_required_topics(config: &Config) -> Result<()> {
     Ok(())
 }
 
-pub async fn run_publish_worker(mut rx: mpsc::Receiver<PublishJob>, publisher: KafkaPublisher) {
-    while let Some(job) = rx.recv().await {
-        if let Err(error) = publisher.publish(&job).await {
+/// Broker-side caps on the DLQ topic: Kafka drops the oldest segments once
+/// either limit is exceeded, so a long outage cannot fill the broker disk.
+fn dlq_topic_configs(config: &Config) -> Vec<(&'static str, String)> {
+    let mut configs = Vec::new();
+    if let Some(bytes) = config.kafka_dlq_retention_bytes {
+        configs.push(("retention.bytes", bytes.to_string()));
+    }
+    if let Some(ms) = config.kafka_dlq_retention_ms {
+        configs.push(("retention.ms", ms.to_string()));
+    }
+    if !configs.is_empty() {
+        configs.push(("cleanup.policy", "delete".to_string()));
+    }
+    configs
+}
+
+pub fn topic_high_watermark(config: &Config, topic: &str) -> Result<i64> {
+    let consumer: BaseConsumer = base_client_config(config)
+        .create()
+        .context("create kafka metadata client")?;
+    let timeout = Timeout::After(Duration::from_secs(5));
+    let metadata = consumer
+        .fetch_metadata(Some(topic), timeout)
+        .with_context(|| format!("fetch metadata for topic {topic}"))?;
+    let Some(topic_metadata) = metadata.topics().iter().find(|entry| entry.name() == topic) else {
+        return Err(anyhow!("topic {topic} not found in metadata"));
+    };
+
+    let mut total = 0i64;
+    for partition in topic_metadata.partitions() {
+        let (_low, high) = consumer
+            .fetch_watermarks(topic, partition.id(), timeout)
+            .with_context(|| format!("fetch watermarks for {topic}/{}", partition.id()))?;
+        total = total.saturating_add(high);
+    }
+    Ok(total)
+}
+
+/// Publishes queued envelopes, batched into transactions when transactional
+/// mode is on; ones that still fail go to `spill`, replayed every
+/// `replay_every`. Once `shutdown` flips the queue closes, and what is left
+/// is published for up to 20 seconds and spilled after that.
+pub async fn run_publish_worker(
+    mut rx: mpsc::Receiver<PublishJob>,
+    publisher: KafkaPublisher,
+    spill: Option<PublishSpill>,
+    replay_every: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    if publisher.transaction_batch_size.is_some()
+        && let Err(error) = publisher.init_transactions().await
+    {
+        error!(error = %format!("{error:#}"), "kafka transactional publishing unavailable");
+        return;
+    }
+    let batch_size = publisher.transaction_batch_size.unwrap_or(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut replay = interval(replay_every);
+    let mut deadline = None;
+    loop {
+        tokio::select! {
+            job = rx.recv() => {
+                let Some(job) = job else { break };
+                batch.push(job);
+                while batch.len() < batch_size {
+                    match rx.try_recv() {
+                        Ok(job) => batch.push(job),
+                        Err(_) => break,
+                    }
+                }
+                publish_or_spill(&publisher, &batch, spill.as_ref()).await;
+                batch.clear();
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    spill_queued(&mut rx, spill.as_ref()).await;
+                    break;
+                }
+            }
+            Ok(()) = shutdown.changed(), if deadline.is_none() => {
+                info!(queued = rx.len(), "draining publish queue");
+                rx.close();
+                deadline = Some(Instant::now() + SHUTDOWN_PUBLISH_DEADLINE);
+            }
+            _ = replay.tick(), if spill.is_some() && deadline.is_none() => {
+                if let Some(spill) = &spill {
+                    replay_spill(&publisher, spill).await;
+                }
+            }
+        }
+    }
+}
+
+async fn publish_or_spill(
+    publisher: &KafkaPublisher,
+    jobs: &[PublishJob],
+    spill: Option<&PublishSpill>,
+) {
+    let failed = if publisher.transaction_batch_size.is_some() {
+        match publisher.publish_batch(jobs).await {
+            Ok(()) => Vec::new(),
+            Err(error) => jobs.iter().map(|job| (job, error.to_string())).collect(),
+        }
+    } else {
+        let mut failed = Vec::new();
+        for job in jobs {
+            if let Err(error) = publisher.publish(job).await {
+                failed.push((job, error.to_string()));
+            }
+        }
+        failed
+    };
+
+    for (job, error) in failed {
+        let Some(spill) = spill else {
             error!(
                 topic = %job.topic,
                 event_id = %job.envelope.id,
                 error = %error,
                 "failed to publish envelope to kafka"
             );
+            continue;
+        };
+        match spill.write(job).await {
+            Ok(()) => warn!(
+                topic = %job.topic,
+                event_id = %job.envelope.id,
+                error = %error,
+                "failed to publish envelope to kafka; spilled to disk"
+            ),
+            Err(spill_error) => error!(
+                topic = %job.topic,
+                event_id = %job.envelope.id,
+                error = %error,
+                spill_error = %format!("{spill_error:#}"),
+                "failed to publish or spill envelope; dropped"
+            ),
+        }
+    }
+}
+
+/// Writes the jobs still queued at the shutdown deadline to the spill, so
+/// the next start publishes them.
+async fn spill_queued(rx: &mut mpsc::Receiver<PublishJob>, spill: Option<&PublishSpill>) {
+    let mut spilled = 0usize;
+    while let Ok(job) = rx.try_recv() {
+        let result = match spill {
+            Some(spill) => spill.write(&job).await,
+            None => Err(anyhow!("RELAY_PUBLISH_SPILL_DIR is not set")),
+        };
+        match result {
+            Ok(()) => spilled += 1,
+            Err(error) => error!(
+                topic = %job.topic,
+                event_id = %job.envelope.id,
+                error = %format!("{error:#}"),
+                "publish queue not drained before shutdown; envelope dropped"
+            ),
+        }
+    }
+    if spilled > 0 {
+        warn!(
+            spilled,
+            "publish queue not drained before shutdown; rest spilled to disk"
+        );
+    }
+}
+
+/// Re-publishes spilled envelopes oldest first, stopping at the first
+/// failure since Kafka is evidently still unavailable.
+async fn replay_spill(publisher: &KafkaPublisher, spill: &PublishSpill) {
+    let pending = match spill.pending().await {
+        Ok(pending) => pending,
+        Err(error) => {
+            error!(error = %format!("{error:#}"), "failed to read publish spill");
+            return;
+        }
+    };
+    for (path, job) in pending {
+        let published = if publisher.transaction_batch_size.is_some() {
+            publisher.publish_batch(std::slice::from_ref(&job)).await
+        } else {
+            publisher.publish(&job).await
+        };
+        if let Err(error) = published {
+            warn!(
+                event_id = %job.envelope.id,
+                error = %format!("{error:#}"),
+                "spilled envelope replay failed; will retry"
+            );
+            return;
+        }
+        info!(event_id = %job.envelope.id, "replayed spilled envelope to kafka");
+        if let Err(error) = spill.remove(&path).await {
+            // The file is published again on the next pass.
+            error!(error = %format!("{error:#}"), "failed to remove replayed envelope");
         }
     }
 }
@@ -201,6 +507,40 @@ fn base_client_config(config: &Config) -> ClientConfig {
     client_config
 }
 
+/// Producer settings on top of the shared client config. Kept out of
+/// `base_client_config` so consumers and admin clients don't log warnings
+/// about producer-only properties.
+fn producer_client_config(config: &Config) -> ClientConfig {
+    let mut client_config = base_client_config(config);
+    client_config
+        .set("queue.buffering.max.ms", "5")
+        .set("acks", &config.kafka_producer_acks)
+        .set(
+            "enable.idempotence",
+            config.kafka_producer_idempotence.to_string(),
+        )
+        .set(
+            "delivery.timeout.ms",
+            config.kafka_producer_delivery_timeout_ms.to_string(),
+        );
+    if let Some(retries) = config.kafka_producer_retries {
+        client_config.set("retries", retries.to_string());
+    }
+    if config.kafka_producer_compression != "none" {
+        client_config.set("compression.type", &config.kafka_producer_compression);
+    }
+    if let Some(transactional_id) = &config.kafka_producer_transactional_id {
+        client_config.set("transactional.id", transactional_id).set(
+            "transaction.timeout.ms",
+            config
+                .kafka_producer_delivery_timeout_ms
+                .max(60_000)
+                .to_string(),
+        );
+    }
+    client_config
+}
+
 #[cfg(test)]
 mod tests {
     use super::retry_backoff_ms;
@@ -0,0 +1,229 @@
+use anyhow::{Result, anyhow};
+use axum::extract::connect_info::Connected;
+use axum::extract::{ConnectInfo, Request};
+use axum::serve::{IncomingStream, Listener};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, sleep, timeout};
+use tracing::warn;
+
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+/// Longest v1 header allowed by the spec, CRLF included.
+const V1_MAX_LEN: usize = 107;
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+const ACCEPT_QUEUE: usize = 128;
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// TCP listener that expects a PROXY protocol (v1 or v2) header on every
+/// connection and reports the client address it carries. Headers are read
+/// off the accept loop so a slow peer cannot stall other connections.
+pub struct ProxyProtocolListener {
+    local_addr: SocketAddr,
+    accepted: mpsc::Receiver<(TcpStream, SocketAddr)>,
+}
+
+impl ProxyProtocolListener {
+    pub fn new(listener: TcpListener) -> std::io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let (accepted_tx, accepted) = mpsc::channel(ACCEPT_QUEUE);
+        tokio::spawn(async move {
+            while !accepted_tx.is_closed() {
+                let (mut stream, peer) = match listener.accept().await {
+                    Ok(connection) => connection,
+                    Err(error) => {
+                        warn!(error = %error, "accept failed");
+                        sleep(ACCEPT_ERROR_BACKOFF).await;
+                        continue;
+                    }
+                };
+                let accepted_tx = accepted_tx.clone();
+                tokio::spawn(async move {
+                    match timeout(HEADER_TIMEOUT, read_header(&mut stream, peer)).await {
+                        Ok(Ok(client)) => {
+                            let _ = accepted_tx.send((stream, client)).await;
+                        }
+                        Ok(Err(error)) => {
+                            warn!(peer = %peer, error = %error, "rejected connection without valid PROXY header");
+                        }
+                        Err(_) => {
+                            warn!(peer = %peer, "timed out waiting for PROXY header");
+                        }
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            local_addr,
+            accepted,
+        })
+    }
+}
+
+impl Listener for ProxyProtocolListener {
+    type Io = TcpStream;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.accepted.recv().await {
+            Some(connection) => connection,
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+/// Client address from the PROXY header, exposed to handlers as
+/// `ConnectInfo<SocketAddr>` by `insert_proxied_peer`.
+#[derive(Debug, Clone, Copy)]
+pub struct ProxiedPeer(pub SocketAddr);
+
+impl Connected<IncomingStream<'_, ProxyProtocolListener>> for ProxiedPeer {
+    fn connect_info(stream: IncomingStream<'_, ProxyProtocolListener>) -> Self {
+        Self(*stream.remote_addr())
+    }
+}
+
+pub async fn insert_proxied_peer(mut request: Request) -> Request {
+    if let Some(ConnectInfo(ProxiedPeer(client))) = request
+        .extensions()
+        .get::<ConnectInfo<ProxiedPeer>>()
+        .copied()
+    {
+        request.extensions_mut().insert(ConnectInfo(client));
+    }
+    request
+}
+
+/// Consumes exactly the PROXY header and returns the client address.
+/// `LOCAL` (v2) and `UNKNOWN` (v1) connections keep the socket peer.
+pub async fn read_header<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    peer: SocketAddr,
+) -> Result<SocketAddr> {
+    let mut header = [0u8; 12];
+    stream.read_exact(&mut header).await?;
+    if header == V2_SIGNATURE {
+        return read_v2(stream, peer).await;
+    }
+    if !header.starts_with(b"PROXY ") {
+        return Err(anyhow!("missing PROXY protocol signature"));
+    }
+
+    let mut line = header.to_vec();
+    while !line.ends_with(b"\r\n") {
+        if line.len() >= V1_MAX_LEN {
+            return Err(anyhow!("PROXY v1 header too long"));
+        }
+        line.push(stream.read_u8().await?);
+    }
+    parse_v1(&line[..line.len() - 2], peer)
+}
+
+fn parse_v1(line: &[u8], peer: SocketAddr) -> Result<SocketAddr> {
+    let line = std::str::from_utf8(line).map_err(|_| anyhow!("PROXY v1 header is not ASCII"))?;
+    let fields = line.split(' ').collect::<Vec<_>>();
+    match fields.as_slice() {
+        ["PROXY", "UNKNOWN", ..] => Ok(peer),
+        [
+            "PROXY",
+            "TCP4" | "TCP6",
+            source,
+            _destination,
+            source_port,
+            _destination_port,
+        ] => {
+            let ip = source
+                .parse::<IpAddr>()
+                .map_err(|_| anyhow!("invalid PROXY v1 source address '{source}'"))?;
+            let port = source_port
+                .parse::<u16>()
+                .map_err(|_| anyhow!("invalid PROXY v1 source port '{source_port}'"))?;
+            Ok(SocketAddr::new(ip, port))
+        }
+        _ => Err(anyhow!("malformed PROXY v1 header '{line}'")),
+    }
+}
+
+async fn read_v2<S: AsyncRead + Unpin>(stream: &mut S, peer: SocketAddr) -> Result<SocketAddr> {
+    let version_command = stream.read_u8().await?;
+    let family = stream.read_u8().await?;
+    let length = usize::from(stream.read_u16().await?);
+    let mut addresses = vec![0u8; length];
+    stream.read_exact(&mut addresses).await?;
+
+    if version_command >> 4 != 2 {
+        return Err(anyhow!("unsupported PROXY v2 version"));
+    }
+    match version_command & 0x0f {
+        0x0 => return Ok(peer),
+        0x1 => {}
+        _ => return Err(anyhow!("unsupported PROXY v2 command")),
+    }
+    match family >> 4 {
+        0x1 if addresses.len() >= 12 => {
+            let ip = Ipv4Addr::new(addresses[0], addresses[1], addresses[2], addresses[3]);
+            let port = u16::from_be_bytes([addresses[8], addresses[9]]);
+            Ok(SocketAddr::new(IpAddr::V4(ip), port))
+        }
+        0x2 if addresses.len() >= 36 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&addresses[..16]);
+            let port = u16::from_be_bytes([addresses[32], addresses[33]]);
+            Ok(SocketAddr::new(IpAddr::V6(Ipv6Addr::from(octets)), port))
+        }
+        // AF_UNSPEC or AF_UNIX: nothing usable, keep the socket peer.
+        0x0 | 0x3 => Ok(peer),
+        _ => Err(anyhow!("truncated PROXY v2 address block")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer() -> SocketAddr {
+        SocketAddr::from(([10, 0, 0, 1], 40_000))
+    }
+
+    #[tokio::test]
+    async fn reads_v1_header_and_leaves_request_bytes() {
+        let mut stream: &[u8] =
+            b"PROXY TCP4 203.0.113.7 10.0.0.2 51234 8080\r\nPOST /webhook/github HTTP/1.1\r\n";
+        let client = read_header(&mut stream, peer()).await.expect("header");
+        assert_eq!(client, "203.0.113.7:51234".parse().expect("addr"));
+        assert!(stream.starts_with(b"POST "));
+
+        let mut unknown: &[u8] = b"PROXY UNKNOWN\r\n";
+        assert_eq!(
+            read_header(&mut unknown, peer()).await.expect("unknown"),
+            peer()
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_v2_ipv6_header() {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend_from_slice(&[0x21, 0x21, 0x00, 36]);
+        header.extend_from_slice(&"2001:db8::7".parse::<Ipv6Addr>().expect("v6").octets());
+        header.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        header.extend_from_slice(&51234u16.to_be_bytes());
+        header.extend_from_slice(&8080u16.to_be_bytes());
+        header.extend_from_slice(b"GET");
+
+        let mut stream = header.as_slice();
+        let client = read_header(&mut stream, peer()).await.expect("header");
+        assert_eq!(client, "[2001:db8::7]:51234".parse().expect("addr"));
+        assert_eq!(stream, b"GET");
+    }
+
+    #[tokio::test]
+    async fn rejects_plain_http() {
+        let mut stream: &[u8] = b"GET /health HTTP/1.1\r\nHost: relay\r\n\r\n";
+        assert!(read_header(&mut stream, peer()).await.is_err());
+    }
+}
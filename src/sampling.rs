@@ -0,0 +1,102 @@
+use crate::config::SamplingRule;
+use crate::digest::activity_scope;
+use serde_json::Value;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// True when a matching rule samples this entity out. The first rule whose
+/// source, event type and scope match decides; `entity_key` keeps the
+/// decision stable for every event about the same entity.
+pub fn sampled_out(
+    rules: &[SamplingRule],
+    source: &str,
+    event_type: &str,
+    payload: &Value,
+    entity_key: &str,
+) -> bool {
+    let scope = activity_scope(source, payload);
+    let scope = scope
+        .as_deref()
+        .and_then(|scope| scope.split_once(':'))
+        .map(|(_, scope)| scope);
+    let Some(rule) = rules.iter().find(|rule| {
+        rule.source == source
+            && rule.event_type == event_type
+            && rule
+                .scope
+                .as_deref()
+                .is_none_or(|wanted| scope == Some(wanted))
+    }) else {
+        return false;
+    };
+    sample_point(entity_key) >= rule.rate
+}
+
+/// Maps the key onto [0, 1) with FNV-1a, which is stable across builds and
+/// restarts unlike the std hasher.
+fn sample_point(key: &str) -> f64 {
+    let hash = key.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(scope: Option<&str>, rate: f64) -> SamplingRule {
+        SamplingRule {
+            source: "github".to_string(),
+            event_type: "issue_comment.created".to_string(),
+            scope: scope.map(str::to_string),
+            rate,
+        }
+    }
+
+    #[test]
+    fn sampling_is_deterministic_and_close_to_rate() {
+        let rules = [rule(None, 0.1)];
+        let payload = json!({"repository":{"full_name":"acme/api"}});
+        let kept = (0..10_000)
+            .filter(|index| {
+                !sampled_out(
+                    &rules,
+                    "github",
+                    "issue_comment.created",
+                    &payload,
+                    &format!("cooldown-github-acme-api-{index}"),
+                )
+            })
+            .count();
+        assert!((800..1_200).contains(&kept), "kept {kept}");
+
+        let first = sampled_out(&rules, "github", "issue_comment.created", &payload, "k");
+        let again = sampled_out(&rules, "github", "issue_comment.created", &payload, "k");
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn scoped_rules_only_apply_to_their_repo() {
+        let rules = [rule(Some("acme/api"), 0.0)];
+        let other = json!({"repository":{"full_name":"acme/web"}});
+        let scoped = json!({"repository":{"full_name":"acme/api"}});
+        assert!(!sampled_out(
+            &rules,
+            "github",
+            "issue_comment.created",
+            &other,
+            "k"
+        ));
+        assert!(sampled_out(
+            &rules,
+            "github",
+            "issue_comment.created",
+            &scoped,
+            "k"
+        ));
+        assert!(!sampled_out(&rules, "github", "push", &scoped, "k"));
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result, anyhow};
+use rhai::{AST, Dynamic, Engine, ImmutableString, Map, Scope};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+const DECISION_KEY: &str = "decision";
+const REASON_KEY: &str = "reason";
+const PRIORITY_KEY: &str = "priority";
+const DECISION_ACCEPT: &str = "accept";
+const DECISION_DROP: &str = "drop";
+const MAX_SCRIPT_OPERATIONS: u64 = 100_000;
+const MAX_SCRIPT_CALL_LEVELS: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptDecision {
+    Accept { priority: Option<i64> },
+    Drop { reason: String },
+}
+
+pub struct EnqueueScript {
+    engine: Engine,
+    ast: AST,
+}
+
+impl EnqueueScript {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let script = fs::read_to_string(path)
+            .with_context(|| format!("read enqueue script {}", path.display()))?;
+        Self::compile(&script).with_context(|| format!("compile enqueue script {}", path.display()))
+    }
+
+    pub fn compile(script: &str) -> Result<Self> {
+        let engine = build_engine();
+        let ast = engine
+            .compile(script)
+            .map_err(|error| anyhow!("invalid enqueue script: {error}"))?;
+        Ok(Self { engine, ast })
+    }
+
+    pub fn evaluate(
+        &self,
+        source: &str,
+        event_type: &str,
+        payload: &Value,
+    ) -> Result<ScriptDecision> {
+        let payload = rhai::serde::to_dynamic(payload)
+            .map_err(|error| anyhow!("convert payload for enqueue script: {error}"))?;
+        let mut scope = Scope::new();
+        scope.push_constant("source", source.to_string());
+        scope.push_constant("event_type", event_type.to_string());
+        scope.push_constant("payload", payload);
+
+        let result = self
+            .engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &self.ast)
+            .map_err(|error| anyhow!("enqueue script failed: {error}"))?;
+        decision_from_dynamic(result)
+    }
+}
+
+fn build_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+    engine.set_max_call_levels(MAX_SCRIPT_CALL_LEVELS);
+    engine.register_fn("accept", || decision_map(DECISION_ACCEPT, None, None));
+    engine.register_fn("drop", |reason: ImmutableString| {
+        decision_map(DECISION_DROP, Some(reason), None)
+    });
+    engine.register_fn("set_priority", |priority: i64| {
+        decision_map(DECISION_ACCEPT, None, Some(priority))
+    });
+    engine
+}
+
+fn decision_map(decision: &str, reason: Option<ImmutableString>, priority: Option<i64>) -> Map {
+    let mut map = Map::new();
+    map.insert(DECISION_KEY.into(), decision.into());
+    if let Some(reason) = reason {
+        map.insert(REASON_KEY.into(), reason.into());
+    }
+    if let Some(priority) = priority {
+        map.insert(PRIORITY_KEY.into(), priority.into());
+    }
+    map
+}
+
+fn decision_from_dynamic(result: Dynamic) -> Result<ScriptDecision> {
+    if result.is_unit() {
+        return Ok(ScriptDecision::Accept { priority: None });
+    }
+
+    let map = result.try_cast::<Map>().ok_or_else(|| {
+        anyhow!("enqueue script must return accept(), drop(reason), or set_priority(n)")
+    })?;
+    let decision = map
+        .get(DECISION_KEY)
+        .and_then(|value| value.clone().into_immutable_string().ok())
+        .ok_or_else(|| anyhow!("enqueue script result is missing a decision"))?;
+
+    match decision.as_str() {
+        DECISION_ACCEPT => Ok(ScriptDecision::Accept {
+            priority: map.get(PRIORITY_KEY).and_then(|value| value.as_int().ok()),
+        }),
+        DECISION_DROP => Ok(ScriptDecision::Drop {
+            reason: map
+                .get(REASON_KEY)
+                .and_then(|value| value.clone().into_immutable_string().ok())
+                .map(|reason| reason.to_string())
+                .unwrap_or_default(),
+        }),
+        other => Err(anyhow!("unsupported enqueue script decision '{other}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EnqueueScript, ScriptDecision};
+    use serde_json::json;
+
+    const SCRIPT: &str = r#"
+        if source == "github" && payload.sender.login == "dependabot[bot]" {
+            drop("bot sender")
+        } else if event_type == "pull_request.opened" {
+            set_priority(10)
+        } else {
+            accept()
+        }
+    "#;
+
+    #[test]
+    fn script_can_drop_accept_and_prioritize() {
+        let script = EnqueueScript::compile(SCRIPT).expect("compile script");
+
+        let bot = json!({"sender":{"login":"dependabot[bot]"}});
+        assert_eq!(
+            script.evaluate("github", "push", &bot).expect("evaluate"),
+            ScriptDecision::Drop {
+                reason: "bot sender".to_string()
+            }
+        );
+
+        let human = json!({"sender":{"login":"octocat"}});
+        assert_eq!(
+            script
+                .evaluate("github", "pull_request.opened", &human)
+                .expect("evaluate"),
+            ScriptDecision::Accept { priority: Some(10) }
+        );
+        assert_eq!(
+            script.evaluate("github", "push", &human).expect("evaluate"),
+            ScriptDecision::Accept { priority: None }
+        );
+    }
+
+    #[test]
+    fn unit_result_accepts_event() {
+        let script = EnqueueScript::compile("let x = 1;").expect("compile script");
+        assert_eq!(
+            script
+                .evaluate("linear", "issue.create", &json!({}))
+                .expect("evaluate"),
+            ScriptDecision::Accept { priority: None }
+        );
+    }
+
+    #[test]
+    fn rejects_unexpected_return_values() {
+        let script = EnqueueScript::compile("42").expect("compile script");
+        assert!(
+            script
+                .evaluate("linear", "issue.create", &json!({}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn runaway_scripts_fail_closed() {
+        let script = EnqueueScript::compile("loop { }").expect("compile script");
+        assert!(
+            script
+                .evaluate("linear", "issue.create", &json!({}))
+                .is_err()
+        );
+    }
+}
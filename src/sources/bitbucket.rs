@@ -0,0 +1,150 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
+use axum::http::HeaderMap;
+use relay_core::keys::{bitbucket_cooldown_key, bitbucket_dedup_key};
+use relay_core::signatures::verify_prefixed_sha256_signature;
+use serde_json::Value;
+
+const BITBUCKET_SOURCE_NAME: &str = "bitbucket";
+const BITBUCKET_SIGNATURE_HEADER: &str = "X-Hub-Signature";
+const BITBUCKET_EVENT_HEADER: &str = "X-Event-Key";
+const BITBUCKET_REQUEST_HEADER: &str = "X-Request-UUID";
+const MISSING_BITBUCKET_SECRET_MESSAGE: &str = "missing bitbucket secret";
+const MISSING_BITBUCKET_SIGNATURE_MESSAGE: &str = "missing bitbucket signature";
+const INVALID_BITBUCKET_SIGNATURE_MESSAGE: &str = "invalid bitbucket signature";
+const MISSING_EVENT_KEY_MESSAGE: &str = "missing X-Event-Key";
+
+/// Bitbucket Cloud repository webhooks with a secret configured.
+#[derive(Debug, Default)]
+pub struct BitbucketSourceHandler;
+
+pub static HANDLER: BitbucketSourceHandler = BitbucketSourceHandler;
+
+impl SourceHandler for BitbucketSourceHandler {
+    fn source_name(&self) -> &'static str {
+        BITBUCKET_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .bitbucket
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(
+                MISSING_BITBUCKET_SECRET_MESSAGE,
+            ))?;
+        validate(secret, headers, body)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn event_type(&self, headers: &HeaderMap, _payload: &Value) -> Result<String, ValidationError> {
+        event_type(headers)
+    }
+
+    fn dedup_key(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let request_id = header_value(headers, BITBUCKET_REQUEST_HEADER)
+            .ok_or(ValidationError::BadRequest("missing X-Request-UUID"))?;
+        let event_type = event_type(headers)?;
+        let entity_id = entity_id(payload).unwrap_or_else(|| "unknown".to_string());
+        Ok(bitbucket_dedup_key(&request_id, &event_type, &entity_id))
+    }
+
+    fn cooldown_key(&self, payload: &Value) -> Option<String> {
+        let repo = payload_token(payload, &["repository", "full_name"])?;
+        let entity_id = entity_id(payload)?;
+        Some(bitbucket_cooldown_key(&repo, &entity_id))
+    }
+}
+
+pub fn validate(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ValidationError> {
+    let signature = header_value(headers, BITBUCKET_SIGNATURE_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_BITBUCKET_SIGNATURE_MESSAGE),
+    )?;
+    if verify_prefixed_sha256_signature(secret, body, &signature) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_BITBUCKET_SIGNATURE_MESSAGE,
+        ))
+    }
+}
+
+/// `X-Event-Key` with `:` swapped for `.`, e.g. `pullrequest.created`.
+pub fn event_type(headers: &HeaderMap) -> Result<String, ValidationError> {
+    header_value(headers, BITBUCKET_EVENT_HEADER)
+        .map(|key| key.to_ascii_lowercase().replace(':', "."))
+        .ok_or(ValidationError::BadRequest(MISSING_EVENT_KEY_MESSAGE))
+}
+
+fn entity_id(payload: &Value) -> Option<String> {
+    payload_token(payload, &["pullrequest", "id"])
+        .map(|id| format!("pr-{id}"))
+        .or_else(|| payload_token(payload, &["issue", "id"]).map(|id| format!("issue-{id}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use relay_core::signatures::compute_hmac_sha256_hex;
+    use serde_json::json;
+
+    #[test]
+    fn validates_sha256_hub_signature() {
+        let body = br#"{"pullrequest":{"id":7}}"#;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            BITBUCKET_SIGNATURE_HEADER,
+            HeaderValue::from_str(&format!(
+                "sha256={}",
+                compute_hmac_sha256_hex("bitbucket-secret", body)
+            ))
+            .expect("signature header"),
+        );
+
+        assert!(validate("bitbucket-secret", &headers, body).is_ok());
+        assert_eq!(
+            validate("other-secret", &headers, body),
+            Err(ValidationError::Unauthorized(
+                INVALID_BITBUCKET_SIGNATURE_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn keys_use_request_uuid_and_pull_request_id() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            BITBUCKET_EVENT_HEADER,
+            HeaderValue::from_static("pullrequest:updated"),
+        );
+        headers.insert(BITBUCKET_REQUEST_HEADER, HeaderValue::from_static("req-1"));
+        let payload = json!({
+            "repository": {"full_name": "acme/relay"},
+            "pullrequest": {"id": 7, "title": "Fix"}
+        });
+
+        assert_eq!(
+            event_type(&headers).expect("event type"),
+            "pullrequest.updated"
+        );
+        assert_eq!(
+            HANDLER.dedup_key(&headers, &payload).expect("dedup key"),
+            "bitbucket:req-1:pullrequest.updated:pr-7"
+        );
+        assert_eq!(
+            HANDLER.cooldown_key(&payload).as_deref(),
+            Some("cooldown-bitbucket-acme-relay-pr-7")
+        );
+        assert_eq!(
+            HANDLER.cooldown_key(&json!({"repository": {"full_name": "acme/relay"}})),
+            None
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{
+    INVALID_JSON_PAYLOAD_MESSAGE, SignatureKind, SourceHandler, ValidationError, header_value,
+    payload_token,
+};
+use axum::http::HeaderMap;
+use relay_core::signatures::verify_sentry_signature;
+use serde_json::{Value, json};
+
+const CI_SOURCE_NAME: &str = "ci";
+const CIRCLECI_SIGNATURE_HEADER: &str = "circleci-signature";
+const CIRCLECI_SIGNATURE_VERSION: &str = "v1=";
+const CIRCLECI_APP_URL: &str = "https://app.circleci.com/pipelines";
+const CIRCLECI: &str = "circleci";
+const GITHUB_ACTIONS: &str = "github_actions";
+const GITHUB_CHECKS: &str = "github_checks";
+const COMPLETED_ACTION: &str = "completed";
+pub const CI_COMPLETED_EVENT: &str = "ci.completed";
+pub const CI_FAILED_EVENT: &str = "ci.failed";
+pub const METADATA_FIELD: &str = "_ci";
+/// Conclusions (GitHub) and statuses (CircleCI) that mean the run broke.
+/// Anything else, including `cancelled`, is a plain completion.
+const FAILED_STATUSES: &[&str] = &[
+    "failure",
+    "failed",
+    "error",
+    "timed_out",
+    "timedout",
+    "infrastructure_fail",
+    "startup_failure",
+];
+const MISSING_CI_SECRET_MESSAGE: &str = "missing ci secret";
+const MISSING_CIRCLECI_SIGNATURE_MESSAGE: &str = "missing circleci signature";
+const INVALID_CIRCLECI_SIGNATURE_MESSAGE: &str = "invalid circleci signature";
+const UNRECOGNIZED_PAYLOAD_MESSAGE: &str = "unrecognized circleci payload";
+const MISSING_EVENT_ID_MESSAGE: &str = "missing circleci event id";
+
+/// CircleCI `workflow-completed` and `job-completed` webhooks, normalized
+/// the same way as completed GitHub `workflow_run` and `check_suite` events:
+/// `ci.completed` or `ci.failed`, with the run's details under `_ci`.
+#[derive(Debug, Default)]
+pub struct CiSourceHandler;
+
+pub static HANDLER: CiSourceHandler = CiSourceHandler;
+
+impl SourceHandler for CiSourceHandler {
+    fn source_name(&self) -> &'static str {
+        CI_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .ci
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(MISSING_CI_SECRET_MESSAGE))?;
+        validate(secret, headers, body)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn parse_payload(&self, _headers: &HeaderMap, body: &[u8]) -> Result<Value, ValidationError> {
+        let mut payload: Value = serde_json::from_slice(body)
+            .map_err(|_| ValidationError::BadRequest(INVALID_JSON_PAYLOAD_MESSAGE))?;
+        let metadata = circleci_metadata(&payload)
+            .ok_or(ValidationError::BadRequest(UNRECOGNIZED_PAYLOAD_MESSAGE))?;
+        let Some(fields) = payload.as_object_mut() else {
+            return Err(ValidationError::BadRequest(UNRECOGNIZED_PAYLOAD_MESSAGE));
+        };
+        fields.insert(METADATA_FIELD.to_string(), metadata);
+        Ok(payload)
+    }
+
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let status = payload_token(payload, &[METADATA_FIELD, "status"])
+            .ok_or(ValidationError::BadRequest(UNRECOGNIZED_PAYLOAD_MESSAGE))?;
+        Ok(event_type_for_status(&status).to_string())
+    }
+
+    fn dedup_key(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let event_id = payload_token(payload, &["id"])
+            .ok_or(ValidationError::BadRequest(MISSING_EVENT_ID_MESSAGE))?;
+        Ok(format!("{CI_SOURCE_NAME}:{CIRCLECI}:{event_id}"))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+}
+
+/// `circleci-signature` may list several `<version>=<hex>` entries; only
+/// `v1` (HMAC-SHA256 of the body) is checked.
+pub fn validate(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ValidationError> {
+    let header = header_value(headers, CIRCLECI_SIGNATURE_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_CIRCLECI_SIGNATURE_MESSAGE),
+    )?;
+    let verified = header
+        .split(',')
+        .filter_map(|entry| entry.trim().strip_prefix(CIRCLECI_SIGNATURE_VERSION))
+        .any(|signature| verify_sentry_signature(secret, body, signature));
+    if verified {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_CIRCLECI_SIGNATURE_MESSAGE,
+        ))
+    }
+}
+
+pub fn event_type_for_status(status: &str) -> &'static str {
+    if FAILED_STATUSES.contains(&status.to_ascii_lowercase().as_str()) {
+        CI_FAILED_EVENT
+    } else {
+        CI_COMPLETED_EVENT
+    }
+}
+
+/// `_ci` for a CircleCI `workflow-completed` or `job-completed` event. Job
+/// events link to the job; workflow events to the workflow.
+pub fn circleci_metadata(payload: &Value) -> Option<Value> {
+    let project = payload_token(payload, &["project", "slug"]);
+    let (name, status, url) = match payload_token(payload, &["type"])?.as_str() {
+        "workflow-completed" => (
+            payload_token(payload, &["workflow", "name"]),
+            payload_token(payload, &["workflow", "status"])?,
+            payload_token(payload, &["workflow", "url"]),
+        ),
+        "job-completed" => {
+            let url = project
+                .as_ref()
+                .zip(payload_token(payload, &["pipeline", "number"]))
+                .zip(payload_token(payload, &["workflow", "id"]))
+                .zip(payload_token(payload, &["job", "number"]))
+                .map(|(((project, pipeline), workflow), job)| {
+                    format!(
+                        "{CIRCLECI_APP_URL}/{project}/{pipeline}/workflows/{workflow}/jobs/{job}"
+                    )
+                });
+            (
+                payload_token(payload, &["job", "name"]),
+                payload_token(payload, &["job", "status"])?,
+                url.or_else(|| payload_token(payload, &["workflow", "url"])),
+            )
+        }
+        _ => return None,
+    };
+    Some(json!({
+        "provider": CIRCLECI,
+        "name": name,
+        "status": status,
+        "url": url,
+        "project": project,
+        "branch": payload_token(payload, &["pipeline", "vcs", "branch"]),
+        "commit": payload_token(payload, &["pipeline", "vcs", "revision"]),
+    }))
+}
+
+/// `_ci` for a completed GitHub `workflow_run` or `check_suite`; `None` for
+/// every other event and action.
+pub fn github_metadata(event_name: &str, payload: &Value) -> Option<Value> {
+    if payload_token(payload, &["action"]).as_deref() != Some(COMPLETED_ACTION) {
+        return None;
+    }
+    let repository = payload_token(payload, &["repository", "full_name"]);
+    let (provider, run, name, url) = match event_name {
+        "workflow_run" => {
+            let run = payload.get("workflow_run")?;
+            (
+                GITHUB_ACTIONS,
+                run,
+                payload_token(run, &["name"]),
+                payload_token(run, &["html_url"]),
+            )
+        }
+        "check_suite" => {
+            let suite = payload.get("check_suite")?;
+            let url = payload_token(payload, &["repository", "html_url"])
+                .zip(payload_token(suite, &["head_sha"]))
+                .zip(payload_token(suite, &["id"]))
+                .map(|((repository_url, sha), id)| {
+                    format!("{repository_url}/commit/{sha}/checks?check_suite_id={id}")
+                });
+            (
+                GITHUB_CHECKS,
+                suite,
+                payload_token(suite, &["app", "name"]),
+                url,
+            )
+        }
+        _ => return None,
+    };
+    Some(json!({
+        "provider": provider,
+        "name": name,
+        "status": payload_token(run, &["conclusion"])?,
+        "url": url,
+        "project": repository,
+        "branch": payload_token(run, &["head_branch"]),
+        "commit": payload_token(run, &["head_sha"]),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use relay_core::signatures::compute_hmac_sha256_hex;
+
+    fn job_completed() -> Value {
+        json!({
+            "type": "job-completed",
+            "id": "3888f21b-eaa7-38e3-8f3d-75a63bba8895",
+            "project": {"slug": "gh/acme/api"},
+            "pipeline": {"number": 130, "vcs": {"branch": "main", "revision": "1285fe1d"}},
+            "workflow": {"id": "fda08377-fe7e-46b1-8992-3a7aaecac9c3", "name": "build-test", "url": "https://app.circleci.com/pipelines/gh/acme/api/130/workflows/fda08377-fe7e-46b1-8992-3a7aaecac9c3"},
+            "job": {"name": "test", "number": 136, "status": "failed"}
+        })
+    }
+
+    #[test]
+    fn validates_v1_signature_among_versions() {
+        let body = job_completed().to_string();
+        let signature = compute_hmac_sha256_hex("ci-secret", body.as_bytes());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CIRCLECI_SIGNATURE_HEADER,
+            HeaderValue::from_str(&format!("v2=unused,v1={signature}")).expect("header"),
+        );
+
+        assert!(validate("ci-secret", &headers, body.as_bytes()).is_ok());
+        assert_eq!(
+            validate("other-secret", &headers, body.as_bytes()),
+            Err(ValidationError::Unauthorized(
+                INVALID_CIRCLECI_SIGNATURE_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn failed_circleci_job_links_to_the_job() {
+        let payload = HANDLER
+            .parse_payload(&HeaderMap::new(), job_completed().to_string().as_bytes())
+            .expect("parse payload");
+
+        assert_eq!(
+            HANDLER
+                .event_type(&HeaderMap::new(), &payload)
+                .expect("event type"),
+            "ci.failed"
+        );
+        assert_eq!(payload["_ci"]["status"], "failed");
+        assert_eq!(
+            payload["_ci"]["url"],
+            "https://app.circleci.com/pipelines/gh/acme/api/130/workflows/fda08377-fe7e-46b1-8992-3a7aaecac9c3/jobs/136"
+        );
+        assert_eq!(
+            HANDLER
+                .dedup_key(&HeaderMap::new(), &payload)
+                .expect("dedup key"),
+            "ci:circleci:3888f21b-eaa7-38e3-8f3d-75a63bba8895"
+        );
+    }
+
+    #[test]
+    fn normalizes_completed_github_runs_only() {
+        let workflow_run = json!({
+            "action": "completed",
+            "repository": {"full_name": "acme/api"},
+            "workflow_run": {
+                "name": "CI",
+                "conclusion": "success",
+                "html_url": "https://github.com/acme/api/actions/runs/42",
+                "head_branch": "main",
+                "head_sha": "1285fe1d"
+            }
+        });
+        let metadata = github_metadata("workflow_run", &workflow_run).expect("metadata");
+        assert_eq!(metadata["provider"], "github_actions");
+        assert_eq!(
+            metadata["url"],
+            "https://github.com/acme/api/actions/runs/42"
+        );
+        assert_eq!(event_type_for_status("success"), "ci.completed");
+        assert_eq!(event_type_for_status("timed_out"), "ci.failed");
+        assert_eq!(event_type_for_status("cancelled"), "ci.completed");
+
+        let mut requested = workflow_run.clone();
+        requested["action"] = json!("requested");
+        assert_eq!(github_metadata("workflow_run", &requested), None);
+        assert_eq!(github_metadata("workflow_job", &workflow_run), None);
+
+        let check_suite = json!({
+            "action": "completed",
+            "repository": {"full_name": "acme/api", "html_url": "https://github.com/acme/api"},
+            "check_suite": {"id": 7, "conclusion": "failure", "head_sha": "1285fe1d", "app": {"name": "Buildkite"}}
+        });
+        let metadata = github_metadata("check_suite", &check_suite).expect("metadata");
+        assert_eq!(
+            metadata["url"],
+            "https://github.com/acme/api/commit/1285fe1d/checks?check_suite_id=7"
+        );
+        assert_eq!(metadata["name"], "Buildkite");
+    }
+}
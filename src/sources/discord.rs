@@ -0,0 +1,165 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
+use axum::http::HeaderMap;
+use relay_core::signatures::verify_ed25519_signature;
+use serde_json::{Value, json};
+
+const DISCORD_SOURCE_NAME: &str = "discord";
+const DISCORD_SIGNATURE_HEADER: &str = "X-Signature-Ed25519";
+const DISCORD_TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+const MISSING_DISCORD_PUBLIC_KEY_MESSAGE: &str = "missing discord public key";
+const MISSING_DISCORD_SIGNATURE_MESSAGE: &str = "missing discord signature";
+const MISSING_DISCORD_TIMESTAMP_MESSAGE: &str = "missing discord signature timestamp";
+const INVALID_DISCORD_SIGNATURE_MESSAGE: &str = "invalid discord signature";
+const MISSING_INTERACTION_TYPE_MESSAGE: &str = "missing discord interaction type";
+const MISSING_INTERACTION_ID_MESSAGE: &str = "missing discord interaction id";
+const PING_INTERACTION_TYPE: u64 = 1;
+
+/// Discord interactions endpoint. Discord signs `timestamp + body` with the
+/// application's Ed25519 key and probes the endpoint with PINGs, which serve
+/// answers itself.
+#[derive(Debug, Default)]
+pub struct DiscordSourceHandler;
+
+pub static HANDLER: DiscordSourceHandler = DiscordSourceHandler;
+
+impl SourceHandler for DiscordSourceHandler {
+    fn source_name(&self) -> &'static str {
+        DISCORD_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let public_key = secrets
+            .discord
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(
+                MISSING_DISCORD_PUBLIC_KEY_MESSAGE,
+            ))?;
+        validate(public_key, headers, body)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn reply(&self, payload: &Value) -> Option<Value> {
+        (payload.get("type").and_then(Value::as_u64) == Some(PING_INTERACTION_TYPE))
+            .then(|| json!({"type": PING_INTERACTION_TYPE}))
+    }
+
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        event_type(payload)
+    }
+
+    fn dedup_key(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let interaction_id = payload_token(payload, &["id"])
+            .ok_or(ValidationError::BadRequest(MISSING_INTERACTION_ID_MESSAGE))?;
+        Ok(format!("{DISCORD_SOURCE_NAME}:{interaction_id}"))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+}
+
+pub fn validate(public_key: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ValidationError> {
+    let signature = header_value(headers, DISCORD_SIGNATURE_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_DISCORD_SIGNATURE_MESSAGE),
+    )?;
+    let timestamp = header_value(headers, DISCORD_TIMESTAMP_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_DISCORD_TIMESTAMP_MESSAGE),
+    )?;
+    let mut message = Vec::with_capacity(timestamp.len() + body.len());
+    message.extend_from_slice(timestamp.as_bytes());
+    message.extend_from_slice(body);
+    if verify_ed25519_signature(public_key, &message, &signature) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_DISCORD_SIGNATURE_MESSAGE,
+        ))
+    }
+}
+
+/// The interaction type's name, suffixed with the command name for slash
+/// commands and autocomplete, e.g. `application_command.deploy`.
+pub fn event_type(payload: &Value) -> Result<String, ValidationError> {
+    let kind = match payload.get("type").and_then(Value::as_u64) {
+        Some(1) => "ping",
+        Some(2) => "application_command",
+        Some(3) => "message_component",
+        Some(4) => "application_command_autocomplete",
+        Some(5) => "modal_submit",
+        Some(_) => "interaction",
+        None => {
+            return Err(ValidationError::BadRequest(
+                MISSING_INTERACTION_TYPE_MESSAGE,
+            ));
+        }
+    };
+    match payload_token(payload, &["data", "name"]) {
+        Some(name) => Ok(format!("{kind}.{}", name.to_ascii_lowercase())),
+        None => Ok(kind.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    #[test]
+    fn validates_ed25519_signature_over_timestamp_and_body() {
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&[3; 32]).expect("key pair");
+        let public_key = hex::encode(key_pair.public_key().as_ref());
+        let body = br#"{"id":"1","type":1}"#;
+        let signature = key_pair.sign(&[b"1700000000".as_slice(), body].concat());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            DISCORD_SIGNATURE_HEADER,
+            HeaderValue::from_str(&hex::encode(signature.as_ref())).expect("signature header"),
+        );
+        headers.insert(
+            DISCORD_TIMESTAMP_HEADER,
+            HeaderValue::from_static("1700000000"),
+        );
+
+        assert!(validate(&public_key, &headers, body).is_ok());
+        headers.insert(
+            DISCORD_TIMESTAMP_HEADER,
+            HeaderValue::from_static("1700000001"),
+        );
+        assert_eq!(
+            validate(&public_key, &headers, body),
+            Err(ValidationError::Unauthorized(
+                INVALID_DISCORD_SIGNATURE_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn answers_pings_and_names_commands() {
+        assert_eq!(
+            HANDLER.reply(&json!({"id": "1", "type": 1})),
+            Some(json!({"type": 1}))
+        );
+
+        let command = json!({"id": "2", "type": 2, "data": {"name": "Deploy"}});
+        assert_eq!(HANDLER.reply(&command), None);
+        assert_eq!(
+            event_type(&command).expect("event type"),
+            "application_command.deploy"
+        );
+        assert_eq!(
+            HANDLER
+                .dedup_key(&HeaderMap::new(), &command)
+                .expect("dedup key"),
+            "discord:2"
+        );
+    }
+}
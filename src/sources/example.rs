@@ -1,5 +1,5 @@
-use crate::config::Config;
-use crate::sources::{SourceHandler, ValidationError, header_value, payload_token};
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
 use axum::http::HeaderMap;
 use relay_core::signatures::verify_shared_token;
 use serde_json::Value;
@@ -28,17 +28,20 @@ impl SourceHandler for ExampleSourceHandler {
 
     fn validate_request(
         &self,
-        config: &Config,
+        _config: &Config,
+        secrets: &SourceSecrets,
         headers: &HeaderMap,
         body: &[u8],
-    ) -> Result<(), ValidationError> {
-        let secret = config
-            .hmac_secret_example
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .example
             .as_deref()
             .ok_or(ValidationError::Unauthorized(
                 MISSING_EXAMPLE_SECRET_MESSAGE,
             ))?;
-        validate(secret, headers, body)
+        validate(secret, headers, body)?;
+        Ok(SignatureKind::Primary)
     }
 
     fn event_type(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
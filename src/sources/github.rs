@@ -1,13 +1,23 @@
-use crate::config::Config;
-use crate::sources::{SourceHandler, ValidationError, header_value, payload_token};
-use axum::http::HeaderMap;
+use crate::config::{Config, SourceSecrets};
+use crate::github_app::ENRICHMENT_FIELD;
+use crate::sources::{
+    INVALID_JSON_PAYLOAD_MESSAGE, SignatureKind, SourceHandler, SourceServices, ValidationError,
+    ci, header_value, payload_token,
+};
+use anyhow::Result;
+use axum::http::{HeaderMap, header};
+use futures_util::future::BoxFuture;
 use relay_core::keys::{github_cooldown_key, github_dedup_key};
-use relay_core::signatures::verify_github_signature;
+use relay_core::signatures::{verify_github_sha1_signature, verify_github_signature};
 use serde_json::Value;
 
 const GITHUB_SIGNATURE_HEADER: &str = "X-Hub-Signature-256";
+const GITHUB_SHA1_SIGNATURE_HEADER: &str = "X-Hub-Signature";
 const GITHUB_EVENT_HEADER: &str = "X-GitHub-Event";
 const GITHUB_DELIVERY_HEADER: &str = "X-GitHub-Delivery";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+const FORM_PAYLOAD_FIELD: &str = "payload";
+const MISSING_FORM_PAYLOAD_MESSAGE: &str = "missing payload form field";
 const UNKNOWN_ACTION: &str = "unknown";
 const GITHUB_SOURCE_NAME: &str = "github";
 const MISSING_GITHUB_SECRET_MESSAGE: &str = "missing github secret";
@@ -25,14 +35,30 @@ impl SourceHandler for GithubSourceHandler {
     fn validate_request(
         &self,
         config: &Config,
+        secrets: &SourceSecrets,
         headers: &HeaderMap,
         body: &[u8],
-    ) -> Result<(), ValidationError> {
-        let secret = config
-            .hmac_secret_github
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .github
             .as_deref()
             .ok_or(ValidationError::Unauthorized(MISSING_GITHUB_SECRET_MESSAGE))?;
-        validate(secret, headers, body)
+        validate(secret, config.github_allow_sha1, headers, body)
+    }
+
+    /// Completed `workflow_run` and `check_suite` events also get `_ci`, the
+    /// same summary the `ci` source publishes for CircleCI.
+    fn parse_payload(&self, headers: &HeaderMap, body: &[u8]) -> Result<Value, ValidationError> {
+        let mut payload = parse_payload(headers, body)?;
+        let event_name = header_string(headers, GITHUB_EVENT_HEADER).unwrap_or_default();
+        if let (Some(metadata), Some(fields)) = (
+            ci::github_metadata(&event_name, &payload),
+            payload.as_object_mut(),
+        ) {
+            fields.insert(ci::METADATA_FIELD.to_string(), metadata);
+        }
+        Ok(payload)
     }
 
     fn event_type(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
@@ -53,23 +79,86 @@ impl SourceHandler for GithubSourceHandler {
         let entity_id = entity_id_for_cooldown(payload)?;
         Some(github_cooldown_key(&repo, &entity_id))
     }
+
+    /// With a GitHub App configured, attaches a pull request's changed files
+    /// under `_enrichment`.
+    fn enrich<'a>(
+        &'a self,
+        services: &'a SourceServices,
+        payload: &'a mut Value,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let Some(github_app) = &services.github_app else {
+                return Ok(());
+            };
+            if let Some(enrichment) = github_app.pull_request_enrichment(payload).await?
+                && let Some(fields) = payload.as_object_mut()
+            {
+                fields.insert(ENRICHMENT_FIELD.to_string(), enrichment);
+            }
+            Ok(())
+        })
+    }
 }
 
-pub fn validate(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ValidationError> {
-    let signature = header_string(headers, GITHUB_SIGNATURE_HEADER)
-        .ok_or(ValidationError::Unauthorized("missing github signature"))?;
+/// SHA1 is only consulted when allowed and the SHA256 header is absent, so a
+/// bad SHA256 signature can never be rescued by a SHA1 one.
+pub fn validate(
+    secret: &str,
+    allow_sha1: bool,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<SignatureKind, ValidationError> {
+    if let Some(signature) = header_string(headers, GITHUB_SIGNATURE_HEADER) {
+        return if verify_github_signature(secret, body, &signature) {
+            Ok(SignatureKind::Primary)
+        } else {
+            Err(ValidationError::Unauthorized("invalid github signature"))
+        };
+    }
 
-    if verify_github_signature(secret, body, &signature) {
-        Ok(())
+    let legacy = allow_sha1
+        .then(|| header_string(headers, GITHUB_SHA1_SIGNATURE_HEADER))
+        .flatten()
+        .ok_or(ValidationError::Unauthorized("missing github signature"))?;
+    if verify_github_sha1_signature(secret, body, &legacy) {
+        Ok(SignatureKind::LegacySha1)
     } else {
         Err(ValidationError::Unauthorized("invalid github signature"))
     }
 }
 
+/// Hooks configured with the form content type send the JSON document in a
+/// `payload=` field.
+pub fn parse_payload(headers: &HeaderMap, body: &[u8]) -> Result<Value, ValidationError> {
+    let is_form = header_string(headers, header::CONTENT_TYPE.as_str()).is_some_and(|value| {
+        value
+            .split(';')
+            .next()
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case(FORM_CONTENT_TYPE))
+    });
+    let json = if is_form {
+        let field = form_urlencoded::parse(body)
+            .find(|(name, _)| name == FORM_PAYLOAD_FIELD)
+            .map(|(_, value)| value.into_owned())
+            .ok_or(ValidationError::BadRequest(MISSING_FORM_PAYLOAD_MESSAGE))?;
+        serde_json::from_str(&field)
+    } else {
+        serde_json::from_slice(body)
+    };
+    json.map_err(|_| ValidationError::BadRequest(INVALID_JSON_PAYLOAD_MESSAGE))
+}
+
 pub fn event_type(headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
     let event_name = header_string(headers, GITHUB_EVENT_HEADER)
         .ok_or(ValidationError::BadRequest("missing X-GitHub-Event"))?;
 
+    if let Some(status) = ci::github_metadata(&event_name, payload)
+        .and_then(|metadata| payload_token(&metadata, &["status"]))
+    {
+        return Ok(ci::event_type_for_status(&status).to_string());
+    }
+
     let action = payload
         .get("action")
         .and_then(Value::as_str)
@@ -96,6 +185,8 @@ fn entity_id(payload: &Value) -> String {
     entity_id_for_cooldown(payload)
         .or_else(|| payload_token(payload, &["comment", "id"]))
         .or_else(|| payload_token(payload, &["review", "id"]))
+        .or_else(|| payload_token(payload, &["workflow_run", "id"]))
+        .or_else(|| payload_token(payload, &["check_suite", "id"]))
         .or_else(|| payload_token(payload, &["repository", "id"]))
         .unwrap_or_else(|| "unknown".to_string())
 }
@@ -110,7 +201,7 @@ fn entity_id_for_cooldown(payload: &Value) -> Option<String> {
 mod tests {
     use super::*;
     use axum::http::{HeaderMap, HeaderValue};
-    use relay_core::signatures::compute_hmac_sha256_hex;
+    use relay_core::signatures::{compute_hmac_sha1_hex, compute_hmac_sha256_hex};
     use serde_json::json;
 
     #[test]
@@ -125,8 +216,61 @@ mod tests {
             HeaderValue::from_str(&format!("sha256={digest}")).expect("valid signature header"),
         );
 
-        assert!(validate(secret, &headers, body).is_ok());
-        assert!(validate("wrong", &headers, body).is_err());
+        assert_eq!(
+            validate(secret, false, &headers, body),
+            Ok(SignatureKind::Primary)
+        );
+        assert!(validate("wrong", false, &headers, body).is_err());
+    }
+
+    #[test]
+    fn parses_form_encoded_payload_field() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded; charset=utf-8"),
+        );
+        let body = b"payload=%7B%22action%22%3A%22opened%22%7D";
+
+        assert_eq!(
+            parse_payload(&headers, body),
+            Ok(json!({"action":"opened"}))
+        );
+        assert_eq!(
+            parse_payload(&headers, b"other=1"),
+            Err(ValidationError::BadRequest(MISSING_FORM_PAYLOAD_MESSAGE))
+        );
+        assert_eq!(
+            parse_payload(&HeaderMap::new(), br#"{"action":"opened"}"#),
+            Ok(json!({"action":"opened"}))
+        );
+    }
+
+    #[test]
+    fn sha1_fallback_is_opt_in_and_never_overrides_sha256() {
+        let secret = "github-secret";
+        let body = br#"{"action":"opened"}"#;
+        let sha1 = format!("sha1={}", compute_hmac_sha1_hex(secret, body));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            GITHUB_SHA1_SIGNATURE_HEADER,
+            HeaderValue::from_str(&sha1).expect("valid signature header"),
+        );
+        assert_eq!(
+            validate(secret, false, &headers, body),
+            Err(ValidationError::Unauthorized("missing github signature"))
+        );
+        assert_eq!(
+            validate(secret, true, &headers, body),
+            Ok(SignatureKind::LegacySha1)
+        );
+
+        headers.insert(
+            GITHUB_SIGNATURE_HEADER,
+            HeaderValue::from_static("sha256=deadbeef"),
+        );
+        assert!(validate(secret, true, &headers, body).is_err());
     }
 
     #[test]
@@ -144,6 +288,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn completed_workflow_runs_become_ci_events() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            GITHUB_EVENT_HEADER,
+            HeaderValue::from_static("workflow_run"),
+        );
+
+        let failed = json!({"action":"completed","workflow_run":{"conclusion":"failure"}});
+        assert_eq!(
+            event_type(&headers, &failed).expect("event type"),
+            "ci.failed"
+        );
+        let in_progress = json!({"action":"in_progress","workflow_run":{"conclusion":null}});
+        assert_eq!(
+            event_type(&headers, &in_progress).expect("event type"),
+            "workflow_run.in_progress"
+        );
+    }
+
     #[test]
     fn accepts_arbitrary_event_and_action_values() {
         let mut headers = HeaderMap::new();
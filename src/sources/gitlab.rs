@@ -0,0 +1,159 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
+use axum::http::HeaderMap;
+use relay_core::keys::{gitlab_cooldown_key, gitlab_dedup_key};
+use relay_core::signatures::verify_bearer_token;
+use serde_json::Value;
+
+const GITLAB_SOURCE_NAME: &str = "gitlab";
+const GITLAB_TOKEN_HEADER: &str = "X-Gitlab-Token";
+const GITLAB_EVENT_UUID_HEADER: &str = "X-Gitlab-Event-UUID";
+const MISSING_GITLAB_SECRET_MESSAGE: &str = "missing gitlab secret";
+const MISSING_GITLAB_TOKEN_MESSAGE: &str = "missing gitlab token";
+const INVALID_GITLAB_TOKEN_MESSAGE: &str = "invalid gitlab token";
+const MISSING_OBJECT_KIND_MESSAGE: &str = "missing gitlab object_kind";
+const UNKNOWN_ACTION: &str = "unknown";
+
+/// GitLab project and group webhooks. GitLab does not sign payloads; it
+/// echoes the configured secret token in `X-Gitlab-Token`.
+#[derive(Debug, Default)]
+pub struct GitlabSourceHandler;
+
+pub static HANDLER: GitlabSourceHandler = GitlabSourceHandler;
+
+impl SourceHandler for GitlabSourceHandler {
+    fn source_name(&self) -> &'static str {
+        GITLAB_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        _body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .gitlab
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(MISSING_GITLAB_SECRET_MESSAGE))?;
+        validate(secret, headers)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        event_type(payload)
+    }
+
+    fn dedup_key(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let event_uuid = header_value(headers, GITLAB_EVENT_UUID_HEADER)
+            .ok_or(ValidationError::BadRequest("missing X-Gitlab-Event-UUID"))?;
+        let action = action(payload).unwrap_or_else(|| UNKNOWN_ACTION.to_string());
+        let entity_id = entity_id(payload).unwrap_or_else(|| "unknown".to_string());
+        Ok(gitlab_dedup_key(&event_uuid, &action, &entity_id))
+    }
+
+    fn cooldown_key(&self, payload: &Value) -> Option<String> {
+        let project = payload_token(payload, &["project", "path_with_namespace"])?;
+        let entity_id = entity_id(payload)?;
+        Some(gitlab_cooldown_key(&project, &entity_id))
+    }
+}
+
+pub fn validate(secret: &str, headers: &HeaderMap) -> Result<(), ValidationError> {
+    let token = header_value(headers, GITLAB_TOKEN_HEADER)
+        .ok_or(ValidationError::Unauthorized(MISSING_GITLAB_TOKEN_MESSAGE))?;
+    if verify_bearer_token(secret, &token) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(INVALID_GITLAB_TOKEN_MESSAGE))
+    }
+}
+
+/// `object_kind`, suffixed with `object_attributes.action` when GitLab sends
+/// one, e.g. `merge_request.open` or `push`.
+pub fn event_type(payload: &Value) -> Result<String, ValidationError> {
+    let object_kind = payload_token(payload, &["object_kind"])
+        .ok_or(ValidationError::BadRequest(MISSING_OBJECT_KIND_MESSAGE))?
+        .to_ascii_lowercase();
+    match action(payload) {
+        Some(action) => Ok(format!("{object_kind}.{}", action.to_ascii_lowercase())),
+        None => Ok(object_kind),
+    }
+}
+
+fn action(payload: &Value) -> Option<String> {
+    payload_token(payload, &["object_attributes", "action"])
+}
+
+/// Merge requests and issues are keyed by their project-scoped `iid`; other
+/// objects (notes, pipelines) by id, and pushes by the resulting commit.
+fn entity_id(payload: &Value) -> Option<String> {
+    payload_token(payload, &["object_attributes", "iid"])
+        .or_else(|| payload_token(payload, &["merge_request", "iid"]))
+        .or_else(|| payload_token(payload, &["issue", "iid"]))
+        .or_else(|| payload_token(payload, &["object_attributes", "id"]))
+        .or_else(|| payload_token(payload, &["checkout_sha"]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    #[test]
+    fn validates_secret_token() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            GITLAB_TOKEN_HEADER,
+            HeaderValue::from_static("gitlab-secret"),
+        );
+
+        assert!(validate("gitlab-secret", &headers).is_ok());
+        assert_eq!(
+            validate("other-secret", &headers),
+            Err(ValidationError::Unauthorized(INVALID_GITLAB_TOKEN_MESSAGE))
+        );
+        assert_eq!(
+            validate("gitlab-secret", &HeaderMap::new()),
+            Err(ValidationError::Unauthorized(MISSING_GITLAB_TOKEN_MESSAGE))
+        );
+    }
+
+    #[test]
+    fn event_type_joins_object_kind_and_action() {
+        let merge_request =
+            json!({"object_kind":"merge_request","object_attributes":{"action":"open"}});
+        assert_eq!(
+            event_type(&merge_request).expect("event type"),
+            "merge_request.open"
+        );
+        assert_eq!(
+            event_type(&json!({"object_kind":"push"})).expect("event type"),
+            "push"
+        );
+        assert!(event_type(&json!({"event_name":"push"})).is_err());
+    }
+
+    #[test]
+    fn keys_use_event_uuid_project_and_iid() {
+        let mut headers = HeaderMap::new();
+        headers.insert(GITLAB_EVENT_UUID_HEADER, HeaderValue::from_static("uuid-1"));
+        let payload = json!({
+            "object_kind": "merge_request",
+            "project": {"path_with_namespace": "acme/relay"},
+            "object_attributes": {"id": 901, "iid": 12, "action": "update"}
+        });
+
+        assert_eq!(
+            HANDLER.dedup_key(&headers, &payload).expect("dedup key"),
+            "gitlab:uuid-1:update:12"
+        );
+        assert_eq!(
+            HANDLER.cooldown_key(&payload).as_deref(),
+            Some("cooldown-gitlab-acme-relay-12")
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::pubsub::decode_message_data;
+use crate::sources::{
+    INVALID_JSON_PAYLOAD_MESSAGE, QUERY_TOKEN_HEADER, SignatureKind, SourceHandler,
+    ValidationError, header_value, payload_token,
+};
+use axum::http::HeaderMap;
+use relay_core::sanitize::sanitize_gmail_payload;
+use relay_core::signatures::verify_bearer_token;
+use serde_json::{Value, json};
+
+const GMAIL_SOURCE_NAME: &str = "gmail";
+const GMAIL_EVENT_TYPE: &str = "mailbox.changed";
+const MISSING_GMAIL_SECRET_MESSAGE: &str = "missing gmail secret";
+const MISSING_GMAIL_TOKEN_MESSAGE: &str = "missing gmail token";
+const INVALID_GMAIL_TOKEN_MESSAGE: &str = "invalid gmail token";
+const INVALID_MESSAGE_DATA_MESSAGE: &str = "invalid pubsub message data";
+const MISSING_HISTORY_ID_MESSAGE: &str = "missing gmail historyId";
+
+/// Gmail `users.watch` notifications, delivered by a Pub/Sub push
+/// subscription whose endpoint URL carries `?token=<HMAC_SECRET_GMAIL>`.
+#[derive(Debug, Default)]
+pub struct GmailSourceHandler;
+
+pub static HANDLER: GmailSourceHandler = GmailSourceHandler;
+
+impl SourceHandler for GmailSourceHandler {
+    fn source_name(&self) -> &'static str {
+        GMAIL_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        _body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .gmail
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(MISSING_GMAIL_SECRET_MESSAGE))?;
+        validate(secret, headers)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    /// Unwraps the Pub/Sub envelope into the decoded notification plus the
+    /// message id and publish time.
+    fn parse_payload(&self, _headers: &HeaderMap, body: &[u8]) -> Result<Value, ValidationError> {
+        let envelope: Value = serde_json::from_slice(body)
+            .map_err(|_| ValidationError::BadRequest(INVALID_JSON_PAYLOAD_MESSAGE))?;
+        decode_push(&envelope)
+    }
+
+    fn event_type(
+        &self,
+        _headers: &HeaderMap,
+        _payload: &Value,
+    ) -> Result<String, ValidationError> {
+        Ok(GMAIL_EVENT_TYPE.to_string())
+    }
+
+    fn dedup_key(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let history_id = payload_token(payload, &["historyId"])
+            .ok_or(ValidationError::BadRequest(MISSING_HISTORY_ID_MESSAGE))?;
+        let mailbox =
+            payload_token(payload, &["emailAddress"]).unwrap_or_else(|| "unknown".to_string());
+        Ok(format!("{GMAIL_SOURCE_NAME}:{mailbox}:{history_id}"))
+    }
+
+    /// A burst of changes to one mailbox cools down together; the consumer
+    /// reads history from the first id anyway.
+    fn cooldown_key(&self, payload: &Value) -> Option<String> {
+        payload_token(payload, &["emailAddress"])
+            .map(|mailbox| format!("cooldown-{GMAIL_SOURCE_NAME}-{mailbox}"))
+    }
+
+    fn sanitize(&self, payload: &Value) -> Result<Value, String> {
+        sanitize_gmail_payload(payload)
+    }
+}
+
+pub fn validate(secret: &str, headers: &HeaderMap) -> Result<(), ValidationError> {
+    let token = header_value(headers, QUERY_TOKEN_HEADER)
+        .ok_or(ValidationError::Unauthorized(MISSING_GMAIL_TOKEN_MESSAGE))?;
+    if verify_bearer_token(secret, &token) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(INVALID_GMAIL_TOKEN_MESSAGE))
+    }
+}
+
+pub fn decode_push(envelope: &Value) -> Result<Value, ValidationError> {
+    let mut notification = decode_message_data(envelope)?;
+    let Some(fields) = notification.as_object_mut() else {
+        return Err(ValidationError::BadRequest(INVALID_MESSAGE_DATA_MESSAGE));
+    };
+    for (key, pointer) in [
+        ("messageId", "/message/messageId"),
+        ("publishTime", "/message/publishTime"),
+    ] {
+        if let Some(value) = envelope.pointer(pointer) {
+            fields.insert(key.to_string(), value.clone());
+        }
+    }
+    Ok(json!(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+
+    #[test]
+    fn validates_query_token() {
+        let mut headers = HeaderMap::new();
+        headers.insert(QUERY_TOKEN_HEADER, HeaderValue::from_static("gmail-secret"));
+
+        assert!(validate("gmail-secret", &headers).is_ok());
+        assert_eq!(
+            validate("other-secret", &headers),
+            Err(ValidationError::Unauthorized(INVALID_GMAIL_TOKEN_MESSAGE))
+        );
+        assert_eq!(
+            validate("gmail-secret", &HeaderMap::new()),
+            Err(ValidationError::Unauthorized(MISSING_GMAIL_TOKEN_MESSAGE))
+        );
+    }
+
+    #[test]
+    fn decodes_push_envelope_and_keys_on_history_id() {
+        let data = BASE64.encode(br#"{"emailAddress":"ops@example.com","historyId":9876543210}"#);
+        let envelope = json!({
+            "message": {"data": data, "messageId": "136969346945", "publishTime": "2026-10-15T09:00:00Z"},
+            "subscription": "projects/acme/subscriptions/gmail-push"
+        });
+
+        let payload = decode_push(&envelope).expect("decode push");
+        assert_eq!(payload["emailAddress"], "ops@example.com");
+        assert_eq!(payload["messageId"], "136969346945");
+        assert_eq!(
+            HANDLER
+                .dedup_key(&HeaderMap::new(), &payload)
+                .expect("dedup key"),
+            "gmail:ops@example.com:9876543210"
+        );
+        assert_eq!(
+            HANDLER.cooldown_key(&payload).as_deref(),
+            Some("cooldown-gmail-ops@example.com")
+        );
+        assert!(decode_push(&json!({"message": {"data": "%%%"}})).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
+use axum::http::HeaderMap;
+use relay_core::keys::content_dedup_key;
+use relay_core::signatures::verify_bearer_token;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const INTERNAL_SOURCE_NAME: &str = "internal";
+const DEFAULT_EVENT_TYPE: &str = "webhook";
+const NO_INTERNAL_TOKENS_MESSAGE: &str = "missing internal secret";
+const MISSING_BEARER_TOKEN_MESSAGE: &str = "missing internal bearer token";
+const INVALID_BEARER_TOKEN_MESSAGE: &str = "invalid internal bearer token";
+
+/// Plain JSON from in-house services, authenticated by a bearer token per
+/// producer in `RELAY_INTERNAL_TOKENS`. Envelopes name the producer as
+/// `internal:<producer>`.
+#[derive(Debug, Default)]
+pub struct InternalSourceHandler;
+
+pub static HANDLER: InternalSourceHandler = InternalSourceHandler;
+
+impl SourceHandler for InternalSourceHandler {
+    fn source_name(&self) -> &'static str {
+        INTERNAL_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        _body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        if secrets.internal.is_empty() {
+            return Err(ValidationError::Unauthorized(NO_INTERNAL_TOKENS_MESSAGE));
+        }
+        let token = bearer_token(headers)
+            .ok_or(ValidationError::Unauthorized(MISSING_BEARER_TOKEN_MESSAGE))?;
+        producer_for_token(&secrets.internal, &token)
+            .ok_or(ValidationError::Unauthorized(INVALID_BEARER_TOKEN_MESSAGE))?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        Ok(payload_token(payload, &["type"])
+            .or_else(|| payload_token(payload, &["event"]))
+            .unwrap_or_else(|| DEFAULT_EVENT_TYPE.to_string()))
+    }
+
+    /// Key for a request whose producer is unknown; the pipeline uses
+    /// `sender_dedup_key`.
+    fn dedup_key(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        Ok(producer_dedup_key(INTERNAL_SOURCE_NAME, payload))
+    }
+
+    /// Scoped to the producer, so two producers may reuse the same ids.
+    fn sender_dedup_key(
+        &self,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        payload: &Value,
+    ) -> Result<String, ValidationError> {
+        let source = self
+            .envelope_source(secrets, headers)
+            .ok_or(ValidationError::Unauthorized(INVALID_BEARER_TOKEN_MESSAGE))?;
+        Ok(producer_dedup_key(&source, payload))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+
+    fn envelope_source(&self, secrets: &SourceSecrets, headers: &HeaderMap) -> Option<String> {
+        let producer = producer_for_token(&secrets.internal, &bearer_token(headers)?)?;
+        Some(format!("{INTERNAL_SOURCE_NAME}:{producer}"))
+    }
+}
+
+/// The payload `id` when the producer sends one, else a content hash.
+fn producer_dedup_key(source: &str, payload: &Value) -> String {
+    match payload_token(payload, &["id"]) {
+        Some(id) => format!("{source}:{id}"),
+        None => content_dedup_key(source, payload),
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    header_value(headers, "Authorization")?
+        .strip_prefix("Bearer ")
+        .map(|token| token.trim().to_string())
+}
+
+/// Compares against every producer's token so the match position does not
+/// show in timing.
+pub fn producer_for_token<'a>(
+    tokens: &'a BTreeMap<String, String>,
+    token: &str,
+) -> Option<&'a str> {
+    tokens.iter().fold(None, |matched, (producer, expected)| {
+        if verify_bearer_token(expected, token) {
+            Some(producer.as_str())
+        } else {
+            matched
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn secrets() -> SourceSecrets {
+        SourceSecrets {
+            internal: BTreeMap::from([
+                ("billing".to_string(), "billing-token".to_string()),
+                ("deploy-bot".to_string(), "deploy-token".to_string()),
+            ]),
+            ..SourceSecrets::default()
+        }
+    }
+
+    fn headers(authorization: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("Authorization", HeaderValue::from_static(authorization));
+        headers
+    }
+
+    #[test]
+    fn identifies_producer_by_bearer_token() {
+        assert_eq!(
+            HANDLER
+                .envelope_source(&secrets(), &headers("Bearer deploy-token"))
+                .as_deref(),
+            Some("internal:deploy-bot")
+        );
+        assert_eq!(
+            HANDLER.envelope_source(&secrets(), &headers("Bearer other-token")),
+            None
+        );
+        assert_eq!(
+            producer_for_token(&secrets().internal, "billing-token"),
+            Some("billing")
+        );
+        assert_eq!(producer_for_token(&secrets().internal, "billing"), None);
+    }
+
+    #[test]
+    fn event_type_and_dedup_follow_custom_source_conventions() {
+        let payload = serde_json::json!({"type": "invoice.paid", "id": "inv-7"});
+        assert_eq!(
+            HANDLER
+                .event_type(&HeaderMap::new(), &payload)
+                .expect("event type"),
+            "invoice.paid"
+        );
+        assert_eq!(
+            HANDLER
+                .dedup_key(&HeaderMap::new(), &payload)
+                .expect("dedup key"),
+            "internal:inv-7"
+        );
+        assert_eq!(
+            HANDLER
+                .event_type(&HeaderMap::new(), &serde_json::json!({}))
+                .expect("event type"),
+            "webhook"
+        );
+    }
+
+    #[test]
+    fn producers_reusing_an_id_do_not_dedup_each_other() {
+        let payload = serde_json::json!({"type": "invoice.paid", "id": "1"});
+        let billing = HANDLER
+            .sender_dedup_key(&secrets(), &headers("Bearer billing-token"), &payload)
+            .expect("billing key");
+        let deploy = HANDLER
+            .sender_dedup_key(&secrets(), &headers("Bearer deploy-token"), &payload)
+            .expect("deploy key");
+        assert_eq!(billing, "internal:billing:1");
+        assert_eq!(deploy, "internal:deploy-bot:1");
+
+        let without_id = serde_json::json!({"type": "deploy.finished"});
+        assert_ne!(
+            HANDLER
+                .sender_dedup_key(&secrets(), &headers("Bearer billing-token"), &without_id)
+                .expect("billing hash"),
+            HANDLER
+                .sender_dedup_key(&secrets(), &headers("Bearer deploy-token"), &without_id)
+                .expect("deploy hash")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
+use axum::http::HeaderMap;
+use relay_core::keys::{jira_cooldown_key, jira_dedup_key};
+use relay_core::sanitize::sanitize_jira_payload;
+use relay_core::signatures::verify_prefixed_sha256_signature;
+use serde_json::Value;
+
+const JIRA_SOURCE_NAME: &str = "jira";
+const JIRA_SIGNATURE_HEADER: &str = "X-Hub-Signature";
+const JIRA_WEBHOOK_ID_HEADER: &str = "X-Atlassian-Webhook-Identifier";
+const JIRA_EVENT_PREFIX: &str = "jira:";
+const MISSING_JIRA_SECRET_MESSAGE: &str = "missing jira secret";
+const MISSING_JIRA_SIGNATURE_MESSAGE: &str = "missing jira signature";
+const INVALID_JIRA_SIGNATURE_MESSAGE: &str = "invalid jira signature";
+const MISSING_WEBHOOK_EVENT_MESSAGE: &str = "missing jira webhookEvent";
+
+/// Jira Cloud admin webhooks with a secret, which Jira uses to sign each
+/// delivery.
+#[derive(Debug, Default)]
+pub struct JiraSourceHandler;
+
+pub static HANDLER: JiraSourceHandler = JiraSourceHandler;
+
+impl SourceHandler for JiraSourceHandler {
+    fn source_name(&self) -> &'static str {
+        JIRA_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .jira
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(MISSING_JIRA_SECRET_MESSAGE))?;
+        validate(secret, headers, body)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        event_type(payload)
+    }
+
+    fn dedup_key(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let webhook_id = header_value(headers, JIRA_WEBHOOK_ID_HEADER).ok_or(
+            ValidationError::BadRequest("missing X-Atlassian-Webhook-Identifier"),
+        )?;
+        let event_type = event_type(payload)?;
+        let issue_key =
+            payload_token(payload, &["issue", "key"]).unwrap_or_else(|| "unknown".to_string());
+        Ok(jira_dedup_key(&webhook_id, &event_type, &issue_key))
+    }
+
+    fn cooldown_key(&self, payload: &Value) -> Option<String> {
+        payload_token(payload, &["issue", "key"]).map(|issue_key| jira_cooldown_key(&issue_key))
+    }
+
+    fn sanitize(&self, payload: &Value) -> Result<Value, String> {
+        sanitize_jira_payload(payload)
+    }
+}
+
+pub fn validate(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ValidationError> {
+    let signature = header_value(headers, JIRA_SIGNATURE_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_JIRA_SIGNATURE_MESSAGE),
+    )?;
+    if verify_prefixed_sha256_signature(secret, body, &signature) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_JIRA_SIGNATURE_MESSAGE,
+        ))
+    }
+}
+
+/// `webhookEvent` without the `jira:` prefix, suffixed with
+/// `issue_event_type_name` when present, e.g. `issue_updated.issue_assigned`.
+pub fn event_type(payload: &Value) -> Result<String, ValidationError> {
+    let webhook_event = payload_token(payload, &["webhookEvent"])
+        .ok_or(ValidationError::BadRequest(MISSING_WEBHOOK_EVENT_MESSAGE))?
+        .to_ascii_lowercase();
+    let webhook_event = webhook_event
+        .strip_prefix(JIRA_EVENT_PREFIX)
+        .unwrap_or(&webhook_event);
+    match payload_token(payload, &["issue_event_type_name"]) {
+        Some(name) => Ok(format!("{webhook_event}.{}", name.to_ascii_lowercase())),
+        None => Ok(webhook_event.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use relay_core::signatures::compute_hmac_sha256_hex;
+    use serde_json::json;
+
+    #[test]
+    fn validates_secret_signature() {
+        let body = br#"{"webhookEvent":"jira:issue_created"}"#;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            JIRA_SIGNATURE_HEADER,
+            HeaderValue::from_str(&format!(
+                "sha256={}",
+                compute_hmac_sha256_hex("jira-secret", body)
+            ))
+            .expect("signature header"),
+        );
+
+        assert!(validate("jira-secret", &headers, body).is_ok());
+        assert_eq!(
+            validate("jira-secret", &headers, br#"{"webhookEvent":"other"}"#),
+            Err(ValidationError::Unauthorized(
+                INVALID_JIRA_SIGNATURE_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn event_type_combines_webhook_event_and_issue_event_name() {
+        let assigned = json!({
+            "webhookEvent": "jira:issue_updated",
+            "issue_event_type_name": "issue_assigned"
+        });
+        assert_eq!(
+            event_type(&assigned).expect("event type"),
+            "issue_updated.issue_assigned"
+        );
+        assert_eq!(
+            event_type(&json!({"webhookEvent": "comment_created"})).expect("event type"),
+            "comment_created"
+        );
+    }
+
+    #[test]
+    fn keys_use_webhook_identifier_and_issue_key() {
+        let mut headers = HeaderMap::new();
+        headers.insert(JIRA_WEBHOOK_ID_HEADER, HeaderValue::from_static("wh-1"));
+        let payload = json!({"webhookEvent": "jira:issue_created", "issue": {"key": "ENG-42"}});
+
+        assert_eq!(
+            HANDLER.dedup_key(&headers, &payload).expect("dedup key"),
+            "jira:wh-1:issue_created:ENG-42"
+        );
+        assert_eq!(
+            HANDLER.cooldown_key(&payload).as_deref(),
+            Some("cooldown-jira-ENG-42")
+        );
+    }
+}
@@ -1,16 +1,47 @@
-use crate::config::Config;
-use crate::sources::{SourceHandler, ValidationError, header_value, payload_token};
+use crate::config::{Config, LinearStateTransition, SourceSecrets};
+use crate::sources::{
+    ConfirmError, SignatureKind, SourceHandler, ValidationError, header_value, payload_token,
+};
+use anyhow::{Context, Result, anyhow};
 use axum::http::HeaderMap;
+use futures_util::future::BoxFuture;
 use relay_core::keys::{linear_cooldown_key, linear_dedup_key};
 use relay_core::signatures::verify_linear_signature;
 use relay_core::timestamps::verify_linear_timestamp_window;
-use serde_json::Value;
+use serde_json::{Value, json};
+use std::sync::LazyLock;
+use std::time::Duration;
 
 const LINEAR_SIGNATURE_HEADER: &str = "Linear-Signature";
 const LINEAR_DELIVERY_HEADER: &str = "Linear-Delivery";
 const UNKNOWN_ACTION: &str = "unknown";
 const LINEAR_SOURCE_NAME: &str = "linear";
 const MISSING_LINEAR_SECRET_MESSAGE: &str = "missing linear secret";
+const ISSUE_UPDATE_EVENT_TYPE: &str = "issue.update";
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+const LINEAR_API_TIMEOUT: Duration = Duration::from_secs(10);
+const UNCONFIRMED_MESSAGE: &str = "linear delivery not confirmed by the linear api";
+const API_UNAVAILABLE_MESSAGE: &str = "linear api unavailable";
+const REMOVE_ACTION: &str = "remove";
+/// Webhook `type` values and the GraphQL query that fetches one by id.
+const API_ENTITY_QUERIES: &[(&str, &str)] = &[
+    ("Issue", "issue"),
+    ("Comment", "comment"),
+    ("IssueLabel", "issueLabel"),
+    ("Project", "project"),
+    ("ProjectUpdate", "projectUpdate"),
+    ("Cycle", "cycle"),
+    ("Reaction", "reaction"),
+    ("Document", "document"),
+    ("Attachment", "attachment"),
+];
+
+static API_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(LINEAR_API_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
 
 #[derive(Debug, Default)]
 pub struct LinearSourceHandler;
@@ -24,15 +55,18 @@ impl SourceHandler for LinearSourceHandler {
 
     fn validate_request(
         &self,
-        config: &Config,
+        _config: &Config,
+        secrets: &SourceSecrets,
         headers: &HeaderMap,
         body: &[u8],
-    ) -> Result<(), ValidationError> {
-        let secret = config
-            .hmac_secret_linear
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .linear
             .as_deref()
             .ok_or(ValidationError::Unauthorized(MISSING_LINEAR_SECRET_MESSAGE))?;
-        validate(secret, headers, body)
+        validate(secret, headers, body)?;
+        Ok(SignatureKind::Primary)
     }
 
     fn validate_payload(
@@ -55,10 +89,40 @@ impl SourceHandler for LinearSourceHandler {
         }
     }
 
+    /// With `RELAY_LINEAR_VERIFY_WITH_API`, asks the Linear API whether the
+    /// delivery's entity exists.
+    fn confirm<'a>(
+        &'a self,
+        config: &'a Config,
+        payload: &'a Value,
+    ) -> BoxFuture<'a, Result<(), ConfirmError>> {
+        Box::pin(async move {
+            let Some(api_token) = config
+                .linear_api_token
+                .as_deref()
+                .filter(|_| config.linear_verify_with_api)
+            else {
+                return Ok(());
+            };
+            match confirm_with_api(api_token, payload).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(ConfirmError::Unconfirmed(UNCONFIRMED_MESSAGE)),
+                Err(error) => Err(ConfirmError::Unavailable(API_UNAVAILABLE_MESSAGE, error)),
+            }
+        })
+    }
+
     fn event_type(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
         event_type(headers, payload)
     }
 
+    fn should_forward(&self, config: &Config, event_type: &str, payload: &Value) -> bool {
+        if config.linear_state_transitions.is_empty() || event_type != ISSUE_UPDATE_EVENT_TYPE {
+            return true;
+        }
+        state_transition_allowed(&config.linear_state_transitions, payload)
+    }
+
     fn dedup_key(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
         let delivery_id = header_value(headers, LINEAR_DELIVERY_HEADER)
             .ok_or(ValidationError::BadRequest("missing Linear-Delivery"))?;
@@ -113,6 +177,34 @@ pub fn event_type(headers: &HeaderMap, payload: &Value) -> Result<String, Valida
     }
 }
 
+pub fn state_transition_allowed(rules: &[LinearStateTransition], payload: &Value) -> bool {
+    let Some(from_state) = payload_token(payload, &["updatedFrom", "stateId"]) else {
+        return false;
+    };
+    let to_states = [
+        payload_token(payload, &["data", "state", "id"]),
+        payload_token(payload, &["data", "stateId"]),
+        payload_token(payload, &["data", "state", "name"]),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+
+    rules.iter().any(|rule| {
+        state_matches(rule.from.as_deref(), std::slice::from_ref(&from_state))
+            && state_matches(rule.to.as_deref(), &to_states)
+    })
+}
+
+fn state_matches(selector: Option<&str>, states: &[String]) -> bool {
+    match selector {
+        None => !states.is_empty(),
+        Some(selector) => states
+            .iter()
+            .any(|state| state.eq_ignore_ascii_case(selector)),
+    }
+}
+
 fn header_string(headers: &HeaderMap, name: &str) -> Option<String> {
     headers
         .get(name)
@@ -133,6 +225,55 @@ fn entity_id_for_cooldown(payload: &Value) -> Option<String> {
         .or_else(|| payload_token(payload, &["data", "identifier"]))
 }
 
+/// The GraphQL query field and entity id to confirm a delivery with, or
+/// `None` when it cannot be looked up: removals (the entity is gone) and
+/// types without a by-id query.
+pub fn api_lookup(payload: &Value) -> Option<(&'static str, String)> {
+    if payload_token(payload, &["action"]).as_deref() == Some(REMOVE_ACTION) {
+        return None;
+    }
+    let linear_type = payload_token(payload, &["type"])?;
+    let (_, field) = API_ENTITY_QUERIES
+        .iter()
+        .find(|(name, _)| *name == linear_type)?;
+    Some((field, payload_token(payload, &["data", "id"])?))
+}
+
+/// Asks the Linear API whether the delivery's entity exists. `Ok(false)`
+/// means Linear does not know it; `Err` means the API could not be asked.
+pub async fn confirm_with_api(api_token: &str, payload: &Value) -> Result<bool> {
+    let Some((field, id)) = api_lookup(payload) else {
+        return Ok(true);
+    };
+    let response = API_CLIENT
+        .post(LINEAR_API_URL)
+        .header("Authorization", api_token)
+        .json(&json!({
+            "query": format!("query($id: String!) {{ {field}(id: $id) {{ id }} }}"),
+            "variables": {"id": id},
+        }))
+        .send()
+        .await
+        .context("query linear api")?;
+    if response.status().is_server_error() || response.status().as_u16() == 429 {
+        return Err(anyhow!("linear api returned {}", response.status()));
+    }
+    if response.status().as_u16() == 401 {
+        return Err(anyhow!("linear api rejected RELAY_LINEAR_API_TOKEN"));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .context("decode linear api response")?;
+    Ok(entity_confirmed(&body, field, &id))
+}
+
+fn entity_confirmed(body: &Value, field: &str, id: &str) -> bool {
+    body.pointer(&format!("/data/{field}/id"))
+        .and_then(Value::as_str)
+        == Some(id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,4 +399,69 @@ mod tests {
             Some("cooldown-linear-ENG-issue-42")
         );
     }
+
+    fn transition(from: Option<&str>, to: Option<&str>) -> LinearStateTransition {
+        LinearStateTransition {
+            from: from.map(ToString::to_string),
+            to: to.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn allows_transition_into_matching_state_name() {
+        let rules = [transition(None, Some("In Progress"))];
+        let payload = json!({
+            "data":{"stateId":"state-doing","state":{"id":"state-doing","name":"In Progress"}},
+            "updatedFrom":{"stateId":"state-todo"}
+        });
+        assert!(state_transition_allowed(&rules, &payload));
+    }
+
+    #[test]
+    fn matches_from_and_to_state_ids() {
+        let rules = [transition(Some("state-todo"), Some("state-done"))];
+        let payload = json!({
+            "data":{"stateId":"state-done"},
+            "updatedFrom":{"stateId":"state-todo"}
+        });
+        assert!(state_transition_allowed(&rules, &payload));
+
+        let other_origin = json!({
+            "data":{"stateId":"state-done"},
+            "updatedFrom":{"stateId":"state-backlog"}
+        });
+        assert!(!state_transition_allowed(&rules, &other_origin));
+    }
+
+    #[test]
+    fn rejects_updates_without_state_change() {
+        let rules = [transition(None, None)];
+        let payload = json!({
+            "data":{"stateId":"state-todo","state":{"name":"Todo"}},
+            "updatedFrom":{"title":"old title"}
+        });
+        assert!(!state_transition_allowed(&rules, &payload));
+    }
+
+    #[test]
+    fn api_lookup_skips_removals_and_unknown_types() {
+        let update = json!({"type": "Issue", "action": "update", "data": {"id": "issue-1"}});
+        assert_eq!(api_lookup(&update), Some(("issue", "issue-1".to_string())));
+        let removed = json!({"type": "Comment", "action": "remove", "data": {"id": "comment-1"}});
+        assert_eq!(api_lookup(&removed), None);
+        let unknown =
+            json!({"type": "AppUserNotification", "action": "create", "data": {"id": "n-1"}});
+        assert_eq!(api_lookup(&unknown), None);
+
+        assert!(entity_confirmed(
+            &json!({"data": {"issue": {"id": "issue-1"}}}),
+            "issue",
+            "issue-1"
+        ));
+        assert!(!entity_confirmed(
+            &json!({"data": null, "errors": [{"message": "Entity not found"}]}),
+            "issue",
+            "issue-1"
+        ));
+    }
 }
@@ -1,12 +1,37 @@
-use crate::config::Config;
+use crate::config::{Config, SourceSecrets};
+use crate::github_app::GithubApp;
+use anyhow::{Context, Result};
 use axum::http::HeaderMap;
+use futures_util::future::BoxFuture;
+use relay_core::sanitize::sanitize_payload;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
+pub mod bitbucket;
+pub mod ci;
+pub mod custom;
+pub mod discord;
 pub mod example;
 pub mod github;
+pub mod gitlab;
+pub mod gmail;
+pub mod internal;
+pub mod jira;
 pub mod linear;
+pub mod pubsub;
+pub mod registry;
+pub mod sentry;
+pub mod shopify;
+pub mod sns;
+pub mod standard_webhooks;
+pub mod stripe;
+
+pub(crate) const INVALID_JSON_PAYLOAD_MESSAGE: &str = "invalid json payload";
+/// Carries a `?token=` query parameter to sources whose provider can only
+/// authenticate through the URL. Serve always overwrites it, so a client
+/// cannot set it directly.
+pub(crate) const QUERY_TOKEN_HEADER: &str = "x-relay-query-token";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ValidationError {
@@ -14,6 +39,36 @@ pub enum ValidationError {
     BadRequest(&'static str),
 }
 
+/// Clients built once at startup and shared by source hooks.
+#[derive(Default)]
+pub struct SourceServices {
+    pub github_app: Option<GithubApp>,
+}
+
+impl SourceServices {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self {
+            github_app: GithubApp::from_config(config).context("load github app")?,
+        })
+    }
+}
+
+/// Why `SourceHandler::confirm` refused a request.
+#[derive(Debug)]
+pub enum ConfirmError {
+    /// The provider doesn't know the event; answered `401`.
+    Unconfirmed(&'static str),
+    /// The provider couldn't be asked; answered `503` so the sender retries.
+    Unavailable(&'static str, anyhow::Error),
+}
+
+/// Which signature scheme accepted a request; legacy schemes are metered apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureKind {
+    Primary,
+    LegacySha1,
+}
+
 pub trait SourceHandler: Sync {
     fn source_name(&self) -> &'static str;
 
@@ -21,12 +76,31 @@ pub trait SourceHandler: Sync {
         config.source_topic_name(self.source_name())
     }
 
+    /// Async work `validate_request` depends on, e.g. fetching a signing
+    /// certificate it reads from a cache. Runs before authentication.
+    fn prepare<'a>(
+        &'a self,
+        _config: &'a Config,
+        _body: &'a [u8],
+        _now_epoch_seconds: i64,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(std::future::ready(()))
+    }
+
     fn validate_request(
         &self,
         config: &Config,
+        secrets: &SourceSecrets,
         headers: &HeaderMap,
         body: &[u8],
-    ) -> Result<(), ValidationError>;
+        now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError>;
+
+    /// Runs after signature verification, which always covers the raw body.
+    fn parse_payload(&self, _headers: &HeaderMap, body: &[u8]) -> Result<Value, ValidationError> {
+        serde_json::from_slice(body)
+            .map_err(|_| ValidationError::BadRequest(INVALID_JSON_PAYLOAD_MESSAGE))
+    }
 
     fn validate_payload(
         &self,
@@ -37,11 +111,63 @@ pub trait SourceHandler: Sync {
         Ok(())
     }
 
+    /// Async checks after `validate_payload`, e.g. asking the provider's API
+    /// whether the event is real.
+    fn confirm<'a>(
+        &'a self,
+        _config: &'a Config,
+        _payload: &'a Value,
+    ) -> BoxFuture<'a, Result<(), ConfirmError>> {
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    /// Answers a verified request directly instead of publishing it, e.g. a
+    /// provider's endpoint handshake.
+    fn reply(&self, _payload: &Value) -> Option<Value> {
+        None
+    }
+
     fn event_type(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError>;
 
+    fn should_forward(&self, _config: &Config, _event_type: &str, _payload: &Value) -> bool {
+        true
+    }
+
     fn dedup_key(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError>;
 
+    /// The dedup key the pipeline uses. Override when it depends on who sent
+    /// the request, resolved from the secrets like `envelope_source`.
+    fn sender_dedup_key(
+        &self,
+        _secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        payload: &Value,
+    ) -> Result<String, ValidationError> {
+        self.dedup_key(headers, payload)
+    }
+
     fn cooldown_key(&self, payload: &Value) -> Option<String>;
+
+    /// Adds provider data to a payload about to be published. Runs before
+    /// `sanitize`, so additions get the same scan. Best effort: an error is
+    /// logged and the event goes out without it.
+    fn enrich<'a>(
+        &'a self,
+        _services: &'a SourceServices,
+        _payload: &'a mut Value,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn sanitize(&self, payload: &Value) -> Result<Value, String> {
+        sanitize_payload(self.source_name(), payload)
+    }
+
+    /// The envelope's `source`, when it should say more than the source
+    /// name, e.g. which producer sent the request.
+    fn envelope_source(&self, _secrets: &SourceSecrets, _headers: &HeaderMap) -> Option<String> {
+        None
+    }
 }
 
 static SOURCE_HANDLERS: LazyLock<HashMap<&'static str, &'static dyn SourceHandler>> =
@@ -49,7 +175,24 @@ static SOURCE_HANDLERS: LazyLock<HashMap<&'static str, &'static dyn SourceHandle
         let mut handlers: HashMap<&'static str, &'static dyn SourceHandler> = HashMap::new();
         handlers.insert(example::HANDLER.source_name(), &example::HANDLER);
         handlers.insert(github::HANDLER.source_name(), &github::HANDLER);
+        handlers.insert(gitlab::HANDLER.source_name(), &gitlab::HANDLER);
+        handlers.insert(bitbucket::HANDLER.source_name(), &bitbucket::HANDLER);
+        handlers.insert(jira::HANDLER.source_name(), &jira::HANDLER);
+        handlers.insert(stripe::HANDLER.source_name(), &stripe::HANDLER);
+        handlers.insert(sentry::HANDLER.source_name(), &sentry::HANDLER);
+        handlers.insert(gmail::HANDLER.source_name(), &gmail::HANDLER);
+        handlers.insert(shopify::HANDLER.source_name(), &shopify::HANDLER);
+        handlers.insert(registry::HANDLER.source_name(), &registry::HANDLER);
+        handlers.insert(ci::HANDLER.source_name(), &ci::HANDLER);
+        handlers.insert(internal::HANDLER.source_name(), &internal::HANDLER);
+        handlers.insert(pubsub::HANDLER.source_name(), &pubsub::HANDLER);
+        handlers.insert(sns::HANDLER.source_name(), &sns::HANDLER);
+        handlers.insert(discord::HANDLER.source_name(), &discord::HANDLER);
         handlers.insert(linear::HANDLER.source_name(), &linear::HANDLER);
+        handlers.insert(
+            standard_webhooks::HANDLER.source_name(),
+            &standard_webhooks::HANDLER,
+        );
         handlers
     });
 
@@ -58,6 +201,19 @@ pub fn handler_for_source(source: &str) -> Option<&'static dyn SourceHandler> {
     SOURCE_HANDLERS.get(normalized.as_str()).copied()
 }
 
+/// Built-in handler for `source`, or the handler for a source declared in
+/// `RELAY_CUSTOM_SOURCES`.
+pub fn handler_for(config: &Config, source: &str) -> Option<&'static dyn SourceHandler> {
+    let normalized = normalize_source_name(source)?;
+    if let Some(handler) = SOURCE_HANDLERS.get(normalized.as_str()) {
+        return Some(*handler);
+    }
+    config
+        .custom_sources
+        .contains_key(&normalized)
+        .then(|| custom::handler(&normalized) as &'static dyn SourceHandler)
+}
+
 pub fn has_handler(source: &str) -> bool {
     handler_for_source(source).is_some()
 }
@@ -108,7 +264,8 @@ pub(crate) fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{known_source_names, normalize_source_name};
+    use super::{handler_for_source, known_source_names, normalize_source_name};
+    use serde_json::json;
 
     #[test]
     fn normalizes_source_names() {
@@ -121,6 +278,30 @@ mod tests {
         let names = known_source_names();
         assert!(names.contains(&"example"));
         assert!(names.contains(&"github"));
+        assert!(names.contains(&"gitlab"));
+        assert!(names.contains(&"bitbucket"));
+        assert!(names.contains(&"jira"));
+        assert!(names.contains(&"stripe"));
+        assert!(names.contains(&"sentry"));
+        assert!(names.contains(&"gmail"));
+        assert!(names.contains(&"shopify"));
+        assert!(names.contains(&"registry"));
+        assert!(names.contains(&"ci"));
+        assert!(names.contains(&"internal"));
+        assert!(names.contains(&"pubsub"));
+        assert!(names.contains(&"sns"));
+        assert!(names.contains(&"discord"));
         assert!(names.contains(&"linear"));
+        assert!(names.contains(&"standard-webhooks"));
+    }
+
+    #[test]
+    fn default_sanitize_profile_marks_payload() {
+        let handler = handler_for_source("example").expect("example handler");
+        let sanitized = handler
+            .sanitize(&json!({"title":"hello"}))
+            .expect("sanitize example payload");
+        assert_eq!(sanitized["_sanitized"], json!(true));
+        assert_eq!(sanitized["title"], json!("hello"));
     }
 }
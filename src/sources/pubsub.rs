@@ -0,0 +1,299 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{
+    INVALID_JSON_PAYLOAD_MESSAGE, SignatureKind, SourceHandler, ValidationError, header_value,
+    payload_token,
+};
+use anyhow::{Context, Result, anyhow};
+use axum::http::HeaderMap;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use relay_core::jwt::{Jwk, JwkSet, verify_rs256_jwt};
+use serde_json::{Value, json};
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+const PUBSUB_SOURCE_NAME: &str = "pubsub";
+const DEFAULT_EVENT_TYPE: &str = "message";
+const METADATA_FIELD: &str = "_pubsub";
+const GOOGLE_ISSUERS: &[&str] = &["https://accounts.google.com", "accounts.google.com"];
+/// Allowed clock skew on `exp` and `iat`.
+const CLOCK_SKEW_SECONDS: i64 = 60;
+/// Google rotates its signing keys every few days and publishes new ones well
+/// before first use, so hourly is plenty.
+const KEY_REFRESH_INTERVAL: Duration = Duration::from_secs(3_600);
+const KEY_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+const KEY_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const MISSING_BEARER_TOKEN_MESSAGE: &str = "missing pubsub bearer token";
+const KEYS_NOT_LOADED_MESSAGE: &str = "google signing keys not loaded";
+const INVALID_TOKEN_MESSAGE: &str = "invalid pubsub token";
+const WRONG_ISSUER_MESSAGE: &str = "pubsub token has the wrong issuer";
+const WRONG_AUDIENCE_MESSAGE: &str = "pubsub token has the wrong audience";
+const EXPIRED_TOKEN_MESSAGE: &str = "pubsub token outside its validity timestamp";
+const WRONG_SERVICE_ACCOUNT_MESSAGE: &str = "pubsub token from the wrong service account";
+const MISSING_MESSAGE_DATA_MESSAGE: &str = "missing pubsub message data";
+const INVALID_MESSAGE_DATA_MESSAGE: &str = "invalid pubsub message data";
+const MISSING_MESSAGE_ID_MESSAGE: &str = "missing pubsub messageId";
+
+static GOOGLE_KEYS: LazyLock<RwLock<Vec<Jwk>>> = LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Pub/Sub push subscriptions with authentication enabled. Google signs an
+/// OIDC token for the subscription's service account and sends it as a
+/// bearer token; the inner message is published, not the push envelope.
+#[derive(Debug, Default)]
+pub struct PubsubSourceHandler;
+
+pub static HANDLER: PubsubSourceHandler = PubsubSourceHandler;
+
+impl SourceHandler for PubsubSourceHandler {
+    fn source_name(&self) -> &'static str {
+        PUBSUB_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        config: &Config,
+        _secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        _body: &[u8],
+        now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let token = header_value(headers, "Authorization")
+            .and_then(|value| value.strip_prefix("Bearer ").map(str::to_string))
+            .ok_or(ValidationError::Unauthorized(MISSING_BEARER_TOKEN_MESSAGE))?;
+        let keys = GOOGLE_KEYS
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if keys.is_empty() {
+            return Err(ValidationError::Unauthorized(KEYS_NOT_LOADED_MESSAGE));
+        }
+        let claims = verify_rs256_jwt(&token, &keys)
+            .ok_or(ValidationError::Unauthorized(INVALID_TOKEN_MESSAGE))?;
+        let audience = config
+            .pubsub_audience
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(WRONG_AUDIENCE_MESSAGE))?;
+        let service_account = config
+            .pubsub_service_account
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(WRONG_SERVICE_ACCOUNT_MESSAGE))?;
+        validate_claims(&claims, audience, service_account, now_epoch_seconds)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn parse_payload(&self, _headers: &HeaderMap, body: &[u8]) -> Result<Value, ValidationError> {
+        let envelope: Value = serde_json::from_slice(body)
+            .map_err(|_| ValidationError::BadRequest(INVALID_JSON_PAYLOAD_MESSAGE))?;
+        let mut payload = decode_message_data(&envelope)?;
+        let Some(fields) = payload.as_object_mut() else {
+            return Err(ValidationError::BadRequest(INVALID_MESSAGE_DATA_MESSAGE));
+        };
+        fields.insert(
+            METADATA_FIELD.to_string(),
+            json!({
+                "messageId": envelope.pointer("/message/messageId"),
+                "publishTime": envelope.pointer("/message/publishTime"),
+                "attributes": envelope.pointer("/message/attributes"),
+                "subscription": envelope.get("subscription"),
+            }),
+        );
+        Ok(payload)
+    }
+
+    /// The `eventType` message attribute (set by Cloud Storage and most
+    /// Google producers), else the payload `type`, else `message`.
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        Ok(
+            payload_token(payload, &[METADATA_FIELD, "attributes", "eventType"])
+                .or_else(|| payload_token(payload, &["type"]))
+                .unwrap_or_else(|| DEFAULT_EVENT_TYPE.to_string()),
+        )
+    }
+
+    fn dedup_key(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let message_id = payload_token(payload, &[METADATA_FIELD, "messageId"])
+            .ok_or(ValidationError::BadRequest(MISSING_MESSAGE_ID_MESSAGE))?;
+        let subscription = payload_token(payload, &[METADATA_FIELD, "subscription"])
+            .unwrap_or_else(|| "unknown".to_string());
+        Ok(format!("{PUBSUB_SOURCE_NAME}:{subscription}:{message_id}"))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+}
+
+pub fn validate_claims(
+    claims: &Value,
+    audience: &str,
+    service_account: &str,
+    now_epoch_seconds: i64,
+) -> Result<(), ValidationError> {
+    let claim = |name: &str| claims.get(name).and_then(Value::as_str);
+    if !claim("iss").is_some_and(|issuer| GOOGLE_ISSUERS.contains(&issuer)) {
+        return Err(ValidationError::Unauthorized(WRONG_ISSUER_MESSAGE));
+    }
+    if claim("aud") != Some(audience) {
+        return Err(ValidationError::Unauthorized(WRONG_AUDIENCE_MESSAGE));
+    }
+    let expires_at = claims.get("exp").and_then(Value::as_i64).unwrap_or(0);
+    let issued_at = claims
+        .get("iat")
+        .and_then(Value::as_i64)
+        .unwrap_or(i64::MAX);
+    if expires_at + CLOCK_SKEW_SECONDS < now_epoch_seconds
+        || issued_at - CLOCK_SKEW_SECONDS > now_epoch_seconds
+    {
+        return Err(ValidationError::Unauthorized(EXPIRED_TOKEN_MESSAGE));
+    }
+    let verified = claims.get("email_verified").and_then(Value::as_bool) == Some(true);
+    if !verified || claim("email") != Some(service_account) {
+        return Err(ValidationError::Unauthorized(WRONG_SERVICE_ACCOUNT_MESSAGE));
+    }
+    Ok(())
+}
+
+/// Base64-decodes `message.data` of a push envelope as JSON.
+pub fn decode_message_data(envelope: &Value) -> Result<Value, ValidationError> {
+    let data = envelope
+        .pointer("/message/data")
+        .and_then(Value::as_str)
+        .ok_or(ValidationError::BadRequest(MISSING_MESSAGE_DATA_MESSAGE))?;
+    let decoded = BASE64
+        .decode(data.trim())
+        .map_err(|_| ValidationError::BadRequest(INVALID_MESSAGE_DATA_MESSAGE))?;
+    serde_json::from_slice(&decoded)
+        .map_err(|_| ValidationError::BadRequest(INVALID_MESSAGE_DATA_MESSAGE))
+}
+
+/// Replaces the cached Google signing keys.
+pub fn set_google_keys(keys: Vec<Jwk>) {
+    *GOOGLE_KEYS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = keys;
+}
+
+async fn fetch_google_keys(client: &reqwest::Client, url: &str) -> Result<Vec<Jwk>> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .context("fetch google signing keys")?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "google signing keys returned {}",
+            response.status()
+        ));
+    }
+    let key_set: JwkSet = response
+        .json()
+        .await
+        .context("decode google signing keys")?;
+    Ok(key_set.keys)
+}
+
+/// Keeps the signing key cache fresh; retries sooner while a fetch fails.
+pub async fn run_google_keys_refresher(url: String) {
+    let client = match reqwest::Client::builder()
+        .timeout(KEY_FETCH_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(error) => {
+            warn!(error = %error, "pubsub key refresher disabled");
+            return;
+        }
+    };
+    loop {
+        let wait = match fetch_google_keys(&client, &url).await {
+            Ok(keys) => {
+                info!(keys = keys.len(), "google signing keys refreshed");
+                set_google_keys(keys);
+                KEY_REFRESH_INTERVAL
+            }
+            Err(error) => {
+                warn!(error = %error, "failed to refresh google signing keys");
+                KEY_RETRY_INTERVAL
+            }
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(now: i64) -> Value {
+        json!({
+            "iss": "https://accounts.google.com",
+            "aud": AUDIENCE,
+            "email": "push@acme.iam.gserviceaccount.com",
+            "email_verified": true,
+            "iat": now - 10,
+            "exp": now + 3_590,
+        })
+    }
+
+    const AUDIENCE: &str = "https://relay.example.com/webhook/pubsub";
+    const SERVICE_ACCOUNT: &str = "push@acme.iam.gserviceaccount.com";
+
+    #[test]
+    fn claims_must_match_issuer_audience_expiry_and_account() {
+        let now = 1_760_000_000;
+        assert!(validate_claims(&claims(now), AUDIENCE, SERVICE_ACCOUNT, now).is_ok());
+
+        let mut wrong_audience = claims(now);
+        wrong_audience["aud"] = json!("https://elsewhere.example.com");
+        assert_eq!(
+            validate_claims(&wrong_audience, AUDIENCE, SERVICE_ACCOUNT, now),
+            Err(ValidationError::Unauthorized(WRONG_AUDIENCE_MESSAGE))
+        );
+        assert_eq!(
+            validate_claims(&claims(now), AUDIENCE, SERVICE_ACCOUNT, now + 7_200),
+            Err(ValidationError::Unauthorized(EXPIRED_TOKEN_MESSAGE))
+        );
+        let mut other_account = claims(now);
+        other_account["email"] = json!("intruder@example.com");
+        assert_eq!(
+            validate_claims(&other_account, AUDIENCE, SERVICE_ACCOUNT, now),
+            Err(ValidationError::Unauthorized(WRONG_SERVICE_ACCOUNT_MESSAGE))
+        );
+        let mut unverified = claims(now);
+        unverified["email_verified"] = json!(false);
+        assert_eq!(
+            validate_claims(&unverified, AUDIENCE, SERVICE_ACCOUNT, now),
+            Err(ValidationError::Unauthorized(WRONG_SERVICE_ACCOUNT_MESSAGE))
+        );
+    }
+
+    #[test]
+    fn unwraps_message_and_keys_on_message_id() {
+        let data = BASE64.encode(br#"{"bucket":"uploads","name":"report.csv"}"#);
+        let body = json!({
+            "message": {
+                "data": data,
+                "messageId": "2070443601311540",
+                "attributes": {"eventType": "OBJECT_FINALIZE"}
+            },
+            "subscription": "projects/acme/subscriptions/relay-push"
+        });
+        let payload = HANDLER
+            .parse_payload(&HeaderMap::new(), body.to_string().as_bytes())
+            .expect("parse payload");
+
+        assert_eq!(payload["bucket"], "uploads");
+        assert_eq!(
+            HANDLER
+                .event_type(&HeaderMap::new(), &payload)
+                .expect("event type"),
+            "OBJECT_FINALIZE"
+        );
+        assert_eq!(
+            HANDLER
+                .dedup_key(&HeaderMap::new(), &payload)
+                .expect("dedup key"),
+            "pubsub:projects/acme/subscriptions/relay-push:2070443601311540"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{
+    INVALID_JSON_PAYLOAD_MESSAGE, QUERY_TOKEN_HEADER, SignatureKind, SourceHandler,
+    ValidationError, header_value, payload_token,
+};
+use axum::http::HeaderMap;
+use relay_core::signatures::verify_bearer_token;
+use serde_json::{Value, json};
+
+const REGISTRY_SOURCE_NAME: &str = "registry";
+const DOCKER_HUB: &str = "dockerhub";
+const GHCR: &str = "ghcr";
+const GHCR_HOST: &str = "ghcr.io";
+const PUSH_EVENT_TYPE: &str = "registry.push";
+const GHCR_PUBLISHED_ACTION: &str = "published";
+const METADATA_FIELD: &str = "_registry";
+/// Docker Hub's webhook-chain callback; anyone holding it can report the
+/// pipeline's status, so it is not published.
+const DOCKER_HUB_CALLBACK_FIELD: &str = "callback_url";
+const UNKNOWN: &str = "unknown";
+const MISSING_REGISTRY_SECRET_MESSAGE: &str = "missing registry secret";
+const MISSING_REGISTRY_TOKEN_MESSAGE: &str = "missing registry token";
+const INVALID_REGISTRY_TOKEN_MESSAGE: &str = "invalid registry token";
+const UNRECOGNIZED_PAYLOAD_MESSAGE: &str = "unrecognized registry payload";
+
+/// Image pushes from Docker Hub repository webhooks and GitHub `package`
+/// webhooks for GHCR containers. Neither can be signed with a relay secret,
+/// so the shared token travels as `?token=` on the webhook URL (or as an
+/// `Authorization: Bearer` header where the registry allows one).
+#[derive(Debug, Default)]
+pub struct RegistrySourceHandler;
+
+pub static HANDLER: RegistrySourceHandler = RegistrySourceHandler;
+
+impl SourceHandler for RegistrySourceHandler {
+    fn source_name(&self) -> &'static str {
+        REGISTRY_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        _body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .registry
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(
+                MISSING_REGISTRY_SECRET_MESSAGE,
+            ))?;
+        validate(secret, headers)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    /// Adds `_registry` with the normalized registry, repository, tag and
+    /// digest.
+    fn parse_payload(&self, _headers: &HeaderMap, body: &[u8]) -> Result<Value, ValidationError> {
+        let mut payload: Value = serde_json::from_slice(body)
+            .map_err(|_| ValidationError::BadRequest(INVALID_JSON_PAYLOAD_MESSAGE))?;
+        let push = push_metadata(&payload)?;
+        let Some(fields) = payload.as_object_mut() else {
+            return Err(ValidationError::BadRequest(UNRECOGNIZED_PAYLOAD_MESSAGE));
+        };
+        fields.remove(DOCKER_HUB_CALLBACK_FIELD);
+        fields.insert(METADATA_FIELD.to_string(), push);
+        Ok(payload)
+    }
+
+    /// `registry.push` for Docker Hub pushes and published GHCR versions;
+    /// other GHCR package actions become `registry.<action>`.
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        match payload_token(payload, &["action"]) {
+            Some(action)
+                if payload_token(payload, &[METADATA_FIELD, "registry"]).as_deref()
+                    == Some(GHCR)
+                    && action != GHCR_PUBLISHED_ACTION =>
+            {
+                Ok(format!("registry.{}", action.to_ascii_lowercase()))
+            }
+            _ => Ok(PUSH_EVENT_TYPE.to_string()),
+        }
+    }
+
+    /// Repository, tag and digest. Docker Hub sends no digest, so its
+    /// `pushed_at` stands in for one.
+    fn dedup_key(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let field = |name: &str| {
+            payload_token(payload, &[METADATA_FIELD, name]).unwrap_or_else(|| UNKNOWN.to_string())
+        };
+        Ok(format!(
+            "{REGISTRY_SOURCE_NAME}:{}:{}:{}",
+            field("repository"),
+            field("tag"),
+            field("digest")
+        ))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+}
+
+pub fn validate(secret: &str, headers: &HeaderMap) -> Result<(), ValidationError> {
+    let token = header_value(headers, QUERY_TOKEN_HEADER)
+        .or_else(|| {
+            header_value(headers, "Authorization")
+                .map(|value| value.strip_prefix("Bearer ").unwrap_or(&value).to_string())
+        })
+        .ok_or(ValidationError::Unauthorized(
+            MISSING_REGISTRY_TOKEN_MESSAGE,
+        ))?;
+    if verify_bearer_token(secret, &token) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_REGISTRY_TOKEN_MESSAGE,
+        ))
+    }
+}
+
+/// `{registry, repository, tag, digest}` for a Docker Hub push or a GitHub
+/// `package` / `registry_package` event about a container.
+pub fn push_metadata(payload: &Value) -> Result<Value, ValidationError> {
+    if payload.get("push_data").is_some() {
+        return Ok(json!({
+            "registry": DOCKER_HUB,
+            "repository": payload_token(payload, &["repository", "repo_name"]),
+            "tag": payload_token(payload, &["push_data", "tag"]),
+            "digest": payload_token(payload, &["push_data", "pushed_at"]),
+        }));
+    }
+
+    let package = payload
+        .get("package")
+        .or_else(|| payload.get("registry_package"))
+        .filter(|package| {
+            payload_token(package, &["package_type"])
+                .is_some_and(|kind| kind.eq_ignore_ascii_case("container"))
+        })
+        .ok_or(ValidationError::BadRequest(UNRECOGNIZED_PAYLOAD_MESSAGE))?;
+    let namespace = payload_token(package, &["namespace"])
+        .or_else(|| payload_token(package, &["owner", "login"]))
+        .map(|namespace| namespace.to_ascii_lowercase());
+    let repository = namespace
+        .zip(payload_token(package, &["name"]))
+        .map(|(namespace, name)| format!("{GHCR_HOST}/{namespace}/{name}"));
+    let version = package.get("package_version").unwrap_or(&Value::Null);
+    Ok(json!({
+        "registry": GHCR,
+        "repository": repository,
+        "tag": payload_token(version, &["container_metadata", "tag", "name"]),
+        "digest": payload_token(version, &["container_metadata", "tag", "digest"])
+            .or_else(|| payload_token(version, &["version"])),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn accepts_query_or_bearer_token() {
+        let mut query = HeaderMap::new();
+        query.insert(
+            QUERY_TOKEN_HEADER,
+            HeaderValue::from_static("registry-secret"),
+        );
+        let mut bearer = HeaderMap::new();
+        bearer.insert(
+            "Authorization",
+            HeaderValue::from_static("Bearer registry-secret"),
+        );
+
+        assert!(validate("registry-secret", &query).is_ok());
+        assert!(validate("registry-secret", &bearer).is_ok());
+        assert_eq!(
+            validate("other-secret", &bearer),
+            Err(ValidationError::Unauthorized(
+                INVALID_REGISTRY_TOKEN_MESSAGE
+            ))
+        );
+        assert_eq!(
+            validate("registry-secret", &HeaderMap::new()),
+            Err(ValidationError::Unauthorized(
+                MISSING_REGISTRY_TOKEN_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn normalizes_ghcr_package_events() {
+        let body = json!({
+            "action": "published",
+            "package": {
+                "name": "api",
+                "namespace": "Acme",
+                "package_type": "CONTAINER",
+                "package_version": {
+                    "version": "sha256:3f1c",
+                    "container_metadata": {"tag": {"name": "v1.4.0", "digest": "sha256:3f1c"}}
+                }
+            }
+        });
+        let payload = HANDLER
+            .parse_payload(&HeaderMap::new(), body.to_string().as_bytes())
+            .expect("parse payload");
+
+        assert_eq!(payload["_registry"]["repository"], "ghcr.io/acme/api");
+        assert_eq!(
+            HANDLER
+                .event_type(&HeaderMap::new(), &payload)
+                .expect("event type"),
+            "registry.push"
+        );
+        assert_eq!(
+            HANDLER
+                .dedup_key(&HeaderMap::new(), &payload)
+                .expect("dedup key"),
+            "registry:ghcr.io/acme/api:v1.4.0:sha256:3f1c"
+        );
+
+        let mut updated = payload.clone();
+        updated["action"] = json!("updated");
+        assert_eq!(
+            HANDLER
+                .event_type(&HeaderMap::new(), &updated)
+                .expect("event type"),
+            "registry.updated"
+        );
+    }
+
+    #[test]
+    fn rejects_non_container_packages() {
+        let npm = json!({"action": "published", "package": {"name": "ui", "package_type": "npm"}});
+        assert_eq!(
+            push_metadata(&npm),
+            Err(ValidationError::BadRequest(UNRECOGNIZED_PAYLOAD_MESSAGE))
+        );
+    }
+}
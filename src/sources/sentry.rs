@@ -0,0 +1,133 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
+use axum::http::HeaderMap;
+use relay_core::sanitize::sanitize_sentry_payload;
+use relay_core::signatures::verify_sentry_signature;
+use serde_json::Value;
+
+const SENTRY_SOURCE_NAME: &str = "sentry";
+const SENTRY_SIGNATURE_HEADER: &str = "Sentry-Hook-Signature";
+const SENTRY_RESOURCE_HEADER: &str = "Sentry-Hook-Resource";
+const SENTRY_REQUEST_ID_HEADER: &str = "Request-ID";
+const MISSING_SENTRY_SECRET_MESSAGE: &str = "missing sentry secret";
+const MISSING_SENTRY_SIGNATURE_MESSAGE: &str = "missing sentry signature";
+const INVALID_SENTRY_SIGNATURE_MESSAGE: &str = "invalid sentry signature";
+const MISSING_RESOURCE_MESSAGE: &str = "missing Sentry-Hook-Resource";
+
+/// Sentry internal integration webhooks (issue, event and metric alerts).
+#[derive(Debug, Default)]
+pub struct SentrySourceHandler;
+
+pub static HANDLER: SentrySourceHandler = SentrySourceHandler;
+
+impl SourceHandler for SentrySourceHandler {
+    fn source_name(&self) -> &'static str {
+        SENTRY_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .sentry
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(MISSING_SENTRY_SECRET_MESSAGE))?;
+        validate(secret, headers, body)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn event_type(&self, headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        event_type(headers, payload)
+    }
+
+    fn dedup_key(&self, headers: &HeaderMap, _payload: &Value) -> Result<String, ValidationError> {
+        let request_id = header_value(headers, SENTRY_REQUEST_ID_HEADER)
+            .ok_or(ValidationError::BadRequest("missing Request-ID"))?;
+        Ok(format!("{SENTRY_SOURCE_NAME}:{request_id}"))
+    }
+
+    /// Repeated alerts for one issue cool down together.
+    fn cooldown_key(&self, payload: &Value) -> Option<String> {
+        payload_token(payload, &["data", "issue", "id"])
+            .or_else(|| payload_token(payload, &["data", "event", "issue_id"]))
+            .map(|issue_id| format!("cooldown-{SENTRY_SOURCE_NAME}-{issue_id}"))
+    }
+
+    fn sanitize(&self, payload: &Value) -> Result<Value, String> {
+        sanitize_sentry_payload(payload)
+    }
+}
+
+pub fn validate(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ValidationError> {
+    let signature = header_value(headers, SENTRY_SIGNATURE_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_SENTRY_SIGNATURE_MESSAGE),
+    )?;
+    if verify_sentry_signature(secret, body, &signature) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_SENTRY_SIGNATURE_MESSAGE,
+        ))
+    }
+}
+
+/// `Sentry-Hook-Resource`, suffixed with the payload `action` when present,
+/// e.g. `issue.created` or `event_alert.triggered`.
+pub fn event_type(headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+    let resource = header_value(headers, SENTRY_RESOURCE_HEADER)
+        .ok_or(ValidationError::BadRequest(MISSING_RESOURCE_MESSAGE))?
+        .to_ascii_lowercase();
+    match payload_token(payload, &["action"]) {
+        Some(action) => Ok(format!("{resource}.{}", action.to_ascii_lowercase())),
+        None => Ok(resource),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use relay_core::signatures::compute_hmac_sha256_hex;
+    use serde_json::json;
+
+    #[test]
+    fn validates_hook_signature() {
+        let body = br#"{"action":"created"}"#;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            SENTRY_SIGNATURE_HEADER,
+            HeaderValue::from_str(&compute_hmac_sha256_hex("sentry-secret", body))
+                .expect("signature header"),
+        );
+
+        assert!(validate("sentry-secret", &headers, body).is_ok());
+        assert_eq!(
+            validate("other-secret", &headers, body),
+            Err(ValidationError::Unauthorized(
+                INVALID_SENTRY_SIGNATURE_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn event_type_joins_resource_and_action() {
+        let mut headers = HeaderMap::new();
+        headers.insert(SENTRY_RESOURCE_HEADER, HeaderValue::from_static("issue"));
+        let payload = json!({"action": "resolved", "data": {"issue": {"id": "42"}}});
+
+        assert_eq!(
+            event_type(&headers, &payload).expect("event type"),
+            "issue.resolved"
+        );
+        assert_eq!(
+            HANDLER.cooldown_key(&payload).as_deref(),
+            Some("cooldown-sentry-42")
+        );
+        assert!(event_type(&HeaderMap::new(), &payload).is_err());
+    }
+}
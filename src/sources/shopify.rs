@@ -0,0 +1,127 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value};
+use axum::http::HeaderMap;
+use relay_core::sanitize::sanitize_shopify_payload;
+use relay_core::signatures::verify_base64_sha256_signature;
+use serde_json::Value;
+
+const SHOPIFY_SOURCE_NAME: &str = "shopify";
+const SHOPIFY_SIGNATURE_HEADER: &str = "X-Shopify-Hmac-Sha256";
+const SHOPIFY_TOPIC_HEADER: &str = "X-Shopify-Topic";
+const SHOPIFY_WEBHOOK_ID_HEADER: &str = "X-Shopify-Webhook-Id";
+const MISSING_SHOPIFY_SECRET_MESSAGE: &str = "missing shopify secret";
+const MISSING_SHOPIFY_SIGNATURE_MESSAGE: &str = "missing shopify signature";
+const INVALID_SHOPIFY_SIGNATURE_MESSAGE: &str = "invalid shopify signature";
+const MISSING_TOPIC_MESSAGE: &str = "missing X-Shopify-Topic";
+
+/// Shopify app and store webhooks, signed with the app's client secret (or
+/// the store's webhook signing key).
+#[derive(Debug, Default)]
+pub struct ShopifySourceHandler;
+
+pub static HANDLER: ShopifySourceHandler = ShopifySourceHandler;
+
+impl SourceHandler for ShopifySourceHandler {
+    fn source_name(&self) -> &'static str {
+        SHOPIFY_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        _config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        body: &[u8],
+        _now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .shopify
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(
+                MISSING_SHOPIFY_SECRET_MESSAGE,
+            ))?;
+        validate(secret, headers, body)?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn event_type(&self, headers: &HeaderMap, _payload: &Value) -> Result<String, ValidationError> {
+        event_type(headers)
+    }
+
+    fn dedup_key(&self, headers: &HeaderMap, _payload: &Value) -> Result<String, ValidationError> {
+        let webhook_id = header_value(headers, SHOPIFY_WEBHOOK_ID_HEADER)
+            .ok_or(ValidationError::BadRequest("missing X-Shopify-Webhook-Id"))?;
+        Ok(format!("{SHOPIFY_SOURCE_NAME}:{webhook_id}"))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+
+    fn sanitize(&self, payload: &Value) -> Result<Value, String> {
+        sanitize_shopify_payload(payload)
+    }
+}
+
+pub fn validate(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ValidationError> {
+    let signature = header_value(headers, SHOPIFY_SIGNATURE_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_SHOPIFY_SIGNATURE_MESSAGE),
+    )?;
+    if verify_base64_sha256_signature(secret, body, &signature) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_SHOPIFY_SIGNATURE_MESSAGE,
+        ))
+    }
+}
+
+/// `X-Shopify-Topic` with `/` swapped for `.`, e.g. `orders.create`.
+pub fn event_type(headers: &HeaderMap) -> Result<String, ValidationError> {
+    header_value(headers, SHOPIFY_TOPIC_HEADER)
+        .map(|topic| topic.to_ascii_lowercase().replace('/', "."))
+        .ok_or(ValidationError::BadRequest(MISSING_TOPIC_MESSAGE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use relay_core::signatures::compute_hmac_sha256_hex;
+
+    #[test]
+    fn validates_base64_hmac_and_maps_topic() {
+        let body = br#"{"id":820982911946154508}"#;
+        let digest = hex::decode(compute_hmac_sha256_hex("shopify-secret", body)).expect("hex");
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            SHOPIFY_SIGNATURE_HEADER,
+            HeaderValue::from_str(&BASE64.encode(digest)).expect("signature header"),
+        );
+        headers.insert(
+            SHOPIFY_TOPIC_HEADER,
+            HeaderValue::from_static("orders/create"),
+        );
+        headers.insert(
+            SHOPIFY_WEBHOOK_ID_HEADER,
+            HeaderValue::from_static("b54557e4-bdd9-4b37-8a5f-bf7d70bcd043"),
+        );
+
+        assert!(validate("shopify-secret", &headers, body).is_ok());
+        assert_eq!(
+            validate("other-secret", &headers, body),
+            Err(ValidationError::Unauthorized(
+                INVALID_SHOPIFY_SIGNATURE_MESSAGE
+            ))
+        );
+        assert_eq!(event_type(&headers).expect("event type"), "orders.create");
+        assert_eq!(
+            HANDLER
+                .dedup_key(&headers, &Value::Null)
+                .expect("dedup key"),
+            "shopify:b54557e4-bdd9-4b37-8a5f-bf7d70bcd043"
+        );
+    }
+}
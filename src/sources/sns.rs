@@ -0,0 +1,555 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{
+    INVALID_JSON_PAYLOAD_MESSAGE, SignatureKind, SourceHandler, ValidationError, payload_token,
+};
+use anyhow::{Context, Result, anyhow};
+use axum::http::HeaderMap;
+use chrono::DateTime;
+use futures_util::future::BoxFuture;
+use relay_core::signatures::{decode_pem_certificate, verify_certificate_sha256_signature};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+const SNS_SOURCE_NAME: &str = "sns";
+const NOTIFICATION: &str = "Notification";
+const SUBSCRIPTION_CONFIRMATION: &str = "SubscriptionConfirmation";
+const UNSUBSCRIBE_CONFIRMATION: &str = "UnsubscribeConfirmation";
+const NOTIFICATION_EVENT_TYPE: &str = "notification";
+const METADATA_FIELD: &str = "_sns";
+/// SignatureVersion 2 is RSA-SHA256; version 1 (SHA1) is not accepted.
+const SIGNATURE_VERSION: &str = "2";
+const NOTIFICATION_FIELDS: &[&str] = &[
+    "Message",
+    "MessageId",
+    "Subject",
+    "Timestamp",
+    "TopicArn",
+    "Type",
+];
+const CONFIRMATION_FIELDS: &[&str] = &[
+    "Message",
+    "MessageId",
+    "SubscribeURL",
+    "Timestamp",
+    "Token",
+    "TopicArn",
+    "Type",
+];
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+const INVALID_MESSAGE_MESSAGE: &str = "invalid sns message";
+const TOPIC_NOT_ALLOWED_MESSAGE: &str = "sns topic not allowed";
+const STALE_MESSAGE_MESSAGE: &str = "sns Timestamp outside tolerance window";
+const UNSUPPORTED_SIGNATURE_VERSION_MESSAGE: &str = "unsupported sns signature version";
+const INVALID_CERT_URL_MESSAGE: &str = "invalid sns signing cert url";
+const CERT_NOT_LOADED_MESSAGE: &str = "sns signing cert not loaded";
+const MISSING_SIGNATURE_MESSAGE: &str = "missing sns signature";
+const INVALID_SIGNATURE_MESSAGE: &str = "invalid sns signature";
+const INVALID_SUBSCRIBE_URL_MESSAGE: &str = "invalid sns subscribe url";
+const MISSING_MESSAGE_ID_MESSAGE: &str = "missing sns MessageId";
+
+/// DER signing certificates keyed by `SigningCertURL`.
+static SIGNING_CERTS: LazyLock<RwLock<HashMap<String, Vec<u8>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+/// Amazon SNS HTTPS subscriptions. Each message is signed with a certificate
+/// SNS hosts under `sns.<region>.amazonaws.com`; subscription confirmations
+/// are answered by visiting their `SubscribeURL`, and notifications are
+/// published.
+#[derive(Debug, Default)]
+pub struct SnsSourceHandler;
+
+pub static HANDLER: SnsSourceHandler = SnsSourceHandler;
+
+impl SourceHandler for SnsSourceHandler {
+    fn source_name(&self) -> &'static str {
+        SNS_SOURCE_NAME
+    }
+
+    /// Fetches the signing certificate ahead of the synchronous signature
+    /// check, which only reads the cache.
+    fn prepare<'a>(
+        &'a self,
+        config: &'a Config,
+        body: &'a [u8],
+        now_epoch_seconds: i64,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let Ok(message) = serde_json::from_slice::<Value>(body) else {
+                return;
+            };
+            if let Some(url) = cert_url_to_prefetch(
+                &message,
+                &config.sns_topic_arns,
+                now_epoch_seconds,
+                config.sns_tolerance_seconds,
+            ) {
+                prefetch_signing_cert(url).await;
+            }
+        })
+    }
+
+    fn validate_request(
+        &self,
+        config: &Config,
+        _secrets: &SourceSecrets,
+        _headers: &HeaderMap,
+        body: &[u8],
+        now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let message: Value = serde_json::from_slice(body)
+            .map_err(|_| ValidationError::Unauthorized(INVALID_MESSAGE_MESSAGE))?;
+        validate_message(
+            &message,
+            &config.sns_topic_arns,
+            now_epoch_seconds,
+            config.sns_tolerance_seconds,
+            |url| {
+                SIGNING_CERTS
+                    .read()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .get(url)
+                    .cloned()
+            },
+        )?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn parse_payload(&self, _headers: &HeaderMap, body: &[u8]) -> Result<Value, ValidationError> {
+        let message: Value = serde_json::from_slice(body)
+            .map_err(|_| ValidationError::BadRequest(INVALID_JSON_PAYLOAD_MESSAGE))?;
+        Ok(payload_from_message(&message))
+    }
+
+    fn reply(&self, payload: &Value) -> Option<Value> {
+        match payload_token(payload, &[METADATA_FIELD, "Type"])?.as_str() {
+            SUBSCRIPTION_CONFIRMATION => {
+                let subscribe_url = payload_token(payload, &[METADATA_FIELD, "SubscribeURL"])?;
+                tokio::spawn(confirm_subscription(subscribe_url));
+                Some(json!({"status": "confirming subscription"}))
+            }
+            UNSUBSCRIBE_CONFIRMATION => Some(json!({"status": "ignored"})),
+            _ => None,
+        }
+    }
+
+    fn event_type(
+        &self,
+        _headers: &HeaderMap,
+        _payload: &Value,
+    ) -> Result<String, ValidationError> {
+        Ok(NOTIFICATION_EVENT_TYPE.to_string())
+    }
+
+    fn dedup_key(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let message_id = payload_token(payload, &[METADATA_FIELD, "MessageId"])
+            .ok_or(ValidationError::BadRequest(MISSING_MESSAGE_ID_MESSAGE))?;
+        let topic_arn = payload_token(payload, &[METADATA_FIELD, "TopicArn"])
+            .unwrap_or_else(|| "unknown".to_string());
+        Ok(format!("{SNS_SOURCE_NAME}:{topic_arn}:{message_id}"))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+}
+
+/// Checks the topic allowlist, the message age, the certificate and
+/// subscribe URLs, and the signature. `signing_cert` looks up the DER
+/// certificate for a URL.
+pub fn validate_message(
+    message: &Value,
+    allowed_topic_arns: &[String],
+    now_epoch_seconds: i64,
+    tolerance_seconds: i64,
+    signing_cert: impl Fn(&str) -> Option<Vec<u8>>,
+) -> Result<(), ValidationError> {
+    let field = |name: &str| message.get(name).and_then(Value::as_str);
+    check_topic_and_age(
+        message,
+        allowed_topic_arns,
+        now_epoch_seconds,
+        tolerance_seconds,
+    )?;
+    if field("SignatureVersion") != Some(SIGNATURE_VERSION) {
+        return Err(ValidationError::Unauthorized(
+            UNSUPPORTED_SIGNATURE_VERSION_MESSAGE,
+        ));
+    }
+    let cert_url = field("SigningCertURL")
+        .filter(|url| is_sns_url(url, ".pem"))
+        .ok_or(ValidationError::Unauthorized(INVALID_CERT_URL_MESSAGE))?;
+    if field("Type") != Some(NOTIFICATION)
+        && !field("SubscribeURL").is_some_and(|url| is_sns_url(url, "/"))
+    {
+        return Err(ValidationError::Unauthorized(INVALID_SUBSCRIBE_URL_MESSAGE));
+    }
+    let signature =
+        field("Signature").ok_or(ValidationError::Unauthorized(MISSING_SIGNATURE_MESSAGE))?;
+    let string_to_sign =
+        string_to_sign(message).ok_or(ValidationError::Unauthorized(INVALID_MESSAGE_MESSAGE))?;
+    let certificate =
+        signing_cert(cert_url).ok_or(ValidationError::Unauthorized(CERT_NOT_LOADED_MESSAGE))?;
+    if verify_certificate_sha256_signature(&certificate, string_to_sign.as_bytes(), signature) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(INVALID_SIGNATURE_MESSAGE))
+    }
+}
+
+/// The checks that need no certificate, so they also gate fetching one.
+/// `Timestamp` is signed, and SNS keeps it across delivery retries.
+fn check_topic_and_age(
+    message: &Value,
+    allowed_topic_arns: &[String],
+    now_epoch_seconds: i64,
+    tolerance_seconds: i64,
+) -> Result<(), ValidationError> {
+    let field = |name: &str| message.get(name).and_then(Value::as_str);
+    let topic_arn =
+        field("TopicArn").ok_or(ValidationError::Unauthorized(INVALID_MESSAGE_MESSAGE))?;
+    if !allowed_topic_arns
+        .iter()
+        .any(|allowed| allowed == topic_arn)
+    {
+        return Err(ValidationError::Unauthorized(TOPIC_NOT_ALLOWED_MESSAGE));
+    }
+    let within_window = field("Timestamp")
+        .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+        .is_some_and(|sent_at| {
+            (now_epoch_seconds - sent_at.timestamp()).abs() <= tolerance_seconds
+        });
+    if !within_window {
+        return Err(ValidationError::Unauthorized(STALE_MESSAGE_MESSAGE));
+    }
+    Ok(())
+}
+
+/// `name\nvalue\n` pairs of the signed fields, in SNS's order. Only
+/// notifications may omit `Subject`.
+pub fn string_to_sign(message: &Value) -> Option<String> {
+    let fields = match message.get("Type")?.as_str()? {
+        NOTIFICATION => NOTIFICATION_FIELDS,
+        SUBSCRIPTION_CONFIRMATION | UNSUBSCRIBE_CONFIRMATION => CONFIRMATION_FIELDS,
+        _ => return None,
+    };
+    let mut signed = String::new();
+    for &name in fields {
+        match message.get(name).and_then(Value::as_str) {
+            Some(value) => {
+                signed.push_str(name);
+                signed.push('\n');
+                signed.push_str(value);
+                signed.push('\n');
+            }
+            None if name == "Subject" => {}
+            None => return None,
+        }
+    }
+    Some(signed)
+}
+
+/// `https://sns.<region>.amazonaws.com[.cn]/...`, with a path ending in
+/// `suffix`.
+pub fn is_sns_url(raw: &str, suffix: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(raw) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let region = host
+        .strip_suffix(".amazonaws.com")
+        .or_else(|| host.strip_suffix(".amazonaws.com.cn"))
+        .and_then(|rest| rest.strip_prefix("sns."));
+    url.scheme() == "https"
+        && url.port().is_none()
+        && region.is_some_and(|region| {
+            !region.is_empty()
+                && region.chars().all(|character| {
+                    character.is_ascii_lowercase() || character.is_ascii_digit() || character == '-'
+                })
+        })
+        && url.path().ends_with(suffix)
+}
+
+/// Notifications publish `Message`, parsed when it is a JSON object and
+/// wrapped as `{"message": ...}` otherwise. The SNS envelope fields are kept
+/// under `_sns`.
+fn payload_from_message(message: &Value) -> Value {
+    let field = |name: &str| message.get(name).cloned().unwrap_or(Value::Null);
+    if message.get("Type").and_then(Value::as_str) != Some(NOTIFICATION) {
+        return json!({
+            METADATA_FIELD: {
+                "Type": field("Type"),
+                "MessageId": field("MessageId"),
+                "TopicArn": field("TopicArn"),
+                "SubscribeURL": field("SubscribeURL"),
+            }
+        });
+    }
+    let text = message
+        .get("Message")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let mut payload = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::from_iter([("message".to_string(), Value::String(text.to_string()))]),
+    };
+    payload.insert(
+        METADATA_FIELD.to_string(),
+        json!({
+            "Type": NOTIFICATION,
+            "MessageId": field("MessageId"),
+            "TopicArn": field("TopicArn"),
+            "Subject": field("Subject"),
+            "Timestamp": field("Timestamp"),
+            "MessageAttributes": field("MessageAttributes"),
+        }),
+    );
+    Value::Object(payload)
+}
+
+/// The `SigningCertURL` of a message worth fetching a certificate for: an
+/// allowed topic, a fresh `Timestamp` and an SNS-hosted URL. Anyone can post
+/// to the endpoint, so nothing else makes serve reach out.
+fn cert_url_to_prefetch<'a>(
+    message: &'a Value,
+    allowed_topic_arns: &[String],
+    now_epoch_seconds: i64,
+    tolerance_seconds: i64,
+) -> Option<&'a str> {
+    check_topic_and_age(
+        message,
+        allowed_topic_arns,
+        now_epoch_seconds,
+        tolerance_seconds,
+    )
+    .ok()?;
+    message
+        .get("SigningCertURL")
+        .and_then(Value::as_str)
+        .filter(|url| is_sns_url(url, ".pem"))
+}
+
+/// Fetches and caches a signing certificate, so the synchronous signature
+/// check can find it. Failures are logged; the request is then rejected as
+/// unsigned.
+async fn prefetch_signing_cert(url: &str) {
+    if SIGNING_CERTS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .contains_key(url)
+    {
+        return;
+    }
+    match fetch_signing_cert(url).await {
+        Ok(certificate) => {
+            info!(url, "sns signing cert cached");
+            SIGNING_CERTS
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .insert(url.to_string(), certificate);
+        }
+        Err(error) => warn!(url, error = %error, "failed to fetch sns signing cert"),
+    }
+}
+
+async fn fetch_signing_cert(url: &str) -> Result<Vec<u8>> {
+    let response = HTTP_CLIENT
+        .get(url)
+        .send()
+        .await
+        .context("fetch sns signing cert")?;
+    if !response.status().is_success() {
+        return Err(anyhow!("sns signing cert returned {}", response.status()));
+    }
+    let pem = response.text().await.context("read sns signing cert")?;
+    decode_pem_certificate(&pem).ok_or_else(|| anyhow!("sns signing cert is not a PEM certificate"))
+}
+
+async fn confirm_subscription(subscribe_url: String) {
+    if !is_sns_url(&subscribe_url, "/") {
+        warn!("refusing to confirm sns subscription outside amazonaws.com");
+        return;
+    }
+    match HTTP_CLIENT.get(&subscribe_url).send().await {
+        Ok(response) if response.status().is_success() => info!("sns subscription confirmed"),
+        Ok(response) => warn!(status = %response.status(), "sns subscription confirmation failed"),
+        Err(error) => warn!(error = %error, "sns subscription confirmation failed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOPIC_ARN: &str = "arn:aws:sns:us-east-1:123456789012:deploys";
+    /// The `Timestamp` of `notification()`.
+    const SENT_AT: i64 = 1_790_856_000;
+    const TOLERANCE: i64 = 3600;
+
+    fn notification() -> Value {
+        json!({
+            "Type": "Notification",
+            "MessageId": "22b80b92-fdea-4c2c-8f9d-bdfb0c7bf324",
+            "TopicArn": TOPIC_ARN,
+            "Subject": "Deploy finished",
+            "Message": "{\"service\":\"api\",\"status\":\"ok\"}",
+            "Timestamp": "2026-10-01T12:00:00.000Z",
+            "SignatureVersion": "2",
+            "Signature": "c2ln",
+            "SigningCertURL": "https://sns.us-east-1.amazonaws.com/SimpleNotificationService-abc.pem"
+        })
+    }
+
+    #[test]
+    fn string_to_sign_lists_fields_in_order() {
+        assert_eq!(
+            string_to_sign(&notification()).expect("string to sign"),
+            concat!(
+                "Message\n{\"service\":\"api\",\"status\":\"ok\"}\n",
+                "MessageId\n22b80b92-fdea-4c2c-8f9d-bdfb0c7bf324\n",
+                "Subject\nDeploy finished\n",
+                "Timestamp\n2026-10-01T12:00:00.000Z\n",
+                "TopicArn\narn:aws:sns:us-east-1:123456789012:deploys\n",
+                "Type\nNotification\n",
+            )
+        );
+        let mut confirmation = notification();
+        confirmation["Type"] = json!("SubscriptionConfirmation");
+        assert_eq!(string_to_sign(&confirmation), None);
+        confirmation["SubscribeURL"] = json!("https://sns.us-east-1.amazonaws.com/?Action=x");
+        confirmation["Token"] = json!("token");
+        assert!(
+            string_to_sign(&confirmation)
+                .expect("string to sign")
+                .contains("SubscribeURL\nhttps://sns.us-east-1.amazonaws.com/?Action=x\n")
+        );
+    }
+
+    #[test]
+    fn only_sns_hosts_are_trusted() {
+        assert!(is_sns_url(
+            "https://sns.eu-west-1.amazonaws.com/SimpleNotificationService-1.pem",
+            ".pem"
+        ));
+        assert!(is_sns_url(
+            "https://sns.cn-north-1.amazonaws.com.cn/cert.pem",
+            ".pem"
+        ));
+        assert!(!is_sns_url(
+            "http://sns.us-east-1.amazonaws.com/cert.pem",
+            ".pem"
+        ));
+        assert!(!is_sns_url("https://sns.evil.example.com/cert.pem", ".pem"));
+        assert!(!is_sns_url(
+            "https://sns.us-east-1.amazonaws.com.evil.example/cert.pem",
+            ".pem"
+        ));
+        assert!(!is_sns_url("https://s3.amazonaws.com/cert.pem", ".pem"));
+    }
+
+    #[test]
+    fn rejects_topics_outside_the_allowlist_before_checking_signatures() {
+        let allowed = vec![TOPIC_ARN.to_string()];
+        assert_eq!(
+            validate_message(&notification(), &[], SENT_AT, TOLERANCE, |_| None),
+            Err(ValidationError::Unauthorized(TOPIC_NOT_ALLOWED_MESSAGE))
+        );
+        assert_eq!(
+            validate_message(&notification(), &allowed, SENT_AT, TOLERANCE, |_| None),
+            Err(ValidationError::Unauthorized(CERT_NOT_LOADED_MESSAGE))
+        );
+        let mut sha1 = notification();
+        sha1["SignatureVersion"] = json!("1");
+        assert_eq!(
+            validate_message(&sha1, &allowed, SENT_AT, TOLERANCE, |_| None),
+            Err(ValidationError::Unauthorized(
+                UNSUPPORTED_SIGNATURE_VERSION_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_messages_outside_the_tolerance_window() {
+        let allowed = vec![TOPIC_ARN.to_string()];
+        assert_eq!(
+            validate_message(
+                &notification(),
+                &allowed,
+                SENT_AT + TOLERANCE + 1,
+                TOLERANCE,
+                |_| None
+            ),
+            Err(ValidationError::Unauthorized(STALE_MESSAGE_MESSAGE))
+        );
+        let mut undated = notification();
+        undated.as_object_mut().expect("object").remove("Timestamp");
+        assert_eq!(
+            validate_message(&undated, &allowed, SENT_AT, TOLERANCE, |_| None),
+            Err(ValidationError::Unauthorized(STALE_MESSAGE_MESSAGE))
+        );
+    }
+
+    #[test]
+    fn prefetches_certs_only_for_allowed_fresh_messages() {
+        let allowed = vec![TOPIC_ARN.to_string()];
+        assert_eq!(
+            cert_url_to_prefetch(&notification(), &allowed, SENT_AT, TOLERANCE),
+            Some("https://sns.us-east-1.amazonaws.com/SimpleNotificationService-abc.pem")
+        );
+        assert_eq!(
+            cert_url_to_prefetch(&notification(), &[], SENT_AT, TOLERANCE),
+            None
+        );
+        assert_eq!(
+            cert_url_to_prefetch(
+                &notification(),
+                &allowed,
+                SENT_AT + TOLERANCE + 1,
+                TOLERANCE
+            ),
+            None
+        );
+        let mut elsewhere = notification();
+        elsewhere["SigningCertURL"] = json!("https://attacker.example.com/cert.pem");
+        assert_eq!(
+            cert_url_to_prefetch(&elsewhere, &allowed, SENT_AT, TOLERANCE),
+            None
+        );
+    }
+
+    #[test]
+    fn notifications_publish_the_message_with_sns_metadata() {
+        let payload = payload_from_message(&notification());
+        assert_eq!(payload["service"], "api");
+        assert_eq!(payload["_sns"]["Subject"], "Deploy finished");
+        assert_eq!(HANDLER.reply(&payload), None);
+        assert_eq!(
+            HANDLER
+                .dedup_key(&HeaderMap::new(), &payload)
+                .expect("dedup key"),
+            "sns:arn:aws:sns:us-east-1:123456789012:deploys:22b80b92-fdea-4c2c-8f9d-bdfb0c7bf324"
+        );
+
+        let mut plain = notification();
+        plain["Message"] = json!("disk almost full");
+        assert_eq!(payload_from_message(&plain)["message"], "disk almost full");
+
+        let mut unsubscribe = notification();
+        unsubscribe["Type"] = json!("UnsubscribeConfirmation");
+        assert_eq!(
+            HANDLER.reply(&payload_from_message(&unsubscribe)),
+            Some(json!({"status": "ignored"}))
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
+use axum::http::HeaderMap;
+use relay_core::signatures::verify_standard_webhook_signature;
+use serde_json::Value;
+
+const STANDARD_WEBHOOKS_SOURCE_NAME: &str = "standard-webhooks";
+const WEBHOOK_ID_HEADER: &str = "webhook-id";
+const WEBHOOK_TIMESTAMP_HEADER: &str = "webhook-timestamp";
+const WEBHOOK_SIGNATURE_HEADER: &str = "webhook-signature";
+const MISSING_STANDARD_WEBHOOKS_SECRET_MESSAGE: &str = "missing standard-webhooks secret";
+const MISSING_WEBHOOK_ID_MESSAGE: &str = "missing webhook-id";
+const MISSING_WEBHOOK_TIMESTAMP_MESSAGE: &str = "missing webhook-timestamp";
+const MISSING_WEBHOOK_SIGNATURE_MESSAGE: &str = "missing webhook-signature";
+const STALE_WEBHOOK_TIMESTAMP_MESSAGE: &str = "webhook-timestamp outside tolerance window";
+const INVALID_WEBHOOK_SIGNATURE_MESSAGE: &str = "invalid standard-webhooks signature";
+const MISSING_EVENT_TYPE_MESSAGE: &str = "missing payload type";
+
+/// Providers following the Standard Webhooks spec (Svix and others).
+#[derive(Debug, Default)]
+pub struct StandardWebhooksSourceHandler;
+
+pub static HANDLER: StandardWebhooksSourceHandler = StandardWebhooksSourceHandler;
+
+impl SourceHandler for StandardWebhooksSourceHandler {
+    fn source_name(&self) -> &'static str {
+        STANDARD_WEBHOOKS_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        body: &[u8],
+        now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .standard_webhooks
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(
+                MISSING_STANDARD_WEBHOOKS_SECRET_MESSAGE,
+            ))?;
+        validate(
+            secret,
+            headers,
+            body,
+            now_epoch_seconds,
+            config.standard_webhooks_tolerance_seconds,
+        )?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        payload_token(payload, &["type"])
+            .ok_or(ValidationError::BadRequest(MISSING_EVENT_TYPE_MESSAGE))
+    }
+
+    fn dedup_key(&self, headers: &HeaderMap, _payload: &Value) -> Result<String, ValidationError> {
+        let message_id = header_value(headers, WEBHOOK_ID_HEADER)
+            .ok_or(ValidationError::BadRequest(MISSING_WEBHOOK_ID_MESSAGE))?;
+        Ok(format!("{STANDARD_WEBHOOKS_SOURCE_NAME}:{message_id}"))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+}
+
+/// The timestamp is checked before the signature; both are required since
+/// the timestamp is part of the signed content.
+pub fn validate(
+    secret: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    now_epoch_seconds: i64,
+    tolerance_seconds: i64,
+) -> Result<(), ValidationError> {
+    let message_id = header_value(headers, WEBHOOK_ID_HEADER)
+        .ok_or(ValidationError::Unauthorized(MISSING_WEBHOOK_ID_MESSAGE))?;
+    let timestamp = header_value(headers, WEBHOOK_TIMESTAMP_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_WEBHOOK_TIMESTAMP_MESSAGE),
+    )?;
+    let signature = header_value(headers, WEBHOOK_SIGNATURE_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_WEBHOOK_SIGNATURE_MESSAGE),
+    )?;
+
+    let within_window = timestamp
+        .parse::<i64>()
+        .is_ok_and(|sent_at| (now_epoch_seconds - sent_at).abs() <= tolerance_seconds);
+    if !within_window {
+        return Err(ValidationError::Unauthorized(
+            STALE_WEBHOOK_TIMESTAMP_MESSAGE,
+        ));
+    }
+
+    if verify_standard_webhook_signature(secret, &message_id, &timestamp, body, &signature) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_WEBHOOK_SIGNATURE_MESSAGE,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use relay_core::signatures::compute_standard_webhook_signature;
+    use serde_json::json;
+
+    const SECRET: &str = "whsec_MfKQ9r8GKYqrTwjUPD8ILPZIo2LaLaSw";
+    const SENT_AT: i64 = 1_700_000_000;
+
+    fn signed_headers(body: &[u8], timestamp: i64) -> HeaderMap {
+        let timestamp = timestamp.to_string();
+        let signature =
+            compute_standard_webhook_signature(SECRET, "msg_1", &timestamp, body).expect("sign");
+        let mut headers = HeaderMap::new();
+        headers.insert(WEBHOOK_ID_HEADER, HeaderValue::from_static("msg_1"));
+        headers.insert(
+            WEBHOOK_TIMESTAMP_HEADER,
+            HeaderValue::from_str(&timestamp).expect("timestamp header"),
+        );
+        headers.insert(
+            WEBHOOK_SIGNATURE_HEADER,
+            HeaderValue::from_str(&format!("v1,{signature}")).expect("signature header"),
+        );
+        headers
+    }
+
+    #[test]
+    fn accepts_signed_request_inside_window() {
+        let body = br#"{"type":"invoice.paid","data":{}}"#;
+        let headers = signed_headers(body, SENT_AT);
+
+        assert!(validate(SECRET, &headers, body, SENT_AT + 299, 300).is_ok());
+        assert_eq!(
+            validate(SECRET, &headers, br#"{"type":"other"}"#, SENT_AT, 300),
+            Err(ValidationError::Unauthorized(
+                INVALID_WEBHOOK_SIGNATURE_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_replayed_or_future_timestamps() {
+        let body = br#"{"type":"invoice.paid"}"#;
+        let headers = signed_headers(body, SENT_AT);
+
+        for now in [SENT_AT + 301, SENT_AT - 301] {
+            assert_eq!(
+                validate(SECRET, &headers, body, now, 300),
+                Err(ValidationError::Unauthorized(
+                    STALE_WEBHOOK_TIMESTAMP_MESSAGE
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn requires_all_three_headers() {
+        let body = br#"{"type":"invoice.paid"}"#;
+        for header in [
+            WEBHOOK_ID_HEADER,
+            WEBHOOK_TIMESTAMP_HEADER,
+            WEBHOOK_SIGNATURE_HEADER,
+        ] {
+            let mut headers = signed_headers(body, SENT_AT);
+            headers.remove(header);
+            assert!(validate(SECRET, &headers, body, SENT_AT, 300).is_err());
+        }
+    }
+
+    #[test]
+    fn dedups_on_message_id_and_reads_payload_type() {
+        let headers = signed_headers(b"{}", SENT_AT);
+        let payload = json!({"type":"invoice.paid"});
+
+        assert_eq!(
+            HANDLER.dedup_key(&headers, &payload),
+            Ok("standard-webhooks:msg_1".to_string())
+        );
+        assert_eq!(
+            HANDLER.event_type(&headers, &payload),
+            Ok("invoice.paid".to_string())
+        );
+        assert!(HANDLER.event_type(&headers, &json!({})).is_err());
+    }
+}
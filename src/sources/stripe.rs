@@ -0,0 +1,169 @@
+use crate::config::{Config, SourceSecrets};
+use crate::sources::{SignatureKind, SourceHandler, ValidationError, header_value, payload_token};
+use axum::http::HeaderMap;
+use relay_core::signatures::verify_stripe_signature;
+use serde_json::Value;
+
+const STRIPE_SOURCE_NAME: &str = "stripe";
+const STRIPE_SIGNATURE_HEADER: &str = "Stripe-Signature";
+const MISSING_STRIPE_SECRET_MESSAGE: &str = "missing stripe secret";
+const MISSING_STRIPE_SIGNATURE_MESSAGE: &str = "missing Stripe-Signature";
+const MISSING_STRIPE_TIMESTAMP_MESSAGE: &str = "missing Stripe-Signature timestamp";
+const STALE_STRIPE_TIMESTAMP_MESSAGE: &str = "stripe timestamp outside tolerance window";
+const INVALID_STRIPE_SIGNATURE_MESSAGE: &str = "invalid stripe signature";
+const MISSING_EVENT_TYPE_MESSAGE: &str = "missing stripe event type";
+const MISSING_EVENT_ID_MESSAGE: &str = "missing stripe event id";
+
+/// Stripe webhook endpoints. Stripe retries an event under the same `id`,
+/// so dedup is keyed on it alone.
+#[derive(Debug, Default)]
+pub struct StripeSourceHandler;
+
+pub static HANDLER: StripeSourceHandler = StripeSourceHandler;
+
+impl SourceHandler for StripeSourceHandler {
+    fn source_name(&self) -> &'static str {
+        STRIPE_SOURCE_NAME
+    }
+
+    fn validate_request(
+        &self,
+        config: &Config,
+        secrets: &SourceSecrets,
+        headers: &HeaderMap,
+        body: &[u8],
+        now_epoch_seconds: i64,
+    ) -> Result<SignatureKind, ValidationError> {
+        let secret = secrets
+            .stripe
+            .as_deref()
+            .ok_or(ValidationError::Unauthorized(MISSING_STRIPE_SECRET_MESSAGE))?;
+        validate(
+            secret,
+            headers,
+            body,
+            now_epoch_seconds,
+            config.stripe_tolerance_seconds,
+        )?;
+        Ok(SignatureKind::Primary)
+    }
+
+    fn event_type(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        payload_token(payload, &["type"])
+            .ok_or(ValidationError::BadRequest(MISSING_EVENT_TYPE_MESSAGE))
+    }
+
+    fn dedup_key(&self, _headers: &HeaderMap, payload: &Value) -> Result<String, ValidationError> {
+        let event_id = payload_token(payload, &["id"])
+            .ok_or(ValidationError::BadRequest(MISSING_EVENT_ID_MESSAGE))?;
+        Ok(format!("{STRIPE_SOURCE_NAME}:{event_id}"))
+    }
+
+    fn cooldown_key(&self, _payload: &Value) -> Option<String> {
+        None
+    }
+}
+
+/// `Stripe-Signature: t=<unix>,v1=<hex>[,v1=<hex>]`. The timestamp is
+/// signed, so it is checked against the tolerance before the HMAC.
+pub fn validate(
+    secret: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    now_epoch_seconds: i64,
+    tolerance_seconds: i64,
+) -> Result<(), ValidationError> {
+    let signature = header_value(headers, STRIPE_SIGNATURE_HEADER).ok_or(
+        ValidationError::Unauthorized(MISSING_STRIPE_SIGNATURE_MESSAGE),
+    )?;
+    let timestamp = signature
+        .split(',')
+        .find_map(|entry| entry.trim().strip_prefix("t="))
+        .ok_or(ValidationError::Unauthorized(
+            MISSING_STRIPE_TIMESTAMP_MESSAGE,
+        ))?;
+
+    let within_window = timestamp
+        .parse::<i64>()
+        .is_ok_and(|sent_at| (now_epoch_seconds - sent_at).abs() <= tolerance_seconds);
+    if !within_window {
+        return Err(ValidationError::Unauthorized(
+            STALE_STRIPE_TIMESTAMP_MESSAGE,
+        ));
+    }
+
+    if verify_stripe_signature(secret, timestamp, body, &signature) {
+        Ok(())
+    } else {
+        Err(ValidationError::Unauthorized(
+            INVALID_STRIPE_SIGNATURE_MESSAGE,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use relay_core::signatures::compute_stripe_signature;
+    use serde_json::json;
+
+    const SECRET: &str = "whsec_test_secret";
+    const SENT_AT: i64 = 1_700_000_000;
+
+    fn signed_headers(body: &[u8], timestamp: i64) -> HeaderMap {
+        let timestamp = timestamp.to_string();
+        let signature = compute_stripe_signature(SECRET, &timestamp, body);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            STRIPE_SIGNATURE_HEADER,
+            HeaderValue::from_str(&format!("t={timestamp},v1={signature},v0=legacy"))
+                .expect("signature header"),
+        );
+        headers
+    }
+
+    #[test]
+    fn accepts_signed_request_inside_tolerance() {
+        let body = br#"{"id":"evt_1","type":"invoice.paid"}"#;
+        let headers = signed_headers(body, SENT_AT);
+
+        assert!(validate(SECRET, &headers, body, SENT_AT + 299, 300).is_ok());
+        assert_eq!(
+            validate("whsec_other", &headers, body, SENT_AT, 300),
+            Err(ValidationError::Unauthorized(
+                INVALID_STRIPE_SIGNATURE_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_stale_timestamps_before_checking_signature() {
+        let body = br#"{"id":"evt_1","type":"invoice.paid"}"#;
+        let headers = signed_headers(body, SENT_AT);
+
+        assert_eq!(
+            validate(SECRET, &headers, body, SENT_AT + 301, 300),
+            Err(ValidationError::Unauthorized(
+                STALE_STRIPE_TIMESTAMP_MESSAGE
+            ))
+        );
+    }
+
+    #[test]
+    fn dedups_on_event_id() {
+        let payload = json!({"id": "evt_1", "type": "customer.subscription.deleted"});
+        assert_eq!(
+            HANDLER
+                .dedup_key(&HeaderMap::new(), &payload)
+                .expect("dedup key"),
+            "stripe:evt_1"
+        );
+        assert_eq!(
+            HANDLER
+                .event_type(&HeaderMap::new(), &payload)
+                .expect("event type"),
+            "customer.subscription.deleted"
+        );
+    }
+}
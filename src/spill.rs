@@ -0,0 +1,199 @@
+use crate::audit::{create_private_dir, write_private_file};
+use crate::producer::PublishJob;
+use anyhow::{Context, Result};
+use relay_core::model::WebhookEnvelope;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Envelopes replayed per pass, so a large backlog doesn't starve the live
+/// queue.
+const REPLAY_BATCH: usize = 100;
+
+/// Disk overflow for the publish queue. Envelopes land here when the queue
+/// is full or Kafka keeps rejecting them, and the publish worker re-publishes
+/// them once the broker recovers.
+#[derive(Debug, Clone)]
+pub struct PublishSpill {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SpilledJob {
+    topic: String,
+    envelope: WebhookEnvelope,
+}
+
+impl PublishSpill {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Writes `dir/<event id>.json`, readable by the owner only.
+    pub async fn write(&self, job: &PublishJob) -> Result<()> {
+        create_private_dir(&self.dir).await?;
+        let encoded = serde_json::to_vec(&SpilledJob {
+            topic: job.topic.clone(),
+            envelope: job.envelope.clone(),
+        })
+        .context("encode spilled envelope")?;
+        // Write under a temp name first so a crash never leaves a truncated
+        // file for replay to choke on.
+        let path = self.dir.join(format!("{}.json", job.envelope.id));
+        let partial = path.with_extension("json.partial");
+        write_private_file(&partial, &encoded).await?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .with_context(|| format!("commit spilled envelope {}", path.display()))
+    }
+
+    /// Oldest spilled envelopes first, up to one replay batch. Files that
+    /// can't be read or decoded are renamed to `*.json.bad` and skipped, so
+    /// one bad file doesn't block the rest.
+    pub async fn pending(&self) -> Result<Vec<(PathBuf, PublishJob)>> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("read spill dir {}", self.dir.display()));
+            }
+        };
+        let mut paths = Vec::new();
+        while let Some(entry) = entries.next_entry().await.context("read spill entry")? {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                match entry
+                    .metadata()
+                    .await
+                    .and_then(|metadata| metadata.modified())
+                {
+                    Ok(modified) => paths.push((modified, path)),
+                    Err(error) => warn!(
+                        path = %path.display(),
+                        error = %error,
+                        "failed to stat spilled envelope; skipped"
+                    ),
+                }
+            }
+        }
+        paths.sort();
+
+        let mut jobs = Vec::new();
+        for (_, path) in paths.into_iter().take(REPLAY_BATCH) {
+            let spilled = match read_spilled(&path).await {
+                Ok(spilled) => spilled,
+                Err(error) => {
+                    quarantine(&path, &error).await;
+                    continue;
+                }
+            };
+            jobs.push((
+                path,
+                PublishJob {
+                    topic: spilled.topic,
+                    envelope: spilled.envelope,
+                },
+            ));
+        }
+        Ok(jobs)
+    }
+
+    pub async fn remove(&self, path: &Path) -> Result<()> {
+        tokio::fs::remove_file(path)
+            .await
+            .with_context(|| format!("remove spilled envelope {}", path.display()))
+    }
+}
+
+async fn read_spilled(path: &Path) -> Result<SpilledJob> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("read spilled envelope {}", path.display()))?;
+    serde_json::from_slice(&bytes)
+        .with_context(|| format!("decode spilled envelope {}", path.display()))
+}
+
+async fn quarantine(path: &Path, error: &anyhow::Error) {
+    let bad = path.with_extension("json.bad");
+    match tokio::fs::rename(path, &bad).await {
+        Ok(()) => warn!(
+            path = %bad.display(),
+            error = %format!("{error:#}"),
+            "unreadable spilled envelope quarantined"
+        ),
+        Err(rename_error) => warn!(
+            path = %path.display(),
+            error = %format!("{error:#}"),
+            rename_error = %rename_error,
+            "unreadable spilled envelope could not be quarantined"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::build_envelope;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn spilled_envelopes_round_trip_until_removed() {
+        let dir = std::env::temp_dir().join(format!("hook-serve-spill-{}", std::process::id()));
+        let spill = PublishSpill::new(&dir);
+        assert!(spill.pending().await.expect("empty").is_empty());
+
+        let envelope = build_envelope(
+            "github",
+            "pull_request".to_string(),
+            json!({"action": "opened"}),
+            None,
+        );
+        let event_id = envelope.id.clone();
+        spill
+            .write(&PublishJob {
+                topic: "webhooks.github".to_string(),
+                envelope,
+            })
+            .await
+            .expect("write");
+
+        let pending = spill.pending().await.expect("pending");
+        assert_eq!(pending.len(), 1);
+        let (path, job) = &pending[0];
+        assert_eq!(job.topic, "webhooks.github");
+        assert_eq!(job.envelope.id, event_id);
+
+        spill.remove(path).await.expect("remove");
+        assert!(spill.pending().await.expect("drained").is_empty());
+        std::fs::remove_dir_all(&dir).expect("clean up");
+    }
+
+    #[tokio::test]
+    async fn corrupt_files_are_quarantined_without_blocking_replay() {
+        let dir = std::env::temp_dir().join(format!("hook-serve-spill-bad-{}", std::process::id()));
+        let spill = PublishSpill::new(&dir);
+        create_private_dir(&dir).await.expect("create dir");
+        std::fs::write(dir.join("truncated.json"), b"{\"topic\":").expect("write corrupt");
+
+        let envelope = build_envelope("linear", "Issue".to_string(), json!({}), None);
+        let event_id = envelope.id.clone();
+        spill
+            .write(&PublishJob {
+                topic: "webhooks.linear".to_string(),
+                envelope,
+            })
+            .await
+            .expect("write");
+
+        let pending = spill.pending().await.expect("pending");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].1.envelope.id, event_id);
+        assert!(dir.join("truncated.json.bad").exists());
+        assert!(!dir.join("truncated.json").exists());
+        std::fs::remove_dir_all(&dir).expect("clean up");
+    }
+}
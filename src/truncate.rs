@@ -0,0 +1,97 @@
+use serde_json::{Value, json};
+
+/// Payload field marking a payload cut down to fit its source limit.
+const TRUNCATED_FIELD: &str = "_truncated";
+/// String lengths (in chars) tried in turn until the payload fits.
+const STRING_CAPS: [usize; 5] = [4_096, 1_024, 256, 64, 0];
+
+/// Shrinks `payload` to at most `max_bytes` of JSON, keeping its shape:
+/// long strings are cut first, then arrays are emptied. Keys, numbers and
+/// booleans are kept. Returns the original size when anything was cut.
+pub fn truncate_payload(payload: &mut Value, max_bytes: usize) -> Option<usize> {
+    let original_bytes = encoded_len(payload);
+    if original_bytes <= max_bytes {
+        return None;
+    }
+    for cap in STRING_CAPS {
+        cap_strings(payload, cap);
+        if encoded_len(payload) <= max_bytes {
+            break;
+        }
+    }
+    if encoded_len(payload) > max_bytes {
+        empty_arrays(payload);
+    }
+    if let Some(object) = payload.as_object_mut() {
+        object.insert(
+            TRUNCATED_FIELD.to_string(),
+            json!({"original_bytes": original_bytes, "max_bytes": max_bytes}),
+        );
+    }
+    Some(original_bytes)
+}
+
+fn encoded_len(value: &Value) -> usize {
+    serde_json::to_vec(value).map_or(usize::MAX, |encoded| encoded.len())
+}
+
+fn cap_strings(value: &mut Value, cap: usize) {
+    match value {
+        Value::String(text) => {
+            if let Some((index, _)) = text.char_indices().nth(cap) {
+                text.truncate(index);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| cap_strings(item, cap)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| cap_strings(field, cap)),
+        _ => {}
+    }
+}
+
+fn empty_arrays(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.clear(),
+        Value::Object(fields) => fields.values_mut().for_each(empty_arrays),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_long_strings_before_touching_structure() {
+        let mut payload = json!({
+            "action": "opened",
+            "number": 42,
+            "pull_request": {"id": 7, "title": "Fix", "body": "x".repeat(50_000)},
+        });
+
+        let original = truncate_payload(&mut payload, 2_048).expect("truncated");
+
+        assert!(original > 50_000);
+        assert!(serde_json::to_vec(&payload).expect("encode").len() <= 2_048 + 64);
+        assert_eq!(payload["action"], "opened");
+        assert_eq!(payload["number"], 42);
+        assert_eq!(payload["pull_request"]["title"], "Fix");
+        assert_eq!(
+            payload["pull_request"]["body"].as_str().map(str::len),
+            Some(1_024)
+        );
+        assert_eq!(payload["_truncated"]["original_bytes"], original);
+    }
+
+    #[test]
+    fn empties_arrays_when_strings_are_not_enough() {
+        let mut payload = json!({"commits": (0..2_000).collect::<Vec<_>>(), "ref": "main"});
+
+        truncate_payload(&mut payload, 256).expect("truncated");
+
+        assert_eq!(payload["commits"], json!([]));
+        assert_eq!(payload["ref"], "");
+        assert!(truncate_payload(&mut json!({"ok": true}), 256).is_none());
+    }
+}
@@ -0,0 +1,313 @@
+mod support;
+
+use axum::http::StatusCode;
+use hook_serve::clock::Clock;
+use hook_serve::config::OversizedPayloadMode;
+use serde_json::json;
+use support::{CONFIG, GITLAB_SECRET, GMAIL_SECRET, Harness, REGISTRY_SECRET, load_fixture};
+
+#[tokio::test]
+async fn github_pull_request_is_published_to_source_topic() {
+    let mut harness = Harness::new();
+    let fixture = load_fixture("github", "pull_request_opened");
+
+    let response = harness.replay(&fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body["status"], "ok");
+
+    let job = harness.published().expect("published job");
+    assert_eq!(job.topic, "webhooks.github");
+    assert_eq!(job.envelope.source, "github");
+    assert_eq!(job.envelope.event_type, "pull_request.opened");
+    assert_eq!(job.envelope.payload["_sanitized"], json!(true));
+    assert!(job.envelope.payload.get("_flags").is_none());
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn form_encoded_github_delivery_is_published() {
+    let mut harness = Harness::new();
+    let fixture = load_fixture("github", "pull_request_opened");
+
+    let response = harness.replay_form_encoded(&fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let job = harness.published().expect("published job");
+    assert_eq!(job.envelope.event_type, "pull_request.opened");
+    assert_eq!(
+        job.envelope.payload["pull_request"]["number"],
+        fixture.payload["pull_request"]["number"]
+    );
+}
+
+#[tokio::test]
+async fn oversized_pull_request_is_rejected_or_truncated_per_mode() {
+    let mut config = CONFIG.clone();
+    config
+        .source_max_payload_bytes
+        .insert("github".to_string(), 16 * 1024);
+    let mut fixture = load_fixture("github", "pull_request_opened");
+    fixture.payload["pull_request"]["body"] = json!("lorem ipsum ".repeat(4_000));
+
+    let mut rejecting = Harness::with_config(config.clone());
+    let response = rejecting.replay(&fixture).await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(rejecting.published().is_none());
+
+    config.oversized_payload_mode = OversizedPayloadMode::Truncate;
+    let mut truncating = Harness::with_config(config);
+    let response = truncating.replay(&fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let payload = truncating
+        .published()
+        .expect("published job")
+        .envelope
+        .payload;
+    assert!(payload["_truncated"]["original_bytes"].as_u64() > Some(16 * 1024));
+    assert_eq!(
+        payload["pull_request"]["number"],
+        fixture.payload["pull_request"]["number"]
+    );
+    assert!(payload["pull_request"]["body"].as_str().map(str::len) < Some(4_097));
+}
+
+#[tokio::test]
+async fn injection_attempts_are_flagged_not_dropped() {
+    let mut harness = Harness::new();
+    let fixture = load_fixture("github", "issue_comment_injection");
+
+    let response = harness.replay(&fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let job = harness.published().expect("published job");
+    assert_eq!(job.envelope.event_type, "issue_comment.created");
+    let flags = job.envelope.payload["_flags"]
+        .as_array()
+        .expect("sanitizer flags");
+    assert!(
+        flags
+            .iter()
+            .any(|flag| flag["field"] == json!("comment.body"))
+    );
+}
+
+#[tokio::test]
+async fn replayed_delivery_is_ignored_as_duplicate() {
+    let mut harness = Harness::new();
+    let fixture = load_fixture("github", "push");
+
+    assert_eq!(harness.replay(&fixture).await.status, StatusCode::OK);
+    let duplicate = harness.replay(&fixture).await;
+    assert_eq!(duplicate.status, StatusCode::OK);
+    assert_eq!(duplicate.body["reason"], "duplicate");
+
+    assert!(harness.published().is_some());
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn identical_payload_under_new_delivery_id_is_ignored_with_content_dedup() {
+    let mut config = CONFIG.clone();
+    config.content_dedup_ttl_seconds = Some(3_600);
+    let mut harness = Harness::with_config(config);
+    let fixture = load_fixture("github", "push");
+    let mut redelivered = fixture.clone();
+    redelivered.headers.insert(
+        "X-GitHub-Delivery".to_string(),
+        "0f1e2d3c-1a2b-11ef-8f3c-0242ac120002".to_string(),
+    );
+
+    assert_eq!(harness.replay(&fixture).await.status, StatusCode::OK);
+    let duplicate = harness.replay(&redelivered).await;
+    assert_eq!(duplicate.status, StatusCode::OK);
+    assert_eq!(duplicate.body["reason"], "duplicate_content");
+
+    assert!(harness.published().is_some());
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn debounce_publishes_only_the_latest_event_per_entity() {
+    let mut config = CONFIG.clone();
+    config.debounce_window_ms = Some(50);
+    let mut harness = Harness::with_config(config);
+    let fixture = load_fixture("github", "pull_request_opened");
+    let mut follow_up = fixture.clone();
+    follow_up.headers.insert(
+        "X-GitHub-Delivery".to_string(),
+        "0f1e2d3c-1a2b-11ef-8f3c-0242ac120003".to_string(),
+    );
+    follow_up.payload["action"] = json!("synchronize");
+
+    let first = harness.replay(&fixture).await;
+    assert_eq!(first.body["debounced"], json!(true));
+    let second = harness.replay(&follow_up).await;
+    assert_eq!(second.status, StatusCode::OK);
+    assert!(harness.published().is_none());
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let job = harness.published().expect("debounced job");
+    assert_eq!(job.envelope.id, second.body["id"]);
+    assert_eq!(job.envelope.event_type, "pull_request.synchronize");
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn synchronize_bursts_coalesce_to_latest_head() {
+    let mut config = CONFIG.clone();
+    config.github_synchronize_coalesce_ms = Some(50);
+    let mut harness = Harness::with_config(config);
+    let mut push = load_fixture("github", "pull_request_opened");
+    push.payload["action"] = json!("synchronize");
+
+    for (delivery, head_sha) in [
+        ("0f1e2d3c-1a2b-11ef-8f3c-0242ac120011", "aaaa"),
+        ("0f1e2d3c-1a2b-11ef-8f3c-0242ac120012", "bbbb"),
+        ("0f1e2d3c-1a2b-11ef-8f3c-0242ac120013", "cccc"),
+    ] {
+        push.headers
+            .insert("X-GitHub-Delivery".to_string(), delivery.to_string());
+        push.payload["pull_request"]["head"]["sha"] = json!(head_sha);
+        let response = harness.replay(&push).await;
+        assert_eq!(response.body["debounced"], json!(true));
+    }
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let job = harness.published().expect("coalesced job");
+    assert_eq!(job.envelope.payload["pull_request"]["head"]["sha"], "cccc");
+    assert_eq!(job.envelope.payload["_coalesced"]["count"], 3);
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn tampered_signature_is_rejected_before_queueing() {
+    let mut harness = Harness::new();
+    let fixture = load_fixture("github", "pull_request_opened");
+
+    let response = harness
+        .replay_with_signature(&fixture, "sha256=deadbeef")
+        .await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn linear_fixtures_are_published_with_normalized_event_types() {
+    let mut harness = Harness::new();
+
+    for (name, event_type) in [
+        ("issue_update_state", "issue.update"),
+        ("comment_create", "comment.create"),
+    ] {
+        let fixture = load_fixture("linear", name);
+        let response = harness.replay(&fixture).await;
+        assert_eq!(response.status, StatusCode::OK, "fixture {name}");
+
+        let job = harness.published().expect("published job");
+        assert_eq!(job.topic, "webhooks.linear");
+        assert_eq!(job.envelope.event_type, event_type);
+    }
+}
+
+#[tokio::test]
+async fn gitlab_merge_request_is_published_with_token_auth() {
+    let mut config = CONFIG.clone();
+    config.enabled_sources.push("gitlab".to_string());
+    config.hmac_secret_gitlab = Some(GITLAB_SECRET.to_string());
+    let mut harness = Harness::with_config(config);
+    let fixture = load_fixture("gitlab", "merge_request_open");
+
+    let response = harness.replay(&fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let job = harness.published().expect("published job");
+    assert_eq!(job.topic, "webhooks.gitlab");
+    assert_eq!(job.envelope.event_type, "merge_request.open");
+
+    let response = harness.replay_with_signature(&fixture, "wrong-token").await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn gmail_push_is_published_with_query_token_auth() {
+    let mut config = CONFIG.clone();
+    config.enabled_sources.push("gmail".to_string());
+    config.hmac_secret_gmail = Some(GMAIL_SECRET.to_string());
+    let mut harness = Harness::with_config(config);
+    let fixture = load_fixture("gmail", "mailbox_changed");
+
+    let response = harness.replay(&fixture).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+    assert!(harness.published().is_none());
+
+    let uri = format!("/webhook/gmail?token={GMAIL_SECRET}");
+    let response = harness.replay_at(&uri, &fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let job = harness.published().expect("published job");
+    assert_eq!(job.topic, "webhooks.gmail");
+    assert_eq!(job.envelope.event_type, "mailbox.changed");
+    assert_eq!(job.envelope.payload["historyId"], "9876543210");
+    assert!(job.envelope.payload.get("subscription").is_none());
+}
+
+#[tokio::test]
+async fn docker_hub_push_is_published_once_per_tag_push() {
+    let mut config = CONFIG.clone();
+    config.enabled_sources.push("registry".to_string());
+    config.hmac_secret_registry = Some(REGISTRY_SECRET.to_string());
+    let mut harness = Harness::with_config(config);
+    let fixture = load_fixture("registry", "dockerhub_push");
+
+    let response = harness.replay(&fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let job = harness.published().expect("published job");
+    assert_eq!(job.topic, "webhooks.registry");
+    assert_eq!(job.envelope.event_type, "registry.push");
+    assert_eq!(job.envelope.payload["_registry"]["repository"], "acme/api");
+    assert_eq!(job.envelope.payload["_registry"]["tag"], "v1.4.0");
+    assert!(job.envelope.payload.get("callback_url").is_none());
+
+    let duplicate = harness.replay(&fixture).await;
+    assert_eq!(duplicate.body["reason"], "duplicate");
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn cooldown_releases_once_clock_advances() {
+    let mut harness = Harness::new();
+    let fixture = load_fixture("github", "pull_request_opened");
+    let mut redelivery = fixture.clone();
+    redelivery
+        .headers
+        .insert("X-GitHub-Delivery".to_string(), "redelivery-1".to_string());
+
+    assert_eq!(harness.replay(&fixture).await.body["status"], "ok");
+    let cooling = harness.replay(&redelivery).await;
+    assert_eq!(cooling.body["reason"], "cooldown");
+
+    harness.clock().advance(31);
+    let mut later = fixture.clone();
+    later
+        .headers
+        .insert("X-GitHub-Delivery".to_string(), "redelivery-2".to_string());
+    assert_eq!(harness.replay(&later).await.body["status"], "ok");
+
+    assert!(harness.published().is_some());
+    assert!(harness.published().is_some());
+    assert!(harness.published().is_none());
+}
+
+#[tokio::test]
+async fn stale_linear_timestamp_is_rejected_by_injected_clock() {
+    let harness = Harness::new();
+    let fixture = load_fixture("linear", "comment_create");
+    let now = harness.clock().now_epoch_seconds();
+
+    let response = harness.replay_signed_at(&fixture, now - 3_600).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+    harness.clock().set(now - 3_600);
+    let response = harness.replay(&fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+}
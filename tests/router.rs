@@ -0,0 +1,405 @@
+mod support;
+
+use axum::body::{Body, Bytes};
+use axum::http::{Request, StatusCode};
+use hook_serve::check_config;
+use hook_serve::config::{CustomSource, SourceSecrets};
+use relay_core::signatures::{HmacAlgorithm, compute_hmac_sha256_hex};
+use relay_core::tenants::TenantConfig;
+use std::collections::BTreeMap;
+use support::{CONFIG, GITHUB_SECRET, Harness, load_fixture, with_test_peer};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn health_route_is_mounted() {
+    let harness = Harness::new();
+    let response = harness.get("/health").await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body["status"], "ok");
+}
+
+#[tokio::test]
+async fn ready_reflects_publish_worker_state() {
+    let harness = Harness::with_stopped_publisher();
+    let response = harness.get("/ready").await;
+    assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn version_and_build_info_metric_agree() {
+    let harness = Harness::new();
+    let version = harness.get("/version").await;
+    assert_eq!(version.status, StatusCode::OK);
+    assert_eq!(version.body["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(version.body["features"]["sources"], "github,linear");
+    let git_commit = version.body["git_commit"].as_str().expect("git commit");
+
+    let metrics = harness.get("/metrics").await;
+    assert_eq!(metrics.status, StatusCode::OK);
+    assert!(
+        metrics
+            .text
+            .contains(&format!("git_commit=\"{git_commit}\""))
+    );
+    assert!(metrics.text.contains("webhook_relay_build_info{"));
+}
+
+#[tokio::test]
+async fn reload_secrets_requires_token_and_keeps_verifying() {
+    let mut config = CONFIG.clone();
+    config.admin_token = Some("admin-token-value".to_string());
+    let harness = Harness::with_config(config);
+
+    let rejected = harness
+        .post_with_bearer("/admin/reload-secrets", "nope")
+        .await;
+    assert_eq!(rejected.status, StatusCode::UNAUTHORIZED);
+
+    let reloaded = harness
+        .post_with_bearer("/admin/reload-secrets", "admin-token-value")
+        .await;
+    assert_eq!(reloaded.status, StatusCode::OK);
+    assert_eq!(reloaded.body["status"], "reloaded");
+
+    let response = harness
+        .replay(&load_fixture("github", "pull_request_opened"))
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn admin_activity_counts_per_repository() {
+    let mut config = CONFIG.clone();
+    config.admin_token = Some("admin-token-value".to_string());
+    let harness = Harness::with_config(config);
+    let fixture = load_fixture("github", "pull_request_opened");
+    assert_eq!(harness.replay(&fixture).await.status, StatusCode::OK);
+    assert_eq!(harness.replay(&fixture).await.status, StatusCode::OK);
+
+    let rejected = harness.get_with_bearer("/admin/activity", "nope").await;
+    assert_eq!(rejected.status, StatusCode::UNAUTHORIZED);
+
+    let response = harness
+        .get_with_bearer("/admin/activity?limit=5", "admin-token-value")
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    let scope = &response.body["scopes"][0];
+    assert_eq!(scope["scope"], "github:acme/relay");
+    assert_eq!(scope["received"], 2);
+    assert_eq!(scope["forwarded"], 1);
+    assert_eq!(scope["dropped"], 1);
+}
+
+#[tokio::test]
+async fn source_paths_mount_static_routes_for_one_source() {
+    let mut config = CONFIG.clone();
+    config
+        .source_paths
+        .insert("github".to_string(), "/hooks/github-pr".to_string());
+    let mut harness = Harness::with_config(config);
+    let fixture = load_fixture("github", "pull_request_opened");
+
+    let response = harness.replay_at("/hooks/github-pr", &fixture).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(
+        harness.published().expect("published").envelope.source,
+        "github"
+    );
+
+    let linear = load_fixture("linear", "comment_create");
+    let response = harness.replay_at("/hooks/github-pr", &linear).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn custom_sources_are_served_under_hooks_custom() {
+    let mut config = CONFIG.clone();
+    config.enabled_sources.push("billing".to_string());
+    config.custom_sources.insert(
+        "billing".to_string(),
+        CustomSource {
+            signature_header: "X-Billing-Signature".to_string(),
+            algorithm: HmacAlgorithm::Sha256,
+        },
+    );
+    config
+        .hmac_secret_custom
+        .insert("billing".to_string(), "billing-secret".to_string());
+    let mut harness = Harness::with_config(config);
+    let body = br#"{"type":"invoice.paid","id":"inv-1"}"#.to_vec();
+    let signature = compute_hmac_sha256_hex("billing-secret", &body);
+
+    let response = harness
+        .post_signed(
+            "/hooks/custom/billing",
+            "X-Billing-Signature",
+            &signature,
+            body.clone(),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    let published = harness.published().expect("published");
+    assert_eq!(published.envelope.source, "billing");
+    assert_eq!(published.envelope.event_type, "invoice.paid");
+
+    let response = harness
+        .post_signed(
+            "/hooks/custom/billing",
+            "X-Billing-Signature",
+            "bad-signature",
+            body,
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn tenant_paths_use_tenant_secrets_and_key_space() {
+    let mut config = CONFIG.clone();
+    for (id, secret) in [("payments", GITHUB_SECRET), ("search", "search-secret")] {
+        config.tenants.push(TenantConfig {
+            id: id.to_string(),
+            secret_envs: BTreeMap::from([("github".to_string(), "UNUSED".to_string())]),
+            cooldown_seconds: None,
+            gateway_url: None,
+            gateway_token_env: None,
+        });
+        config.tenant_secrets.insert(
+            id.to_string(),
+            SourceSecrets {
+                github: Some(secret.to_string()),
+                ..SourceSecrets::default()
+            },
+        );
+    }
+    let mut harness = Harness::with_config(config);
+    let fixture = load_fixture("github", "pull_request_opened");
+
+    assert_eq!(harness.replay(&fixture).await.status, StatusCode::OK);
+    let response = harness
+        .replay_at("/webhook/payments/github", &fixture)
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body["status"], "ok");
+    harness.published().expect("default delivery");
+    let tenant_job = harness.published().expect("tenant delivery");
+    assert_eq!(
+        tenant_job
+            .envelope
+            .meta
+            .and_then(|meta| meta.tenant)
+            .as_deref(),
+        Some("payments")
+    );
+
+    let response = harness.replay_at("/webhook/search/github", &fixture).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+    let linear = load_fixture("linear", "comment_create");
+    let response = harness.replay_at("/webhook/payments/linear", &linear).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    let response = harness.replay_at("/webhook/unknown/github", &fixture).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn unavailable_publish_queue_spills_to_disk() {
+    let dir = std::env::temp_dir().join(format!("hook-serve-spill-router-{}", std::process::id()));
+    let mut config = CONFIG.clone();
+    config.publish_spill_dir = Some(dir.display().to_string());
+    let mut harness = Harness::with_config(config);
+    harness.close_publish_queue();
+
+    let response = harness
+        .replay(&load_fixture("github", "pull_request_opened"))
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body["spilled"], true);
+    let spilled = dir.join(format!(
+        "{}.json",
+        response.body["id"].as_str().expect("id")
+    ));
+    assert!(spilled.exists());
+
+    let mut unspilled = Harness::new();
+    unspilled.close_publish_queue();
+    let response = unspilled
+        .replay(&load_fixture("github", "pull_request_opened"))
+        .await;
+    assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+
+    std::fs::remove_dir_all(&dir).expect("clean up");
+}
+
+#[tokio::test]
+async fn openapi_document_lists_mounted_routes() {
+    let harness = Harness::new();
+    let response = harness.get("/openapi.json").await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert!(
+        response.body["openapi"]
+            .as_str()
+            .is_some_and(|version| version.starts_with("3."))
+    );
+    let paths = &response.body["paths"];
+    assert!(paths["/webhook/{source}"]["post"].is_object());
+    assert!(paths["/ready"]["get"].is_object());
+    assert!(paths["/mcp/ingest"].is_null());
+    assert!(response.body["components"]["securitySchemes"]["admin_token"].is_object());
+}
+
+#[tokio::test]
+async fn admin_bind_moves_operator_routes_off_public_router() {
+    let mut config = CONFIG.clone();
+    config.admin_bind_addr = Some("127.0.0.1:9090".to_string());
+    config.admin_token = Some("admin-token-value".to_string());
+    let harness = Harness::with_config(config);
+
+    for path in [
+        "/metrics",
+        "/version",
+        "/openapi.json",
+        "/admin/config",
+        "/admin/reload-secrets",
+    ] {
+        let response = harness.get(path).await;
+        assert_eq!(response.status, StatusCode::NOT_FOUND, "{path}");
+    }
+    assert_eq!(harness.get("/health").await.status, StatusCode::OK);
+}
+
+#[test]
+fn check_config_validates_without_binding() {
+    check_config(&CONFIG).expect("fixture config is valid");
+
+    let mut config = CONFIG.clone();
+    config.bind_addr = "not-an-address".to_string();
+    let error = check_config(&config).expect_err("bad bind address");
+    assert!(error.to_string().contains("RELAY_BIND"));
+
+    let file = tempfile::NamedTempFile::new().expect("temp file");
+    config.blob_dir = Some(file.path().join("blobs").display().to_string());
+    config.github_api_url = "api.github.com".to_string();
+    config.publish_backoff_base_ms = 500;
+    config.publish_backoff_max_ms = 100;
+    let report = check_config(&config)
+        .expect_err("several problems")
+        .to_string();
+    assert!(report.starts_with("4 config problems:"), "{report}");
+    for name in [
+        "RELAY_BIND",
+        "RELAY_BLOB_DIR",
+        "RELAY_GITHUB_API_URL",
+        "RELAY_PUBLISH_BACKOFF_MAX_MS",
+    ] {
+        assert!(report.contains(name), "{name} missing from {report}");
+    }
+}
+
+#[tokio::test]
+async fn signature_outcomes_are_exported_as_metrics() {
+    let harness = Harness::new();
+    let fixture = load_fixture("github", "pull_request_opened");
+    assert_eq!(harness.replay(&fixture).await.status, StatusCode::OK);
+    let rejected = harness
+        .replay_with_signature(&fixture, "sha256=deadbeef")
+        .await;
+    assert_eq!(rejected.status, StatusCode::UNAUTHORIZED);
+
+    let metrics = harness.get("/metrics").await.text;
+    assert!(metrics.contains(
+        "webhook_relay_signature_verifications_total{source=\"github\",result=\"valid\"} 1"
+    ));
+    assert!(metrics.contains(
+        "webhook_relay_signature_failures_total{source=\"github\",reason=\"mismatch\",client_ip_bucket=\"127.0.0.0/24\"} 1"
+    ));
+    assert!(metrics.contains(
+        "webhook_relay_signature_verification_duration_seconds_count{source=\"github\"} 2"
+    ));
+}
+
+#[tokio::test]
+async fn admin_config_requires_token_and_masks_secrets() {
+    let unmounted = Harness::new().get("/admin/config").await;
+    assert_eq!(unmounted.status, StatusCode::NOT_FOUND);
+
+    let mut config = CONFIG.clone();
+    config.admin_token = Some("admin-token-value".to_string());
+    config.tenants_file = Some("/etc/hook/tenants.toml".to_string());
+    for (id, cooldown_seconds) in [("payments", Some(120)), ("search", None)] {
+        config.tenants.push(TenantConfig {
+            id: id.to_string(),
+            secret_envs: BTreeMap::from([(
+                "github".to_string(),
+                format!("{}_HMAC_SECRET_GITHUB", id.to_ascii_uppercase()),
+            )]),
+            cooldown_seconds,
+            gateway_url: Some(format!("https://openclaw.{id}.example.com/hooks/agent")),
+            gateway_token_env: Some(format!("{}_OPENCLAW_TOKEN", id.to_ascii_uppercase())),
+        });
+    }
+    let harness = Harness::with_config(config);
+
+    let anonymous = harness.get("/admin/config").await;
+    assert_eq!(anonymous.status, StatusCode::UNAUTHORIZED);
+    let wrong = harness.get_with_bearer("/admin/config", "nope").await;
+    assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+
+    let response = harness
+        .get_with_bearer("/admin/config", "admin-token-value")
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    let body = &response.body;
+    assert_eq!(body["HMAC_SECRET_GITHUB"]["value"], "[REDACTED]");
+    assert_eq!(body["HMAC_SECRET_GITHUB"]["source"], "env");
+    assert_eq!(body["RELAY_ADMIN_TOKEN"]["value"], "[REDACTED]");
+    assert_eq!(body["RELAY_COOLDOWN_SECONDS"]["value"], 30);
+    assert_eq!(body["RELAY_COOLDOWN_SECONDS"]["source"], "default");
+    let tenants_file = &body["RELAY_TENANTS_FILE"];
+    assert_eq!(tenants_file["value"], "/etc/hook/tenants.toml");
+    assert_eq!(tenants_file["tenants"][0]["id"], "payments");
+    assert_eq!(tenants_file["tenants"][0]["cooldown_seconds"], 120);
+    assert_eq!(tenants_file["tenants"][0]["cooldown_source"], "tenant");
+    assert_eq!(
+        tenants_file["tenants"][0]["secret_envs"]["github"],
+        "PAYMENTS_HMAC_SECRET_GITHUB"
+    );
+    assert_eq!(
+        tenants_file["tenants"][0]["gateway_token_env"],
+        "PAYMENTS_OPENCLAW_TOKEN"
+    );
+    assert_eq!(tenants_file["tenants"][1]["cooldown_seconds"], 30);
+    assert_eq!(
+        tenants_file["tenants"][1]["cooldown_source"],
+        "RELAY_COOLDOWN_SECONDS"
+    );
+    assert!(!body.to_string().contains(support::GITHUB_SECRET));
+}
+
+#[tokio::test]
+async fn requests_over_in_flight_limit_are_shed() {
+    let mut config = CONFIG.clone();
+    config.max_in_flight_requests = Some(1);
+    let harness = Harness::with_config(config);
+
+    // A body that never arrives keeps the only slot busy.
+    let mut stalled = Request::post("/webhook/github")
+        .header("Content-Type", "application/json")
+        .body(Body::from_stream(futures_util::stream::pending::<
+            Result<Bytes, std::io::Error>,
+        >()))
+        .expect("build request");
+    with_test_peer(&mut stalled);
+    let router = harness.router();
+    let stalled = tokio::spawn(async move { router.oneshot(stalled).await });
+    tokio::task::yield_now().await;
+
+    let shed = harness
+        .replay(&load_fixture("github", "pull_request_opened"))
+        .await;
+    assert_eq!(shed.status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(shed.body["error"], "overloaded");
+
+    let health = harness.get("/health").await;
+    assert_eq!(health.status, StatusCode::OK);
+    stalled.abort();
+}
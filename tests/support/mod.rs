@@ -0,0 +1,304 @@
+#![allow(dead_code)]
+
+use axum::Router;
+use axum::body::{Body, to_bytes};
+use axum::extract::ConnectInfo;
+use axum::http::{Request, StatusCode};
+use hook_serve::build_router_with_clock;
+use hook_serve::clock::{Clock, ManualClock};
+use hook_serve::config::Config;
+use hook_serve::producer::PublishJob;
+use relay_core::signatures::compute_hmac_sha256_hex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, LazyLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tower::ServiceExt;
+
+pub const GITHUB_SECRET: &str = "github-secret";
+pub const LINEAR_SECRET: &str = "linear-secret";
+pub const GITLAB_SECRET: &str = "gitlab-secret";
+pub const GMAIL_SECRET: &str = "gmail-secret";
+pub const REGISTRY_SECRET: &str = "registry-secret";
+
+const TEST_QUEUE_CAPACITY: usize = 64;
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+const TEST_PEER: ([u8; 4], u16) = ([127, 0, 0, 1], 40_000);
+const JSON_CONTENT_TYPE: &str = "application/json";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+pub static CONFIG: LazyLock<Config> = LazyLock::new(|| {
+    // Safety: the env is only written here, once, before any config is read.
+    unsafe {
+        std::env::set_var("KAFKA_BROKERS", "127.0.0.1:9092");
+        std::env::set_var("KAFKA_SECURITY_PROTOCOL", "plaintext");
+        std::env::set_var("KAFKA_ALLOW_PLAINTEXT", "true");
+        std::env::set_var("RELAY_ENABLED_SOURCES", "github,linear");
+        std::env::set_var("HMAC_SECRET_GITHUB", GITHUB_SECRET);
+        std::env::set_var("HMAC_SECRET_LINEAR", LINEAR_SECRET);
+    }
+    Config::from_env().expect("load test config")
+});
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    #[serde(skip)]
+    pub source: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub payload: Value,
+}
+
+pub fn load_fixture(source: &str, name: &str) -> Fixture {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(source)
+        .join(format!("{name}.json"));
+    let raw = std::fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("read fixture {}: {error}", path.display()));
+    let mut fixture: Fixture = serde_json::from_str(&raw)
+        .unwrap_or_else(|error| panic!("parse fixture {}: {error}", path.display()));
+    fixture.source = source.to_string();
+    fixture
+}
+
+pub struct Response {
+    pub status: StatusCode,
+    pub body: Value,
+    pub text: String,
+}
+
+pub struct Harness {
+    router: Router,
+    publish_rx: mpsc::Receiver<PublishJob>,
+    clock: Arc<ManualClock>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self::with_config(CONFIG.clone())
+    }
+
+    pub fn with_config(config: Config) -> Self {
+        Self::build(config, true)
+    }
+
+    pub fn with_stopped_publisher() -> Self {
+        Self::build(CONFIG.clone(), false)
+    }
+
+    fn build(config: Config, publish_worker_alive: bool) -> Self {
+        let (publish_tx, publish_rx) = mpsc::channel(TEST_QUEUE_CAPACITY);
+        let clock = Arc::new(ManualClock::new(now_epoch_seconds()));
+        let router = build_router_with_clock(
+            config,
+            publish_tx,
+            Arc::new(AtomicBool::new(publish_worker_alive)),
+            clock.clone(),
+        )
+        .expect("build router");
+        Self {
+            router,
+            publish_rx,
+            clock,
+        }
+    }
+
+    pub fn router(&self) -> Router {
+        self.router.clone()
+    }
+
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    pub async fn get(&self, uri: &str) -> Response {
+        let request = Request::get(uri)
+            .body(Body::empty())
+            .expect("build request");
+        self.send(request).await
+    }
+
+    pub async fn get_with_bearer(&self, uri: &str, token: &str) -> Response {
+        let request = Request::get(uri)
+            .header("Authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .expect("build request");
+        self.send(request).await
+    }
+
+    pub async fn post_with_bearer(&self, uri: &str, token: &str) -> Response {
+        let request = Request::post(uri)
+            .header("Authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .expect("build request");
+        self.send(request).await
+    }
+
+    pub async fn replay(&self, fixture: &Fixture) -> Response {
+        let body = self.signed_body(fixture);
+        let signature = sign(&fixture.source, &body);
+        self.post_webhook(fixture, body, signature).await
+    }
+
+    /// Sends the fixture as `payload=<json>`, signed over the encoded form body.
+    /// Replays the fixture against `uri` instead of `/webhook/<source>`.
+    pub async fn replay_at(&self, uri: &str, fixture: &Fixture) -> Response {
+        let body = self.signed_body(fixture);
+        let signature = sign(&fixture.source, &body);
+        self.post_to(uri, fixture, JSON_CONTENT_TYPE, body, signature)
+            .await
+    }
+
+    pub async fn replay_form_encoded(&self, fixture: &Fixture) -> Response {
+        let json = String::from_utf8(self.signed_body(fixture)).expect("utf-8 fixture payload");
+        let body = form_urlencoded::Serializer::new(String::new())
+            .append_pair("payload", &json)
+            .finish()
+            .into_bytes();
+        let signature = sign(&fixture.source, &body);
+        self.post(fixture, FORM_CONTENT_TYPE, body, signature).await
+    }
+
+    pub async fn replay_signed_at(&self, fixture: &Fixture, epoch_seconds: i64) -> Response {
+        let body = signed_body(fixture, epoch_seconds);
+        let signature = sign(&fixture.source, &body);
+        self.post_webhook(fixture, body, signature).await
+    }
+
+    pub async fn replay_with_signature(&self, fixture: &Fixture, signature: &str) -> Response {
+        let body = self.signed_body(fixture);
+        self.post_webhook(fixture, body, signature.to_string())
+            .await
+    }
+
+    /// Posts a JSON `body` to `uri` with one signature header.
+    pub async fn post_signed(
+        &self,
+        uri: &str,
+        signature_header: &str,
+        signature: &str,
+        body: Vec<u8>,
+    ) -> Response {
+        let request = Request::post(uri)
+            .header("Content-Type", JSON_CONTENT_TYPE)
+            .header(signature_header, signature)
+            .body(Body::from(body))
+            .expect("build request");
+        self.send(request).await
+    }
+
+    pub fn published(&mut self) -> Option<PublishJob> {
+        self.publish_rx.try_recv().ok()
+    }
+
+    /// Makes every further enqueue fail as if the publish worker had exited.
+    pub fn close_publish_queue(&mut self) {
+        self.publish_rx.close();
+    }
+
+    fn signed_body(&self, fixture: &Fixture) -> Vec<u8> {
+        signed_body(fixture, self.clock.now_epoch_seconds())
+    }
+
+    async fn post_webhook(&self, fixture: &Fixture, body: Vec<u8>, signature: String) -> Response {
+        self.post(fixture, JSON_CONTENT_TYPE, body, signature).await
+    }
+
+    async fn post(
+        &self,
+        fixture: &Fixture,
+        content_type: &str,
+        body: Vec<u8>,
+        signature: String,
+    ) -> Response {
+        let uri = format!("/webhook/{}", fixture.source);
+        self.post_to(&uri, fixture, content_type, body, signature)
+            .await
+    }
+
+    async fn post_to(
+        &self,
+        uri: &str,
+        fixture: &Fixture,
+        content_type: &str,
+        body: Vec<u8>,
+        signature: String,
+    ) -> Response {
+        let mut builder = Request::post(uri)
+            .header("Content-Type", content_type)
+            .header(signature_header(&fixture.source), signature);
+        for (name, value) in &fixture.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let request = builder.body(Body::from(body)).expect("build request");
+        self.send(request).await
+    }
+
+    async fn send(&self, mut request: Request<Body>) -> Response {
+        with_test_peer(&mut request);
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("call router");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), MAX_RESPONSE_BYTES)
+            .await
+            .expect("read response body");
+        let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        Response { status, body, text }
+    }
+}
+
+/// Adds the connection info the rate limiter keys on.
+pub fn with_test_peer(request: &mut Request<Body>) {
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(TEST_PEER)));
+}
+
+fn signed_body(fixture: &Fixture, epoch_seconds: i64) -> Vec<u8> {
+    let mut payload = fixture.payload.clone();
+    if let Some(timestamp) = payload.get_mut("webhookTimestamp") {
+        *timestamp = Value::from(epoch_seconds * 1_000);
+    }
+    serde_json::to_vec(&payload).expect("serialize fixture payload")
+}
+
+fn sign(source: &str, body: &[u8]) -> String {
+    match source {
+        "github" => format!("sha256={}", compute_hmac_sha256_hex(GITHUB_SECRET, body)),
+        "linear" => compute_hmac_sha256_hex(LINEAR_SECRET, body),
+        "gitlab" => GITLAB_SECRET.to_string(),
+        "gmail" => GMAIL_SECRET.to_string(),
+        "registry" => format!("Bearer {REGISTRY_SECRET}"),
+        other => panic!("no fixture signer for source {other}"),
+    }
+}
+
+fn signature_header(source: &str) -> &'static str {
+    match source {
+        "github" => "X-Hub-Signature-256",
+        "linear" => "Linear-Signature",
+        "gitlab" => "X-Gitlab-Token",
+        // Serve strips this header; only the `?token=` query authenticates.
+        "gmail" => "X-Relay-Query-Token",
+        "registry" => "Authorization",
+        other => panic!("no fixture signature header for source {other}"),
+    }
+}
+
+fn now_epoch_seconds() -> i64 {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock after epoch");
+    i64::try_from(elapsed.as_secs()).expect("epoch seconds fit in i64")
+}
@@ -75,23 +75,92 @@ pub fn detect_serve_capability(context: &AppContext) -> RoleCapability {
     let enabled_sources = parse_csv_lower(&sources);
     for source in enabled_sources {
         match source.as_str() {
-            "github" => {
-                if context.resolve_value(None, "HMAC_SECRET_GITHUB").is_none() {
-                    reasons
-                        .push("missing HMAC_SECRET_GITHUB for enabled source github".to_string());
-                }
+            "github" if context.resolve_value(None, "HMAC_SECRET_GITHUB").is_none() => {
+                reasons.push("missing HMAC_SECRET_GITHUB for enabled source github".to_string());
             }
-            "linear" => {
-                if context.resolve_value(None, "HMAC_SECRET_LINEAR").is_none() {
-                    reasons
-                        .push("missing HMAC_SECRET_LINEAR for enabled source linear".to_string());
-                }
+            "linear" if context.resolve_value(None, "HMAC_SECRET_LINEAR").is_none() => {
+                reasons.push("missing HMAC_SECRET_LINEAR for enabled source linear".to_string());
             }
-            "example" => {
-                if context.resolve_value(None, "HMAC_SECRET_EXAMPLE").is_none() {
-                    reasons
-                        .push("missing HMAC_SECRET_EXAMPLE for enabled source example".to_string());
-                }
+            "example" if context.resolve_value(None, "HMAC_SECRET_EXAMPLE").is_none() => {
+                reasons.push("missing HMAC_SECRET_EXAMPLE for enabled source example".to_string());
+            }
+            "gitlab" if context.resolve_value(None, "HMAC_SECRET_GITLAB").is_none() => {
+                reasons.push("missing HMAC_SECRET_GITLAB for enabled source gitlab".to_string());
+            }
+            "bitbucket"
+                if context
+                    .resolve_value(None, "HMAC_SECRET_BITBUCKET")
+                    .is_none() =>
+            {
+                reasons
+                    .push("missing HMAC_SECRET_BITBUCKET for enabled source bitbucket".to_string());
+            }
+            "jira" if context.resolve_value(None, "HMAC_SECRET_JIRA").is_none() => {
+                reasons.push("missing HMAC_SECRET_JIRA for enabled source jira".to_string());
+            }
+            "stripe" if context.resolve_value(None, "HMAC_SECRET_STRIPE").is_none() => {
+                reasons.push("missing HMAC_SECRET_STRIPE for enabled source stripe".to_string());
+            }
+            "sentry" if context.resolve_value(None, "HMAC_SECRET_SENTRY").is_none() => {
+                reasons.push("missing HMAC_SECRET_SENTRY for enabled source sentry".to_string());
+            }
+            "gmail" if context.resolve_value(None, "HMAC_SECRET_GMAIL").is_none() => {
+                reasons.push("missing HMAC_SECRET_GMAIL for enabled source gmail".to_string());
+            }
+            "shopify" if context.resolve_value(None, "HMAC_SECRET_SHOPIFY").is_none() => {
+                reasons.push("missing HMAC_SECRET_SHOPIFY for enabled source shopify".to_string());
+            }
+            "registry"
+                if context
+                    .resolve_value(None, "HMAC_SECRET_REGISTRY")
+                    .is_none() =>
+            {
+                reasons
+                    .push("missing HMAC_SECRET_REGISTRY for enabled source registry".to_string());
+            }
+            "ci" if context.resolve_value(None, "HMAC_SECRET_CI").is_none() => {
+                reasons.push("missing HMAC_SECRET_CI for enabled source ci".to_string());
+            }
+            "internal"
+                if context
+                    .resolve_value(None, "RELAY_INTERNAL_TOKENS")
+                    .is_none() =>
+            {
+                reasons
+                    .push("missing RELAY_INTERNAL_TOKENS for enabled source internal".to_string());
+            }
+            "pubsub"
+                if context
+                    .resolve_value(None, "RELAY_PUBSUB_AUDIENCE")
+                    .is_none() =>
+            {
+                reasons.push("missing RELAY_PUBSUB_AUDIENCE for enabled source pubsub".to_string());
+            }
+            "sns"
+                if context
+                    .resolve_value(None, "RELAY_SNS_TOPIC_ARNS")
+                    .is_none() =>
+            {
+                reasons.push("missing RELAY_SNS_TOPIC_ARNS for enabled source sns".to_string());
+            }
+            "discord"
+                if context
+                    .resolve_value(None, "RELAY_DISCORD_PUBLIC_KEY")
+                    .is_none() =>
+            {
+                reasons.push(
+                    "missing RELAY_DISCORD_PUBLIC_KEY for enabled source discord".to_string(),
+                );
+            }
+            "standard-webhooks"
+                if context
+                    .resolve_value(None, "HMAC_SECRET_STANDARD_WEBHOOKS")
+                    .is_none() =>
+            {
+                reasons.push(
+                    "missing HMAC_SECRET_STANDARD_WEBHOOKS for enabled source standard-webhooks"
+                        .to_string(),
+                );
             }
             _ => {}
         }
@@ -40,6 +40,7 @@ pub enum HookCommand {
     Test(TestArgs),
     Replay(ReplayArgs),
     Debug(DebugArgs),
+    Dlq(DlqArgs),
     Introduce(IntroduceArgs),
     Config(ConfigArgs),
     Infra(InfraArgs),
@@ -115,6 +116,31 @@ pub struct TestArgs {
 pub enum TestCommand {
     Env,
     Smoke(SmokeArgs),
+    Loadgen(LoadgenArgs),
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum LoadgenSource {
+    Github,
+    Linear,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct LoadgenArgs {
+    #[arg(long, default_value = "http://127.0.0.1:8080")]
+    pub url: String,
+    #[arg(long, value_enum, default_value = "github")]
+    pub source: LoadgenSource,
+    #[arg(long, default_value_t = 10)]
+    pub rate: u32,
+    #[arg(long, default_value_t = 30)]
+    pub duration_seconds: u64,
+    #[arg(long, default_value_t = 8_192)]
+    pub payload_bytes: usize,
+    #[arg(long)]
+    pub secret: Option<String>,
+    #[arg(long)]
+    pub insecure: bool,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -157,6 +183,62 @@ pub struct ReplayWebhookArgs {
     pub headers: Vec<String>,
 }
 
+#[derive(Debug, Clone, Args)]
+pub struct DlqArgs {
+    #[command(subcommand)]
+    pub command: DlqCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum DlqCommand {
+    Export(DlqExportArgs),
+    Replay(DlqReplayArgs),
+    Show(DlqShowArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DlqExportArgs {
+    /// NDJSON output path, or `-` for stdout.
+    #[arg(long)]
+    pub out: PathBuf,
+    #[arg(long)]
+    pub topic: Option<String>,
+    #[arg(long)]
+    pub brokers: Option<String>,
+    #[arg(long, default_value_t = 30)]
+    pub idle_timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DlqReplayArgs {
+    #[arg(long)]
+    pub event_id: String,
+    /// JSON merge-patch (RFC 7386) applied to the payload before requeueing.
+    #[arg(long)]
+    pub patch: Option<PathBuf>,
+    /// Destination topic; defaults to `webhooks.<source>`.
+    #[arg(long)]
+    pub topic: Option<String>,
+    #[arg(long)]
+    pub dlq_topic: Option<String>,
+    #[arg(long)]
+    pub brokers: Option<String>,
+    #[arg(long, default_value_t = 30)]
+    pub idle_timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DlqShowArgs {
+    #[arg(long)]
+    pub event_id: String,
+    #[arg(long)]
+    pub topic: Option<String>,
+    #[arg(long)]
+    pub brokers: Option<String>,
+    #[arg(long, default_value_t = 30)]
+    pub idle_timeout_seconds: u64,
+}
+
 #[derive(Debug, Clone, Args)]
 pub struct ReplayKafkaArgs {
     #[arg(long)]
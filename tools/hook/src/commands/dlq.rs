@@ -0,0 +1,442 @@
+use crate::cli::{DlqArgs, DlqCommand, DlqExportArgs, DlqReplayArgs, DlqShowArgs};
+use crate::commands::replay::kafka_client_config;
+use crate::config::AppContext;
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, SecondsFormat, TimeDelta, Utc};
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::message::Message;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use rdkafka::{Offset, TopicPartitionList};
+use relay_core::model::{
+    DEFAULT_SOURCE_TOPIC_PREFIX, DlqEnvelope, EventMeta, ReplayMeta, source_topic_name,
+};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+const DEFAULT_DLQ_TOPIC: &str = "webhooks.dlq";
+const EXPORT_GROUP_ID: &str = "hook-dlq-export";
+const STDOUT_PATH: &str = "-";
+const METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub async fn run(context: &AppContext, arguments: &DlqArgs) -> Result<()> {
+    match &arguments.command {
+        DlqCommand::Export(details) => export(context, details).await,
+        DlqCommand::Replay(details) => replay(context, details).await,
+        DlqCommand::Show(details) => show(context, details).await,
+    }
+}
+
+fn dlq_consumer(context: &AppContext, brokers: Option<&str>) -> Result<BaseConsumer> {
+    let brokers = context
+        .resolve_value(brokers, "KAFKA_BROKERS")
+        .ok_or_else(|| anyhow!("missing KAFKA_BROKERS or --brokers"))?;
+    kafka_client_config(context, &brokers)
+        .set("group.id", EXPORT_GROUP_ID)
+        .set("enable.auto.commit", "false")
+        .create::<BaseConsumer>()
+        .context("create dlq consumer")
+}
+
+fn dlq_topic(context: &AppContext, topic: Option<&str>) -> String {
+    context
+        .resolve_value(topic, "KAFKA_DLQ_TOPIC")
+        .unwrap_or_else(|| DEFAULT_DLQ_TOPIC.to_string())
+}
+
+/// Bounded by the high watermark seen at start, so the export finishes even
+/// while smash keeps appending.
+async fn export(context: &AppContext, arguments: &DlqExportArgs) -> Result<()> {
+    let topic = dlq_topic(context, arguments.topic.as_deref());
+    let idle_timeout = Duration::from_secs(arguments.idle_timeout_seconds);
+    let consumer = dlq_consumer(context, arguments.brokers.as_deref())?;
+    let mut writer = open_output(&arguments.out)?;
+
+    let summary = tokio::task::spawn_blocking(move || {
+        let (mut exported, mut skipped) = (0u64, 0u64);
+        scan_topic(&consumer, &topic, idle_timeout, |payload| {
+            match ndjson_line(payload) {
+                Some(line) => {
+                    writeln!(writer, "{line}").context("write dlq export")?;
+                    exported += 1;
+                }
+                None => skipped += 1,
+            }
+            Ok(())
+        })?;
+        writer.flush().context("flush dlq export")?;
+        Ok::<_, anyhow::Error>((topic, exported, skipped))
+    })
+    .await
+    .context("join dlq export")??;
+    let (topic, exported, skipped) = summary;
+
+    eprintln!(
+        "exported {exported} dlq events from topic={topic} to {} (skipped {skipped} non-json)",
+        arguments.out.display()
+    );
+    Ok(())
+}
+
+fn open_output(path: &Path) -> Result<BufWriter<Box<dyn Write + Send>>> {
+    let writer: Box<dyn Write + Send> = if path == Path::new(STDOUT_PATH) {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(
+            File::create(path).with_context(|| format!("create export file {}", path.display()))?,
+        )
+    };
+    Ok(BufWriter::new(writer))
+}
+
+/// Prints every DLQ entry for the event, oldest first, including the
+/// envelope, meta flags, and structured failure detail.
+async fn show(context: &AppContext, arguments: &DlqShowArgs) -> Result<()> {
+    let entries = find_entries(
+        context,
+        arguments.topic.as_deref(),
+        arguments.brokers.as_deref(),
+        arguments.idle_timeout_seconds,
+        &arguments.event_id,
+    )
+    .await?;
+    if entries.is_empty() {
+        return Err(anyhow!("event {} not found in dlq", arguments.event_id));
+    }
+    let timelines = entries.iter().map(timeline).collect::<Vec<_>>();
+    let report = json!({
+        "event_id": arguments.event_id,
+        "dlq_entries": entries,
+        "timelines": timelines,
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).context("serialize dlq entries")?
+    );
+    Ok(())
+}
+
+/// Lifecycle milestones for one failed delivery, in time order: received by
+/// serve, each attempt's start and end, and the DLQ write.
+fn timeline(entry: &DlqEnvelope) -> Vec<Value> {
+    let mut milestones = vec![json!({
+        "at": entry.envelope.received_at,
+        "milestone": "received",
+    })];
+    for (index, attempt) in entry
+        .failure
+        .iter()
+        .flat_map(|failure| &failure.attempts)
+        .enumerate()
+    {
+        milestones.push(json!({
+            "at": attempt.at,
+            "milestone": "attempt_started",
+            "attempt": index + 1,
+        }));
+        let finished_at = DateTime::parse_from_rfc3339(&attempt.at)
+            .ok()
+            .and_then(|at| {
+                at.checked_add_signed(TimeDelta::milliseconds(
+                    i64::try_from(attempt.duration_ms).ok()?,
+                ))
+            })
+            .map(|at| at.to_utc().to_rfc3339_opts(SecondsFormat::Millis, true));
+        milestones.push(json!({
+            "at": finished_at,
+            "milestone": "attempt_finished",
+            "attempt": index + 1,
+            "duration_ms": attempt.duration_ms,
+            "outcome": attempt.reason,
+            "outcome_class": attempt.outcome,
+            "status_code": attempt.status_code,
+        }));
+    }
+    milestones.push(json!({
+        "at": entry.failed_at,
+        "milestone": "dead_lettered",
+        "error": entry.error,
+    }));
+    milestones
+}
+
+/// DLQ entries for `event_id` in topic order; an event that failed again
+/// after a replay has more than one.
+async fn find_entries(
+    context: &AppContext,
+    topic: Option<&str>,
+    brokers: Option<&str>,
+    idle_timeout_seconds: u64,
+    event_id: &str,
+) -> Result<Vec<DlqEnvelope>> {
+    let topic = dlq_topic(context, topic);
+    let idle_timeout = Duration::from_secs(idle_timeout_seconds);
+    let consumer = dlq_consumer(context, brokers)?;
+    let event_id = event_id.to_string();
+    tokio::task::spawn_blocking(move || {
+        let mut entries = Vec::new();
+        scan_topic(&consumer, &topic, idle_timeout, |payload| {
+            if let Ok(entry) = serde_json::from_slice::<DlqEnvelope>(payload)
+                && entry.envelope.id == event_id
+            {
+                entries.push(entry);
+            }
+            Ok(())
+        })?;
+        Ok(entries)
+    })
+    .await
+    .context("join dlq scan")?
+}
+
+/// Finds the most recent DLQ entry for the event, applies the optional
+/// merge-patch to its payload, and produces it back to the source topic.
+async fn replay(context: &AppContext, arguments: &DlqReplayArgs) -> Result<()> {
+    let patch = arguments
+        .patch
+        .as_deref()
+        .map(|path| {
+            let raw = fs::read_to_string(path)
+                .with_context(|| format!("read patch file: {}", path.display()))?;
+            serde_json::from_str::<Value>(&raw)
+                .with_context(|| format!("parse patch file: {}", path.display()))
+        })
+        .transpose()?;
+    let entry = find_entries(
+        context,
+        arguments.dlq_topic.as_deref(),
+        arguments.brokers.as_deref(),
+        arguments.idle_timeout_seconds,
+        &arguments.event_id,
+    )
+    .await?
+    .pop()
+    .ok_or_else(|| anyhow!("event {} not found in dlq", arguments.event_id))?;
+
+    let target_topic = match &arguments.topic {
+        Some(topic) => topic.clone(),
+        None => source_topic_name(DEFAULT_SOURCE_TOPIC_PREFIX, &entry.envelope.source)
+            .ok_or_else(|| anyhow!("dlq entry has no source; pass --topic"))?,
+    };
+    let patched = patch.is_some();
+    let mut envelope = entry.envelope;
+    if let Some(patch) = &patch {
+        merge_patch(&mut envelope.payload, patch);
+    }
+    envelope.meta.get_or_insert_with(EventMeta::default).replay = Some(ReplayMeta {
+        replayed_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        dlq_failed_at: entry.failed_at,
+        patch,
+    });
+
+    let brokers = context
+        .resolve_value(arguments.brokers.as_deref(), "KAFKA_BROKERS")
+        .ok_or_else(|| anyhow!("missing KAFKA_BROKERS or --brokers"))?;
+    let producer = kafka_client_config(context, &brokers)
+        .create::<FutureProducer>()
+        .context("create dlq replay producer")?;
+    let payload = serde_json::to_string(&envelope).context("serialize replayed envelope")?;
+    producer
+        .send(
+            FutureRecord::to(&target_topic)
+                .key(envelope.id.as_str())
+                .payload(&payload),
+            Timeout::After(METADATA_TIMEOUT),
+        )
+        .await
+        .map_err(|(error, _)| anyhow!("dlq replay send failed: {error}"))?;
+
+    println!(
+        "replayed dlq event id={} to topic={} patched={patched}",
+        envelope.id, target_topic
+    );
+    Ok(())
+}
+
+/// Visits each message from the earliest retained offset up to the high
+/// watermark seen at start.
+fn scan_topic(
+    consumer: &BaseConsumer,
+    topic: &str,
+    idle_timeout: Duration,
+    mut visit: impl FnMut(&[u8]) -> Result<()>,
+) -> Result<()> {
+    let metadata = consumer
+        .fetch_metadata(Some(topic), METADATA_TIMEOUT)
+        .with_context(|| format!("fetch metadata for {topic}"))?;
+    let partitions = metadata
+        .topics()
+        .iter()
+        .find(|candidate| candidate.name() == topic)
+        .map(|candidate| {
+            candidate
+                .partitions()
+                .iter()
+                .map(|partition| partition.id())
+                .collect::<Vec<_>>()
+        })
+        .filter(|partitions| !partitions.is_empty())
+        .ok_or_else(|| anyhow!("dlq topic {topic} not found"))?;
+
+    let mut assignment = TopicPartitionList::new();
+    let mut remaining = BTreeMap::new();
+    for partition in partitions {
+        let (low, high) = consumer
+            .fetch_watermarks(topic, partition, METADATA_TIMEOUT)
+            .with_context(|| format!("fetch watermarks for {topic}/{partition}"))?;
+        if high > low {
+            assignment
+                .add_partition_offset(topic, partition, Offset::Offset(low))
+                .context("build dlq partition assignment")?;
+            remaining.insert(partition, high);
+        }
+    }
+    consumer
+        .assign(&assignment)
+        .context("assign dlq partitions")?;
+
+    while !remaining.is_empty() {
+        let message = match consumer.poll(idle_timeout) {
+            Some(message) => message.context("read dlq message")?,
+            None => {
+                return Err(anyhow!(
+                    "no dlq messages for {}s with {} partition(s) unfinished",
+                    idle_timeout.as_secs(),
+                    remaining.len()
+                ));
+            }
+        };
+        let Some(&high) = remaining.get(&message.partition()) else {
+            continue;
+        };
+        if message.offset() >= high {
+            remaining.remove(&message.partition());
+            continue;
+        }
+        visit(message.payload().unwrap_or_default())?;
+        if message.offset() + 1 >= high {
+            remaining.remove(&message.partition());
+        }
+    }
+    Ok(())
+}
+
+/// RFC 7386 JSON merge-patch: objects merge recursively, `null` removes a
+/// key, anything else replaces the target.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch_fields) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    let Value::Object(target_fields) = target else {
+        return;
+    };
+    for (key, value) in patch_fields {
+        if value.is_null() {
+            target_fields.remove(key);
+        } else {
+            merge_patch(
+                target_fields.entry(key.clone()).or_insert(Value::Null),
+                value,
+            );
+        }
+    }
+}
+
+fn ndjson_line(payload: &[u8]) -> Option<String> {
+    serde_json::from_slice::<Value>(payload)
+        .ok()
+        .and_then(|value| serde_json::to_string(&value).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_patch, ndjson_line, timeline};
+    use relay_core::model::{
+        AttemptOutcome, DeliveryAttempt, DeliveryFailure, DlqEnvelope, ENVELOPE_SCHEMA_VERSION,
+        EventEnvelope,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn timeline_orders_attempts_between_receipt_and_dlq() {
+        let entry = DlqEnvelope {
+            schema_version: ENVELOPE_SCHEMA_VERSION,
+            failed_at: "2026-01-01T00:00:05.000Z".to_string(),
+            error: "forward failed after 1 attempts".to_string(),
+            envelope: EventEnvelope {
+                schema_version: ENVELOPE_SCHEMA_VERSION,
+                id: "evt-1".to_string(),
+                source: "github".to_string(),
+                event_type: "push".to_string(),
+                received_at: "2026-01-01T00:00:00.000Z".to_string(),
+                payload: json!({}),
+                meta: None,
+            },
+            tenant: None,
+            failure: Some(DeliveryFailure {
+                attempts: vec![DeliveryAttempt {
+                    at: "2026-01-01T00:00:01.000Z".to_string(),
+                    duration_ms: 1_250,
+                    reason: "OpenClaw returned 502 Bad Gateway".to_string(),
+                    status_code: Some(502),
+                    outcome: Some(AttemptOutcome::RetryableStatus),
+                }],
+                ..DeliveryFailure::default()
+            }),
+        };
+
+        let milestones = timeline(&entry);
+        let names = milestones
+            .iter()
+            .map(|milestone| milestone["milestone"].as_str().unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "received",
+                "attempt_started",
+                "attempt_finished",
+                "dead_lettered"
+            ]
+        );
+        assert_eq!(milestones[2]["at"], "2026-01-01T00:00:02.250Z");
+        assert_eq!(milestones[2]["status_code"], 502);
+        assert_eq!(milestones[2]["outcome_class"], "retryable_status");
+    }
+
+    #[test]
+    fn merge_patch_follows_rfc_7386() {
+        let mut payload = json!({"title":"x","labels":["a"],"issue":{"id":1,"body":"bad"}});
+        merge_patch(
+            &mut payload,
+            &json!({"title":"fixed","labels":null,"issue":{"body":"ok","state":"open"}}),
+        );
+        assert_eq!(
+            payload,
+            json!({"title":"fixed","issue":{"id":1,"body":"ok","state":"open"}})
+        );
+
+        let mut scalar = json!("text");
+        merge_patch(&mut scalar, &json!({"a":{"b":null,"c":1}}));
+        assert_eq!(scalar, json!({"a":{"c":1}}));
+    }
+
+    #[test]
+    fn dlq_payloads_are_compacted_to_single_lines() {
+        let payload =
+            b"{\n  \"error\": \"forward failed\",\n  \"envelope\": {\"id\": \"evt-1\"}\n}";
+        assert_eq!(
+            ndjson_line(payload).as_deref(),
+            Some(r#"{"envelope":{"id":"evt-1"},"error":"forward failed"}"#)
+        );
+        assert!(ndjson_line(b"not json").is_none());
+    }
+}
@@ -6,7 +6,7 @@ use crate::cli::{
 use crate::config::AppContext;
 use anyhow::{Context, Result, anyhow};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 pub async fn run(context: &AppContext, arguments: &InfraArgs) -> Result<()> {
@@ -191,14 +191,14 @@ fn generate_certs(
             "-out",
             path_arg(&ca_crt_path),
         ])?;
-    } else if generate_relay || generate_consumer {
-        if !ca_key_path.exists() || !ca_crt_path.exists() {
-            return Err(anyhow!(
-                "CA files are required to generate client certs; missing {} or {}",
-                ca_key_path.display(),
-                ca_crt_path.display()
-            ));
-        }
+    } else if (generate_relay || generate_consumer)
+        && (!ca_key_path.exists() || !ca_crt_path.exists())
+    {
+        return Err(anyhow!(
+            "CA files are required to generate client certs; missing {} or {}",
+            ca_key_path.display(),
+            ca_crt_path.display()
+        ));
     }
 
     if generate_relay {
@@ -254,9 +254,9 @@ fn ensure_openssl_available() -> Result<()> {
 
 fn write_client_cert(
     name: &str,
-    output_dir: &PathBuf,
-    ca_key_path: &PathBuf,
-    ca_crt_path: &PathBuf,
+    output_dir: &Path,
+    ca_key_path: &Path,
+    ca_crt_path: &Path,
 ) -> Result<()> {
     let key_file = output_dir.join(format!("{name}.key"));
     let csr_file = output_dir.join(format!("{name}.csr"));
@@ -319,7 +319,7 @@ fn run_openssl(arguments: &[&str]) -> Result<()> {
     run_command(command, None)
 }
 
-fn path_arg(path: &PathBuf) -> &str {
+fn path_arg(path: &Path) -> &str {
     path.to_str().unwrap_or("")
 }
 
@@ -0,0 +1,315 @@
+use crate::cli::{LoadgenArgs, LoadgenSource};
+use crate::config::AppContext;
+use anyhow::{Context, Result, anyhow};
+use chrono::Utc;
+use relay_core::signatures::compute_hmac_sha256_hex;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tokio::time::{MissedTickBehavior, interval};
+
+const LOADGEN_REQUEST_TIMEOUT_SECONDS: u64 = 10;
+const PADDING_CHUNK: &str = "Synthetic load-generation body text for capacity planning. ";
+const PERCENTILES: [f64; 3] = [0.50, 0.95, 0.99];
+
+#[derive(Debug, Clone, Copy)]
+enum Outcome {
+    Accepted,
+    Ignored,
+    Rejected(u16),
+    Failed,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct LoadgenReport {
+    source: String,
+    target_rate_per_second: u32,
+    duration_seconds: f64,
+    payload_bytes: usize,
+    sent: usize,
+    accepted: usize,
+    ignored: usize,
+    rejected: usize,
+    failed: usize,
+    achieved_rate_per_second: f64,
+    latency_ms_p50: f64,
+    latency_ms_p95: f64,
+    latency_ms_p99: f64,
+    latency_ms_max: f64,
+    rejected_statuses: Vec<u16>,
+}
+
+pub async fn run(context: &AppContext, arguments: &LoadgenArgs) -> Result<()> {
+    if arguments.rate == 0 {
+        return Err(anyhow!("--rate must be positive"));
+    }
+    if arguments.duration_seconds == 0 {
+        return Err(anyhow!("--duration-seconds must be positive"));
+    }
+
+    let secret_key = match arguments.source {
+        LoadgenSource::Github => "HMAC_SECRET_GITHUB",
+        LoadgenSource::Linear => "HMAC_SECRET_LINEAR",
+    };
+    let secret = context
+        .resolve_value(arguments.secret.as_deref(), secret_key)
+        .ok_or_else(|| anyhow!("missing {secret_key} or --secret"))?;
+
+    let client = Client::builder()
+        .danger_accept_invalid_certs(arguments.insecure)
+        .timeout(Duration::from_secs(LOADGEN_REQUEST_TIMEOUT_SECONDS))
+        .build()
+        .context("build loadgen http client")?;
+    let url = format!(
+        "{}/webhook/{}",
+        arguments.url.trim_end_matches('/'),
+        source_name(arguments.source)
+    );
+    let run_id = Utc::now().timestamp_millis();
+    let total = u64::from(arguments.rate).saturating_mul(arguments.duration_seconds);
+
+    let mut ticker = interval(Duration::from_secs_f64(1.0 / f64::from(arguments.rate)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+    let mut tasks = JoinSet::new();
+    let started = Instant::now();
+
+    for sequence in 0..total {
+        ticker.tick().await;
+        let request = build_request(
+            &client,
+            &url,
+            arguments.source,
+            &secret,
+            run_id,
+            sequence,
+            arguments.payload_bytes,
+        )?;
+        tasks.spawn(async move {
+            let sent_at = Instant::now();
+            let outcome = classify(request.send().await).await;
+            (outcome, sent_at.elapsed())
+        });
+    }
+
+    let mut results = Vec::with_capacity(tasks.len());
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined.context("join loadgen request task")?);
+    }
+
+    let report = summarize(arguments, &results, started.elapsed());
+    if context.global.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    Ok(())
+}
+
+fn build_request(
+    client: &Client,
+    url: &str,
+    source: LoadgenSource,
+    secret: &str,
+    run_id: i64,
+    sequence: u64,
+    payload_bytes: usize,
+) -> Result<reqwest::RequestBuilder> {
+    let delivery_id = format!("loadgen-{run_id}-{sequence}");
+    let payload = synthetic_payload(source, run_id, sequence, payload_bytes);
+    let body = serde_json::to_vec(&payload).context("serialize loadgen payload")?;
+    let digest = compute_hmac_sha256_hex(secret, &body);
+
+    let request = client.post(url).header("Content-Type", "application/json");
+    let request = match source {
+        LoadgenSource::Github => request
+            .header("X-GitHub-Event", "pull_request")
+            .header("X-GitHub-Delivery", delivery_id)
+            .header("X-Hub-Signature-256", format!("sha256={digest}")),
+        LoadgenSource::Linear => request
+            .header("Linear-Event", "Issue")
+            .header("Linear-Delivery", delivery_id)
+            .header("Linear-Signature", digest),
+    };
+    Ok(request.body(body))
+}
+
+fn synthetic_payload(
+    source: LoadgenSource,
+    run_id: i64,
+    sequence: u64,
+    payload_bytes: usize,
+) -> Value {
+    let mut payload = match source {
+        LoadgenSource::Github => json!({
+            "action": "opened",
+            "number": sequence,
+            "pull_request": {
+                "id": sequence,
+                "number": sequence,
+                "title": format!("loadgen pull request {sequence}"),
+                "body": "",
+                "user": {"login": "hook-loadgen"},
+            },
+            "repository": {"full_name": format!("hook-loadgen/run-{run_id}")},
+            "sender": {"login": "hook-loadgen"},
+        }),
+        LoadgenSource::Linear => json!({
+            "action": "create",
+            "type": "Issue",
+            "webhookTimestamp": Utc::now().timestamp_millis(),
+            "data": {
+                "id": format!("loadgen-{run_id}-{sequence}"),
+                "title": format!("loadgen issue {sequence}"),
+                "description": "",
+                "team": {"key": "LOADGEN"},
+            },
+        }),
+    };
+
+    let base_len = payload.to_string().len();
+    let padding = padding_text(payload_bytes.saturating_sub(base_len));
+    let text_field = match source {
+        LoadgenSource::Github => payload.pointer_mut("/pull_request/body"),
+        LoadgenSource::Linear => payload.pointer_mut("/data/description"),
+    };
+    if let Some(field) = text_field {
+        *field = Value::String(padding);
+    }
+    payload
+}
+
+fn padding_text(length: usize) -> String {
+    PADDING_CHUNK.chars().cycle().take(length).collect()
+}
+
+async fn classify(result: reqwest::Result<reqwest::Response>) -> Outcome {
+    let Ok(response) = result else {
+        return Outcome::Failed;
+    };
+    let status = response.status();
+    if !status.is_success() {
+        return Outcome::Rejected(status.as_u16());
+    }
+
+    match response.json::<Value>().await {
+        Ok(body) if body.get("status").and_then(Value::as_str) == Some("ignored") => {
+            Outcome::Ignored
+        }
+        _ => Outcome::Accepted,
+    }
+}
+
+fn summarize(
+    arguments: &LoadgenArgs,
+    results: &[(Outcome, Duration)],
+    elapsed: Duration,
+) -> LoadgenReport {
+    let mut report = LoadgenReport {
+        source: source_name(arguments.source).to_string(),
+        target_rate_per_second: arguments.rate,
+        duration_seconds: elapsed.as_secs_f64(),
+        payload_bytes: arguments.payload_bytes,
+        sent: results.len(),
+        ..LoadgenReport::default()
+    };
+
+    for (outcome, _) in results {
+        match outcome {
+            Outcome::Accepted => report.accepted += 1,
+            Outcome::Ignored => report.ignored += 1,
+            Outcome::Rejected(status) => {
+                report.rejected += 1;
+                if !report.rejected_statuses.contains(status) {
+                    report.rejected_statuses.push(*status);
+                }
+            }
+            Outcome::Failed => report.failed += 1,
+        }
+    }
+
+    if elapsed.as_secs_f64() > 0.0 {
+        report.achieved_rate_per_second = report.accepted as f64 / elapsed.as_secs_f64();
+    }
+
+    let mut latencies_ms = results
+        .iter()
+        .map(|(_, latency)| latency.as_secs_f64() * 1_000.0)
+        .collect::<Vec<_>>();
+    latencies_ms.sort_by(f64::total_cmp);
+    let [p50, p95, p99] = PERCENTILES.map(|quantile| percentile(&latencies_ms, quantile));
+    report.latency_ms_p50 = p50;
+    report.latency_ms_p95 = p95;
+    report.latency_ms_p99 = p99;
+    report.latency_ms_max = latencies_ms.last().copied().unwrap_or_default();
+    report
+}
+
+fn percentile(sorted: &[f64], quantile: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (quantile * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+fn print_report(report: &LoadgenReport) {
+    println!(
+        "loadgen source={} target_rate={}/s duration={:.1}s payload_bytes={}",
+        report.source, report.target_rate_per_second, report.duration_seconds, report.payload_bytes
+    );
+    println!(
+        "sent={} accepted={} ignored={} rejected={} failed={} achieved_rate={:.1}/s",
+        report.sent,
+        report.accepted,
+        report.ignored,
+        report.rejected,
+        report.failed,
+        report.achieved_rate_per_second
+    );
+    println!(
+        "enqueue_latency_ms p50={:.1} p95={:.1} p99={:.1} max={:.1}",
+        report.latency_ms_p50, report.latency_ms_p95, report.latency_ms_p99, report.latency_ms_max
+    );
+    if !report.rejected_statuses.is_empty() {
+        println!("rejected_statuses={:?}", report.rejected_statuses);
+    }
+}
+
+fn source_name(source: LoadgenSource) -> &'static str {
+    match source {
+        LoadgenSource::Github => "github",
+        LoadgenSource::Linear => "linear",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synthetic_payloads_are_padded_to_requested_size() {
+        for source in [LoadgenSource::Github, LoadgenSource::Linear] {
+            let payload = synthetic_payload(source, 1, 7, 2_048);
+            let size = payload.to_string().len();
+            assert!((2_048..2_048 + 16).contains(&size), "size {size}");
+        }
+    }
+
+    #[test]
+    fn synthetic_payloads_use_unique_entities() {
+        let first = synthetic_payload(LoadgenSource::Github, 1, 1, 0);
+        let second = synthetic_payload(LoadgenSource::Github, 1, 2, 0);
+        assert_ne!(first["pull_request"]["id"], second["pull_request"]["id"]);
+    }
+
+    #[test]
+    fn percentile_picks_nearest_rank() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&sorted, 0.50), 3.0);
+        assert_eq!(percentile(&sorted, 0.99), 5.0);
+        assert_eq!(percentile(&[], 0.50), 0.0);
+    }
+}
@@ -1,7 +1,9 @@
 pub mod config;
 pub mod debug;
+pub mod dlq;
 pub mod infra;
 pub mod introduce;
+pub mod loadgen;
 pub mod logs;
 pub mod relay;
 pub mod replay;
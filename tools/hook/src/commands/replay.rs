@@ -73,8 +73,34 @@ async fn replay_kafka(context: &AppContext, arguments: &ReplayKafkaArgs) -> Resu
         ));
     }
 
+    let producer = kafka_client_config(context, &brokers)
+        .create::<FutureProducer>()
+        .context("create replay producer")?;
+
+    let mut record = FutureRecord::to(&arguments.topic).payload(&payload);
+    if let Some(key) = &arguments.key {
+        record = record.key(key);
+    }
+
+    producer
+        .send(
+            record,
+            Timeout::After(Duration::from_secs(DEFAULT_REPLAY_TIMEOUT_SECONDS)),
+        )
+        .await
+        .map_err(|(error, _)| anyhow!("kafka replay send failed: {error}"))?;
+
+    println!(
+        "replayed payload to kafka topic={} mode={:?}",
+        arguments.topic, arguments.mode
+    );
+
+    Ok(())
+}
+
+pub(crate) fn kafka_client_config(context: &AppContext, brokers: &str) -> ClientConfig {
     let mut config = ClientConfig::new();
-    config.set("bootstrap.servers", &brokers).set(
+    config.set("bootstrap.servers", brokers).set(
         "security.protocol",
         context
             .resolve_value(None, "KAFKA_SECURITY_PROTOCOL")
@@ -99,28 +125,5 @@ async fn replay_kafka(context: &AppContext, arguments: &ReplayKafkaArgs) -> Resu
     if let Some(password) = context.resolve_value(None, "KAFKA_SASL_PASSWORD") {
         config.set("sasl.password", &password);
     }
-
-    let producer = config
-        .create::<FutureProducer>()
-        .context("create replay producer")?;
-
-    let mut record = FutureRecord::to(&arguments.topic).payload(&payload);
-    if let Some(key) = &arguments.key {
-        record = record.key(key);
-    }
-
-    producer
-        .send(
-            record,
-            Timeout::After(Duration::from_secs(DEFAULT_REPLAY_TIMEOUT_SECONDS)),
-        )
-        .await
-        .map_err(|(error, _)| anyhow!("kafka replay send failed: {error}"))?;
-
-    println!(
-        "replayed payload to kafka topic={} mode={:?}",
-        arguments.topic, arguments.mode
-    );
-
-    Ok(())
+    config
 }
@@ -33,20 +33,61 @@ pub async fn run(context: &AppContext, arguments: &ServeArgs) -> Result<()> {
 
     for source in parse_csv_lower(&enabled_sources) {
         match source.as_str() {
-            "github" => {
-                if value(context, None, "HMAC_SECRET_GITHUB").is_none() {
-                    reasons.push("missing HMAC_SECRET_GITHUB for source github".to_string());
-                }
+            "github" if value(context, None, "HMAC_SECRET_GITHUB").is_none() => {
+                reasons.push("missing HMAC_SECRET_GITHUB for source github".to_string());
             }
-            "linear" => {
-                if value(context, None, "HMAC_SECRET_LINEAR").is_none() {
-                    reasons.push("missing HMAC_SECRET_LINEAR for source linear".to_string());
-                }
+            "linear" if value(context, None, "HMAC_SECRET_LINEAR").is_none() => {
+                reasons.push("missing HMAC_SECRET_LINEAR for source linear".to_string());
             }
-            "example" => {
-                if value(context, None, "HMAC_SECRET_EXAMPLE").is_none() {
-                    reasons.push("missing HMAC_SECRET_EXAMPLE for source example".to_string());
-                }
+            "example" if value(context, None, "HMAC_SECRET_EXAMPLE").is_none() => {
+                reasons.push("missing HMAC_SECRET_EXAMPLE for source example".to_string());
+            }
+            "gitlab" if value(context, None, "HMAC_SECRET_GITLAB").is_none() => {
+                reasons.push("missing HMAC_SECRET_GITLAB for source gitlab".to_string());
+            }
+            "bitbucket" if value(context, None, "HMAC_SECRET_BITBUCKET").is_none() => {
+                reasons.push("missing HMAC_SECRET_BITBUCKET for source bitbucket".to_string());
+            }
+            "jira" if value(context, None, "HMAC_SECRET_JIRA").is_none() => {
+                reasons.push("missing HMAC_SECRET_JIRA for source jira".to_string());
+            }
+            "stripe" if value(context, None, "HMAC_SECRET_STRIPE").is_none() => {
+                reasons.push("missing HMAC_SECRET_STRIPE for source stripe".to_string());
+            }
+            "sentry" if value(context, None, "HMAC_SECRET_SENTRY").is_none() => {
+                reasons.push("missing HMAC_SECRET_SENTRY for source sentry".to_string());
+            }
+            "gmail" if value(context, None, "HMAC_SECRET_GMAIL").is_none() => {
+                reasons.push("missing HMAC_SECRET_GMAIL for source gmail".to_string());
+            }
+            "shopify" if value(context, None, "HMAC_SECRET_SHOPIFY").is_none() => {
+                reasons.push("missing HMAC_SECRET_SHOPIFY for source shopify".to_string());
+            }
+            "registry" if value(context, None, "HMAC_SECRET_REGISTRY").is_none() => {
+                reasons.push("missing HMAC_SECRET_REGISTRY for source registry".to_string());
+            }
+            "ci" if value(context, None, "HMAC_SECRET_CI").is_none() => {
+                reasons.push("missing HMAC_SECRET_CI for source ci".to_string());
+            }
+            "internal" if value(context, None, "RELAY_INTERNAL_TOKENS").is_none() => {
+                reasons.push("missing RELAY_INTERNAL_TOKENS for source internal".to_string());
+            }
+            "pubsub" if value(context, None, "RELAY_PUBSUB_AUDIENCE").is_none() => {
+                reasons.push("missing RELAY_PUBSUB_AUDIENCE for source pubsub".to_string());
+            }
+            "sns" if value(context, None, "RELAY_SNS_TOPIC_ARNS").is_none() => {
+                reasons.push("missing RELAY_SNS_TOPIC_ARNS for source sns".to_string());
+            }
+            "discord" if value(context, None, "RELAY_DISCORD_PUBLIC_KEY").is_none() => {
+                reasons.push("missing RELAY_DISCORD_PUBLIC_KEY for source discord".to_string());
+            }
+            "standard-webhooks"
+                if value(context, None, "HMAC_SECRET_STANDARD_WEBHOOKS").is_none() =>
+            {
+                reasons.push(
+                    "missing HMAC_SECRET_STANDARD_WEBHOOKS for source standard-webhooks"
+                        .to_string(),
+                );
             }
             _ => {}
         }
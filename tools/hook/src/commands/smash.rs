@@ -125,11 +125,13 @@ struct ActiveSmashContract {
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "driver", rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
 enum SmashAdapterEnv {
     OpenclawHttpOutput {
         id: String,
         url: String,
         token_env: String,
+        signing_secret_env: Option<String>,
         timeout_seconds: u64,
         max_retries: u32,
         plugins: Vec<SmashPluginEnv>,
@@ -183,6 +185,16 @@ struct SmashRouteEnv {
     source_topic_pattern: String,
     event_filters: Vec<String>,
     destinations: Vec<SmashDestinationEnv>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule: Option<SmashRouteScheduleEnv>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SmashRouteScheduleEnv {
+    hours: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utc_offset: Option<String>,
+    outside: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -308,6 +320,14 @@ fn resolve_active_smash_contract(
                         required: destination.required,
                     })
                     .collect::<Vec<_>>(),
+                schedule: route
+                    .schedule
+                    .as_ref()
+                    .map(|schedule| SmashRouteScheduleEnv {
+                        hours: schedule.hours.clone(),
+                        utc_offset: schedule.utc_offset.clone(),
+                        outside: schedule.outside,
+                    }),
             })
         })
         .collect::<Result<Vec<_>>>()?;
@@ -345,6 +365,7 @@ fn to_smash_adapter_env(adapter: &relay_core::contract::EgressAdapter) -> Result
             id: adapter.id.clone(),
             url: required_string_config(&adapter.config, "url", &adapter.id)?,
             token_env: required_string_config(&adapter.config, "token_env", &adapter.id)?,
+            signing_secret_env: optional_string_config(&adapter.config, "signing_secret_env"),
             timeout_seconds: required_u64_config(&adapter.config, "timeout_seconds", &adapter.id)?,
             max_retries: required_u32_config(&adapter.config, "max_retries", &adapter.id)?,
             plugins,
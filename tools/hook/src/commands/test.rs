@@ -13,6 +13,7 @@ pub async fn run(context: &AppContext, arguments: &TestArgs) -> Result<()> {
             SmokeCommand::Relay => run_smoke_relay(context),
             SmokeCommand::Smash => run_smoke_smash(context).await,
         },
+        TestCommand::Loadgen(loadgen) => crate::commands::loadgen::run(context, loadgen).await,
     }
 }
 
@@ -7,15 +7,20 @@ use anyhow::Result;
 use clap::Parser;
 use cli::{Cli, HookCommand};
 use config::AppContext;
+use relay_core::redact::Redactor;
 use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    tracing_subscriber::fmt().with_env_filter(filter).init();
+    let redactor = Redactor::shared();
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(move || redactor.writer(std::io::stdout()))
+        .init();
 
     let cli = Cli::parse();
-    let load_contract_config = !matches!(cli.command, HookCommand::Relay(_));
+    let load_contract_config = !matches!(cli.command, HookCommand::Relay(_) | HookCommand::Dlq(_));
     let context = AppContext::load_with_contract(cli.global.clone(), load_contract_config)?;
 
     match &cli.command {
@@ -25,6 +30,7 @@ async fn main() -> Result<()> {
         HookCommand::Test(arguments) => commands::test::run(&context, arguments).await,
         HookCommand::Replay(arguments) => commands::replay::run(&context, arguments).await,
         HookCommand::Debug(arguments) => commands::debug::run(&context, arguments).await,
+        HookCommand::Dlq(arguments) => commands::dlq::run(&context, arguments).await,
         HookCommand::Introduce(arguments) => commands::introduce::run(&context, arguments).await,
         HookCommand::Config(arguments) => commands::config::run(&context, arguments).await,
         HookCommand::Infra(arguments) => commands::infra::run(&context, arguments).await,